@@ -1,11 +1,20 @@
 use crate::{
     error::ContractResult,
     msg::{AuthExecuteMsg, AuthQueryMsg},
-    orderbook::set_maker_fee,
+    orderbook::{
+        set_activation_delay, set_crossing_tick_policy, set_maker_fee, set_maker_fee_discount,
+        set_max_batch_claim, set_max_orders_per_tick, set_max_price_impact,
+        set_max_ticks_per_order, set_min_fee_transfer, set_min_market_order_size,
+        set_placement_fee, set_prune_reward,
+    },
     state::MAKER_FEE_RECIPIENT,
-    sudo, ContractError,
+    sudo,
+    types::{CrossingTickPolicy, MakerFeeDiscount, PausedOps},
+    ContractError,
+};
+use cosmwasm_std::{
+    ensure, Addr, Api, Coin, Decimal256, Deps, DepsMut, MessageInfo, Response, Storage, Uint128,
 };
-use cosmwasm_std::{ensure, Addr, Api, Decimal256, Deps, DepsMut, MessageInfo, Response, Storage};
 use cw_storage_plus::Item;
 
 pub const ADMIN: Item<Addr> = Item::new("admin");
@@ -57,6 +66,11 @@ pub(crate) fn dispatch(
         // Set the active state of the contract
         AuthExecuteMsg::SetActive { active } => dispatch_set_active(deps, info, active),
 
+        // Set the operations paused on the contract
+        AuthExecuteMsg::SetPausedOps { paused_ops } => {
+            dispatch_set_paused_ops(deps, info, paused_ops)
+        }
+
         // Set the maker fee amount for the contract
         AuthExecuteMsg::SetMakerFee { fee } => dispatch_set_maker_fee(deps, info, fee),
 
@@ -64,6 +78,62 @@ pub(crate) fn dispatch(
         AuthExecuteMsg::SetMakerFeeRecipient { recipient } => {
             dispatch_set_maker_fee_recipient(deps, info, recipient)
         }
+
+        // Set the maximum number of orders per tick and direction for the contract
+        AuthExecuteMsg::SetMaxOrdersPerTick {
+            max_orders_per_tick,
+        } => dispatch_set_max_orders_per_tick(deps, info, max_orders_per_tick),
+
+        // Set the maximum number of ticks a single market order may fill against
+        AuthExecuteMsg::SetMaxTicksPerOrder {
+            max_ticks_per_order,
+        } => dispatch_set_max_ticks_per_order(deps, info, max_ticks_per_order),
+
+        // Set the maximum number of orders processed by a single batch claim
+        AuthExecuteMsg::SetMaxBatchClaim { max_batch_claim } => {
+            dispatch_set_max_batch_claim(deps, info, max_batch_claim)
+        }
+
+        // Set the reward paid for pruning a tick
+        AuthExecuteMsg::SetPruneReward { reward } => dispatch_set_prune_reward(deps, info, reward),
+
+        // Set the maximum price impact of a market order
+        AuthExecuteMsg::SetMaxPriceImpact { max_price_impact } => {
+            dispatch_set_max_price_impact(deps, info, max_price_impact)
+        }
+
+        // Set the upper bound for claim bounties on newly placed limit orders
+        AuthExecuteMsg::SetMaxClaimBounty { max_claim_bounty } => {
+            dispatch_set_max_claim_bounty(deps, info, max_claim_bounty)
+        }
+
+        // Set the maker fee amount below which fees are waived
+        AuthExecuteMsg::SetMinFeeTransfer { min_fee_transfer } => {
+            dispatch_set_min_fee_transfer(deps, info, min_fee_transfer)
+        }
+
+        // Set the smallest input accepted for a market order
+        AuthExecuteMsg::SetMinMarketOrderSize {
+            min_market_order_size,
+        } => dispatch_set_min_market_order_size(deps, info, min_market_order_size),
+
+        // Set the flat fee charged for placing a limit order
+        AuthExecuteMsg::SetPlacementFee { fee } => dispatch_set_placement_fee(deps, info, fee),
+
+        // Set the maker fee discount for long resting orders
+        AuthExecuteMsg::SetMakerFeeDiscount { discount } => {
+            dispatch_set_maker_fee_discount(deps, info, discount)
+        }
+
+        // Set the number of blocks before placed orders can be filled
+        AuthExecuteMsg::SetActivationDelay { activation_delay } => {
+            dispatch_set_activation_delay(deps, info, activation_delay)
+        }
+
+        // Set how orders placed on the best opposite tick are handled
+        AuthExecuteMsg::SetCrossingTickPolicy { policy } => {
+            dispatch_set_crossing_tick_policy(deps, info, policy)
+        }
     }
 }
 
@@ -304,6 +374,22 @@ pub(crate) fn dispatch_set_active(
     sudo::set_active(deps, active)
 }
 
+/// Sets the operations that are paused on the orderbook.
+///
+/// Governance controls pausing through `SudoMsg::SetPausedOps`; this allows the moderator or admin
+/// to do the same on chains where sudo messages are unavailable, such as testnets.
+///
+/// Only callable by either moderator or admin.
+pub(crate) fn dispatch_set_paused_ops(
+    deps: DepsMut,
+    info: MessageInfo,
+    paused_ops: PausedOps,
+) -> ContractResult<Response> {
+    ensure_is_admin_or_moderator(deps.as_ref(), &info.sender)?;
+
+    sudo::set_paused_ops(deps, paused_ops)
+}
+
 /// Sets the maker fee amount for the orderbook.
 ///
 /// Only callable by either moderator or admin.
@@ -344,6 +430,239 @@ pub(crate) fn dispatch_set_maker_fee_recipient(
     ]))
 }
 
+/// Sets the maximum number of orders allowed per tick and direction for the orderbook.
+///
+/// Only callable by the admin.
+pub(crate) fn dispatch_set_max_orders_per_tick(
+    deps: DepsMut,
+    info: MessageInfo,
+    max_orders_per_tick: u64,
+) -> ContractResult<Response> {
+    ensure_is_admin(deps.as_ref(), &info.sender)?;
+
+    let max_orders_per_tick = set_max_orders_per_tick(deps.storage, max_orders_per_tick)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("method", "set_max_orders_per_tick"),
+        ("max_orders_per_tick", &max_orders_per_tick.to_string()),
+    ]))
+}
+
+/// Sets the maximum number of ticks a single market order may fill against.
+///
+/// Only callable by the admin.
+pub(crate) fn dispatch_set_max_ticks_per_order(
+    deps: DepsMut,
+    info: MessageInfo,
+    max_ticks_per_order: u32,
+) -> ContractResult<Response> {
+    ensure_is_admin(deps.as_ref(), &info.sender)?;
+
+    let max_ticks_per_order = set_max_ticks_per_order(deps.storage, max_ticks_per_order)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("method", "set_max_ticks_per_order"),
+        ("max_ticks_per_order", &max_ticks_per_order.to_string()),
+    ]))
+}
+
+/// Sets the maximum number of orders processed by a single batch claim.
+///
+/// Only callable by the admin.
+pub(crate) fn dispatch_set_max_batch_claim(
+    deps: DepsMut,
+    info: MessageInfo,
+    max_batch_claim: u32,
+) -> ContractResult<Response> {
+    ensure_is_admin(deps.as_ref(), &info.sender)?;
+
+    let max_batch_claim = set_max_batch_claim(deps.storage, max_batch_claim)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("method", "set_max_batch_claim"),
+        ("max_batch_claim", &max_batch_claim.to_string()),
+    ]))
+}
+
+/// Sets the reward paid to callers for pruning a tick, or disables rewards if `None` is provided.
+///
+/// Only callable by the admin.
+pub(crate) fn dispatch_set_prune_reward(
+    deps: DepsMut,
+    info: MessageInfo,
+    reward: Option<Coin>,
+) -> ContractResult<Response> {
+    ensure_is_admin(deps.as_ref(), &info.sender)?;
+
+    let reward_str = reward.as_ref().map_or("none".to_string(), Coin::to_string);
+    set_prune_reward(deps.storage, reward)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("method", "set_prune_reward"),
+        ("prune_reward", &reward_str),
+    ]))
+}
+
+/// Sets the maximum fraction a market order may move the price by, or removes the bound if `None`
+/// is provided.
+///
+/// Only callable by the admin.
+pub(crate) fn dispatch_set_max_price_impact(
+    deps: DepsMut,
+    info: MessageInfo,
+    max_price_impact: Option<Decimal256>,
+) -> ContractResult<Response> {
+    ensure_is_admin(deps.as_ref(), &info.sender)?;
+
+    let max_price_impact_str = max_price_impact.map_or("none".to_string(), |m| m.to_string());
+    set_max_price_impact(deps.storage, max_price_impact)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("method", "set_max_price_impact"),
+        ("max_price_impact", &max_price_impact_str),
+    ]))
+}
+
+/// Sets the upper bound for claim bounties on newly placed limit orders.
+///
+/// Mirrors `SudoMsg::SetMaxClaimBounty` for chains where sudo messages are unavailable.
+///
+/// Only callable by the admin.
+pub(crate) fn dispatch_set_max_claim_bounty(
+    deps: DepsMut,
+    info: MessageInfo,
+    max_claim_bounty: Decimal256,
+) -> ContractResult<Response> {
+    ensure_is_admin(deps.as_ref(), &info.sender)?;
+
+    sudo::dispatch_set_max_claim_bounty(deps, max_claim_bounty)
+}
+
+/// Sets the maker fee amount below which fees are waived to the claimer rather than transferred.
+///
+/// Only callable by the admin.
+pub(crate) fn dispatch_set_min_fee_transfer(
+    deps: DepsMut,
+    info: MessageInfo,
+    min_fee_transfer: Uint128,
+) -> ContractResult<Response> {
+    ensure_is_admin(deps.as_ref(), &info.sender)?;
+
+    let min_fee_transfer = set_min_fee_transfer(deps.storage, min_fee_transfer)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("method", "set_min_fee_transfer"),
+        ("min_fee_transfer", &min_fee_transfer.to_string()),
+    ]))
+}
+
+/// Sets the smallest input accepted for a market order.
+///
+/// Only callable by the admin.
+pub(crate) fn dispatch_set_min_market_order_size(
+    deps: DepsMut,
+    info: MessageInfo,
+    min_market_order_size: Uint128,
+) -> ContractResult<Response> {
+    ensure_is_admin(deps.as_ref(), &info.sender)?;
+
+    let min_market_order_size = set_min_market_order_size(deps.storage, min_market_order_size)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("method", "set_min_market_order_size"),
+        ("min_market_order_size", &min_market_order_size.to_string()),
+    ]))
+}
+
+/// Sets the flat fee charged for placing a limit order, or makes placements free if `None` is
+/// provided.
+///
+/// Only callable by the admin.
+pub(crate) fn dispatch_set_placement_fee(
+    deps: DepsMut,
+    info: MessageInfo,
+    fee: Option<Coin>,
+) -> ContractResult<Response> {
+    ensure_is_admin(deps.as_ref(), &info.sender)?;
+
+    let fee_str = fee.as_ref().map_or("none".to_string(), Coin::to_string);
+    set_placement_fee(deps.storage, fee)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("method", "set_placement_fee"),
+        ("placement_fee", &fee_str),
+    ]))
+}
+
+/// Sets the maker fee discount for orders resting at least `min_blocks`, or removes it if `None`
+/// is provided.
+///
+/// Only callable by the admin.
+pub(crate) fn dispatch_set_maker_fee_discount(
+    deps: DepsMut,
+    info: MessageInfo,
+    discount: Option<MakerFeeDiscount>,
+) -> ContractResult<Response> {
+    ensure_is_admin(deps.as_ref(), &info.sender)?;
+
+    let (min_blocks, discount_str) =
+        discount
+            .as_ref()
+            .map_or(("none".to_string(), "none".to_string()), |discount| {
+                (
+                    discount.min_blocks.to_string(),
+                    discount.discount.to_string(),
+                )
+            });
+    set_maker_fee_discount(deps.storage, discount)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("method", "set_maker_fee_discount"),
+        ("min_blocks", &min_blocks),
+        ("discount", &discount_str),
+    ]))
+}
+
+/// Sets the number of blocks after placement before a limit order can be filled.
+///
+/// Only callable by the admin.
+pub(crate) fn dispatch_set_activation_delay(
+    deps: DepsMut,
+    info: MessageInfo,
+    activation_delay: u64,
+) -> ContractResult<Response> {
+    ensure_is_admin(deps.as_ref(), &info.sender)?;
+
+    let activation_delay = set_activation_delay(deps.storage, activation_delay)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("method", "set_activation_delay"),
+        ("activation_delay", &activation_delay.to_string()),
+    ]))
+}
+
+/// Sets how limit orders placed exactly on the best opposite tick are handled.
+///
+/// Only callable by the admin.
+pub(crate) fn dispatch_set_crossing_tick_policy(
+    deps: DepsMut,
+    info: MessageInfo,
+    policy: CrossingTickPolicy,
+) -> ContractResult<Response> {
+    ensure_is_admin(deps.as_ref(), &info.sender)?;
+
+    let policy = set_crossing_tick_policy(deps.storage, policy)?;
+    let policy_str = match policy {
+        CrossingTickPolicy::Rest => "rest",
+        CrossingTickPolicy::Reject => "reject",
+    };
+
+    Ok(Response::default().add_attributes(vec![
+        ("method", "set_crossing_tick_policy"),
+        ("policy", policy_str),
+    ]))
+}
+
 // -- Ensure Methods --
 
 /// Validates that the provided address is the current contract admin.
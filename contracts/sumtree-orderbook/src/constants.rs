@@ -1,14 +1,68 @@
 use cosmwasm_std::{Decimal, Decimal256};
 use std::str::FromStr;
 
-pub const MIN_TICK: i64 = -108000000;
-pub const MAX_TICK: i64 = 182402823;
-pub const EXPONENT_AT_PRICE_ONE: i32 = -6;
-pub const GEOMETRIC_EXPONENT_INCREMENT_DISTANCE_IN_TICKS: i64 = 9_000_000;
+// Tick prices are split into geometric tiers, the price growing by a factor of TICK_BASE from one
+// tier to the next. TICK_BASE is always a power of ten, 10^TICK_BASE_DIGITS, so that every tick
+// price is exactly representable as a decimal. Within a tier, each tick adds
+// TICK_BASE^(EXPONENT_AT_PRICE_ONE + tier) to the price.
+//
+// The default base of 10 can be swapped for a base of 100 with the `tick-base-100` feature, which
+// keeps the same precision at price one while spanning the price range with fewer, wider tiers.
+#[cfg(not(feature = "tick-base-100"))]
+mod tick_base {
+    pub const TICK_BASE_DIGITS: u32 = 1;
+    pub const MIN_TICK: i64 = -108000000;
+    pub const MAX_TICK: i64 = 182402823;
+    pub const EXPONENT_AT_PRICE_ONE: i32 = -6;
+    pub const GEOMETRIC_EXPONENT_INCREMENT_DISTANCE_IN_TICKS: i64 = 9_000_000;
+}
+#[cfg(feature = "tick-base-100")]
+mod tick_base {
+    pub const TICK_BASE_DIGITS: u32 = 2;
+    pub const MIN_TICK: i64 = -594000000;
+    pub const MAX_TICK: i64 = 992402823;
+    pub const EXPONENT_AT_PRICE_ONE: i32 = -3;
+    pub const GEOMETRIC_EXPONENT_INCREMENT_DISTANCE_IN_TICKS: i64 = 99_000_000;
+}
+pub use tick_base::*;
+pub const TICK_BASE: i64 = 10i64.pow(TICK_BASE_DIGITS);
+
+// Each tier must hold exactly the number of ticks needed to step from TICK_BASE^n to TICK_BASE^(n+1)
+const _: () = assert!(
+    GEOMETRIC_EXPONENT_INCREMENT_DISTANCE_IN_TICKS
+        == (TICK_BASE - 1) * TICK_BASE.pow(EXPONENT_AT_PRICE_ONE.unsigned_abs())
+);
 // The swap fee expected by this contract
 pub const EXPECTED_SWAP_FEE: Decimal = Decimal::zero();
-pub const MAX_BATCH_CLAIM: u32 = 100;
+// Number of orders processed by a single batch claim unless configured otherwise by the admin
+pub const DEFAULT_MAX_BATCH_CLAIM: u32 = 100;
 pub const MAX_MAKER_FEE_PERCENTAGE: Decimal256 = Decimal256::percent(5);
+// Number of tick batching layers used to skip empty tick ranges during market orders.
+// Each layer groups `BATCHING_LAYER_WIDTH_FACTOR` buckets of the layer below it, with the
+// lowest layer grouping `BATCHING_LAYER_WIDTH_FACTOR` ticks.
+pub const BATCHING_LAYER_COUNT: u8 = 4;
+pub const BATCHING_LAYER_WIDTH_FACTOR: i64 = 16;
+// Maximum number of claim records kept per owner, older records are pruned as new ones are added
+pub const MAX_FILL_HISTORY: u64 = 100;
+// Minimum age in seconds of TWAP snapshots before they are pruned, bounding how far back TWAPs can be queried
+pub const TWAP_RETENTION_PERIOD: u64 = 48 * 60 * 60;
+// Maximum number of ticks a liquidity scan may inspect before giving up
+pub const MAX_TICK_SCAN_ITERATIONS: u32 = 1000;
+// Default upper bound for claim bounties, this can be updated by governance.
+// We set a conservative bound of 1% as a guardrail.
+pub const DEFAULT_MAX_CLAIM_BOUNTY: Decimal256 = Decimal256::percent(1);
+// Default maximum number of resting orders per tick and direction, this can be updated by governance
+pub const DEFAULT_MAX_ORDERS_PER_TICK: u64 = 10_000;
+// Default maximum number of ticks a single market order may fill against, this can be updated by governance
+pub const DEFAULT_MAX_TICKS_PER_ORDER: u32 = 10_000;
+// Number of resting orders counted by a single dust audit step unless a limit is provided
+pub const DEFAULT_DUST_AUDIT_LIMIT: u32 = 500;
+// Number of entries handled by a single derived state rebuild step unless a limit is provided
+pub const DEFAULT_REBUILD_LIMIT: u32 = 500;
+// Maximum length of the client order ID used to deduplicate limit order placements
+pub const MAX_CLIENT_ORDER_ID_LENGTH: usize = 64;
+// Maximum length in bytes of the memo attached to a limit order
+pub const MAX_MEMO_LENGTH: usize = 128;
 
 // Address controlled by Osmosis governance
 pub const OSMOSIS_GOV_ADDR: &str = "osmo10d07y265gmmuvt4z0w9aw880jnsr700jjeq4qp";
@@ -1,8 +1,8 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    ensure, to_json_binary, Binary, Decimal256, Deps, DepsMut, Env, MessageInfo, Reply, Response,
-    Uint128,
+    to_json_binary, Addr, Binary, Decimal256, Deps, DepsMut, Env, MessageInfo, Reply, Response,
+    SubMsgResult, Uint128,
 };
 use cw2::set_contract_version;
 
@@ -11,10 +11,14 @@ use crate::constants::{CIRCUIT_BREAKER_SUBDAO_ADDR, OSMOSIS_GOV_ADDR};
 use crate::error::{ContractError, ContractResult};
 use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
 
-use crate::orderbook::create_orderbook;
+use crate::orderbook::{create_orderbook, set_maker_fee, set_maker_fee_recipient};
+use crate::state::{ASSET_TYPES, MAKER_FEE_RECIPIENT};
 use crate::sudo;
-use crate::types::OrderDirection;
-use crate::{auth, order};
+use crate::types::{
+    OrderDirection, REPLY_ID_CLAIM, REPLY_ID_CLAIM_BOUNTY, REPLY_ID_MAKER_FEE,
+    REPLY_ID_PRUNE_REWARD, REPLY_ID_REFUND, REPLY_ID_SUDO_SWAP_EXACT_IN,
+};
+use crate::{auth, order, orderbook, tick};
 use crate::{query, state};
 
 // version info for migration info
@@ -24,7 +28,7 @@ const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 /// Handling contract instantiation
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
-    deps: DepsMut,
+    mut deps: DepsMut,
     _env: Env,
     _info: MessageInfo,
     msg: InstantiateMsg,
@@ -38,71 +42,221 @@ pub fn instantiate(
     let moderator = deps.api.addr_validate(CIRCUIT_BREAKER_SUBDAO_ADDR)?;
     MODERATOR.save(deps.storage, &moderator)?;
 
+    // Register any non-native denoms before the orderbook validates them
+    for (denom, asset_type) in [
+        (&msg.quote_denom, msg.quote_asset_type),
+        (&msg.base_denom, msg.base_asset_type),
+    ] {
+        if let Some(asset_type) = asset_type {
+            ASSET_TYPES.save(deps.storage, denom, &asset_type)?;
+        }
+    }
+
     // Instantiate orderbook
-    create_orderbook(deps, msg.quote_denom.clone(), msg.base_denom.clone())?;
+    create_orderbook(
+        deps.branch(),
+        msg.quote_denom.clone(),
+        msg.base_denom.clone(),
+        msg.min_tick,
+        msg.max_tick,
+    )?;
+
+    // Override the default maker fee config if provided
+    if let Some(maker_fee) = msg.maker_fee {
+        set_maker_fee(deps.storage, maker_fee)?;
+    }
+    if let Some(maker_fee_recipient) = msg.maker_fee_recipient {
+        set_maker_fee_recipient(deps.branch(), maker_fee_recipient.as_str())?;
+    }
 
     Ok(Response::new().add_attributes(vec![
         ("method", "instantiate"),
         ("quote_denom", &msg.quote_denom),
         ("base_denom", &msg.base_denom),
+        (
+            "maker_fee",
+            &state::get_maker_fee(deps.storage)?.to_string(),
+        ),
+        (
+            "maker_fee_recipient",
+            MAKER_FEE_RECIPIENT.load(deps.storage)?.as_str(),
+        ),
     ]))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
-    Ok(Response::default())
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    // Derived state added after the book was created is rebuilt from the ticks and orders, as
+    // resting liquidity placed before the upgrade is otherwise missing from it. The rebuild is
+    // advanced by `RebuildDerivedState` so that the upgrade itself does not walk the whole book
+    state::start_derived_state_rebuild(deps.storage)?;
+
+    Ok(Response::new().add_attribute("method", "migrate"))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
-    // Ensure orderbook is active
-    // Switch does not apply to Auth messages
-    if !matches!(msg, ExecuteMsg::Auth(_)) {
+    // Ensure orderbook is active and its derived state is rebuilt
+    // Switches do not apply to Auth messages or to the rebuild itself
+    if !matches!(
+        msg,
+        ExecuteMsg::Auth(_) | ExecuteMsg::RebuildDerivedState { .. }
+    ) {
         sudo::ensure_is_active(deps.as_ref())?;
+        state::ensure_derived_state_rebuilt(deps.storage)?;
     }
 
-    match msg {
+    // Only messages that can move book liquidity can change the mid price
+    let moves_book = matches!(
+        msg,
+        ExecuteMsg::PlaceLimit { .. }
+            | ExecuteMsg::PlacePeggedLimit { .. }
+            | ExecuteMsg::Reprice { .. }
+            | ExecuteMsg::CancelLimit { .. }
+            | ExecuteMsg::ClaimAndCancel { .. }
+            | ExecuteMsg::ReplaceOrder { .. }
+            | ExecuteMsg::ClaimLimit { .. }
+            | ExecuteMsg::BatchClaim { .. }
+            | ExecuteMsg::Receive(_)
+    );
+
+    let block_time = env.block.time;
+    let response = match msg {
         // Places limit order on given market
         ExecuteMsg::PlaceLimit {
             tick_id,
             order_direction,
             quantity,
             claim_bounty,
+            owner,
+            client_order_id,
+            memo,
+            auto_settle,
         } => dispatch_place_limit(
-            deps,
+            deps.branch(),
             env,
             info,
             tick_id,
             order_direction,
             quantity,
             claim_bounty,
+            owner,
+            client_order_id,
+            memo,
+            auto_settle,
         ),
 
+        // Places limit order pegged to one side of the book
+        ExecuteMsg::PlacePeggedLimit {
+            order_direction,
+            quantity,
+            peg,
+            claim_bounty,
+        } => order::place_pegged_limit(
+            deps.branch(),
+            env,
+            info,
+            order_direction,
+            quantity,
+            peg,
+            claim_bounty,
+        ),
+
+        // Moves pegged limit order to the tick its peg points to
+        ExecuteMsg::Reprice { tick_id, order_id } => {
+            order::reprice(deps.branch(), env, info, tick_id, order_id)
+        }
+
         // Cancels limit order with given ID
         ExecuteMsg::CancelLimit { tick_id, order_id } => {
-            order::cancel_limit(deps, env, info, tick_id, order_id)
+            order::cancel_limit(deps.branch(), env, info, tick_id, order_id)
+        }
+
+        // Claims the filled portion of a limit order and cancels the rest
+        ExecuteMsg::ClaimAndCancel { tick_id, order_id } => {
+            order::claim_and_cancel(deps.branch(), env, info, tick_id, order_id)
         }
 
+        // Cancels a limit order and places its replacement
+        ExecuteMsg::ReplaceOrder {
+            tick_id,
+            order_id,
+            new_tick_id,
+            new_quantity,
+        } => order::replace_order(
+            deps.branch(),
+            env,
+            info,
+            tick_id,
+            order_id,
+            new_tick_id,
+            new_quantity,
+        ),
+
         // Claims a limit order with given ID
-        ExecuteMsg::ClaimLimit { tick_id, order_id } => {
-            order::claim_limit(deps, env, info, tick_id, order_id)
+        ExecuteMsg::ClaimLimit {
+            tick_id,
+            order_id,
+            waive_bounty,
+        } => order::claim_limit(deps.branch(), env, info, tick_id, order_id, waive_bounty),
+
+        ExecuteMsg::BatchClaim {
+            orders,
+            waive_bounty,
+        } => order::batch_claim_limits(deps.branch(), info, env, orders, waive_bounty),
+
+        // Transfers ownership of a limit order with given ID
+        ExecuteMsg::TransferOrder {
+            tick_id,
+            order_id,
+            new_owner,
+        } => order::transfer_order(deps.branch(), info, tick_id, order_id, new_owner),
+
+        // Removes the state of an emptied tick, rewarding the caller
+        ExecuteMsg::PruneTick { tick_id, direction } => {
+            tick::prune_tick(deps.branch(), info, tick_id, direction)
         }
 
-        ExecuteMsg::BatchClaim { orders } => order::batch_claim_limits(deps, info, env, orders),
+        // Adds funds to the pool prune rewards are paid from
+        ExecuteMsg::FundPruneRewards {} => tick::fund_prune_rewards(deps.branch(), info),
+
+        // Donates unattributed balance to the maker fee recipient
+        ExecuteMsg::DonateDust { denom, limit } => {
+            sudo::donate_dust(deps.branch(), env, info, denom, limit)
+        }
+
+        // Advances the rebuild of derived state started by a migration
+        ExecuteMsg::RebuildDerivedState { limit } => {
+            orderbook::rebuild_derived_state(deps.branch(), info, limit)
+        }
 
         // Handles all authorisation messages
-        ExecuteMsg::Auth(auth_msg) => auth::dispatch(deps, info, auth_msg),
+        ExecuteMsg::Auth(auth_msg) => auth::dispatch(deps.branch(), info, auth_msg),
+
+        // Handles CW20 tokens sent to the orderbook
+        ExecuteMsg::Receive(receive_msg) => {
+            order::receive_cw20(deps.branch(), env, info, receive_msg)
+        }
+    }?;
+
+    // Record the mid price left by this message for the TWAP oracle
+    if moves_book {
+        state::update_twap_accumulator(deps.storage, block_time)?;
     }
+
+    Ok(response)
 }
 
 /// Handling contract query
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> ContractResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> ContractResult<Binary> {
     match msg {
         QueryMsg::SpotPrice {
             quote_asset_denom,
@@ -118,6 +272,7 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> ContractResult<Binary> {
             swap_fee,
         } => Ok(to_json_binary(&query::calc_out_amount_given_in(
             deps,
+            env,
             token_in,
             token_out_denom,
             swap_fee,
@@ -134,18 +289,23 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> ContractResult<Binary> {
             deps, start_from, end_at, limit,
         )?)?),
         QueryMsg::IsActive {} => Ok(to_json_binary(&query::is_active(deps)?)?),
+        QueryMsg::PausedOps {} => Ok(to_json_binary(&query::paused_ops(deps)?)?),
         QueryMsg::GetSwapFee {} => Ok(to_json_binary(&query::get_swap_fee()?)?),
         QueryMsg::OrdersByOwner {
             owner,
             start_from,
             end_at,
             limit,
+            sort_by,
         } => Ok(to_json_binary(&query::orders_by_owner(
-            deps, owner, start_from, end_at, limit,
+            deps, owner, start_from, end_at, limit, sort_by,
         )?)?),
         QueryMsg::TicksById { tick_ids } => {
             Ok(to_json_binary(&query::ticks_by_id(deps, tick_ids)?)?)
         }
+        QueryMsg::Order { tick_id, order_id } => {
+            Ok(to_json_binary(&query::order(deps, tick_id, order_id)?)?)
+        }
         QueryMsg::OrdersByTick {
             tick_id,
             start_from,
@@ -159,22 +319,136 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> ContractResult<Binary> {
         QueryMsg::GetUnrealizedCancels { tick_ids } => Ok(to_json_binary(
             &query::ticks_unrealized_cancels_by_id(deps, tick_ids)?,
         )?),
+        QueryMsg::DirectionalLiquidity { direction } => Ok(to_json_binary(
+            &query::directional_liquidity(deps, direction)?,
+        )?),
+        QueryMsg::BookState {} => Ok(to_json_binary(&query::book_state(deps)?)?),
+        QueryMsg::SpreadBps {} => Ok(to_json_binary(&query::spread_bps(deps)?)?),
+        QueryMsg::ArithmeticTwap {
+            start_time,
+            end_time,
+        } => Ok(to_json_binary(&query::arithmetic_twap(
+            deps, env, start_time, end_time,
+        )?)?),
+        QueryMsg::Solvency {} => Ok(to_json_binary(&query::solvency(deps)?)?),
+        QueryMsg::ExpectedReserves {} => Ok(to_json_binary(&query::expected_reserves(deps)?)?),
+        QueryMsg::ClosestTickWithLiquidity {
+            direction,
+            from_tick,
+        } => Ok(to_json_binary(&query::closest_tick_with_liquidity(
+            deps, direction, from_tick,
+        )?)?),
+        QueryMsg::VerifyTree { tick_id, direction } => Ok(to_json_binary(&query::verify_tree(
+            deps, tick_id, direction,
+        )?)?),
+        QueryMsg::PrefixSum {
+            tick_id,
+            direction,
+            etas,
+        } => Ok(to_json_binary(&query::prefix_sum(
+            deps, tick_id, direction, etas,
+        )?)?),
+        QueryMsg::RealizedCancels { tick_id, direction } => Ok(to_json_binary(
+            &query::realized_cancels(deps, tick_id, direction)?,
+        )?),
+        QueryMsg::PruneReward {} => Ok(to_json_binary(&query::prune_reward(deps)?)?),
+        QueryMsg::PlacementFee {} => Ok(to_json_binary(&state::get_placement_fee(deps.storage)?)?),
+        QueryMsg::MakerFeeDiscount {} => Ok(to_json_binary(&state::get_maker_fee_discount(
+            deps.storage,
+        )?)?),
+        QueryMsg::ActivationDelay {} => {
+            Ok(to_json_binary(&state::get_activation_delay(deps.storage)?)?)
+        }
+        QueryMsg::CrossingTickPolicy {} => Ok(to_json_binary(&state::get_crossing_tick_policy(
+            deps.storage,
+        )?)?),
+        QueryMsg::Volume {} => Ok(to_json_binary(&query::volume(deps)?)?),
+        QueryMsg::EstimateTicksTraversed {
+            quantity,
+            direction,
+            tick_bound,
+        } => Ok(to_json_binary(&query::estimate_ticks_traversed(
+            deps, env, quantity, direction, tick_bound,
+        )?)?),
+        QueryMsg::MarketFillReport {
+            quantity,
+            direction,
+            tick_bound,
+        } => Ok(to_json_binary(&query::market_fill_report(
+            deps, env, quantity, direction, tick_bound,
+        )?)?),
+        QueryMsg::SimulatePlaceLimit {
+            tick_id,
+            direction,
+            quantity,
+        } => Ok(to_json_binary(&query::simulate_place_limit(
+            deps, tick_id, direction, quantity,
+        )?)?),
+        QueryMsg::ExportBook { start_after, limit } => Ok(to_json_binary(&query::export_book(
+            deps,
+            start_after,
+            limit,
+        )?)?),
+        QueryMsg::ActiveTicks {
+            direction,
+            start_after,
+            limit,
+        } => Ok(to_json_binary(&query::active_ticks(
+            deps,
+            direction,
+            start_after,
+            limit,
+        )?)?),
+        QueryMsg::FillHistory {
+            owner,
+            start_after,
+            limit,
+        } => Ok(to_json_binary(&query::fill_history(
+            deps,
+            owner,
+            start_after,
+            limit,
+        )?)?),
+        QueryMsg::NextOrderId {} => Ok(to_json_binary(&query::next_order_id(deps)?)?),
+        QueryMsg::OrderCount { owner } => Ok(to_json_binary(&state::get_order_count(
+            deps.storage,
+            &owner,
+        )?)?),
 
         // -- Auth Queries --
         QueryMsg::Auth(msg) => Ok(to_json_binary(&auth::query(deps, msg)?)?),
     }
 }
 
+/// Handles replies from bank sub-messages dispatched by the contract.
+///
+/// All transfers are dispatched with `reply_on_error`, so a reply is only ever received
+/// for a failed send. Rather than swallowing the failure (which would leave the sent funds
+/// unaccounted for), the error is surfaced so that the entire transaction is rolled back.
+///
+/// Errors if:
+/// 1. The reply ID does not correspond to a known sub-message
+/// 2. The sub-message returned an error
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn reply(_deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
-    ensure!(
-        msg.result.is_ok(),
-        ContractError::ReplyError {
-            id: msg.id,
-            error: msg.result.unwrap_err(),
-        }
-    );
-    Ok(Response::default())
+    match msg.id {
+        REPLY_ID_REFUND
+        | REPLY_ID_CLAIM
+        | REPLY_ID_CLAIM_BOUNTY
+        | REPLY_ID_MAKER_FEE
+        | REPLY_ID_SUDO_SWAP_EXACT_IN
+        | REPLY_ID_PRUNE_REWARD => match msg.result {
+            SubMsgResult::Ok(_) => Ok(Response::default()),
+            SubMsgResult::Err(reason) => Err(ContractError::ReplyError {
+                reply_id: msg.id,
+                reason,
+            }),
+        },
+        _ => Err(ContractError::ReplyError {
+            reply_id: msg.id,
+            reason: "Unknown reply ID".to_string(),
+        }),
+    }
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -186,6 +460,10 @@ pub fn dispatch_place_limit(
     order_direction: OrderDirection,
     quantity: Uint128,
     claim_bounty: Option<Decimal256>,
+    owner: Option<Addr>,
+    client_order_id: Option<String>,
+    memo: Option<String>,
+    auto_settle: Option<Uint128>,
 ) -> Result<Response, ContractError> {
     order::place_limit(
         &mut deps,
@@ -195,5 +473,9 @@ pub fn dispatch_place_limit(
         order_direction,
         quantity,
         claim_bounty,
+        owner,
+        client_order_id,
+        memo,
+        auto_settle,
     )
 }
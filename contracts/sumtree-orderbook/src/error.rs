@@ -1,7 +1,9 @@
 use crate::constants::MAX_MAKER_FEE_PERCENTAGE;
+use crate::types::{OrderNotFoundReason, PegReference};
 use cosmwasm_std::{
     CheckedFromRatioError, CheckedMultiplyRatioError, CoinsError, ConversionOverflowError,
-    Decimal256, DecimalRangeExceeded, DivideByZeroError, OverflowError, StdError, Uint128,
+    Decimal256, DecimalRangeExceeded, DivideByZeroError, OverflowError, StdError, Timestamp,
+    Uint128, Uint256,
 };
 use cw_utils::PaymentError;
 use thiserror::Error;
@@ -42,12 +44,56 @@ pub enum ContractError {
     #[error("Invalid tick ID: {tick_id:?}")]
     InvalidTickId { tick_id: i64 },
 
+    #[error("Tick bound {tick_bound:?} is on the wrong side of the current tick {current_tick:?}")]
+    TickBoundCrossesCurrent { tick_bound: i64, current_tick: i64 },
+
+    #[error("Limit order on tick {tick_id:?} crosses the best opposite tick {best_opposite_tick:?}, use a market order instead")]
+    CrossingLimitOrder {
+        tick_id: i64,
+        best_opposite_tick: i64,
+    },
+
+    #[error("Tick scan exceeded the maximum of {limit:?} iterations")]
+    TickScanLimitExceeded { limit: u32 },
+
     #[error("Invalid quantity: {quantity:?}")]
     InvalidQuantity { quantity: Uint128 },
 
+    #[error("Wrong denom sent: expected {expected}, received {received}")]
+    WrongDenom { expected: String, received: String },
+
     #[error("Insufficient funds. Sent: {sent:?}, Required: {required:?}")]
     InsufficientFunds { sent: Uint128, required: Uint128 },
 
+    #[error("No liquidity to peg to on the {reference} side of the book")]
+    EmptyPegReference { reference: PegReference },
+
+    #[error("Peg places order outside of the book bounds: reference tick {reference_tick}, offset {offset_ticks}")]
+    PegOutOfBounds {
+        reference_tick: i64,
+        offset_ticks: i64,
+    },
+
+    #[error("Order is not pegged: tick {tick_id}, order {order_id}")]
+    OrderNotPegged { tick_id: i64, order_id: u64 },
+
+    #[error("Cannot reprice an order that has unclaimed fills")]
+    RepriceFilledOrder,
+
+    #[error("No surplus of {denom} to sweep. Balance: {balance}, Expected reserves: {expected}")]
+    NoSweepableSurplus {
+        denom: String,
+        balance: Uint256,
+        expected: Uint256,
+    },
+
+    #[error("Insufficient placement fee for {denom}. Sent: {sent:?}, Required: {required:?}")]
+    InsufficientPlacementFee {
+        denom: String,
+        sent: Uint128,
+        required: Uint128,
+    },
+
     #[error("Invalid pair: ({token_in_denom}, {token_out_denom})")]
     InvalidPair {
         token_in_denom: String,
@@ -60,19 +106,39 @@ pub enum ContractError {
     #[error("Invalid denom")]
     InvalidDenom { denom: String },
 
+    #[error("Invalid payment asset: {denom} must be paid with its own asset type")]
+    InvalidPaymentAsset { denom: String },
+
     #[error("Base and quote denoms cannot be the same")]
     DuplicateDenoms {},
 
-    #[error("Order not found: {tick_id:?}, {order_id:?}")]
-    OrderNotFound { tick_id: i64, order_id: u64 },
+    #[error("Order not found: {tick_id:?}, {order_id:?}, {reason:?}")]
+    OrderNotFound {
+        tick_id: i64,
+        order_id: u64,
+        // `None` if the order was removed before removals were recorded, or was placed on another tick
+        reason: Option<OrderNotFoundReason>,
+    },
 
-    #[error("Reply error: {id:?}, {error:?}")]
-    ReplyError { id: u64, error: String },
+    #[error("Reply error: {reply_id:?}, {reason:?}")]
+    ReplyError { reply_id: u64, reason: String },
 
     // Tick out of bounds error
     #[error("Tick out of bounds: {tick_id:?}")]
     TickOutOfBounds { tick_id: i64 },
 
+    #[error("Price underflow: price at tick {tick_id:?} is below representable precision")]
+    PriceUnderflow { tick_id: i64 },
+
+    #[error("Price overflow: price at tick {tick_id:?} exceeds the representable range")]
+    PriceOverflow { tick_id: i64 },
+
+    #[error("Market order consumed {input_consumed} input without producing any output")]
+    ZeroOutput { input_consumed: Uint128 },
+
+    #[error("Price impact of {impact} exceeds the maximum allowed price impact")]
+    PriceImpactTooHigh { impact: Decimal256 },
+
     #[error("Mismatched order direction")]
     MismatchedOrderDirection {},
 
@@ -82,6 +148,16 @@ pub enum ContractError {
     #[error("Childless Internal Node")]
     ChildlessInternalNode,
 
+    #[error("Tree corruption: node {node_id:?} on tick {tick_id:?} is part of a cycle")]
+    TreeCorruption { tick_id: i64, node_id: u64 },
+
+    #[error("Invalid tree state at node {node_id:?} on tick {tick_id:?}: {error}")]
+    InvalidTreeState {
+        tick_id: i64,
+        node_id: u64,
+        error: String,
+    },
+
     #[error("Cannot cancel an order that has partially or fully been filled")]
     CancelFilledOrder,
 
@@ -91,26 +167,38 @@ pub enum ContractError {
     #[error("Invalid prefix sum: {error:?}")]
     InvalidPrefixSum { error: Option<String> },
 
+    #[error("Client order ID must be between 1 and {max_length} characters long")]
+    InvalidClientOrderId { max_length: usize },
+
+    #[error("Memo must be at most {max_length} bytes long")]
+    MemoTooLong { max_length: usize },
+
     #[error("Zero Claim: Nothing to be claimed yet")]
     ZeroClaim,
 
+    #[error("Claim inconsistency: tick {tick_id} state is out of bounds for order {order_id}")]
+    ClaimInconsistency { tick_id: i64, order_id: u64 },
+
     #[error("Node insertion error")]
     NodeInsertionError,
 
     #[error("Orderbook ran out of liquidity during market order")]
     InsufficientLiquidity,
 
-    #[error("Claim bounty must be a value between 0 and 0.01 (1%). Received: {claim_bounty:?}")]
-    InvalidClaimBounty { claim_bounty: Option<Decimal256> },
-
     #[error(
-        "Exceeded the maximum number of claims in a batch. Maximum allowed: {max_batch_claim:?}"
+        "Claim bounty must be a value between 0 and the maximum claim bounty. Received: {claim_bounty:?}"
     )]
-    BatchClaimLimitExceeded { max_batch_claim: u32 },
+    InvalidClaimBounty { claim_bounty: Option<Decimal256> },
+
+    #[error("Claim bounty of {claim_bounty:?} exceeds the amount filled")]
+    ClaimBountyExceedsFill { claim_bounty: Decimal256 },
 
     #[error("Orderbook is inactive")]
     Inactive,
 
+    #[error("Orderbook has not been created")]
+    OrderbookNotFound,
+
     #[error("Max spot price exceeded")]
     MaxSpotPriceExceeded,
 
@@ -120,11 +208,83 @@ pub enum ContractError {
     #[error("Invalid Maker Fee Recipient")]
     InvalidMakerFeeRecipient,
 
+    #[error("Undeliverable recipient: {address:?}")]
+    UndeliverableRecipient { address: String },
+
     #[error("Invalid Maker Fee: provided fee must be less than or equal to {MAX_MAKER_FEE_PERCENTAGE:?}")]
     InvalidMakerFee,
 
+    #[error("Invalid maker fee discount: {discount:?} must not exceed one")]
+    InvalidMakerFeeDiscount { discount: Decimal256 },
+
+    #[error("Limit order placements are paused")]
+    PlacementsPaused,
+
+    #[error("Market orders are paused")]
+    MarketOrdersPaused,
+
+    #[error("Claims are paused")]
+    ClaimsPaused,
+
+    #[error("Tick order limit reached: tick {tick_id:?} already holds {limit:?} orders")]
+    TickOrderLimitReached { tick_id: i64, limit: u64 },
+
+    #[error("Invalid tick bounds: min tick {min_tick:?} is greater than max tick {max_tick:?}")]
+    InvalidTickBounds { min_tick: i64, max_tick: i64 },
+
+    #[error("Tick {tick_id:?} still holds liquidity or unclaimed fills")]
+    TickNotEmpty { tick_id: i64 },
+
+    #[error("Tick {tick_id:?} has nothing left to prune")]
+    TickAlreadyPruned { tick_id: i64 },
+
+    #[error("Invalid max orders per tick: must be greater than zero")]
+    InvalidMaxOrdersPerTick,
+
+    #[error("Invalid max ticks per order: must be greater than zero")]
+    InvalidMaxTicksPerOrder,
+
+    #[error("Market order would fill against more than the maximum of {limit:?} ticks")]
+    TooManyTicks { limit: u32 },
+
+    #[error("Market order visited tick {tick_id:?} out of order, filling it more than once")]
+    DuplicateTickFill { tick_id: i64 },
+
+    #[error("Market order too small: {quantity:?} is below the minimum of {min_quantity:?}")]
+    MarketOrderTooSmall {
+        quantity: Uint128,
+        min_quantity: Uint128,
+    },
+
+    #[error("Invalid max batch claim: must be greater than zero")]
+    InvalidMaxBatchClaim,
+
+    #[error("Invalid max claim bounty: must not exceed 1 (100%). Received: {max_claim_bounty:?}")]
+    InvalidMaxClaimBounty { max_claim_bounty: Decimal256 },
+
+    #[error("Invalid TWAP window: start time {start_time} must be before end time {end_time}")]
+    InvalidTwapWindow {
+        start_time: Timestamp,
+        end_time: Timestamp,
+    },
+
+    #[error("TWAP end time {end_time} is after the current block time {block_time}")]
+    TwapEndTimeInFuture {
+        end_time: Timestamp,
+        block_time: Timestamp,
+    },
+
+    #[error("No TWAP data is available at {time}")]
+    TwapUnavailable { time: Timestamp },
+
     #[error("Invalid sumtree: {error}")]
     InvalidSumtree { error: String },
+
+    #[error("Derived state is being rebuilt after a migration")]
+    RebuildInProgress,
+
+    #[error("Query is only available in debug builds")]
+    DebugQueryUnavailable,
 }
 
 pub type ContractResult<T> = Result<T, ContractError>;
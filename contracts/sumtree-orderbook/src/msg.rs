@@ -1,6 +1,9 @@
-use crate::types::{LimitOrder, OrderDirection, TickState};
+use crate::types::{
+    AssetType, Coin256, CrossingTickPolicy, FillRecord, LimitOrder, OrderDirection, PausedOps,
+    PegConfig, TickState,
+};
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Coin, Decimal, Decimal256, Uint128, Uint256};
+use cosmwasm_std::{Addr, Binary, Coin, Decimal, Decimal256, Timestamp, Uint128, Uint256};
 use osmosis_std::types::cosmos::base::v1beta1::Coin as ProtoCoin;
 
 /// Message type for `instantiate` entry_point
@@ -8,6 +11,18 @@ use osmosis_std::types::cosmos::base::v1beta1::Coin as ProtoCoin;
 pub struct InstantiateMsg {
     pub base_denom: String,
     pub quote_denom: String,
+    /// Maker fee applied to claims, defaults to `DEFAULT_MAKER_FEE` if not provided
+    pub maker_fee: Option<Decimal256>,
+    /// Recipient of the maker fee, defaults to `DEFAULT_MAKER_FEE_RECIPIENT` if not provided
+    pub maker_fee_recipient: Option<Addr>,
+    /// Lowest tick limit orders may be placed on, defaults to `MIN_TICK` if not provided
+    pub min_tick: Option<i64>,
+    /// Highest tick limit orders may be placed on, defaults to `MAX_TICK` if not provided
+    pub max_tick: Option<i64>,
+    /// Asset type of the base denom, defaults to `AssetType::Native` if not provided
+    pub base_asset_type: Option<AssetType>,
+    /// Asset type of the quote denom, defaults to `AssetType::Native` if not provided
+    pub quote_asset_type: Option<AssetType>,
 }
 
 /// Message type for `execute` entry_point
@@ -18,39 +33,210 @@ pub enum ExecuteMsg {
         order_direction: OrderDirection,
         quantity: Uint128,
         claim_bounty: Option<Decimal256>,
+        /// Places the order on behalf of the given address, defaults to the sender
+        owner: Option<Addr>,
+        /// Deduplicates placements per sender: while an order placed with this ID is resting,
+        /// repeating the placement returns that order and refunds the sent funds
+        client_order_id: Option<String>,
+        /// Free-form note stored with the order, at most `MAX_MEMO_LENGTH` bytes
+        #[serde(default)]
+        memo: Option<String>,
+        /// Opts the order into auto-settlement: market orders filling it send the output to the
+        /// owner as soon as at least this much is claimable, instead of it accruing until claimed
+        #[serde(default)]
+        auto_settle: Option<Uint128>,
+    },
+    /// Places a limit order at an offset from the best tick of one side of the book, funded by
+    /// the sender. The order can be kept at the offset with `Reprice`
+    PlacePeggedLimit {
+        order_direction: OrderDirection,
+        quantity: Uint128,
+        peg: PegConfig,
+        claim_bounty: Option<Decimal256>,
+    },
+    /// Moves a pegged order to the tick its peg currently points to, resetting its priority.
+    /// Callable by anyone
+    Reprice {
+        tick_id: i64,
+        order_id: u64,
     },
     CancelLimit {
         tick_id: i64,
         order_id: u64,
     },
+    /// Claims the filled portion of an order and cancels its unfilled remainder in one message.
+    /// Only callable by the order owner
+    ClaimAndCancel {
+        tick_id: i64,
+        order_id: u64,
+    },
+    /// Cancels an order and places a replacement on `new_tick_id` in one message, funding it with
+    /// the cancelled quantity. Only callable by the order owner
+    ReplaceOrder {
+        tick_id: i64,
+        order_id: u64,
+        new_tick_id: i64,
+        new_quantity: Uint128,
+    },
     ClaimLimit {
         tick_id: i64,
         order_id: u64,
+        /// Skips the claim bounty when the owner claims their own order
+        #[serde(default)]
+        waive_bounty: bool,
     },
     BatchClaim {
         orders: Vec<(i64, u64)>,
+        /// Skips the claim bounty on orders owned by the sender
+        #[serde(default)]
+        waive_bounty: bool,
+    },
+    TransferOrder {
+        tick_id: i64,
+        order_id: u64,
+        new_owner: Addr,
+    },
+    /// Removes the state of a tick side once all of its orders have been claimed or cancelled.
+    /// The prune reward is only paid for sides that were filled.
+    PruneTick {
+        tick_id: i64,
+        direction: OrderDirection,
+    },
+    /// Adds the sent funds to the pool prune rewards are paid from
+    FundPruneRewards {},
+    /// Sends the contract's balance of `denom` in excess of its expected reserves to the maker fee
+    /// recipient. Callable by anyone
+    ///
+    /// The expected reserves are counted up to `limit` orders per call, defaulting to
+    /// `DEFAULT_DUST_AUDIT_LIMIT`, and the surplus is only sent once every order has been counted
+    DonateDust {
+        denom: String,
+        #[serde(default)]
+        limit: Option<u32>,
+    },
+    /// Advances the rebuild of derived state started by a migration. Callable by anyone
+    ///
+    /// Each call takes up to `limit` steps, defaulting to `DEFAULT_REBUILD_LIMIT`. Other messages
+    /// are rejected until the rebuild completes
+    RebuildDerivedState {
+        #[serde(default)]
+        limit: Option<u32>,
     },
     Auth(AuthExecuteMsg),
+    /// Entry point for CW20 tokens sent to the orderbook via `Send`
+    Receive(Cw20ReceiveMsg),
+}
+
+/// Wire-compatible replication of `cw20::Cw20ReceiveMsg`
+#[cw_serde]
+pub struct Cw20ReceiveMsg {
+    pub sender: String,
+    pub amount: Uint128,
+    pub msg: Binary,
+}
+
+/// Messages that may be attached to a CW20 `Send` to the orderbook
+#[cw_serde]
+pub enum Cw20HookMsg {
+    /// Places a limit order funded by the sent tokens, the quantity is the amount sent
+    PlaceLimit {
+        tick_id: i64,
+        order_direction: OrderDirection,
+        claim_bounty: Option<Decimal256>,
+        /// Places the order on behalf of the given address, defaults to the CW20 sender
+        owner: Option<Addr>,
+        /// Deduplicates placements per CW20 sender, as for native placements
+        client_order_id: Option<String>,
+        /// Free-form note stored with the order, as for native placements
+        #[serde(default)]
+        memo: Option<String>,
+        /// Opts the order into auto-settlement, as for native placements
+        #[serde(default)]
+        auto_settle: Option<Uint128>,
+    },
 }
 
 #[cw_serde]
 pub enum AuthExecuteMsg {
     // -- Admin Messages --
-    TransferAdmin { new_admin: Addr },
+    TransferAdmin {
+        new_admin: Addr,
+    },
     CancelAdminTransfer {},
     RejectAdminTransfer {},
     ClaimAdmin {},
     RenounceAdminship {},
 
     // -- Moderator Messages --
-    OfferModerator { new_moderator: Addr },
+    OfferModerator {
+        new_moderator: Addr,
+    },
     RejectModeratorOffer {},
     ClaimModerator {},
 
     // -- Shared messages --
-    SetActive { active: bool },
-    SetMakerFee { fee: Decimal256 },
-    SetMakerFeeRecipient { recipient: Addr },
+    SetActive {
+        active: bool,
+    },
+    /// Sets the operations to be paused, mirroring `SudoMsg::SetPausedOps` for chains without governance
+    SetPausedOps {
+        paused_ops: PausedOps,
+    },
+    SetMakerFee {
+        fee: Decimal256,
+    },
+    SetMakerFeeRecipient {
+        recipient: Addr,
+    },
+    SetMaxOrdersPerTick {
+        max_orders_per_tick: u64,
+    },
+    /// Sets the maximum number of ticks a single market order may fill against before reverting
+    SetMaxTicksPerOrder {
+        max_ticks_per_order: u32,
+    },
+    /// Sets the maximum number of orders processed by a single batch claim
+    SetMaxBatchClaim {
+        max_batch_claim: u32,
+    },
+    /// Sets the reward paid for pruning a tick, `None` disables rewards
+    SetPruneReward {
+        reward: Option<Coin>,
+    },
+    /// Sets the maximum fraction a market order may move the price by, `None` removes the bound
+    SetMaxPriceImpact {
+        max_price_impact: Option<Decimal256>,
+    },
+    /// Sets the upper bound for claim bounties, mirroring `SudoMsg::SetMaxClaimBounty` for chains without governance
+    SetMaxClaimBounty {
+        max_claim_bounty: Decimal256,
+    },
+    /// Sets the maker fee amount below which fees are waived to the claimer rather than transferred
+    SetMinFeeTransfer {
+        min_fee_transfer: Uint128,
+    },
+    /// Sets the smallest input accepted for a market order, zero accepting any non-zero input
+    SetMinMarketOrderSize {
+        min_market_order_size: Uint128,
+    },
+    /// Sets the flat fee charged for placing a limit order, `None` makes placements free
+    SetPlacementFee {
+        fee: Option<Coin>,
+    },
+    /// Sets the maker fee discount for orders that rest for at least `min_blocks` before being
+    /// claimed, `None` removes the discount
+    SetMakerFeeDiscount {
+        discount: Option<crate::types::MakerFeeDiscount>,
+    },
+    /// Sets the number of blocks after placement before a limit order can be filled, zero making
+    /// orders fillable immediately
+    SetActivationDelay {
+        activation_delay: u64,
+    },
+    /// Sets how limit orders placed exactly on the best opposite tick are handled
+    SetCrossingTickPolicy {
+        policy: CrossingTickPolicy,
+    },
 }
 
 /// Message type for `migrate` entry_point
@@ -109,6 +295,9 @@ pub enum QueryMsg {
     #[returns(bool)]
     IsActive {},
 
+    #[returns(crate::types::PausedOps)]
+    PausedOps {},
+
     #[returns(Vec<crate::types::LimitOrder>)]
     OrdersByOwner {
         // The address of the order maker
@@ -119,8 +308,14 @@ pub enum QueryMsg {
         end_at: Option<(i64, u64)>,
         // Defaults to 100
         limit: Option<u64>,
+        // Sorts the returned page, defaults to index order
+        #[serde(default)]
+        sort_by: Option<crate::types::OrderSort>,
     },
 
+    #[returns(Option<OrderResponse>)]
+    Order { tick_id: i64, order_id: u64 },
+
     #[returns(OrdersResponse)]
     OrdersByTick {
         tick_id: i64,
@@ -137,6 +332,162 @@ pub enum QueryMsg {
 
     #[returns(GetUnrealizedCancelsResponse)]
     GetUnrealizedCancels { tick_ids: Vec<i64> },
+
+    #[returns(Decimal256)]
+    DirectionalLiquidity { direction: OrderDirection },
+
+    #[returns(BookStateResponse)]
+    BookState {},
+
+    /// Spread between the next ask and next bid ticks in basis points of their mid price, rounded
+    /// down. `None` if either side of the book is empty.
+    #[returns(Option<u64>)]
+    SpreadBps {},
+
+    /// Time-weighted average of the mid price between the next bid and next ask ticks over
+    /// `[start_time, end_time]`. `end_time` defaults to the current block time.
+    #[returns(ArithmeticTwapResponse)]
+    ArithmeticTwap {
+        start_time: Timestamp,
+        end_time: Option<Timestamp>,
+    },
+
+    #[returns(SolvencyResponse)]
+    Solvency {},
+
+    /// Returns the balance the contract should hold in each denom to cover every outstanding
+    /// obligation, including funds set aside for prune rewards. A bank balance below these
+    /// amounts indicates a shortfall.
+    #[returns(Vec<Coin256>)]
+    ExpectedReserves {},
+
+    #[returns(Option<i64>)]
+    ClosestTickWithLiquidity {
+        // The side of the book to find liquidity for
+        direction: OrderDirection,
+        // The tick to start scanning from (inclusive), in the direction that side is filled
+        from_tick: i64,
+    },
+
+    /// Verifies the integrity of the sumtree for a tick, erroring if it is corrupted.
+    /// Only served by debug builds, release builds always return `DebugQueryUnavailable`.
+    #[returns(bool)]
+    VerifyTree {
+        tick_id: i64,
+        direction: OrderDirection,
+    },
+
+    /// Cumulative cancelled quantity on a tick's sumtree that is realized at the given ETAS cutoff.
+    ///
+    /// A cancelled order counts towards the sum once the cutoff reaches the order's `etas`, i.e. once
+    /// every order placed ahead of it has been filled or cancelled. Cancels that end up contiguous
+    /// with those already realized on the tick are also counted, as they would be by a tick sync.
+    /// Returns zero if the tick has no cancellations.
+    #[returns(Decimal256)]
+    PrefixSum {
+        tick_id: i64,
+        direction: OrderDirection,
+        etas: Decimal256,
+    },
+
+    /// Cumulative cancelled quantity realized on a tick's sumtree at the tick's current ETAS, i.e.
+    /// the `cumulative_realized_cancels` the tick would hold after a sync. Cancelled orders only
+    /// count once every order placed ahead of them has been filled or cancelled.
+    ///
+    /// Returns zero if the tick has no cancellations.
+    #[returns(Decimal256)]
+    RealizedCancels {
+        tick_id: i64,
+        direction: OrderDirection,
+    },
+
+    #[returns(PruneRewardResponse)]
+    PruneReward {},
+
+    /// Flat fee charged for placing a limit order, if any
+    #[returns(Option<Coin>)]
+    PlacementFee {},
+
+    /// Maker fee discount for long resting orders, if any
+    #[returns(Option<crate::types::MakerFeeDiscount>)]
+    MakerFeeDiscount {},
+
+    /// Number of blocks after placement before a limit order can be filled
+    #[returns(u64)]
+    ActivationDelay {},
+
+    /// How limit orders placed exactly on the best opposite tick are handled
+    #[returns(CrossingTickPolicy)]
+    CrossingTickPolicy {},
+
+    /// Cumulative output filled by market orders in each of the orderbook's denoms
+    #[returns(VolumeResponse)]
+    Volume {},
+
+    /// Simulates a market order to estimate how much work it would perform, as gas roughly scales
+    /// with the number of ticks traversed. `tick_bound` defaults to the global tick bound.
+    #[returns(EstimateTicksTraversedResponse)]
+    EstimateTicksTraversed {
+        quantity: Uint128,
+        direction: OrderDirection,
+        tick_bound: Option<i64>,
+    },
+
+    /// Simulates a market order, breaking the fill down by the ticks it would fill against in the
+    /// order they would be filled. `tick_bound` defaults to the global tick bound.
+    #[returns(MarketFillReportResponse)]
+    MarketFillReport {
+        quantity: Uint128,
+        direction: OrderDirection,
+        tick_bound: Option<i64>,
+    },
+
+    /// Dry run of a limit order placement, reporting the funds it requires and the order ID it
+    /// would be assigned without placing it. Orders that would cross the book are reported rather
+    /// than rejected, as they would be rejected by the placement itself.
+    #[returns(SimulatePlaceLimitResponse)]
+    SimulatePlaceLimit {
+        tick_id: i64,
+        direction: OrderDirection,
+        quantity: Uint128,
+    },
+
+    /// Every resting order in ascending `(tick_id, order_id)` order, for off-chain reconstruction
+    /// of the book alongside the tick state queries
+    #[returns(ExportBookResponse)]
+    ExportBook {
+        // For indexed based pagination (tick_id, order_id), exclusive
+        start_after: Option<(i64, u64)>,
+        limit: u32,
+    },
+
+    /// Ticks with remaining liquidity for the given direction, in ascending tick order
+    #[returns(ActiveTicksResponse)]
+    ActiveTicks {
+        direction: OrderDirection,
+        // Tick id to start after for pagination, exclusive
+        start_after: Option<i64>,
+        // Defaults to 100
+        limit: Option<u32>,
+    },
+
+    /// ID the next placed limit order will be assigned. IDs are drawn from a single counter shared
+    /// by every tick, so the ID does not depend on where the order is placed.
+    #[returns(u64)]
+    NextOrderId {},
+
+    /// Number of orders held by `owner`, including filled orders that are yet to be claimed
+    #[returns(u32)]
+    OrderCount { owner: Addr },
+
+    #[returns(FillHistoryResponse)]
+    FillHistory {
+        owner: Addr,
+        // Sequence number to start after for pagination, exclusive
+        start_after: Option<u64>,
+        // Defaults to 100
+        limit: Option<u64>,
+    },
 }
 
 #[cw_serde]
@@ -212,6 +563,121 @@ pub struct OrdersResponse {
     pub count: u64,
 }
 
+#[cw_serde]
+pub struct BookStateResponse {
+    pub bid_liquidity: Decimal256,
+    pub ask_liquidity: Decimal256,
+    pub next_bid_tick: i64,
+    pub next_ask_tick: i64,
+    // Only set when both sides of the book have liquidity
+    pub spread: Option<Decimal256>,
+}
+
+#[cw_serde]
+pub struct ArithmeticTwapResponse {
+    pub arithmetic_twap: Decimal256,
+}
+
+#[cw_serde]
+pub struct DenomObligation {
+    pub denom: String,
+    // The direction of orders placed in this denom
+    pub direction: OrderDirection,
+    // Total of refunds for unfilled orders in this denom plus claims owed in this denom
+    pub owed: Uint256,
+}
+
+#[cw_serde]
+pub struct SolvencyResponse {
+    pub obligations: Vec<DenomObligation>,
+}
+
+#[cw_serde]
+pub struct OrderResponse {
+    pub order: LimitOrder,
+    // Output amount the filled portion of the order is worth, before claim bounty and maker fee
+    pub claimable: Uint256,
+    // Portion of the order's remaining quantity that has been filled
+    pub fill_percentage: Decimal256,
+}
+
+#[cw_serde]
+pub struct PruneRewardResponse {
+    // The reward paid per pruned tick, if any
+    pub reward: Option<Coin>,
+    // Funds remaining in the pool for the reward denom
+    pub pool: Uint128,
+}
+
+#[cw_serde]
+pub struct VolumeResponse {
+    pub quote_volume: Coin256,
+    pub base_volume: Coin256,
+}
+
+#[cw_serde]
+pub struct EstimateTicksTraversedResponse {
+    pub ticks_traversed: u32,
+    // Resting orders that would receive part or all of the fill
+    pub orders_consumed: u64,
+}
+
+#[cw_serde]
+pub struct TickFillReport {
+    pub tick_id: i64,
+    pub price: Decimal256,
+    // Input of the market order used to fill against the tick
+    pub input_consumed: Uint256,
+    // Output paid out from the tick's liquidity
+    pub output_produced: Uint256,
+}
+
+#[cw_serde]
+pub struct MarketFillReportResponse {
+    pub fills: Vec<TickFillReport>,
+    // Sums of the per tick figures, excluding any dust input consumed without output
+    pub total_input: Uint256,
+    pub total_output: Uint256,
+}
+
+#[cw_serde]
+pub struct SimulatePlaceLimitResponse {
+    // Funds committed to the order, in the input denom of its direction
+    pub required_funds: Coin,
+    // Flat placement fee that must be sent on top of the order funds, if any
+    pub placement_fee: Option<Coin>,
+    // Whether the order would cross the book, which rejects the placement
+    pub would_cross: bool,
+    pub assigned_order_id: u64,
+}
+
+#[cw_serde]
+pub struct ExportBookResponse {
+    pub orders: Vec<LimitOrder>,
+    // Cursor for the next page, `None` if there are no further orders
+    pub next_start_after: Option<(i64, u64)>,
+}
+
+#[cw_serde]
+pub struct ActiveTick {
+    pub tick_id: i64,
+    pub price: Decimal256,
+    // Liquidity remaining on the tick, denominated in the direction's input denom
+    pub remaining: Decimal256,
+}
+
+#[cw_serde]
+pub struct ActiveTicksResponse {
+    pub ticks: Vec<ActiveTick>,
+    // Cursor for the next page, the last tick inspected, `None` if there are no further ticks
+    pub next_start_after: Option<i64>,
+}
+
+#[cw_serde]
+pub struct FillHistoryResponse {
+    pub fills: Vec<FillRecord>,
+}
+
 #[cw_serde]
 pub enum SudoMsg {
     /// SwapExactAmountIn swaps an exact amount of tokens in for as many tokens out as possible.
@@ -224,6 +690,14 @@ pub enum SudoMsg {
         token_out_denom: String,
         token_out_min_amount: Uint128,
         swap_fee: Decimal,
+        /// Receives the swap output, defaults to the sender. Refunds are always sent to the sender.
+        #[serde(default)]
+        recipient: Option<String>,
+        /// Stops the swap more than this many ticks past the best price at execution, refunding
+        /// any unfilled input. Unlike an absolute bound, this tracks the book if the price moves
+        /// between quoting and execution.
+        #[serde(default)]
+        max_slippage_ticks: Option<u32>,
     },
     // SwapToTick functions exactly as SwapExactAmountIn, but it terminates the swap when the target tick
     // is reached.
@@ -234,6 +708,26 @@ pub enum SudoMsg {
         token_out_min_amount: Uint128,
         swap_fee: Decimal,
         target_tick: i64,
+        #[serde(default)]
+        recipient: Option<String>,
+        // Applied alongside `target_tick`, the swap stops at whichever bound is reached first
+        #[serde(default)]
+        max_slippage_ticks: Option<u32>,
+    },
+    // SwapExactAmountInWithRoute functions as SwapExactAmountIn, but splits the input across tranches
+    // of (price_limit, max_quantity) that are filled in order. Input left after all tranches is refunded.
+    SwapExactAmountInWithRoute {
+        sender: String,
+        token_in: Coin,
+        token_out_denom: String,
+        token_out_min_amount: Uint128,
+        swap_fee: Decimal,
+        tranches: Vec<(Decimal256, Uint128)>,
+        #[serde(default)]
+        recipient: Option<String>,
+        // Applied alongside each tranche's price limit, measured from the best price before the first tranche
+        #[serde(default)]
+        max_slippage_ticks: Option<u32>,
     },
     /// SwapExactAmountOut swaps as many tokens in as possible for an exact amount of tokens out.
     /// The amount of tokens in is determined by the current exchange rate and the swap fee.
@@ -257,12 +751,62 @@ pub enum SudoMsg {
     SetActive {
         active: bool,
     },
+
+    // -- Pause Switch
+    /// Sets the operations to be paused, cancellations always remain available
+    SetPausedOps {
+        paused_ops: PausedOps,
+    },
+
+    /// Sets the upper bound for claim bounties on newly placed limit orders
+    SetMaxClaimBounty {
+        max_claim_bounty: Decimal256,
+    },
+
+    /// Sends the contract's balance of `denom` in excess of its expected reserves to `recipient`
+    ///
+    /// The expected reserves are counted up to `limit` orders per call, defaulting to
+    /// `DEFAULT_DUST_AUDIT_LIMIT`, and the surplus is only sent once every order has been counted
+    SweepDust {
+        denom: String,
+        recipient: String,
+        #[serde(default)]
+        limit: Option<u32>,
+    },
+}
+
+#[cw_serde]
+/// Identifies the limit order created by a `PlaceLimit` message
+pub struct PlaceLimitResponseData {
+    pub tick_id: i64,
+    pub order_id: u64,
+}
+
+#[cw_serde]
+/// Lists the orders a `BatchClaim` message did not process because it exceeded the batch limit,
+/// in the order they were provided so they can be resubmitted
+pub struct BatchClaimResponseData {
+    pub unprocessed: Vec<(i64, u64)>,
 }
 
 #[cw_serde]
 /// Fixing token in amount makes token amount out varies
 pub struct SwapExactAmountInResponseData {
     pub token_out_amount: Uint256,
+    pub swap_result: SwapResult,
+}
+
+/// Breakdown of a swap's fill, returned in the swap response data so that contracts composing
+/// with the orderbook can read it from their reply handler.
+#[cw_serde]
+pub struct SwapResult {
+    /// The input consumed by the swap, excluding any refunded remainder
+    pub token_in: Coin,
+    pub token_out: Coin256,
+    /// Volume weighted average price of the fill, oriented the same way as tick prices.
+    /// `None` if nothing was filled
+    pub avg_price: Option<Decimal256>,
+    pub ticks_traversed: u32,
 }
 
 #[cw_serde]
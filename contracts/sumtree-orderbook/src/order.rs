@@ -1,24 +1,71 @@
-use crate::constants::{MAX_BATCH_CLAIM, MAX_TICK, MIN_TICK};
+use crate::constants::{
+    BATCHING_LAYER_COUNT, MAX_CLIENT_ORDER_ID_LENGTH, MAX_MEMO_LENGTH, MAX_TICK, MIN_TICK,
+};
 use crate::error::{ContractError, ContractResult};
+use crate::msg::{
+    BatchClaimResponseData, Cw20HookMsg, Cw20ReceiveMsg, PlaceLimitResponseData, TickFillReport,
+};
 use crate::state::{
-    add_directional_liquidity, get_maker_fee, new_order_id, orders, subtract_directional_liquidity,
-    MAKER_FEE_RECIPIENT, ORDERBOOK, TICK_STATE,
+    add_directional_liquidity, add_layer_liquidity, add_queued_liquidity, add_volume,
+    decrement_order_count, decrement_tick_order_count, get_activation_delay, get_asset_type,
+    get_crossing_tick_policy, get_layer_buckets, get_liquidity_queued_after, get_maker_fee,
+    get_maker_fee_discount, get_max_batch_claim, get_max_claim_bounty, get_max_price_impact,
+    get_max_ticks_per_order, get_min_fee_transfer, get_min_market_order_size,
+    get_next_tick_with_liquidity, get_orderbook, get_placement_fee, increment_order_count,
+    increment_tick_order_count, layer_bucket_range, load_order, new_order_id, orders, record_fill,
+    remove_client_order_id, reset_dust_audit, save_client_order_id, save_orderbook,
+    subtract_directional_liquidity, subtract_layer_liquidity, subtract_queued_liquidity,
+    AUTO_SETTLE_ORDERS, CLIENT_ORDER_IDS, MAKER_FEE_RECIPIENT, ORDER_CLIENT_IDS, REMOVED_ORDERS,
+    TICK_STATE,
+};
+use crate::sudo::{
+    ensure_claims_not_paused, ensure_market_orders_not_paused, ensure_placements_not_paused,
 };
 use crate::sumtree::node::{generate_node_id, NodeType, TreeNode};
 use crate::sumtree::tree::get_or_init_root_node;
 use crate::tick::sync_tick;
-use crate::tick_math::{amount_to_value, tick_to_price, RoundingDirection};
+use crate::tick_math::{
+    amount_to_value, input_for_output, multiply_by_ratio, output_for_input, tick_to_price,
+    RoundingMode,
+};
 use crate::types::{
-    coin_u256, Coin256, LimitOrder, MarketOrder, MsgSend256, OrderDirection, Orderbook, TickState,
+    coin_u256, AssetType, Coin256, CrossingTickPolicy, Cw20ExecuteMsg, LimitOrder, MarketOrder,
+    MsgSend256, OrderDirection, OrderNotFoundReason, Orderbook, PegConfig, TickState, TickValues,
     REPLY_ID_CLAIM, REPLY_ID_CLAIM_BOUNTY, REPLY_ID_MAKER_FEE, REPLY_ID_REFUND,
 };
 use cosmwasm_std::{
-    coin, ensure, ensure_eq, Addr, BankMsg, Decimal256, DepsMut, Env, Event, MessageInfo, Order,
-    Response, Storage, SubMsg, Uint128, Uint256,
+    coin, ensure, ensure_eq, from_json, to_json_binary, Addr, Api, BankMsg, Coin, CosmosMsg,
+    Decimal256, DepsMut, DivideByZeroError, Env, Event, MessageInfo, Order, Response, StdResult,
+    Storage, SubMsg, Uint128, Uint256, WasmMsg,
 };
 use cw_storage_plus::Bound;
 use cw_utils::{must_pay, nonpayable};
 
+/// Places a limit order on the given tick, funded by the message sender.
+///
+/// If an `owner` is provided the order is placed on their behalf: the sender pays for the order,
+/// but the owner receives the claim/cancel rights along with any claimed or refunded funds.
+/// Otherwise the sender owns the order.
+///
+/// If a `client_order_id` is provided and the sender already has a resting order placed with it,
+/// no new order is placed: the existing order is returned and the sent funds are refunded.
+///
+/// An optional `memo` of at most `MAX_MEMO_LENGTH` bytes is stored with the order and returned by
+/// order queries. It has no effect on matching.
+///
+/// If `auto_settle` is provided, swaps that fill the order send its output to the owner once at
+/// least that much is claimable, see `settle_auto_orders`. Otherwise fills accrue until claimed.
+///
+/// If a placement fee is configured it must be sent in addition to the order funds, and is forwarded
+/// to the maker fee recipient. Placements without a sufficient fee error with
+/// `InsufficientPlacementFee`.
+///
+/// The order must be funded in the input denom of its direction. Sending only a different denom,
+/// such as the quote denom for an ask, errors with `WrongDenom`.
+///
+/// Orders that would be immediately fillable are rejected rather than converted to market orders:
+/// a bid above the best ask or an ask below the best bid errors with `CrossingLimitOrder`. Orders on
+/// the same tick as the best opposite liquidity are accepted.
 #[allow(clippy::manual_range_contains, clippy::too_many_arguments)]
 pub fn place_limit(
     deps: &mut DepsMut,
@@ -28,39 +75,215 @@ pub fn place_limit(
     order_direction: OrderDirection,
     quantity: Uint128,
     claim_bounty: Option<Decimal256>,
+    owner: Option<Addr>,
+    client_order_id: Option<String>,
+    memo: Option<String>,
+    auto_settle: Option<Uint128>,
 ) -> Result<Response, ContractError> {
-    let mut orderbook = ORDERBOOK.load(deps.storage)?;
+    place_limit_internal(
+        deps,
+        env,
+        info,
+        tick_id,
+        order_direction,
+        quantity,
+        claim_bounty,
+        owner,
+        client_order_id,
+        memo,
+        auto_settle,
+        None,
+        None,
+    )
+}
+
+/// Handles CW20 tokens sent to the orderbook through the token contract's `Send` message.
+///
+/// The token contract is the message sender, while the account that sent the tokens is
+/// carried in the `Cw20ReceiveMsg`. That account is treated as the sender of the attached
+/// hook message, which is funded by the sent tokens.
+///
+/// Errors if:
+/// 1. Native funds are sent with the message
+/// 2. The hook message cannot be parsed
+/// 3. The sending token contract is not the expected denom for the order
+pub fn receive_cw20(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    receive_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
 
-    // Validate tick_id is within valid range
+    let Cw20ReceiveMsg {
+        sender,
+        amount,
+        msg,
+    } = receive_msg;
+    let token_info = MessageInfo {
+        sender: deps.api.addr_validate(&sender)?,
+        funds: vec![],
+    };
+    let received = Coin {
+        denom: info.sender.to_string(),
+        amount,
+    };
+
+    match from_json(&msg)? {
+        Cw20HookMsg::PlaceLimit {
+            tick_id,
+            order_direction,
+            claim_bounty,
+            owner,
+            client_order_id,
+            memo,
+            auto_settle,
+        } => place_limit_internal(
+            &mut deps,
+            env,
+            token_info,
+            tick_id,
+            order_direction,
+            amount,
+            claim_bounty,
+            owner,
+            client_order_id,
+            memo,
+            auto_settle,
+            Some(received),
+            None,
+        ),
+    }
+}
+
+/// Places a limit order funded either by the native funds attached to `info` or, if provided,
+/// by the CW20 tokens received through `receive_cw20`.
+#[allow(clippy::manual_range_contains, clippy::too_many_arguments)]
+fn place_limit_internal(
+    deps: &mut DepsMut,
+    env: Env,
+    info: MessageInfo,
+    tick_id: i64,
+    order_direction: OrderDirection,
+    quantity: Uint128,
+    claim_bounty: Option<Decimal256>,
+    owner: Option<Addr>,
+    client_order_id: Option<String>,
+    memo: Option<String>,
+    auto_settle: Option<Uint128>,
+    cw20_funds: Option<Coin>,
+    peg: Option<PegConfig>,
+) -> Result<Response, ContractError> {
+    // Ensure limit order placements are not paused
+    ensure_placements_not_paused(deps.storage)?;
+
+    if let Some(memo) = &memo {
+        ensure!(
+            memo.len() <= MAX_MEMO_LENGTH,
+            ContractError::MemoTooLong {
+                max_length: MAX_MEMO_LENGTH
+            }
+        );
+    }
+
+    // Repeated placements of a resting order are answered with the existing order
+    if let Some(client_order_id) = &client_order_id {
+        ensure!(
+            !client_order_id.is_empty() && client_order_id.len() <= MAX_CLIENT_ORDER_ID_LENGTH,
+            ContractError::InvalidClientOrderId {
+                max_length: MAX_CLIENT_ORDER_ID_LENGTH
+            }
+        );
+        if let Some((existing_tick_id, existing_order_id)) =
+            CLIENT_ORDER_IDS.may_load(deps.storage, (&info.sender, client_order_id))?
+        {
+            return duplicate_placement_response(
+                deps.storage,
+                info,
+                client_order_id,
+                existing_tick_id,
+                existing_order_id,
+                cw20_funds,
+            );
+        }
+    }
+
+    let orderbook = get_orderbook(deps.storage)?;
+
+    // Validate tick_id is within the orderbook's valid range
     ensure!(
-        tick_id >= MIN_TICK && tick_id <= MAX_TICK,
+        orderbook.is_tick_in_bounds(tick_id),
         ContractError::InvalidTickId { tick_id }
     );
 
+    // Ensure the order does not cross the opposite side of the book
+    ensure_not_crossing(deps.storage, &orderbook, tick_id, order_direction)?;
+
+    // Determine the correct denom based on order direction
+    let expected_denom = orderbook.input_denom(&order_direction);
+
+    // Take the placement fee, if any, out of the funds sent with the order
+    let mut info = info;
+    let mut cw20_funds = cw20_funds;
+    let mut quantity = quantity;
+    let placement_fee = match cw20_funds.as_mut() {
+        // CW20 orders are funded by all tokens sent, so the fee is deducted from the order quantity
+        Some(funds) => {
+            let reserved = coin(0, funds.denom.clone());
+            let fee = deduct_placement_fee(deps.storage, std::slice::from_mut(funds), &reserved)?;
+            quantity = funds.amount;
+            fee
+        }
+        None => {
+            let reserved = coin(quantity.u128(), expected_denom.clone());
+            let fee = deduct_placement_fee(deps.storage, &mut info.funds, &reserved)?;
+            info.funds.retain(|funds| !funds.amount.is_zero());
+            fee
+        }
+    };
+
     // Ensure order_quantity is positive
     ensure!(
         quantity > Uint128::zero(),
         ContractError::InvalidQuantity { quantity }
     );
 
-    // If applicable, ensure claim_bounty is between 0 and 0.01.
-    // We set a conservative upper bound of 1% for claim bounties as a guardrail.
+    // If applicable, ensure claim_bounty is between 0 and the configured maximum (1% by default).
     if let Some(claim_bounty_value) = claim_bounty {
         ensure!(
             claim_bounty_value >= Decimal256::zero()
-                && claim_bounty_value <= Decimal256::percent(1),
+                && claim_bounty_value <= get_max_claim_bounty(deps.storage)?,
             ContractError::InvalidClaimBounty {
                 claim_bounty: Some(claim_bounty_value)
             }
         );
     }
 
-    // Determine the correct denom based on order direction
-    let expected_denom = orderbook.get_expected_denom(&order_direction);
-
     // Verify the funds sent with the message match the `quantity` for the correct denom
     // We reject any quantity that is not exactly equal to the amount in the limit order being placed
-    let received = must_pay(&info, &expected_denom)?;
+    // Native denoms must be attached to the message while CW20 denoms must arrive through the hook
+    let received = match (get_asset_type(deps.storage, &expected_denom)?, cw20_funds) {
+        (AssetType::Native, None) => {
+            // A single coin of another denom is most likely the other side of the pair, which
+            // `must_pay` would only report as a missing denom
+            if let [funds] = info.funds.as_slice() {
+                ensure!(
+                    funds.denom == expected_denom,
+                    ContractError::WrongDenom {
+                        expected: expected_denom,
+                        received: funds.denom.clone(),
+                    }
+                );
+            }
+            must_pay(&info, &expected_denom)?
+        }
+        (AssetType::Cw20, Some(funds)) if funds.denom == expected_denom => funds.amount,
+        _ => {
+            return Err(ContractError::InvalidPaymentAsset {
+                denom: expected_denom,
+            })
+        }
+    };
     ensure_eq!(
         received,
         quantity,
@@ -70,61 +293,63 @@ pub fn place_limit(
         }
     );
 
-    // Generate a new order ID
-    let order_id = new_order_id(deps.storage)?;
+    // Track the order against the tick's order limit, erroring if the tick is full
+    increment_tick_order_count(deps.storage, tick_id, order_direction)?;
 
-    // If bid and tick_id is higher than next bid tick, update next bid tick
-    // If ask and tick_id is lower than next ask tick, update next ask tick
-    match order_direction {
-        OrderDirection::Bid => {
-            if tick_id > orderbook.next_bid_tick {
-                orderbook.next_bid_tick = tick_id;
-            }
-        }
-        OrderDirection::Ask => {
-            if tick_id < orderbook.next_ask_tick {
-                orderbook.next_ask_tick = tick_id;
-            }
-        }
-    }
-    ORDERBOOK.save(deps.storage, &orderbook)?;
+    // Ensure provided owner is valid, defaulting to the sender
+    let owner = match owner {
+        Some(owner) => deps.api.addr_validate(owner.as_str())?,
+        None => info.sender.clone(),
+    };
 
-    // Update ETAS from Tick State
-    let mut tick_state = TICK_STATE.load(deps.storage, tick_id).unwrap_or_default();
-    let mut tick_values = tick_state.get_values(order_direction);
+    // Generate a new order ID
+    let order_id = new_order_id(deps.storage)?;
 
-    // Build limit order
+    // Build limit order, its ETAS is set when it is queued on the tick
     let limit_order = LimitOrder::new(
         tick_id,
         order_id,
         order_direction,
-        info.sender.clone(),
+        owner.clone(),
         quantity,
-        tick_values.cumulative_total_value,
+        Decimal256::zero(),
         claim_bounty,
     )
-    .with_placed_at(env.block.time);
-
-    let quant_dec256 = Decimal256::from_ratio(limit_order.quantity.u128(), Uint256::one());
+    .with_placed_at(&env.block)
+    .with_peg(peg)
+    .with_memo(memo)
+    .with_auto_settle(auto_settle);
 
     // Save the order to the orderbook
-    orders().save(deps.storage, &(tick_id, order_id), &limit_order)?;
-
-    tick_values.total_amount_of_liquidity = tick_values
-        .total_amount_of_liquidity
-        .checked_add(quant_dec256)?;
-
-    tick_values.cumulative_total_value = tick_values
-        .cumulative_total_value
-        .checked_add(Decimal256::from_ratio(quantity, Uint256::one()))?;
+    insert_resting_order(deps.storage, limit_order)?;
+    if let Some(client_order_id) = &client_order_id {
+        save_client_order_id(
+            deps.storage,
+            &info.sender,
+            client_order_id,
+            tick_id,
+            order_id,
+        )?;
+    }
 
-    tick_state.set_values(order_direction, tick_values);
-    TICK_STATE.save(deps.storage, tick_id, &tick_state)?;
-    add_directional_liquidity(deps.storage, order_direction, quant_dec256)?;
+    let mut response = Response::default();
+    if let Some(client_order_id) = client_order_id {
+        response = response.add_attribute("client_order_id", client_order_id);
+    }
+    if let Some(placement_fee) = placement_fee {
+        let recipient = MAKER_FEE_RECIPIENT.load(deps.storage)?;
+        response = response
+            .add_attribute("placement_fee", placement_fee.to_string())
+            .add_message(transfer_msg(
+                deps.storage,
+                recipient.to_string(),
+                placement_fee,
+            )?);
+    }
 
-    Ok(Response::default()
+    Ok(response
         .add_attribute("method", "placeLimit")
-        .add_attribute("owner", info.sender.to_string())
+        .add_attribute("owner", owner.to_string())
         .add_attribute("tick_id", tick_id.to_string())
         .add_attribute("order_id", order_id.to_string())
         .add_attribute("order_direction", order_direction.to_string())
@@ -132,8 +357,135 @@ pub fn place_limit(
         .add_attribute("order_denom", expected_denom.to_string())
         .add_attribute(
             "output_denom",
-            orderbook.get_opposite_denom(&order_direction).to_string(),
-        ))
+            orderbook.output_denom(&order_direction).to_string(),
+        )
+        .add_attribute("sender", info.sender.to_string())
+        .set_data(to_json_binary(&PlaceLimitResponseData {
+            tick_id,
+            order_id,
+        })?))
+}
+
+/// Deducts the configured placement fee from `funds`, returning the fee charged.
+///
+/// `reserved` is the amount of `funds` committed to the order itself, which cannot be used to pay
+/// the fee. The fee is sent to the maker fee recipient, but is charged on placement regardless of
+/// whether the order is ever filled. Returns `None` if placements are free.
+///
+/// Errors with `InsufficientPlacementFee` if the unreserved funds do not cover the fee.
+fn deduct_placement_fee(
+    storage: &dyn Storage,
+    funds: &mut [Coin],
+    reserved: &Coin,
+) -> ContractResult<Option<Coin>> {
+    let Some(fee) = get_placement_fee(storage)? else {
+        return Ok(None);
+    };
+
+    let reserved_amount = if reserved.denom == fee.denom {
+        reserved.amount
+    } else {
+        Uint128::zero()
+    };
+    let fee_funds = funds.iter_mut().find(|funds| funds.denom == fee.denom);
+    let sent = fee_funds.as_ref().map_or(Uint128::zero(), |funds| {
+        funds.amount.saturating_sub(reserved_amount)
+    });
+    ensure!(
+        sent >= fee.amount,
+        ContractError::InsufficientPlacementFee {
+            denom: fee.denom,
+            sent,
+            required: fee.amount,
+        }
+    );
+
+    // The fee is covered by the unreserved funds, so the denom must have been sent
+    if let Some(fee_funds) = fee_funds {
+        fee_funds.amount = fee_funds.amount.checked_sub(fee.amount)?;
+    }
+
+    Ok(Some(fee))
+}
+
+/// Ensures a limit order placed on `tick_id` would not be immediately fillable against the opposite
+/// side of the book.
+///
+/// The opposite tick pointer is only a bound on the best opposite tick, as fills may leave it on an
+/// emptied tick, so the best tick holding opposite liquidity is looked up from the pointer.
+///
+/// Errors with `CrossingLimitOrder` if a bid is above the best ask or an ask is below the best bid.
+/// Orders on the best opposite tick itself are handled according to the configured
+/// `CrossingTickPolicy`, only being rejected under `CrossingTickPolicy::Reject`.
+pub(crate) fn ensure_not_crossing(
+    storage: &dyn Storage,
+    orderbook: &Orderbook,
+    tick_id: i64,
+    order_direction: OrderDirection,
+) -> ContractResult<()> {
+    let opposite_pointer = match order_direction {
+        OrderDirection::Bid => orderbook.next_ask_tick,
+        OrderDirection::Ask => orderbook.next_bid_tick,
+    };
+    let Some(best_opposite_tick) =
+        get_next_tick_with_liquidity(storage, order_direction.opposite(), opposite_pointer, None)?
+    else {
+        return Ok(());
+    };
+
+    let crosses = match order_direction {
+        OrderDirection::Bid => tick_id > best_opposite_tick,
+        OrderDirection::Ask => tick_id < best_opposite_tick,
+    };
+    let rejected_on_tick = tick_id == best_opposite_tick
+        && get_crossing_tick_policy(storage)? == CrossingTickPolicy::Reject;
+    ensure!(
+        !crosses && !rejected_on_tick,
+        ContractError::CrossingLimitOrder {
+            tick_id,
+            best_opposite_tick,
+        }
+    );
+
+    Ok(())
+}
+
+/// Answers a repeated placement of a resting order by returning the existing order, refunding the
+/// funds sent with the repeated placement to the sender.
+fn duplicate_placement_response(
+    storage: &dyn Storage,
+    info: MessageInfo,
+    client_order_id: &str,
+    tick_id: i64,
+    order_id: u64,
+    cw20_funds: Option<Coin>,
+) -> ContractResult<Response> {
+    let funds = match cw20_funds {
+        Some(funds) => vec![funds],
+        None => info.funds,
+    };
+    let refund_msgs = funds
+        .into_iter()
+        .map(|funds| {
+            Ok(SubMsg::reply_on_error(
+                transfer_msg(storage, info.sender.to_string(), funds)?,
+                REPLY_ID_REFUND,
+            ))
+        })
+        .collect::<ContractResult<Vec<SubMsg>>>()?;
+
+    Ok(Response::default()
+        .add_attribute("method", "placeLimit")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("client_order_id", client_order_id)
+        .add_attribute("duplicate", "true")
+        .add_attribute("tick_id", tick_id.to_string())
+        .add_attribute("order_id", order_id.to_string())
+        .add_submessages(refund_msgs)
+        .set_data(to_json_binary(&PlaceLimitResponseData {
+            tick_id,
+            order_id,
+        })?))
 }
 
 pub fn cancel_limit(
@@ -146,15 +498,18 @@ pub fn cancel_limit(
     nonpayable(&info)?;
     let key = (tick_id, order_id);
     // Check for the order, error if not found
-    let order = orders()
-        .may_load(deps.storage, &key)?
-        .ok_or(ContractError::OrderNotFound { tick_id, order_id })?;
+    let order = load_order(deps.storage, tick_id, order_id)?;
 
     // Ensure the sender is the order owner
     ensure_eq!(info.sender, order.owner, ContractError::Unauthorized {});
 
+    // Ensure the refund can be delivered to the order owner
+    ensure_deliverable(deps.api, &order.owner)?;
+
     // Sync tick before checking if order is filled
-    let tick_state = TICK_STATE.load(deps.storage, tick_id).unwrap_or_default();
+    let tick_state = TICK_STATE
+        .may_load(deps.storage, tick_id)?
+        .unwrap_or_default();
     sync_tick(
         deps.storage,
         tick_id,
@@ -167,21 +522,571 @@ pub fn cancel_limit(
     )?;
 
     // Ensure the order has not been filled.
-    let tick_state = TICK_STATE.load(deps.storage, tick_id).unwrap_or_default();
+    let tick_state = TICK_STATE
+        .may_load(deps.storage, tick_id)?
+        .unwrap_or_default();
     let tick_values = tick_state.get_values(order.order_direction);
     ensure!(
         tick_values.effective_total_amount_swapped <= order.etas,
         ContractError::CancelFilledOrder
     );
 
+    // Remove the order from the book, releasing its place in the tick's queue
+    remove_resting_order(deps.storage, &order)?;
+    remove_client_order_id(deps.storage, order.tick_id, order.order_id)?;
+    REMOVED_ORDERS.save(deps.storage, &key, &OrderNotFoundReason::Cancelled)?;
+
+    // Get orderbook info for correct denomination
+    let orderbook = get_orderbook(deps.storage)?;
+
+    // Generate refund
+    let expected_denom = orderbook.input_denom(&order.order_direction);
+    let refund_msg = SubMsg::reply_on_error(
+        transfer_msg(
+            deps.storage,
+            order.owner.to_string(),
+            coin(order.quantity.u128(), expected_denom.clone()),
+        )?,
+        REPLY_ID_REFUND,
+    );
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("method", "cancelLimit"),
+            ("owner", info.sender.as_str()),
+            ("tick_id", &tick_id.to_string()),
+            ("order_id", &order_id.to_string()),
+            ("quantity", &order.quantity.to_string()),
+            ("order_direction", &order.order_direction.to_string()),
+            ("initial_quantity", &order.placed_quantity.to_string()),
+            ("order_denom", &expected_denom.to_string()),
+            (
+                "output_denom",
+                &orderbook.output_denom(&order.order_direction).to_string(),
+            ),
+        ])
+        .add_submessage(refund_msg))
+}
+
+/// Claims the filled portion of an order and refunds its unfilled remainder.
+///
+/// The claim is processed exactly as an owner's `claim_limit` without waiving the bounty, so the
+/// claim bounty and maker fee are applied to the filled portion. If the order has no fills only the
+/// cancellation is performed, and if the claim fully fills the order there is nothing left to cancel.
+///
+/// Errors if:
+/// 1. Funds are sent with the message
+/// 2. Claims are paused
+/// 3. The order does not exist
+/// 4. The sender is not the order owner
+pub fn claim_and_cancel(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    tick_id: i64,
+    order_id: u64,
+) -> ContractResult<Response> {
+    nonpayable(&info)?;
+    // Ensure claims are not paused
+    ensure_claims_not_paused(deps.storage)?;
+
+    let key = (tick_id, order_id);
+    // Check for the order, error if not found
+    let order = load_order(deps.storage, tick_id, order_id)?;
+
+    // Ensure the sender is the order owner
+    ensure_eq!(info.sender, order.owner, ContractError::Unauthorized {});
+
+    let mut response = Response::new().add_attributes(vec![
+        ("method", "claimAndCancel"),
+        ("owner", info.sender.as_str()),
+        ("tick_id", &tick_id.to_string()),
+        ("order_id", &order_id.to_string()),
+    ]);
+
+    // Claim the filled portion of the order, if any
+    match claim_order(
+        deps.storage,
+        deps.api,
+        &env,
+        info.sender.clone(),
+        tick_id,
+        order_id,
+        false,
+    ) {
+        Ok((amount_claimed, bank_msgs, order)) => {
+            record_fill(
+                deps.storage,
+                &order.owner,
+                tick_id,
+                order_id,
+                amount_claimed,
+                tick_to_price(tick_id)?,
+                env.block.height,
+            )?;
+
+            let orderbook = get_orderbook(deps.storage)?;
+            let order_denom = orderbook.input_denom(&order.order_direction);
+            let output_denom = orderbook.output_denom(&order.order_direction);
+            let event = generate_claimed_order_event(
+                info.sender.clone(),
+                order,
+                amount_claimed,
+                order_denom,
+                output_denom,
+            );
+            response = response
+                .add_attribute("amount_claimed", amount_claimed.to_string())
+                .add_event(event)
+                .add_submessages(bank_msgs);
+        }
+        Err(ContractError::ZeroClaim) => {}
+        Err(err) => return Err(err),
+    }
+
+    // Refund the unfilled remainder if the claim did not fully fill the order
+    let Some(remaining) = orders().may_load(deps.storage, &key)? else {
+        return Ok(response.add_attribute("refund_quantity", "0"));
+    };
+    let cancel_response = cancel_limit(deps.branch(), env, info, tick_id, order_id)?;
+
+    Ok(response
+        .add_attribute("refund_quantity", remaining.quantity.to_string())
+        .add_submessages(cancel_response.messages))
+}
+
+/// Cancels an order and places a replacement for it in a single message, so that the owner is
+/// never left without an order resting on the book.
+///
+/// The funds of the cancelled order are used for the replacement: if `new_quantity` is larger the
+/// sender must attach the difference in the order's input denom, and if it is smaller the
+/// difference is refunded. The replacement keeps the owner, claim bounty, memo, auto-settlement and
+/// client order ID of the cancelled order, but not its peg, and is queued behind all liquidity resting on
+/// `new_tick_id`. The new order's ID is returned in the response data, and lookups of the cancelled
+/// order report it as `OrderNotFoundReason::Replaced`.
+///
+/// CW20 tokens cannot be attached to the message, so orders with a CW20 input denom can only be
+/// replaced with at most their current quantity. As for CW20 placements, any placement fee is then
+/// deducted from the replacement's quantity.
+///
+/// Errors if:
+/// 1. The order does not exist or the sender is not its owner
+/// 2. The order has been filled, as for `cancel_limit`
+/// 3. The replacement cannot be placed, e.g. it crosses the book or is not funded exactly
+/// 4. The order's input denom is a CW20 and `new_quantity` is larger than its quantity
+pub fn replace_order(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    tick_id: i64,
+    order_id: u64,
+    new_tick_id: i64,
+    new_quantity: Uint128,
+) -> ContractResult<Response> {
+    let key = (tick_id, order_id);
+    let order = load_order(deps.storage, tick_id, order_id)?;
+    let client_order_id = ORDER_CLIENT_IDS.may_load(deps.storage, &key)?;
+
+    // Cancel the order, its funds are credited to the replacement rather than refunded
+    let cancel_info = MessageInfo {
+        sender: info.sender.clone(),
+        funds: vec![],
+    };
+    cancel_limit(deps.branch(), env.clone(), cancel_info, tick_id, order_id)?;
+
+    // Fund the replacement with the cancelled quantity on top of the funds sent
+    let orderbook = get_orderbook(deps.storage)?;
+    let denom = orderbook.input_denom(&order.order_direction);
+    let credited = order.quantity.min(new_quantity);
+    let refund = order.quantity.checked_sub(credited)?;
+    let mut info = info;
+    let cw20_funds = match get_asset_type(deps.storage, &denom)? {
+        AssetType::Native => {
+            match info.funds.iter_mut().find(|funds| funds.denom == denom) {
+                Some(funds) => funds.amount = funds.amount.checked_add(credited)?,
+                None => info.funds.push(coin(credited.u128(), denom.clone())),
+            }
+            info.funds.retain(|funds| !funds.amount.is_zero());
+            None
+        }
+        // The replacement is funded by the cancelled order alone
+        AssetType::Cw20 => {
+            nonpayable(&info)?;
+            ensure!(
+                new_quantity <= order.quantity,
+                ContractError::InsufficientFunds {
+                    sent: order.quantity,
+                    required: new_quantity,
+                }
+            );
+            Some(coin(credited.u128(), denom.clone()))
+        }
+    };
+
+    let mut response = place_limit_internal(
+        &mut deps,
+        env,
+        info,
+        new_tick_id,
+        order.order_direction,
+        new_quantity,
+        order.claim_bounty,
+        Some(order.owner.clone()),
+        None,
+        order.memo,
+        order.auto_settle,
+        cw20_funds,
+        None,
+    )?;
+
+    // Point lookups of the cancelled order at its replacement
+    let placed: PlaceLimitResponseData = from_json(response.data.clone().unwrap_or_default())?;
+    REMOVED_ORDERS.save(
+        deps.storage,
+        &key,
+        &OrderNotFoundReason::Replaced {
+            tick_id: placed.tick_id,
+            order_id: placed.order_id,
+        },
+    )?;
+
+    // Carry the client order ID over to the replacement
+    if let Some((sender, client_order_id)) = client_order_id {
+        save_client_order_id(
+            deps.storage,
+            &sender,
+            &client_order_id,
+            placed.tick_id,
+            placed.order_id,
+        )?;
+    }
+
+    if !refund.is_zero() {
+        response = response.add_submessage(SubMsg::reply_on_error(
+            transfer_msg(
+                deps.storage,
+                order.owner.to_string(),
+                coin(refund.u128(), denom),
+            )?,
+            REPLY_ID_REFUND,
+        ));
+    }
+
+    Ok(response
+        .add_attribute("replaced_tick_id", tick_id.to_string())
+        .add_attribute("replaced_order_id", order_id.to_string())
+        .add_attribute("refund_quantity", refund.to_string()))
+}
+
+/// Places a limit order pegged to the best tick of one side of the book.
+///
+/// The order is placed `peg.offset_ticks` away from the reference tick, and can later be moved to
+/// follow the reference by anyone calling `reprice`. Other than its tick being derived from the peg,
+/// the order is placed as a regular limit order funded by the sender.
+///
+/// Errors if:
+/// 1. The reference side of the book is empty
+/// 2. The pegged tick is outside of the orderbook's tick bounds
+/// 3. Any of the `place_limit` checks fail on the pegged tick, e.g. the order crosses the book
+pub fn place_pegged_limit(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    order_direction: OrderDirection,
+    quantity: Uint128,
+    peg: PegConfig,
+    claim_bounty: Option<Decimal256>,
+) -> Result<Response, ContractError> {
+    let orderbook = get_orderbook(deps.storage)?;
+    let tick_id = pegged_tick(deps.storage, &orderbook, &peg, None)?;
+
+    let response = place_limit_internal(
+        &mut deps,
+        env,
+        info,
+        tick_id,
+        order_direction,
+        quantity,
+        claim_bounty,
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(peg.clone()),
+    )?;
+
+    Ok(response
+        .add_attribute("peg_reference", peg.reference.to_string())
+        .add_attribute("peg_offset_ticks", peg.offset_ticks.to_string()))
+}
+
+/// Moves a pegged order to the tick its peg currently points to.
+///
+/// Callable by anyone, so that keepers can keep pegged orders tracking the book. If the reference
+/// has not moved the order is left in place.
+///
+/// Repricing resets the order's priority: it is queued behind all liquidity already resting on the
+/// new tick, exactly as if it were cancelled and placed again. Its remaining quantity, owner, claim
+/// bounty and ID are preserved, so the order is found at `(new_tick_id, order_id)` afterwards. Its
+/// placement block is reset to the current one, restarting the activation delay.
+///
+/// Errors if:
+/// 1. The order does not exist or is not pegged
+/// 2. The order has unclaimed fills, which must be claimed before it can be moved
+/// 3. The reference side of the book has no liquidity other than the order itself
+/// 4. The pegged tick is outside of the orderbook's tick bounds or crosses the book
+pub fn reprice(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    tick_id: i64,
+    order_id: u64,
+) -> ContractResult<Response> {
+    nonpayable(&info)?;
+    // Repricing places the order anew, so it is paused along with placements
+    ensure_placements_not_paused(deps.storage)?;
+
+    let key = (tick_id, order_id);
+    let order = load_order(deps.storage, tick_id, order_id)?;
+    let peg = order
+        .peg
+        .clone()
+        .ok_or(ContractError::OrderNotPegged { tick_id, order_id })?;
+
+    // Sync tick before checking if order is filled
+    let tick_state = TICK_STATE
+        .may_load(deps.storage, tick_id)?
+        .unwrap_or_default();
+    sync_tick(
+        deps.storage,
+        tick_id,
+        tick_state
+            .get_values(OrderDirection::Bid)
+            .effective_total_amount_swapped,
+        tick_state
+            .get_values(OrderDirection::Ask)
+            .effective_total_amount_swapped,
+    )?;
+
+    // Ensure the order has no fills waiting to be claimed at its current tick
+    let tick_state = TICK_STATE
+        .may_load(deps.storage, tick_id)?
+        .unwrap_or_default();
+    ensure!(
+        tick_state
+            .get_values(order.order_direction)
+            .effective_total_amount_swapped
+            <= order.etas,
+        ContractError::RepriceFilledOrder
+    );
+
+    let orderbook = get_orderbook(deps.storage)?;
+    let new_tick_id = pegged_tick(deps.storage, &orderbook, &peg, Some(&order))?;
+
+    let response = Response::new().add_attributes(vec![
+        ("method", "reprice"),
+        ("sender", info.sender.as_str()),
+        ("order_id", &order_id.to_string()),
+        ("previous_tick_id", &tick_id.to_string()),
+        ("tick_id", &new_tick_id.to_string()),
+    ]);
+    if new_tick_id == tick_id {
+        return Ok(response.add_attribute("repriced", "false"));
+    }
+
+    ensure_not_crossing(deps.storage, &orderbook, new_tick_id, order.order_direction)?;
+
+    // Move the order to the back of the new tick's queue
+    let client_order_id = ORDER_CLIENT_IDS.may_load(deps.storage, &key)?;
+    remove_resting_order(deps.storage, &order)?;
+    remove_client_order_id(deps.storage, tick_id, order_id)?;
+    REMOVED_ORDERS.save(
+        deps.storage,
+        &key,
+        &OrderNotFoundReason::Moved {
+            tick_id: new_tick_id,
+        },
+    )?;
+
+    increment_tick_order_count(deps.storage, new_tick_id, order.order_direction)?;
+    let order = insert_resting_order(
+        deps.storage,
+        LimitOrder {
+            tick_id: new_tick_id,
+            ..order
+        }
+        .with_placed_at(&env.block),
+    )?;
+    if let Some((sender, client_order_id)) = client_order_id {
+        save_client_order_id(
+            deps.storage,
+            &sender,
+            &client_order_id,
+            new_tick_id,
+            order_id,
+        )?;
+    }
+
+    Ok(response
+        .add_attribute("repriced", "true")
+        .add_attribute("quantity", order.quantity.to_string()))
+}
+
+/// Returns the tick a pegged order should rest on given the current state of the book.
+///
+/// The reference is the best tick holding liquidity on the peg's reference side. If `order` is
+/// provided, its own liquidity is ignored, so that an order pegged to its own side of the book
+/// follows the other orders on that side rather than itself.
+///
+/// Errors if the reference side has no liquidity or the pegged tick is out of bounds.
+pub(crate) fn pegged_tick(
+    storage: &dyn Storage,
+    orderbook: &Orderbook,
+    peg: &PegConfig,
+    order: Option<&LimitOrder>,
+) -> ContractResult<i64> {
+    let direction = peg.reference.direction();
+    let pointer = match direction {
+        OrderDirection::Bid => orderbook.next_bid_tick,
+        OrderDirection::Ask => orderbook.next_ask_tick,
+    };
+    let mut reference_tick = get_next_tick_with_liquidity(storage, direction, pointer, None)?;
+
+    // Skip the best tick if the order is the only liquidity resting on it
+    if let (Some(best_tick), Some(order)) = (reference_tick, order) {
+        let best_liquidity = TICK_STATE
+            .load(storage, best_tick)?
+            .get_values(direction)
+            .total_amount_of_liquidity;
+        if order.tick_id == best_tick
+            && order.order_direction == direction
+            && best_liquidity == Decimal256::from_ratio(order.quantity, 1u128)
+        {
+            let next_tick = match direction {
+                OrderDirection::Bid => best_tick.checked_sub(1),
+                OrderDirection::Ask => best_tick.checked_add(1),
+            };
+            reference_tick = match next_tick.filter(|tick| MIN_TICK <= *tick && *tick <= MAX_TICK) {
+                Some(next_tick) => {
+                    get_next_tick_with_liquidity(storage, direction, next_tick, None)?
+                }
+                None => None,
+            };
+        }
+    }
+
+    let reference_tick = reference_tick.ok_or(ContractError::EmptyPegReference {
+        reference: peg.reference,
+    })?;
+    let tick_id = reference_tick
+        .checked_add(peg.offset_ticks)
+        .filter(|tick_id| orderbook.is_tick_in_bounds(*tick_id))
+        .ok_or(ContractError::PegOutOfBounds {
+            reference_tick,
+            offset_ticks: peg.offset_ticks,
+        })?;
+
+    Ok(tick_id)
+}
+
+/// Queues an order at the back of its tick, setting its ETAS to the tick's cumulative total value.
+///
+/// Updates the tick, directional and layer liquidity along with the direction's tick pointer.
+/// Does not check or update the tick's order count.
+fn insert_resting_order(
+    storage: &mut dyn Storage,
+    mut order: LimitOrder,
+) -> ContractResult<LimitOrder> {
+    let tick_id = order.tick_id;
+    let order_direction = order.order_direction;
+    let mut orderbook = get_orderbook(storage)?;
+
+    // If bid and tick_id is higher than next bid tick, update next bid tick
+    // If ask and tick_id is lower than next ask tick, update next ask tick
+    //
+    // On an empty side of the book the pointer rests at the global bound furthest from the
+    // spread (MIN_TICK for bids, MAX_TICK for asks), so the first placement on that side always
+    // moves the pointer to the order's tick and market orders never scan from tick 0.
+    //
+    // Fills may leave the opposite pointer behind the best opposite tick, on ticks that have been
+    // emptied. As the order does not cross the book, no opposite liquidity lies before its tick, so
+    // the opposite pointer is brought up to the order's tick to keep the pointers from crossing.
+    // An order resting on the best opposite tick leaves both pointers on that tick.
+    match order_direction {
+        OrderDirection::Bid => {
+            if tick_id > orderbook.next_bid_tick {
+                orderbook.next_bid_tick = tick_id;
+            }
+            if tick_id > orderbook.next_ask_tick {
+                orderbook.next_ask_tick = tick_id;
+            }
+        }
+        OrderDirection::Ask => {
+            if tick_id < orderbook.next_ask_tick {
+                orderbook.next_ask_tick = tick_id;
+            }
+            if tick_id < orderbook.next_bid_tick {
+                orderbook.next_bid_tick = tick_id;
+            }
+        }
+    }
+    save_orderbook(storage, &mut orderbook)?;
+
+    // Update ETAS from Tick State
+    //
+    // The order is queued at the tick's cumulative total value and fills advance the tick's ETAS
+    // through that queue, so orders on a tick are filled strictly in placement order rather than
+    // in storage iteration order.
+    let mut tick_state = TICK_STATE.may_load(storage, tick_id)?.unwrap_or_default();
+    let mut tick_values = tick_state.get_values(order_direction);
+    order.etas = tick_values.cumulative_total_value;
+
+    let quant_dec256 = Decimal256::from_ratio(order.quantity.u128(), Uint256::one());
+
+    // Save the order to the orderbook
+    orders().save(storage, &(tick_id, order.order_id), &order)?;
+    increment_order_count(storage, &order.owner)?;
+    add_queued_liquidity(storage, &order, order.quantity)?;
+    reset_dust_audit(storage);
+    if let Some(min_payout) = order.auto_settle {
+        AUTO_SETTLE_ORDERS.save(
+            storage,
+            (&order_direction.to_string(), tick_id, order.order_id),
+            &min_payout,
+        )?;
+    }
+
+    tick_values.total_amount_of_liquidity = tick_values
+        .total_amount_of_liquidity
+        .checked_add(quant_dec256)?;
+
+    tick_values.cumulative_total_value = tick_values
+        .cumulative_total_value
+        .checked_add(quant_dec256)?;
+
+    tick_state.set_values(order_direction, tick_values);
+    TICK_STATE.save(storage, tick_id, &tick_state)?;
+    add_directional_liquidity(storage, order_direction, quant_dec256)?;
+    add_layer_liquidity(storage, order_direction, tick_id, quant_dec256)?;
+
+    Ok(order)
+}
+
+/// Removes an unfilled order from its tick, recording its quantity as cancelled in the tick's
+/// sumtree so that the liquidity behind it moves up the queue.
+///
+/// Updates the tick, directional and layer liquidity along with the tick's order count. If the
+/// order emptied the tick the directional pointer references, the pointer is advanced.
+fn remove_resting_order(storage: &mut dyn Storage, order: &LimitOrder) -> ContractResult<()> {
     // Fetch the sumtree from storage, or create one if it does not exist
-    let mut tree = get_or_init_root_node(deps.storage, tick_id, order.order_direction)?;
+    let mut tree = get_or_init_root_node(storage, order.tick_id, order.order_direction)?;
 
     // Generate info for new node to insert to sumtree
-    let node_id = generate_node_id(deps.storage, order.tick_id)?;
+    let node_id = generate_node_id(storage, order.tick_id)?;
     let mut curr_tick_state =
         TICK_STATE
-            .load(deps.storage, order.tick_id)
+            .load(storage, order.tick_id)
             .ok()
             .ok_or(ContractError::InvalidTickId {
                 tick_id: order.tick_id,
@@ -196,73 +1101,149 @@ pub fn cancel_limit(
         NodeType::leaf(order.etas, quant_dec256),
     );
 
-    // Insert new node
-    tree.insert(deps.storage, &mut new_node)?;
+    // Insert new node, merging it with a contiguous cancellation if there is one
+    tree.insert_or_merge(storage, &mut new_node)?;
 
-    // Get orderbook info for correct denomination
-    let orderbook = ORDERBOOK.load(deps.storage)?;
-
-    // Generate refund
-    let expected_denom = orderbook.get_expected_denom(&order.order_direction);
-    let refund_msg = SubMsg::reply_on_error(
-        BankMsg::Send {
-            to_address: order.owner.to_string(),
-            amount: vec![coin(order.quantity.u128(), expected_denom.clone())],
-        },
-        REPLY_ID_REFUND,
+    orders().remove(storage, &(order.tick_id, order.order_id))?;
+    AUTO_SETTLE_ORDERS.remove(
+        storage,
+        (
+            &order.order_direction.to_string(),
+            order.tick_id,
+            order.order_id,
+        ),
     );
-
-    orders().remove(deps.storage, &(order.tick_id, order.order_id))?;
+    decrement_tick_order_count(storage, order.tick_id, order.order_direction)?;
+    decrement_order_count(storage, &order.owner)?;
+    subtract_queued_liquidity(storage, order, order.quantity)?;
 
     curr_tick_values.total_amount_of_liquidity = curr_tick_values
         .total_amount_of_liquidity
-        .checked_sub(Decimal256::from_ratio(order.quantity, Uint256::one()))?;
+        .checked_sub(quant_dec256)?;
+    let tick_emptied = curr_tick_values.total_amount_of_liquidity.is_zero();
     curr_tick_state.set_values(order.order_direction, curr_tick_values);
-    TICK_STATE.save(deps.storage, order.tick_id, &curr_tick_state)?;
-    subtract_directional_liquidity(deps.storage, order.order_direction, quant_dec256)?;
+    TICK_STATE.save(storage, order.tick_id, &curr_tick_state)?;
+    subtract_directional_liquidity(storage, order.order_direction, quant_dec256)?;
+    subtract_layer_liquidity(storage, order.order_direction, order.tick_id, quant_dec256)?;
+
+    // If the removal emptied the tick the directional pointer references, advance the
+    // pointer to the next tick with liquidity so market orders do not start on an empty tick.
+    // If that side of the book is now empty, the pointer is reset to its initial value.
+    if tick_emptied {
+        let mut orderbook = get_orderbook(storage)?;
+        match order.order_direction {
+            OrderDirection::Ask if orderbook.next_ask_tick == order.tick_id => {
+                orderbook.next_ask_tick = get_next_tick_with_liquidity(
+                    storage,
+                    OrderDirection::Ask,
+                    order.tick_id,
+                    None,
+                )?
+                .unwrap_or(MAX_TICK);
+                save_orderbook(storage, &mut orderbook)?;
+            }
+            OrderDirection::Bid if orderbook.next_bid_tick == order.tick_id => {
+                orderbook.next_bid_tick = get_next_tick_with_liquidity(
+                    storage,
+                    OrderDirection::Bid,
+                    order.tick_id,
+                    None,
+                )?
+                .unwrap_or(MIN_TICK);
+                save_orderbook(storage, &mut orderbook)?;
+            }
+            _ => {}
+        }
+    }
 
-    tree.save(deps.storage)?;
+    tree.save(storage)?;
 
-    Ok(Response::new()
-        .add_attributes(vec![
-            ("method", "cancelLimit"),
-            ("owner", info.sender.as_str()),
-            ("tick_id", &tick_id.to_string()),
-            ("order_id", &order_id.to_string()),
-            ("quantity", &order.quantity.to_string()),
-            ("order_direction", &order.order_direction.to_string()),
-            ("initial_quantity", &order.placed_quantity.to_string()),
-            ("order_denom", &expected_denom.to_string()),
-            (
-                "output_denom",
-                &orderbook
-                    .get_opposite_denom(&order.order_direction)
-                    .to_string(),
-            ),
-        ])
-        .add_submessage(refund_msg))
+    Ok(())
 }
 
+/// Transfers ownership of a resting limit order to a new address.
+///
+/// Any future claims or cancellations of the order are paid out to the new owner.
+/// Saving the updated order through the indexed map replaces the owner index entries
+/// in the same write, so the previous owner can no longer see the order.
+///
+/// Errors if:
+/// 1. Funds are sent with the message
+/// 2. The order does not exist (including orders that were already fully filled and claimed)
+/// 3. The sender is not the current owner of the order
+/// 4. The new owner is not a valid address
+pub fn transfer_order(
+    deps: DepsMut,
+    info: MessageInfo,
+    tick_id: i64,
+    order_id: u64,
+    new_owner: Addr,
+) -> ContractResult<Response> {
+    nonpayable(&info)?;
+    let key = (tick_id, order_id);
+    // Check for the order, error if not found
+    let mut order = load_order(deps.storage, tick_id, order_id)?;
+
+    // Ensure the sender is the order owner
+    ensure_eq!(info.sender, order.owner, ContractError::Unauthorized {});
+
+    // Ensure provided address is valid
+    let new_owner = deps.api.addr_validate(new_owner.as_str())?;
+
+    let previous_owner = order.owner;
+    order.owner = new_owner;
+    orders().save(deps.storage, &key, &order)?;
+    decrement_order_count(deps.storage, &previous_owner)?;
+    increment_order_count(deps.storage, &order.owner)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("method", "transferOrder"),
+        ("tick_id", &tick_id.to_string()),
+        ("order_id", &order_id.to_string()),
+        ("previous_owner", previous_owner.as_str()),
+        ("new_owner", order.owner.as_str()),
+    ]))
+}
+
+/// Claims the filled portion of a limit order.
+///
+/// If the order owner is claiming their own order, `waive_bounty` skips the claim bounty so the
+/// full amount is paid to the owner. The stored bounty always applies to third party claims.
 pub fn claim_limit(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     tick_id: i64,
     order_id: u64,
+    waive_bounty: bool,
 ) -> Result<Response, ContractError> {
     nonpayable(&info)?;
+    // Ensure claims are not paused
+    ensure_claims_not_paused(deps.storage)?;
 
     let (amount_claimed, bank_msgs, order) = claim_order(
         deps.storage,
-        env.contract.address,
+        deps.api,
+        &env,
         info.sender.clone(),
         tick_id,
         order_id,
+        waive_bounty,
+    )?;
+
+    record_fill(
+        deps.storage,
+        &order.owner,
+        tick_id,
+        order_id,
+        amount_claimed,
+        tick_to_price(tick_id)?,
+        env.block.height,
     )?;
 
-    let orderbook = ORDERBOOK.load(deps.storage)?;
-    let order_denom = orderbook.get_expected_denom(&order.order_direction);
-    let output_denom = orderbook.get_opposite_denom(&order.order_direction);
+    let orderbook = get_orderbook(deps.storage)?;
+    let order_denom = orderbook.input_denom(&order.order_direction);
+    let output_denom = orderbook.output_denom(&order.order_direction);
 
     let event = generate_claimed_order_event(
         info.sender,
@@ -279,37 +1260,52 @@ pub fn claim_limit(
 }
 
 // batch_claim_limits allows for multiple limit orders to be claimed in a single transaction.
+//
+// At most `get_max_batch_claim` orders are processed per call so that oversized batches cannot
+// exceed the gas limit. Any remaining orders are returned in the response data to be resubmitted.
+//
+// `waive_bounty` only applies to orders owned by the sender, as in `claim_limit`.
 pub fn batch_claim_limits(
     deps: DepsMut,
     info: MessageInfo,
     env: Env,
-    orders: Vec<(i64, u64)>,
+    mut orders: Vec<(i64, u64)>,
+    waive_bounty: bool,
 ) -> Result<Response, ContractError> {
     nonpayable(&info)?;
+    // Ensure claims are not paused
+    ensure_claims_not_paused(deps.storage)?;
 
-    ensure!(
-        orders.len() <= MAX_BATCH_CLAIM as usize,
-        ContractError::BatchClaimLimitExceeded {
-            max_batch_claim: MAX_BATCH_CLAIM
-        }
-    );
+    let max_batch_claim = get_max_batch_claim(deps.storage)? as usize;
+    let unprocessed = orders.split_off(orders.len().min(max_batch_claim));
 
-    let mut responses: Vec<SubMsg> = Vec::new();
+    let mut bank_sends: Vec<(MsgSend256, u64)> = Vec::new();
     let mut events: Vec<Event> = Vec::new();
-    let orderbook = ORDERBOOK.load(deps.storage)?;
+    let orderbook = get_orderbook(deps.storage)?;
 
     for (tick_id, order_id) in orders {
         // Attempt to claim each order
-        match claim_order(
+        match claim_order_internal(
             deps.storage,
-            env.contract.address.clone(),
+            deps.api,
+            &env,
             info.sender.clone(),
             tick_id,
             order_id,
+            waive_bounty,
         ) {
-            Ok((amount_claimed, mut bank_msgs, order)) => {
-                let order_denom = orderbook.get_expected_denom(&order.order_direction);
-                let output_denom = orderbook.get_opposite_denom(&order.order_direction);
+            Ok((amount_claimed, mut sends, order)) => {
+                record_fill(
+                    deps.storage,
+                    &order.owner,
+                    tick_id,
+                    order_id,
+                    amount_claimed,
+                    tick_to_price(tick_id)?,
+                    env.block.height,
+                )?;
+                let order_denom = orderbook.input_denom(&order.order_direction);
+                let output_denom = orderbook.output_denom(&order.order_direction);
                 let event = generate_claimed_order_event(
                     info.sender.clone(),
                     order,
@@ -317,7 +1313,7 @@ pub fn batch_claim_limits(
                     order_denom,
                     output_denom,
                 );
-                responses.append(&mut bank_msgs);
+                bank_sends.append(&mut sends);
                 events.push(event);
             }
             Err(_) => {
@@ -328,11 +1324,156 @@ pub fn batch_claim_limits(
         }
     }
 
-    Ok(Response::new()
-        .add_attribute("method", "batchClaimLimit")
-        .add_attribute("sender", info.sender)
-        .add_events(events)
-        .add_submessages(responses))
+    // Orders in a batch frequently share an owner, so transfers of the same denom to the
+    // same recipient are combined to reduce the number of bank sub-messages.
+    let responses = coalesce_bank_sends(deps.storage, bank_sends)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "batchClaimLimit")
+        .add_attribute("sender", info.sender)
+        .add_attribute("unprocessed_count", unprocessed.len().to_string())
+        .add_events(events)
+        .add_submessages(responses)
+        .set_data(to_json_binary(&BatchClaimResponseData { unprocessed })?))
+}
+
+/// Settles the fills of auto-settling `direction` orders resting on ticks between `start_tick` and
+/// `end_tick` (inclusive), sending their output straight to their owners.
+///
+/// Called after a market order with the range of ticks it filled against. Each order is claimed as
+/// if by its owner waiving the bounty, so only the maker fee is applied. Orders whose claimable
+/// output is below their minimum payout are left to accrue until a later fill or claim.
+///
+/// At most `get_max_batch_claim` orders are visited so that many small auto-settling orders cannot
+/// push a swap over the gas limit; orders beyond the limit remain claimable as usual. Nothing is
+/// settled while claims are paused.
+pub(crate) fn settle_auto_orders(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    env: &Env,
+    direction: OrderDirection,
+    start_tick: i64,
+    end_tick: i64,
+) -> ContractResult<(Vec<SubMsg>, Vec<Event>)> {
+    if ensure_claims_not_paused(storage).is_err() {
+        return Ok((vec![], vec![]));
+    }
+
+    let direction_key = direction.to_string();
+    let max_settlements = get_max_batch_claim(storage)? as usize;
+    let candidates = AUTO_SETTLE_ORDERS
+        .sub_prefix(&direction_key)
+        .range(
+            storage,
+            Some(Bound::inclusive((start_tick.min(end_tick), u64::MIN))),
+            Some(Bound::inclusive((start_tick.max(end_tick), u64::MAX))),
+            Order::Ascending,
+        )
+        .take(max_settlements)
+        .collect::<StdResult<Vec<((i64, u64), Uint128)>>>()?;
+
+    let orderbook = get_orderbook(storage)?;
+    let mut bank_sends: Vec<(MsgSend256, u64)> = Vec::new();
+    let mut events: Vec<Event> = Vec::new();
+    for ((tick_id, order_id), min_payout) in candidates {
+        let Some(order) = orders().may_load(storage, &(tick_id, order_id))? else {
+            AUTO_SETTLE_ORDERS.remove(storage, (&direction_key, tick_id, order_id));
+            continue;
+        };
+
+        // Sync the tick so that the claimable output can be checked against the minimum payout
+        let tick_state = TICK_STATE.load(storage, tick_id)?;
+        sync_tick(
+            storage,
+            tick_id,
+            tick_state
+                .get_values(OrderDirection::Bid)
+                .effective_total_amount_swapped,
+            tick_state
+                .get_values(OrderDirection::Ask)
+                .effective_total_amount_swapped,
+        )?;
+        let tick_etas = TICK_STATE
+            .load(storage, tick_id)?
+            .get_values(direction)
+            .effective_total_amount_swapped;
+        let amount_filled = Uint128::try_from(
+            RoundingMode::Down.round(
+                tick_etas
+                    .saturating_sub(order.etas)
+                    .min(Decimal256::from_ratio(order.quantity, 1u128)),
+            ),
+        )?;
+        let payout = amount_to_value(
+            direction,
+            amount_filled,
+            tick_to_price(tick_id)?,
+            RoundingMode::Down,
+        )?;
+        if amount_filled.is_zero() || payout < Uint256::from_uint128(min_payout) {
+            continue;
+        }
+
+        // Undeliverable claims are left for the owner to claim
+        let Ok((amount_claimed, mut sends, order)) = claim_order_internal(
+            storage,
+            api,
+            env,
+            order.owner.clone(),
+            tick_id,
+            order_id,
+            true,
+        ) else {
+            continue;
+        };
+        record_fill(
+            storage,
+            &order.owner,
+            tick_id,
+            order_id,
+            amount_claimed,
+            tick_to_price(tick_id)?,
+            env.block.height,
+        )?;
+        let order_denom = orderbook.input_denom(&direction);
+        let output_denom = orderbook.output_denom(&direction);
+        events.push(
+            generate_claimed_order_event(
+                env.contract.address.clone(),
+                order,
+                amount_claimed,
+                order_denom,
+                output_denom,
+            )
+            .add_attribute("auto_settled", "true"),
+        );
+        bank_sends.append(&mut sends);
+    }
+
+    Ok((coalesce_bank_sends(storage, bank_sends)?, events))
+}
+
+/// Combines bank sends that share a recipient, denom and reply ID into a single `SubMsg`.
+///
+/// Sends are emitted in the order their recipient/denom/reply ID combination first appears.
+pub(crate) fn coalesce_bank_sends(
+    storage: &dyn Storage,
+    sends: Vec<(MsgSend256, u64)>,
+) -> ContractResult<Vec<SubMsg>> {
+    let mut coalesced: Vec<(MsgSend256, u64)> = Vec::new();
+    'sends: for (send, reply_id) in sends {
+        for (existing, existing_reply_id) in coalesced.iter_mut() {
+            if *existing_reply_id == reply_id && existing.try_merge(&send)? {
+                continue 'sends;
+            }
+        }
+        coalesced.push((send, reply_id));
+    }
+
+    coalesced
+        .into_iter()
+        .map(|(send, reply_id)| Ok(SubMsg::reply_on_error(payout_msg(storage, send)?, reply_id)))
+        .collect()
 }
 
 /// Generates an event when an order is claimed to help with indexing
@@ -361,30 +1502,76 @@ fn generate_claimed_order_event(
 // run_market_order processes a market order from the current active tick on the order's orderbook
 /// up to the passed in `tick_bound`. **Partial fills are not allowed.**
 ///
+/// If `max_ticks` is provided, the fill stops after filling against that many ticks so that very
+/// large orders against a fragmented book stay within gas limits. Any input left unfilled remains in
+/// `order.quantity` and the fill can be resumed from the returned tick in a follow-up transaction.
+///
 /// Note that this mutates the `order` object
 ///
 /// Returns:
 /// * The output after the order has been processed
+/// * The average fill price, or `None` if nothing was filled
 /// * Bank send message to process the balance transfer
+/// * The last tick filled against if the fill was stopped by `max_ticks`, otherwise `None`
+/// * The number of ticks filled against
+/// * A `tick_pointer_moved` event if the fill moved the tick pointer of the filled direction
 ///
 /// Returns error if:
 /// * Provided order has zero quantity
+/// * `max_ticks` is zero
+/// * The order fills against more ticks than the configured maximum ticks per order
 /// * Tick to price conversion fails for any tick
-/// * Order is not fully filled
+/// * Order is not fully filled and was not stopped by `max_ticks`
+/// * Input is consumed without producing output, unless the order allows zero output
 ///
 /// CONTRACT: The caller must ensure that the necessary input funds were actually supplied.
-#[allow(clippy::manual_range_contains)]
+#[allow(clippy::manual_range_contains, clippy::type_complexity)]
 pub fn run_market_order(
     storage: &mut dyn Storage,
-    contract_address: Addr,
+    env: &Env,
     order: &mut MarketOrder,
     tick_bound: i64,
-) -> Result<(Uint256, MsgSend256), ContractError> {
+    max_ticks: Option<u32>,
+) -> Result<
+    (
+        Uint256,
+        Option<Decimal256>,
+        MsgSend256,
+        Option<i64>,
+        u32,
+        Option<Event>,
+    ),
+    ContractError,
+> {
+    // Ensure market orders are not paused
+    ensure_market_orders_not_paused(storage)?;
+
+    let orderbook = get_orderbook(storage)?;
+
     let PostMarketOrderState {
         output,
         tick_updates,
-        updated_orderbook,
-    } = run_market_order_internal(storage, order, tick_bound)?;
+        tick_fills,
+        mut updated_orderbook,
+        avg_price,
+        stopped_at_tick,
+        ..
+    } = run_market_order_with_layers(
+        storage,
+        order,
+        tick_bound,
+        Some(BATCHING_LAYER_COUNT - 1),
+        max_ticks,
+        env.block.height,
+    )?;
+    updated_orderbook.refresh_price_cache()?;
+
+    ensure_price_impact_within_limit(
+        storage,
+        order.order_direction.opposite(),
+        &orderbook,
+        &updated_orderbook,
+    )?;
 
     // After the core tick iteration loop, write all tick updates to state.
     // We cannot do this during the loop due to the borrow checker.
@@ -392,6 +1579,17 @@ pub fn run_market_order(
         TICK_STATE.save(storage, tick_id, &tick_state)?;
     }
 
+    // Remove the filled liquidity from the tick batching layers
+    let ticks_traversed = tick_fills.len() as u32;
+    for (tick_id, fill_amount) in tick_fills {
+        subtract_layer_liquidity(
+            storage,
+            order.order_direction.opposite(),
+            tick_id,
+            fill_amount,
+        )?;
+    }
+
     // Reduce the amount of liquidity in the opposite direction of the order by the output amount
     subtract_directional_liquidity(
         storage,
@@ -399,29 +1597,164 @@ pub fn run_market_order(
         Decimal256::from_ratio(output.amount, Uint256::one()),
     )?;
 
+    // Track the output actually filled, which is less than requested for partial fills
+    add_volume(storage, &output.denom, output.amount)?;
+    reset_dust_audit(storage);
+
     // Update tick pointers in orderbook
-    ORDERBOOK.save(storage, &updated_orderbook)?;
+    save_orderbook(storage, &mut updated_orderbook)?;
+
+    let tick_pointer_event = generate_tick_pointer_moved_event(
+        order.order_direction.opposite(),
+        &orderbook,
+        &updated_orderbook,
+    )?;
 
     Ok((
         output.amount,
+        avg_price,
         MsgSend256 {
-            from_address: contract_address.to_string(),
-            to_address: order.owner.to_string(),
+            from_address: env.contract.address.to_string(),
+            to_address: order.recipient().to_string(),
             amount: vec![output],
         },
+        stopped_at_tick,
+        ticks_traversed,
+        tick_pointer_event,
     ))
 }
 
+/// Ensures that a market order did not move the price of the filled direction by more than the
+/// configured `MAX_PRICE_IMPACT`, if any.
+///
+/// Impact is measured between the best price before the order and the price of the last tick
+/// filled, as `|end_price - start_price| / start_price`.
+///
+/// Errors with `PriceImpactTooHigh` if the impact exceeds the configured maximum.
+pub(crate) fn ensure_price_impact_within_limit(
+    storage: &dyn Storage,
+    direction: OrderDirection,
+    orderbook: &Orderbook,
+    updated_orderbook: &Orderbook,
+) -> ContractResult<()> {
+    let Some(max_price_impact) = get_max_price_impact(storage)? else {
+        return Ok(());
+    };
+
+    let start_price = orderbook.next_tick_price(direction)?;
+    let end_price = updated_orderbook.next_tick_price(direction)?;
+    ensure!(
+        !start_price.is_zero(),
+        ContractError::DivideByZero(DivideByZeroError::new(end_price))
+    );
+    let impact = end_price.abs_diff(start_price).checked_div(start_price)?;
+
+    ensure!(
+        impact <= max_price_impact,
+        ContractError::PriceImpactTooHigh { impact }
+    );
+
+    Ok(())
+}
+
+/// Ensures a market order's input is at least the configured minimum market order size, so that
+/// the book cannot be griefed with many tiny orders that each touch tick state.
+///
+/// Errors with `MarketOrderTooSmall` if the input is below the minimum.
+pub(crate) fn ensure_market_order_size(
+    storage: &dyn Storage,
+    quantity: Uint128,
+) -> ContractResult<()> {
+    let min_quantity = get_min_market_order_size(storage)?;
+    ensure!(
+        quantity >= min_quantity,
+        ContractError::MarketOrderTooSmall {
+            quantity,
+            min_quantity
+        }
+    );
+
+    Ok(())
+}
+
+/// Generates an event when a market order moves the tick pointer of the filled direction, giving
+/// indexers an explicit price movement signal. Returns `None` if the pointer did not move.
+fn generate_tick_pointer_moved_event(
+    direction: OrderDirection,
+    orderbook: &Orderbook,
+    updated_orderbook: &Orderbook,
+) -> ContractResult<Option<Event>> {
+    let (old_tick, new_tick) = match direction {
+        OrderDirection::Ask => (orderbook.next_ask_tick, updated_orderbook.next_ask_tick),
+        OrderDirection::Bid => (orderbook.next_bid_tick, updated_orderbook.next_bid_tick),
+    };
+    if old_tick == new_tick {
+        return Ok(None);
+    }
+
+    Ok(Some(Event::new("tick_pointer_moved").add_attributes(vec![
+        ("direction", direction.to_string()),
+        ("old_tick", old_tick.to_string()),
+        ("new_tick", new_tick.to_string()),
+        (
+            "old_price",
+            orderbook.next_tick_price(direction)?.to_string(),
+        ),
+        (
+            "new_price",
+            updated_orderbook.next_tick_price(direction)?.to_string(),
+        ),
+    ])))
+}
+
 /// Defines the state changes resulting from a market order.
 pub(crate) struct PostMarketOrderState {
     pub output: Coin256,
     pub tick_updates: Vec<(i64, TickState)>,
+    pub tick_fills: Vec<(i64, Decimal256)>,
+    // Input consumed and output produced by each tick filled against, in fill order
+    pub fill_reports: Vec<TickFillReport>,
+    // Input consumed by the fill, excluding any dust consumed without producing output
+    pub total_input: Uint256,
     pub updated_orderbook: Orderbook,
+    // Volume weighted average price of the fill, oriented the same way as tick prices.
+    // `None` if nothing was filled.
+    pub avg_price: Option<Decimal256>,
+    // The last tick filled against if the fill was stopped by a tick limit before completing.
+    pub stopped_at_tick: Option<i64>,
+}
+
+/// Tracks the progress of a market order as it is filled tick by tick.
+struct MarketFillState {
+    orderbook: Orderbook,
+    tick_updates: Vec<(i64, TickState)>,
+    tick_fills: Vec<(i64, Decimal256)>,
+    fill_reports: Vec<TickFillReport>,
+    total_input: Uint256,
+    total_output: Uint256,
+    // The price of the last tick iterated on, if no ticks are iterated price is constant
+    last_tick_price: Decimal256,
+    // Number of ticks filled against so far, bounded by `max_ticks` if provided
+    ticks_filled: u32,
+    max_ticks: Option<u32>,
+    // Filling against more ticks than this reverts the order, see `get_max_ticks_per_order`
+    max_ticks_per_order: u32,
+    // Orders placed after this height are still within the activation delay and cannot be filled.
+    // `None` if there is no activation delay
+    activation_cutoff: Option<u64>,
+    // Set if the fill was stopped by reaching `max_ticks` with input remaining
+    stopped_at_tick: Option<i64>,
+    // Set once the remaining input can no longer generate any output
+    done: bool,
 }
 
 /// Attempts to fill a market order against the orderbook. Due to the sumtree-based orderbook design,
 /// this does not require iterating linearly through all the filled orders.
 ///
+/// Ranges of ticks with no liquidity in the filled direction are skipped using the tick batching
+/// layers (see `state::LAYER_LIQUIDITY`), so the cost of a market order scales with the number of
+/// ticks that actually hold liquidity rather than the number of ticks that have ever been used.
+///
 /// Note that this mutates the `order` object and **does not perform any state mutations**
 ///
 /// Returns:
@@ -429,17 +1762,52 @@ pub(crate) struct PostMarketOrderState {
 /// * Any required tick state updates
 /// * The updated orderbook state
 ///
+/// Orders placed within the activation delay of `block_height` are not filled, see
+/// `get_activation_delay`.
+///
 /// Returns error if:
 /// * Provided order has zero quantity
 /// * Tick to price conversion fails for any tick
 /// * Order is not fully filled
+/// * Input is consumed without producing output, unless the order allows zero output
 ///
 /// CONTRACT: The caller must ensure that the necessary input funds were actually supplied.
-#[allow(clippy::manual_range_contains)]
 pub(crate) fn run_market_order_internal(
     storage: &dyn Storage,
     order: &mut MarketOrder,
     tick_bound: i64,
+    block_height: u64,
+) -> ContractResult<PostMarketOrderState> {
+    run_market_order_with_layers(
+        storage,
+        order,
+        tick_bound,
+        Some(BATCHING_LAYER_COUNT - 1),
+        None,
+        block_height,
+    )
+}
+
+/// Fills a market order by iterating every initialized tick in range, without consulting the
+/// tick batching layers. Used as a reference implementation for the batched fill.
+#[cfg(test)]
+pub(crate) fn run_market_order_internal_unbatched(
+    storage: &dyn Storage,
+    order: &mut MarketOrder,
+    tick_bound: i64,
+    block_height: u64,
+) -> ContractResult<PostMarketOrderState> {
+    run_market_order_with_layers(storage, order, tick_bound, None, None, block_height)
+}
+
+#[allow(clippy::manual_range_contains)]
+fn run_market_order_with_layers(
+    storage: &dyn Storage,
+    order: &mut MarketOrder,
+    tick_bound: i64,
+    top_layer: Option<u8>,
+    max_ticks: Option<u32>,
+    block_height: u64,
 ) -> ContractResult<PostMarketOrderState> {
     // Ensure order is non-empty
     ensure!(
@@ -449,8 +1817,17 @@ pub(crate) fn run_market_order_internal(
         }
     );
 
-    let mut orderbook = ORDERBOOK.load(storage)?;
-    let output_denom = orderbook.get_opposite_denom(&order.order_direction);
+    // Ensure the tick limit allows the order to make progress
+    ensure!(
+        max_ticks != Some(0),
+        ContractError::InvalidSwap {
+            error: "Max ticks cannot be zero".to_string()
+        }
+    );
+
+    let orderbook = get_orderbook(storage)?;
+    let output_denom = orderbook.output_denom(&order.order_direction);
+    let input_quantity = order.quantity;
 
     // Ensure the given tick bound is within global limits
     ensure!(
@@ -460,6 +1837,10 @@ pub(crate) fn run_market_order_internal(
         }
     );
 
+    // Only the liquidity opposite the order's direction is filled. If a bid and an ask rest on the
+    // same tick, a market bid starting from `next_ask_tick` fills only the asks on it and a market
+    // ask only the bids, leaving the other side of the tick untouched.
+    //
     // Derive appropriate bounds for tick iterator based on order direction:
     // * If the order is an Ask, we iterate from [next_bid_tick, tick_bound] in descending order.
     // * If the order is a Bid, we iterate from [tick_bound, next_ask_tick] in ascending order.
@@ -467,8 +1848,9 @@ pub(crate) fn run_market_order_internal(
         OrderDirection::Ask => {
             ensure!(
                 tick_bound <= orderbook.next_bid_tick,
-                ContractError::InvalidTickId {
-                    tick_id: tick_bound
+                ContractError::TickBoundCrossesCurrent {
+                    tick_bound,
+                    current_tick: orderbook.next_bid_tick
                 }
             );
             (tick_bound, orderbook.next_bid_tick, Order::Descending)
@@ -476,142 +1858,558 @@ pub(crate) fn run_market_order_internal(
         OrderDirection::Bid => {
             ensure!(
                 tick_bound >= orderbook.next_ask_tick,
-                ContractError::InvalidTickId {
-                    tick_id: tick_bound
+                ContractError::TickBoundCrossesCurrent {
+                    tick_bound,
+                    current_tick: orderbook.next_ask_tick
                 }
             );
             (orderbook.next_ask_tick, tick_bound, Order::Ascending)
         }
     };
 
-    // Create tick iterator between first tick and requested tick
-    let ticks = TICK_STATE.keys(
+    // Iterate through ticks and fill the market order as appropriate.
+    // Due to our sumtree-based design, this process carries only O(1) overhead per tick.
+    let mut fill_state = MarketFillState {
+        orderbook,
+        tick_updates: Vec::new(),
+        tick_fills: Vec::new(),
+        fill_reports: Vec::new(),
+        total_input: Uint256::zero(),
+        total_output: Uint256::zero(),
+        last_tick_price: Decimal256::one(),
+        ticks_filled: 0,
+        max_ticks,
+        max_ticks_per_order: get_max_ticks_per_order(storage)?,
+        activation_cutoff: match get_activation_delay(storage)? {
+            0 => None,
+            delay => Some(block_height.saturating_sub(delay)),
+        },
+        stopped_at_tick: None,
+        done: false,
+    };
+    fill_tick_range(
         storage,
-        Some(Bound::inclusive(min_tick)),
-        Some(Bound::inclusive(max_tick)),
+        &mut fill_state,
+        order,
+        top_layer,
+        min_tick,
+        max_tick,
         ordering,
+    )?;
+
+    // Determine if filling remaining amount on the last possible tick produced any value
+    // This will be 0 if the remaining balance is dust
+    let remaining_balance = output_for_input(
+        fill_state.last_tick_price,
+        order.quantity,
+        order.order_direction,
+    )?;
+
+    // Since full market orders must have their bound set at MIN_TICK or MAX_TICK,
+    // we identify partial market orders efficiently by checking if the order diverges
+    // from this pattern.
+    let partial_market_order = match order.order_direction {
+        OrderDirection::Ask => tick_bound > MIN_TICK,
+        OrderDirection::Bid => tick_bound < MAX_TICK,
+    };
+
+    // If, after iterating through all remaining ticks, the order quantity is still not filled (excluding dust),
+    // we error out as the orderbook has insufficient liquidity to fill the order.
+    //
+    // We bypass this check if the order is a partial market order, was stopped by the tick limit or
+    // opted into partial fills, all of which are allowed to have remaining input after completion.
+    ensure!(
+        remaining_balance.is_zero()
+            || partial_market_order
+            || fill_state.stopped_at_tick.is_some()
+            || order.allow_partial_fill,
+        ContractError::InsufficientLiquidity
     );
 
-    // Iterate through ticks and fill the market order as appropriate.
-    // Due to our sumtree-based design, this process carries only O(1) overhead per tick.
-    let mut total_output: Uint256 = Uint256::zero();
-    let mut tick_updates: Vec<(i64, TickState)> = Vec::new();
+    // Consuming input for nothing (e.g. when it truncates to zero output at an extreme tick) loses
+    // the taker's funds, so it is only allowed if the order explicitly opts into lossy fills.
+    let input_consumed = input_quantity.checked_sub(order.quantity)?;
+    ensure!(
+        !fill_state.total_output.is_zero() || input_consumed.is_zero() || order.allow_zero_output,
+        ContractError::ZeroOutput { input_consumed }
+    );
 
-    // The price of the last tick iterated on, if no ticks are iterated price is constant
-    let mut last_tick_price = Decimal256::one();
-    for maybe_current_tick in ticks {
-        let current_tick_id = maybe_current_tick?;
-        let mut current_tick = TICK_STATE.load(storage, current_tick_id)?;
-        let mut current_tick_values = current_tick.get_values(order.order_direction.opposite());
-        let tick_price = tick_to_price(current_tick_id)?;
-        last_tick_price = tick_price;
-
-        let output_quantity = amount_to_value(
-            order.order_direction,
-            order.quantity,
-            tick_price,
-            RoundingDirection::Down,
-        )?;
+    let avg_price = average_fill_price(
+        order.order_direction,
+        fill_state.total_input,
+        fill_state.total_output,
+    )?;
 
-        // If the output quantity is zero, the remaining input amount cannot generate any output.
-        // When this is the case, we consume the remaining input (which is either zero or rounding error dust)
-        // and terminate tick iteration.
-        if output_quantity.is_zero() {
-            order.quantity = Uint128::zero();
-            break;
-        }
+    Ok(PostMarketOrderState {
+        output: coin_u256(fill_state.total_output, &output_denom),
+        tick_updates: fill_state.tick_updates,
+        tick_fills: fill_state.tick_fills,
+        fill_reports: fill_state.fill_reports,
+        total_input: fill_state.total_input,
+        updated_orderbook: fill_state.orderbook,
+        avg_price,
+        stopped_at_tick: fill_state.stopped_at_tick,
+    })
+}
+
+/// Returns the volume weighted average price of a market order fill, oriented the same way as
+/// tick prices, or `None` if nothing was filled.
+pub(crate) fn average_fill_price(
+    order_direction: OrderDirection,
+    total_input: Uint256,
+    total_output: Uint256,
+) -> ContractResult<Option<Decimal256>> {
+    if total_input.is_zero() || total_output.is_zero() {
+        return Ok(None);
+    }
 
-        // Update current tick pointer as we visit ticks that contribute to filling the order
-        match order.order_direction.opposite() {
-            OrderDirection::Ask => orderbook.next_ask_tick = current_tick_id,
-            OrderDirection::Bid => orderbook.next_bid_tick = current_tick_id,
+    // Bids receive `input * price` while asks receive `input / price`, so the ratio is
+    // inverted for asks to keep the average price in the same orientation as tick prices.
+    Ok(Some(match order_direction {
+        OrderDirection::Bid => Decimal256::checked_from_ratio(total_output, total_input)?,
+        OrderDirection::Ask => Decimal256::checked_from_ratio(total_input, total_output)?,
+    }))
+}
+
+/// Fills the market order against the ticks in `[min_tick, max_tick]`, iterating in `ordering`.
+///
+/// If a batching layer is provided, only buckets in that layer which hold liquidity are descended into.
+/// The empty ranges between them are skipped in a way that leaves the fill state exactly as if each
+/// tick in them had been visited individually. Without a layer, every initialized tick is visited.
+fn fill_tick_range(
+    storage: &dyn Storage,
+    fill_state: &mut MarketFillState,
+    order: &mut MarketOrder,
+    layer: Option<u8>,
+    min_tick: i64,
+    max_tick: i64,
+    ordering: Order,
+) -> ContractResult<()> {
+    let Some(layer) = layer else {
+        // Create tick iterator between first tick and requested tick
+        let ticks = TICK_STATE.keys(
+            storage,
+            Some(Bound::inclusive(min_tick)),
+            Some(Bound::inclusive(max_tick)),
+            ordering,
+        );
+        for maybe_current_tick in ticks {
+            fill_tick(storage, fill_state, order, maybe_current_tick?)?;
+            if fill_state.done {
+                break;
+            }
         }
+        return Ok(());
+    };
 
-        let output_quantity_dec = Decimal256::from_ratio(output_quantity, Uint256::one());
+    let fill_direction = order.order_direction.opposite();
+    let buckets = get_layer_buckets(storage, layer, fill_direction, min_tick, max_tick, ordering);
 
-        // If order quantity is less than the current tick's liquidity, fill the whole order.
-        // Otherwise, fill the whole tick.
-        let fill_amount_dec = if output_quantity_dec < current_tick_values.total_amount_of_liquidity
-        {
-            output_quantity_dec
-        } else {
-            current_tick_values.total_amount_of_liquidity
+    // The first tick that has not yet been processed, in iteration order
+    let mut cursor = match ordering {
+        Order::Ascending => min_tick,
+        Order::Descending => max_tick,
+    };
+    for maybe_bucket in buckets {
+        let (bucket_min, bucket_max) = layer_bucket_range(layer, maybe_bucket?);
+        let (bucket_min, bucket_max) = (bucket_min.max(min_tick), bucket_max.min(max_tick));
+
+        // Skip the empty range leading up to this bucket
+        let (gap_min, gap_max, next_cursor) = match ordering {
+            Order::Ascending => (cursor, bucket_min - 1, bucket_max + 1),
+            Order::Descending => (bucket_max + 1, cursor, bucket_min - 1),
         };
+        skip_empty_tick_range(storage, fill_state, order, gap_min, gap_max, ordering)?;
+        if fill_state.done {
+            return Ok(());
+        }
+
+        // Descend into the bucket with liquidity
+        let lower_layer = layer.checked_sub(1);
+        fill_tick_range(
+            storage,
+            fill_state,
+            order,
+            lower_layer,
+            bucket_min,
+            bucket_max,
+            ordering,
+        )?;
+        if fill_state.done {
+            return Ok(());
+        }
 
-        // Update tick and order state to process the fill
-        current_tick_values.total_amount_of_liquidity = current_tick_values
-            .total_amount_of_liquidity
-            .checked_sub(fill_amount_dec)?;
+        cursor = next_cursor;
+    }
 
-        current_tick_values.effective_total_amount_swapped = current_tick_values
-            .effective_total_amount_swapped
-            .checked_add(fill_amount_dec)?;
+    // Skip the empty range after the last bucket with liquidity
+    match ordering {
+        Order::Ascending => {
+            skip_empty_tick_range(storage, fill_state, order, cursor, max_tick, ordering)
+        }
+        Order::Descending => {
+            skip_empty_tick_range(storage, fill_state, order, min_tick, cursor, ordering)
+        }
+    }
+}
 
-        // Note: this conversion errors if fill_amount_dec does not fit into Uint128
-        // By the time we get here, this should not be possible.
-        let fill_amount = Uint128::try_from(fill_amount_dec.to_uint_floor())?;
+/// Processes a range of ticks that holds no liquidity in the direction being filled.
+///
+/// Visiting an empty tick only moves the tick pointer and the last tick price to that tick,
+/// unless the remaining input cannot produce any output at its price, in which case the order
+/// terminates. As output only decreases as price worsens, checking the last tick in the range
+/// is sufficient to decide whether the whole range can be skipped.
+fn skip_empty_tick_range(
+    storage: &dyn Storage,
+    fill_state: &mut MarketFillState,
+    order: &mut MarketOrder,
+    min_tick: i64,
+    max_tick: i64,
+    ordering: Order,
+) -> ContractResult<()> {
+    if min_tick > max_tick {
+        return Ok(());
+    }
 
-        let input_filled = amount_to_value(
-            order.order_direction.opposite(),
-            fill_amount,
-            tick_price,
-            RoundingDirection::Up,
-        )?;
-        order.quantity = order
-            .quantity
-            // Safe conversions as amount filled should never be larger than order quantity which is upper bounded by Uint128::MAX
-            .checked_sub(Uint128::try_from(input_filled)?)?;
+    // Find the last initialized tick in iteration order
+    let reverse_ordering = match ordering {
+        Order::Ascending => Order::Descending,
+        Order::Descending => Order::Ascending,
+    };
+    let Some(last_tick_id) = TICK_STATE
+        .keys(
+            storage,
+            Some(Bound::inclusive(min_tick)),
+            Some(Bound::inclusive(max_tick)),
+            reverse_ordering,
+        )
+        .next()
+        .transpose()?
+    else {
+        return Ok(());
+    };
 
-        current_tick.set_values(order.order_direction.opposite(), current_tick_values);
-        // Add the updated tick state to the vector
-        tick_updates.push((current_tick_id, current_tick));
+    let tick_price = tick_to_price(last_tick_id)?;
+    let output_quantity = output_for_input(tick_price, order.quantity, order.order_direction)?;
 
-        total_output = total_output.checked_add(Uint256::from_uint128(fill_amount))?;
+    // The order terminates somewhere in this range, so it must be walked tick by tick
+    if output_quantity.is_zero() {
+        return fill_tick_range(
+            storage, fill_state, order, None, min_tick, max_tick, ordering,
+        );
     }
 
-    // Determine if filling remaining amount on the last possible tick produced any value
-    // This will be 0 if the remaining balance is dust
-    let remaining_balance = amount_to_value(
-        order.order_direction,
-        order.quantity,
-        last_tick_price,
-        RoundingDirection::Down,
-    )?;
+    fill_state.last_tick_price = tick_price;
+    match order.order_direction.opposite() {
+        OrderDirection::Ask => fill_state.orderbook.next_ask_tick = last_tick_id,
+        OrderDirection::Bid => fill_state.orderbook.next_bid_tick = last_tick_id,
+    }
 
-    // Since full market orders must have their bound set at MIN_TICK or MAX_TICK,
-    // we identify partial market orders efficiently by checking if the order diverges
-    // from this pattern.
-    let partial_market_order = match order.order_direction {
-        OrderDirection::Ask => tick_bound > MIN_TICK,
-        OrderDirection::Bid => tick_bound < MAX_TICK,
+    Ok(())
+}
+
+/// Fills as much of the market order as possible against a single tick.
+fn fill_tick(
+    storage: &dyn Storage,
+    fill_state: &mut MarketFillState,
+    order: &mut MarketOrder,
+    current_tick_id: i64,
+) -> ContractResult<()> {
+    let mut current_tick = TICK_STATE.load(storage, current_tick_id)?;
+    let mut current_tick_values = current_tick.get_values(order.order_direction.opposite());
+    let tick_price = tick_to_price(current_tick_id)?;
+    fill_state.last_tick_price = tick_price;
+
+    // Orders restart the activation delay whenever they are queued, so orders still within it are
+    // queued behind all fillable liquidity on the tick and none of them can have been filled.
+    // Capping the fill at the liquidity ahead of them leaves them untouched.
+    let available_liquidity = match fill_state.activation_cutoff {
+        Some(cutoff_height) => current_tick_values.total_amount_of_liquidity.checked_sub(
+            get_liquidity_queued_after(
+                storage,
+                current_tick_id,
+                order.order_direction.opposite(),
+                cutoff_height,
+            )?,
+        )?,
+        None => current_tick_values.total_amount_of_liquidity,
     };
 
-    // If, after iterating through all remaining ticks, the order quantity is still not filled (excluding dust),
-    // we error out as the orderbook has insufficient liquidity to fill the order.
+    let output_quantity = output_for_input(tick_price, order.quantity, order.order_direction)?;
+
+    // If the output quantity is zero, the remaining input amount cannot generate any output.
+    // When this is the case, we consume the remaining input (which is either zero or rounding error dust)
+    // and terminate tick iteration.
+    if output_quantity.is_zero() {
+        // If nothing has been filled yet, the input is not dust: it cannot buy a single unit of
+        // this tick's liquidity, so we error rather than consume the whole order for no output.
+        ensure!(
+            order.quantity.is_zero()
+                || !fill_state.total_output.is_zero()
+                || available_liquidity.is_zero(),
+            ContractError::PriceUnderflow {
+                tick_id: current_tick_id
+            }
+        );
+        order.quantity = Uint128::zero();
+        fill_state.done = true;
+        return Ok(());
+    }
+
+    // Update current tick pointer as we visit ticks that contribute to filling the order.
+    //
+    // A bid and an ask may rest on the same tick, leaving `next_bid_tick == next_ask_tick`. Only the
+    // opposite direction's pointer is moved here, so the overlap is left for the next order.
+    match order.order_direction.opposite() {
+        OrderDirection::Ask => fill_state.orderbook.next_ask_tick = current_tick_id,
+        OrderDirection::Bid => fill_state.orderbook.next_bid_tick = current_tick_id,
+    }
+
+    // Cancelled liquidity is removed from the tick's liquidity when the cancel is processed, while
+    // the cancelled ETAS range is only realized on the next sync. Fills are therefore bounded by the
+    // remaining liquidity rather than by ETAS, so cancelled liquidity can never be filled.
+    //
+    // A tick with no remaining liquidity (e.g. all of its orders were cancelled or are not yet
+    // active) is passed over like an empty tick: it produces no fill, is not written to and does
+    // not count towards `max_ticks`.
+    if available_liquidity.is_zero() {
+        return Ok(());
+    }
+
+    let output_quantity_dec = Decimal256::from_ratio(output_quantity, Uint256::one());
+
+    // If order quantity is less than the current tick's available liquidity, fill the whole order.
+    // Otherwise, fill the whole tick.
+    let fill_amount_dec = if output_quantity_dec < available_liquidity {
+        output_quantity_dec
+    } else {
+        available_liquidity
+    };
+
+    // Ticks are visited strictly in iteration order, so a tick's liquidity can never be filled
+    // twice by the same order. Guard against it as a revisit would pay out the same liquidity again.
+    if let Some((last_tick_id, _)) = fill_state.tick_updates.last() {
+        let in_order = match order.order_direction {
+            OrderDirection::Bid => current_tick_id > *last_tick_id,
+            OrderDirection::Ask => current_tick_id < *last_tick_id,
+        };
+        ensure!(
+            in_order,
+            ContractError::DuplicateTickFill {
+                tick_id: current_tick_id
+            }
+        );
+    }
+
+    // Update tick and order state to process the fill
+    current_tick_values.total_amount_of_liquidity = current_tick_values
+        .total_amount_of_liquidity
+        .checked_sub(fill_amount_dec)?;
+
+    current_tick_values.effective_total_amount_swapped = current_tick_values
+        .effective_total_amount_swapped
+        .checked_add(fill_amount_dec)?;
+
+    // Note: this conversion errors if fill_amount_dec does not fit into Uint128
+    // By the time we get here, this should not be possible.
     //
-    // We bypass this check if the order is a partial market order, which is allowed to have remaining input after
-    // completion.
+    // The fill amount is paid out by the orderbook, so it is rounded down.
+    let fill_amount = Uint128::try_from(RoundingMode::Down.round(fill_amount_dec))?;
+
+    let input_filled = input_for_output(tick_price, fill_amount, order.order_direction)?;
+    order.quantity = order
+        .quantity
+        // Safe conversions as amount filled should never be larger than order quantity which is upper bounded by Uint128::MAX
+        .checked_sub(Uint128::try_from(input_filled)?)?;
+
+    current_tick.set_values(order.order_direction.opposite(), current_tick_values);
+    // Add the updated tick state to the vector
+    fill_state
+        .tick_updates
+        .push((current_tick_id, current_tick));
+    if !fill_amount_dec.is_zero() {
+        fill_state
+            .tick_fills
+            .push((current_tick_id, fill_amount_dec));
+        fill_state.fill_reports.push(TickFillReport {
+            tick_id: current_tick_id,
+            price: tick_price,
+            input_consumed: input_filled,
+            output_produced: Uint256::from_uint128(fill_amount),
+        });
+        fill_state.ticks_filled += 1;
+
+        // Orders fragmented across too many ticks are reverted rather than partially filled
+        ensure!(
+            fill_state.ticks_filled <= fill_state.max_ticks_per_order,
+            ContractError::TooManyTicks {
+                limit: fill_state.max_ticks_per_order
+            }
+        );
+    }
+
+    fill_state.total_input = fill_state.total_input.checked_add(input_filled)?;
+    fill_state.total_output = fill_state
+        .total_output
+        .checked_add(Uint256::from_uint128(fill_amount))?;
+
+    // Stop filling once the tick limit is reached, leaving any remaining input unfilled
+    if !order.quantity.is_zero() && Some(fill_state.ticks_filled) == fill_state.max_ticks {
+        fill_state.stopped_at_tick = Some(current_tick_id);
+        fill_state.done = true;
+    }
+
+    Ok(())
+}
+
+/// Ensures that bank sends from the contract can be delivered to `address`.
+///
+/// Owners are validated when orders are placed, but this guards every send to an order owner
+/// in case a stored address is no longer valid.
+fn ensure_deliverable(api: &dyn Api, address: &Addr) -> ContractResult<()> {
+    api.addr_validate(address.as_str())
+        .map_err(|_| ContractError::UndeliverableRecipient {
+            address: address.to_string(),
+        })?;
+    Ok(())
+}
+
+/// Generates the message transferring `amount` to `to_address`.
+///
+/// Native denoms are sent with a `BankMsg::Send`, while CW20 denoms are sent with a `Transfer`
+/// executed on the token contract.
+pub(crate) fn transfer_msg(
+    storage: &dyn Storage,
+    to_address: String,
+    amount: Coin,
+) -> ContractResult<CosmosMsg> {
+    match get_asset_type(storage, &amount.denom)? {
+        AssetType::Native => Ok(BankMsg::Send {
+            to_address,
+            amount: vec![amount],
+        }
+        .into()),
+        AssetType::Cw20 => Ok(WasmMsg::Execute {
+            contract_addr: amount.denom,
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: to_address,
+                amount: amount.amount,
+            })?,
+            funds: vec![],
+        }
+        .into()),
+    }
+}
+
+/// Converts a `MsgSend256` into the message paying it out.
+///
+/// Single coin sends of a CW20 denom are converted into a CW20 `Transfer`, all other sends
+/// are dispatched as bank sends.
+pub(crate) fn payout_msg(storage: &dyn Storage, send: MsgSend256) -> ContractResult<CosmosMsg> {
+    if let [coin] = send.amount.as_slice() {
+        if get_asset_type(storage, &coin.denom)? == AssetType::Cw20 {
+            let amount = Coin {
+                denom: coin.denom.clone(),
+                amount: Uint128::try_from(coin.amount)?,
+            };
+            return transfer_msg(storage, send.to_address, amount);
+        }
+    }
+    Ok(send.into())
+}
+
+/// Calculates the bounty paid to the claimer out of the gross amount of a claim.
+///
+/// The bounty is rounded down in favor of the order owner and clamped to the gross amount, so the
+/// owner's payout can never underflow regardless of rounding.
+///
+/// Errors if the bounty ratio is above 100%, as no fill could ever pay it. Bounties are capped at 1%
+/// on placement, so this can only be reached through invalid order state.
+pub(crate) fn calculate_claim_bounty(
+    gross_amount: Uint256,
+    claim_bounty: Decimal256,
+) -> ContractResult<Uint256> {
     ensure!(
-        remaining_balance.is_zero() || partial_market_order,
-        ContractError::InsufficientLiquidity
+        claim_bounty <= Decimal256::one(),
+        ContractError::ClaimBountyExceedsFill { claim_bounty }
     );
 
-    Ok(PostMarketOrderState {
-        output: coin_u256(total_output, &output_denom),
-        tick_updates,
-        updated_orderbook: orderbook,
-    })
+    let bounty = multiply_by_ratio(gross_amount, claim_bounty, RoundingMode::Down)?;
+    Ok(bounty.min(gross_amount))
+}
+
+/// Checks that the tick values an order is claimed against are consistent with the order.
+///
+/// Errors with `ClaimInconsistency` if:
+/// 1. The tick was not synced up to the ETAS it held before the claim
+/// 2. The tick's realized cancels exceed its ETAS
+/// 3. The claimed amount extends past the tick's `effective_total_amount_swapped - order.etas`
+/// 4. The order extends past the tick's cumulative total value
+fn ensure_claim_consistency(
+    order: &LimitOrder,
+    tick_values: &TickValues,
+    amount_filled: Decimal256,
+    pre_sync_etas: Decimal256,
+) -> ContractResult<()> {
+    let claimable = tick_values
+        .effective_total_amount_swapped
+        .checked_sub(order.etas)
+        .unwrap_or_default();
+    let order_end = order
+        .etas
+        .checked_add(Decimal256::from_ratio(order.quantity, 1u128))?;
+
+    ensure!(
+        tick_values.last_tick_sync_etas == pre_sync_etas
+            && tick_values.cumulative_realized_cancels
+                <= tick_values.effective_total_amount_swapped
+            && amount_filled <= claimable
+            && order_end <= tick_values.cumulative_total_value,
+        ContractError::ClaimInconsistency {
+            tick_id: order.tick_id,
+            order_id: order.order_id,
+        }
+    );
+
+    Ok(())
 }
 
 // Note: This can be called by anyone
 pub(crate) fn claim_order(
     storage: &mut dyn Storage,
-    contract_address: Addr,
+    api: &dyn Api,
+    env: &Env,
     sender: Addr,
     tick_id: i64,
     order_id: u64,
+    waive_bounty: bool,
 ) -> ContractResult<(Uint256, Vec<SubMsg>, LimitOrder)> {
-    let orderbook = ORDERBOOK.load(storage)?;
+    let (amount, bank_sends, order) =
+        claim_order_internal(storage, api, env, sender, tick_id, order_id, waive_bounty)?;
+    let bank_msgs = bank_sends
+        .into_iter()
+        .map(|(send, reply_id)| Ok(SubMsg::reply_on_error(payout_msg(storage, send)?, reply_id)))
+        .collect::<ContractResult<Vec<SubMsg>>>()?;
+
+    Ok((amount, bank_msgs, order))
+}
+
+/// Claims the filled portion of an order, returning the required bank sends alongside
+/// the reply ID each should be dispatched with.
+///
+/// The claim bounty is skipped if `waive_bounty` is set and the sender owns the order. The maker
+/// fee is discounted if the order was placed at least the discount's `min_blocks` ago.
+#[allow(clippy::type_complexity)]
+fn claim_order_internal(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    env: &Env,
+    sender: Addr,
+    tick_id: i64,
+    order_id: u64,
+    waive_bounty: bool,
+) -> ContractResult<(Uint256, Vec<(MsgSend256, u64)>, LimitOrder)> {
+    let orderbook = get_orderbook(storage)?;
     // Fetch tick values for current order direction
     let tick_state = TICK_STATE
         .may_load(storage, tick_id)?
@@ -619,13 +2417,17 @@ pub(crate) fn claim_order(
 
     let key = (tick_id, order_id);
     // Check for the order, error if not found
-    let mut order = orders()
-        .may_load(storage, &key)?
-        .ok_or(ContractError::OrderNotFound { tick_id, order_id })?;
+    let mut order = load_order(storage, tick_id, order_id)?;
+
+    // Ensure the claim can be delivered to the order owner before any state is updated
+    ensure_deliverable(api, &order.owner)?;
 
     // Sync the tick the order is on to ensure correct ETAS
     let bid_tick_values = tick_state.get_values(OrderDirection::Bid);
     let ask_tick_values = tick_state.get_values(OrderDirection::Ask);
+    let tick_state_pre_sync_etas = tick_state
+        .get_values(order.order_direction)
+        .effective_total_amount_swapped;
     sync_tick(
         storage,
         tick_id,
@@ -652,7 +2454,15 @@ pub(crate) fn claim_order(
         .effective_total_amount_swapped
         .checked_sub(order.etas)?
         .min(Decimal256::from_ratio(order.quantity, 1u128));
-    let amount_filled = Uint128::try_from(amount_filled_dec.to_uint_floor())?;
+    let amount_filled = Uint128::try_from(RoundingMode::Down.round(amount_filled_dec))?;
+
+    // Defense in depth guardrail: ensure the tick was synced and the claim is within bounds.
+    ensure_claim_consistency(
+        &order,
+        &tick_values,
+        amount_filled_dec,
+        tick_state_pre_sync_etas,
+    )?;
 
     // Update order state to reflect the claimed amount.
     //
@@ -660,10 +2470,19 @@ pub(crate) fn claim_order(
     // the order should effectively be left as a fresh order with the remaining quantity.
     order.quantity = order.quantity.checked_sub(amount_filled)?;
     order.etas = order.etas.checked_add(amount_filled_dec)?;
+    subtract_queued_liquidity(storage, &order, amount_filled)?;
 
     // If order fully filled then remove
     if order.quantity.is_zero() {
         orders().remove(storage, &key)?;
+        AUTO_SETTLE_ORDERS.remove(
+            storage,
+            (&order.order_direction.to_string(), tick_id, order_id),
+        );
+        remove_client_order_id(storage, tick_id, order_id)?;
+        REMOVED_ORDERS.save(storage, &key, &OrderNotFoundReason::Claimed)?;
+        decrement_tick_order_count(storage, tick_id, order.order_direction)?;
+        decrement_order_count(storage, &order.owner)?;
     // Else update in state
     } else {
         orders().save(storage, &key, &order)?;
@@ -675,32 +2494,39 @@ pub(crate) fn claim_order(
         order.order_direction,
         amount_filled,
         tick_price,
-        RoundingDirection::Down,
+        RoundingMode::Down,
     )?;
     // Immutable amount to prevent bounty/maker fee calculations affecting each other
     let raw_amount = amount;
 
-    let denom = orderbook.get_opposite_denom(&order.order_direction);
+    let denom = orderbook.output_denom(&order.order_direction);
 
-    // Send claim bounty to sender if applicable
+    // Send claim bounty to sender if applicable, unless the owner waived it on a self-claim
+    let bounty_waived = waive_bounty && sender == order.owner;
     let mut bounty = Uint256::zero();
-    if let Some(claim_bounty) = order.claim_bounty {
+    if let Some(claim_bounty) = order.claim_bounty.filter(|_| !bounty_waived) {
         // Skip this step if the output amount is zero.
         //
         // We use a nested if here because combining `let` with logical operator
         // is currently unstable in Rust.
         if !amount.is_zero() {
-            // Multiply by the claim bounty ratio and convert to Uint128.
             // Ensure claimed amount is updated to reflect the bounty.
-            let bounty_amount =
-                Decimal256::from_ratio(amount, Uint256::one()).checked_mul(claim_bounty)?;
-            bounty = bounty_amount.to_uint_floor();
+            bounty = calculate_claim_bounty(amount, claim_bounty)?;
             amount = amount.checked_sub(bounty)?;
         }
     }
 
     // Get the current maker fee for this orderbook
-    let maker_fee = get_maker_fee(storage)?;
+    let mut maker_fee = get_maker_fee(storage)?;
+
+    // Orders that rested long enough have their maker fee discounted. Fills are not tracked per
+    // order, so the time the order rested is measured up to the claim.
+    if let Some(discount) = get_maker_fee_discount(storage)? {
+        let blocks_rested = env.block.height.saturating_sub(order.placed_at_height);
+        if blocks_rested >= discount.min_blocks {
+            maker_fee = maker_fee.checked_mul(Decimal256::one().checked_sub(discount.discount)?)?;
+        }
+    }
     let mut maker_fee_amount = Uint256::zero();
     if !maker_fee.is_zero() {
         // Skip this step if the output amount is zero.
@@ -709,9 +2535,12 @@ pub(crate) fn claim_order(
         // is currently unstable in Rust.
         if !amount.is_zero() {
             // Calculate the fee amount based on the quantity originally being sent to the claimer
-            maker_fee_amount = Decimal256::from_ratio(raw_amount, 1u128)
-                .checked_mul(maker_fee)?
-                .to_uint_floor();
+            maker_fee_amount = multiply_by_ratio(raw_amount, maker_fee, RoundingMode::Down)?;
+
+            // Fees too small to be worth transferring are waived to the claimer
+            if maker_fee_amount < Uint256::from_uint128(get_min_fee_transfer(storage)?) {
+                maker_fee_amount = Uint256::zero();
+            }
             amount = amount.checked_sub(maker_fee_amount)?;
         }
     }
@@ -721,21 +2550,21 @@ pub(crate) fn claim_order(
     if !amount.is_zero() {
         // Claimed amount always goes to the order owner
         let bank_msg = MsgSend256 {
-            from_address: contract_address.to_string(),
+            from_address: env.contract.address.to_string(),
             to_address: order.owner.to_string(),
             amount: vec![coin_u256(amount, &denom)],
         };
-        bank_msg_vec.push(SubMsg::reply_on_error(bank_msg, REPLY_ID_CLAIM));
+        bank_msg_vec.push((bank_msg, REPLY_ID_CLAIM));
     }
 
     if !bounty.is_zero() {
         // Bounty always goes to the sender
         let bounty_msg = MsgSend256 {
-            from_address: contract_address.to_string(),
+            from_address: env.contract.address.to_string(),
             to_address: sender.to_string(),
             amount: vec![coin_u256(bounty, &denom)],
         };
-        bank_msg_vec.push(SubMsg::reply_on_error(bounty_msg, REPLY_ID_CLAIM_BOUNTY));
+        bank_msg_vec.push((bounty_msg, REPLY_ID_CLAIM_BOUNTY));
     }
 
     if !maker_fee_amount.is_zero() {
@@ -744,11 +2573,11 @@ pub(crate) fn claim_order(
             .may_load(storage)?
             .ok_or(ContractError::NoMakerFeeRecipient)?;
         let maker_fee_msg = MsgSend256 {
-            from_address: contract_address.to_string(),
+            from_address: env.contract.address.to_string(),
             to_address: maker_fee_recipient.to_string(),
             amount: vec![coin_u256(maker_fee_amount, &denom)],
         };
-        bank_msg_vec.push(SubMsg::reply_on_error(maker_fee_msg, REPLY_ID_MAKER_FEE));
+        bank_msg_vec.push((maker_fee_msg, REPLY_ID_MAKER_FEE));
     }
 
     Ok((amount, bank_msg_vec, order))
@@ -1,34 +1,79 @@
 use crate::constants::{
-    DEFAULT_MAKER_FEE, DEFAULT_MAKER_FEE_RECIPIENT, MAX_MAKER_FEE_PERCENTAGE, MAX_TICK, MIN_TICK,
+    DEFAULT_MAKER_FEE, DEFAULT_MAKER_FEE_RECIPIENT, DEFAULT_REBUILD_LIMIT,
+    MAX_MAKER_FEE_PERCENTAGE, MAX_TICK, MIN_TICK,
 };
 use crate::error::ContractResult;
-use crate::state::{MAKER_FEE, MAKER_FEE_RECIPIENT, ORDERBOOK};
-use crate::types::Orderbook;
+use crate::state::{
+    advance_derived_state_rebuild, get_asset_type, save_orderbook, ACTIVATION_DELAY,
+    CROSSING_TICK_POLICY, MAKER_FEE, MAKER_FEE_DISCOUNT, MAKER_FEE_RECIPIENT, MAX_BATCH_CLAIM,
+    MAX_CLAIM_BOUNTY, MAX_ORDERS_PER_TICK, MAX_PRICE_IMPACT, MAX_TICKS_PER_ORDER, MIN_FEE_TRANSFER,
+    MIN_MARKET_ORDER_SIZE, PLACEMENT_FEE, PRUNE_REWARD,
+};
+use crate::types::{AssetType, CrossingTickPolicy, MakerFeeDiscount, Orderbook};
 use crate::ContractError;
-use cosmwasm_std::{ensure, Decimal256, DepsMut, Storage};
+use cosmwasm_std::{ensure, Coin, Decimal256, DepsMut, MessageInfo, Response, Storage, Uint128};
+use cw_utils::nonpayable;
 
+/// Creates the orderbook for the given denom pair.
+///
+/// Limit orders can optionally be restricted to the tick range `[min_tick, max_tick]`, for example
+/// for markets that only ever trade in a narrow band. Overrides are clamped to the global
+/// `MIN_TICK`/`MAX_TICK` constants, which are also used when no override is given.
+///
+/// Denoms registered as `AssetType::Cw20` in `ASSET_TYPES` are validated as contract addresses
+/// rather than by their bank supply.
+///
+/// Errors if:
+/// 1. The denoms are the same
+/// 2. Either denom does not exist
+/// 3. The resulting `min_tick` is greater than the resulting `max_tick`
 pub fn create_orderbook(
     deps: DepsMut,
     quote_denom: String,
     base_denom: String,
+    min_tick: Option<i64>,
+    max_tick: Option<i64>,
 ) -> ContractResult<()> {
     let denoms = [quote_denom.clone(), base_denom.clone()];
 
     ensure!(quote_denom != base_denom, ContractError::DuplicateDenoms {});
 
     for denom in denoms {
-        let maybe_supply = deps.querier.query_supply(denom.clone());
+        match get_asset_type(deps.storage, &denom)? {
+            AssetType::Native => {
+                let maybe_supply = deps.querier.query_supply(denom.clone());
 
-        // Ensure denom exists and has at least 1 token
-        ensure!(
-            maybe_supply.is_ok() && !maybe_supply.unwrap().amount.is_zero(),
-            ContractError::InvalidDenom { denom }
-        );
+                // Ensure denom exists and has at least 1 token
+                ensure!(
+                    maybe_supply.is_ok() && !maybe_supply.unwrap().amount.is_zero(),
+                    ContractError::InvalidDenom { denom }
+                );
+            }
+            // CW20 denoms are the address of the token contract
+            AssetType::Cw20 => {
+                ensure!(
+                    deps.api.addr_validate(&denom).is_ok(),
+                    ContractError::InvalidDenom { denom }
+                );
+            }
+        }
     }
 
-    // Instantiate orderbook and write to state
-    let book = Orderbook::new(quote_denom, base_denom, 0, MIN_TICK, MAX_TICK);
-    ORDERBOOK.save(deps.storage, &book)?;
+    // Clamp the tick bounds to the global limits
+    let min_tick = min_tick.map_or(MIN_TICK, |tick| tick.max(MIN_TICK));
+    let max_tick = max_tick.map_or(MAX_TICK, |tick| tick.min(MAX_TICK));
+    ensure!(
+        min_tick <= max_tick,
+        ContractError::InvalidTickBounds { min_tick, max_tick }
+    );
+
+    // Instantiate orderbook and write to state.
+    //
+    // Tick pointers start at the global bound furthest from the spread, rather than at the
+    // restricted bounds, so the first placement on each side always moves its pointer.
+    let mut book = Orderbook::new(quote_denom, base_denom, 0, MIN_TICK, MAX_TICK)
+        .with_tick_bounds(min_tick, max_tick);
+    save_orderbook(deps.storage, &mut book)?;
 
     // Set maker fee
     set_maker_fee(deps.storage, DEFAULT_MAKER_FEE)?;
@@ -53,6 +98,149 @@ pub fn set_maker_fee(
     Ok(maker_fee)
 }
 
+/// Sets the maximum number of orders allowed on a single tick for a given direction.
+pub fn set_max_orders_per_tick(
+    storage: &mut dyn Storage,
+    max_orders_per_tick: u64,
+) -> ContractResult<u64> {
+    ensure!(
+        max_orders_per_tick > 0,
+        ContractError::InvalidMaxOrdersPerTick
+    );
+    MAX_ORDERS_PER_TICK.save(storage, &max_orders_per_tick)?;
+
+    Ok(max_orders_per_tick)
+}
+
+/// Sets the maximum number of ticks a single market order may fill against.
+pub fn set_max_ticks_per_order(
+    storage: &mut dyn Storage,
+    max_ticks_per_order: u32,
+) -> ContractResult<u32> {
+    ensure!(
+        max_ticks_per_order > 0,
+        ContractError::InvalidMaxTicksPerOrder
+    );
+    MAX_TICKS_PER_ORDER.save(storage, &max_ticks_per_order)?;
+
+    Ok(max_ticks_per_order)
+}
+
+/// Sets the upper bound for claim bounties attached to newly placed limit orders.
+pub fn set_max_claim_bounty(
+    storage: &mut dyn Storage,
+    max_claim_bounty: Decimal256,
+) -> ContractResult<Decimal256> {
+    ensure!(
+        max_claim_bounty <= Decimal256::one(),
+        ContractError::InvalidMaxClaimBounty { max_claim_bounty }
+    );
+    MAX_CLAIM_BOUNTY.save(storage, &max_claim_bounty)?;
+
+    Ok(max_claim_bounty)
+}
+
+/// Sets the maximum number of orders processed by a single batch claim.
+pub fn set_max_batch_claim(storage: &mut dyn Storage, max_batch_claim: u32) -> ContractResult<u32> {
+    ensure!(max_batch_claim > 0, ContractError::InvalidMaxBatchClaim);
+    MAX_BATCH_CLAIM.save(storage, &max_batch_claim)?;
+
+    Ok(max_batch_claim)
+}
+
+/// Sets the maximum price impact of a market order, removing the bound if `None` is provided.
+pub fn set_max_price_impact(
+    storage: &mut dyn Storage,
+    max_price_impact: Option<Decimal256>,
+) -> ContractResult<()> {
+    match max_price_impact {
+        Some(max_price_impact) => MAX_PRICE_IMPACT.save(storage, &max_price_impact)?,
+        None => MAX_PRICE_IMPACT.remove(storage),
+    }
+    Ok(())
+}
+
+/// Sets the maker fee amount below which fees are waived rather than transferred.
+pub fn set_min_fee_transfer(
+    storage: &mut dyn Storage,
+    min_fee_transfer: Uint128,
+) -> ContractResult<Uint128> {
+    MIN_FEE_TRANSFER.save(storage, &min_fee_transfer)?;
+
+    Ok(min_fee_transfer)
+}
+
+/// Sets the smallest input accepted for a market order, zero accepting any non-zero input.
+pub fn set_min_market_order_size(
+    storage: &mut dyn Storage,
+    min_market_order_size: Uint128,
+) -> ContractResult<Uint128> {
+    MIN_MARKET_ORDER_SIZE.save(storage, &min_market_order_size)?;
+
+    Ok(min_market_order_size)
+}
+
+/// Sets the reward paid for pruning a tick, removing it if `None` is provided.
+pub fn set_prune_reward(storage: &mut dyn Storage, reward: Option<Coin>) -> ContractResult<()> {
+    match reward {
+        Some(reward) => PRUNE_REWARD.save(storage, &reward)?,
+        None => PRUNE_REWARD.remove(storage),
+    }
+    Ok(())
+}
+
+/// Sets the flat fee charged for placing a limit order, removing it if `None` is provided.
+pub fn set_placement_fee(storage: &mut dyn Storage, fee: Option<Coin>) -> ContractResult<()> {
+    match fee {
+        Some(fee) => PLACEMENT_FEE.save(storage, &fee)?,
+        None => PLACEMENT_FEE.remove(storage),
+    }
+    Ok(())
+}
+
+/// Sets the maker fee discount for long resting orders, removing it if `None` is provided.
+///
+/// Errors with `InvalidMakerFeeDiscount` if the discount exceeds one.
+pub fn set_maker_fee_discount(
+    storage: &mut dyn Storage,
+    discount: Option<MakerFeeDiscount>,
+) -> ContractResult<()> {
+    match discount {
+        Some(discount) => {
+            ensure!(
+                discount.discount <= Decimal256::one(),
+                ContractError::InvalidMakerFeeDiscount {
+                    discount: discount.discount
+                }
+            );
+            MAKER_FEE_DISCOUNT.save(storage, &discount)?
+        }
+        None => MAKER_FEE_DISCOUNT.remove(storage),
+    }
+    Ok(())
+}
+
+/// Sets the number of blocks after placement before a limit order can be filled, zero making
+/// orders fillable immediately.
+pub fn set_activation_delay(
+    storage: &mut dyn Storage,
+    activation_delay: u64,
+) -> ContractResult<u64> {
+    ACTIVATION_DELAY.save(storage, &activation_delay)?;
+
+    Ok(activation_delay)
+}
+
+/// Sets how limit orders placed exactly on the best opposite tick are handled.
+pub fn set_crossing_tick_policy(
+    storage: &mut dyn Storage,
+    policy: CrossingTickPolicy,
+) -> ContractResult<CrossingTickPolicy> {
+    CROSSING_TICK_POLICY.save(storage, &policy)?;
+
+    Ok(policy)
+}
+
 /// Sets the recipient address for the maker fee for the orderbook.
 pub fn set_maker_fee_recipient(deps: DepsMut, maker_fee_recipient: &str) -> ContractResult<()> {
     let addr = deps
@@ -63,3 +251,27 @@ pub fn set_maker_fee_recipient(deps: DepsMut, maker_fee_recipient: &str) -> Cont
 
     Ok(())
 }
+
+/// Advances the rebuild of the state derived from the ticks and orders started by `migrate`.
+///
+/// Callable by anyone, as the rebuild only ever recomputes state from the ticks and orders. Takes
+/// up to `limit` steps, defaulting to `DEFAULT_REBUILD_LIMIT`.
+///
+/// Errors if funds are sent with the message.
+pub(crate) fn rebuild_derived_state(
+    deps: DepsMut,
+    info: MessageInfo,
+    limit: Option<u32>,
+) -> ContractResult<Response> {
+    nonpayable(&info)?;
+
+    // A zero limit would never make progress
+    let limit = limit.unwrap_or(DEFAULT_REBUILD_LIMIT).max(1);
+    let complete = advance_derived_state_rebuild(deps.storage, limit)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("method", "rebuildDerivedState"),
+        ("sender", info.sender.as_str()),
+        ("rebuild", if complete { "complete" } else { "in_progress" }),
+    ]))
+}
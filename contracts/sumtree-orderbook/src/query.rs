@@ -1,24 +1,36 @@
 use std::str::FromStr;
 
-use cosmwasm_std::{coin, ensure, Addr, Coin, Decimal, Decimal256, Deps, Fraction, Order, Uint128};
+use cosmwasm_std::{
+    coin, ensure, Addr, Coin, Decimal, Decimal256, Deps, Env, Order, StdResult, Timestamp, Uint128,
+    Uint256, Uint64,
+};
 use cw_storage_plus::Bound;
 
 use crate::{
-    constants::{MAX_TICK, MIN_TICK},
+    constants::{MAX_TICK, MAX_TICK_SCAN_ITERATIONS, MIN_TICK},
     error::ContractResult,
     msg::{
-        CalcOutAmtGivenInResponse, DenomsResponse, GetSwapFeeResponse,
-        GetTotalPoolLiquidityResponse, GetUnrealizedCancelsResponse, OrdersResponse,
-        SpotPriceResponse, TickIdAndState, TickUnrealizedCancels, TicksResponse, UnrealizedCancels,
+        ActiveTick, ActiveTicksResponse, ArithmeticTwapResponse, BookStateResponse,
+        CalcOutAmtGivenInResponse, DenomObligation, DenomsResponse, EstimateTicksTraversedResponse,
+        ExportBookResponse, FillHistoryResponse, GetSwapFeeResponse, GetTotalPoolLiquidityResponse,
+        GetUnrealizedCancelsResponse, MarketFillReportResponse, OrderResponse, OrdersResponse,
+        PruneRewardResponse, SimulatePlaceLimitResponse, SolvencyResponse, SpotPriceResponse,
+        TickIdAndState, TickUnrealizedCancels, TicksResponse, UnrealizedCancels, VolumeResponse,
     },
     order,
     state::{
-        get_directional_liquidity, get_orders_by_owner, orders, IS_ACTIVE, ORDERBOOK, TICK_STATE,
+        get_active_ticks, get_directional_liquidity, get_fill_history,
+        get_next_tick_with_liquidity, get_orderbook, get_orders_by_owner, get_paused_ops,
+        get_placement_fee, get_twap_accumulator, get_volume, orders, DEFAULT_PAGE_SIZE, IS_ACTIVE,
+        ORDER_ID, PRUNE_REWARD, PRUNE_REWARD_POOL, TICK_STATE,
+    },
+    sudo::{ensure_placements_not_paused, ensure_swap_fee},
+    sumtree::tree::{get_prefix_sum, get_root_node, get_root_node_if_exists},
+    tick_math::{amount_to_value, tick_to_price, RoundingMode},
+    types::{
+        coin_u256, Coin256, FilterOwnerOrders, LimitOrder, MarketOrder, OrderDirection, OrderSort,
+        PausedOps, TickState,
     },
-    sudo::ensure_swap_fee,
-    sumtree::tree::{get_prefix_sum, get_root_node},
-    tick_math::tick_to_price,
-    types::{FilterOwnerOrders, LimitOrder, MarketOrder, OrderDirection, TickState},
     ContractError,
 };
 
@@ -46,21 +58,15 @@ pub(crate) fn spot_price(
     );
 
     // Fetch orderbook to retrieve tick info
-    let orderbook = ORDERBOOK.load(deps.storage)?;
+    let orderbook = get_orderbook(deps.storage)?;
     // Determine the order direction by denom pairing
     let direction = orderbook.direction_from_pair(base_asset_denom, quote_asset_denom)?;
 
-    // Determine next tick based on desired order direction
-    let next_tick = match direction {
-        OrderDirection::Ask => orderbook.next_bid_tick,
-        OrderDirection::Bid => orderbook.next_ask_tick,
-    };
-
-    // Generate spot price based on current active tick for desired order direction
-    let price = tick_to_price(next_tick)?;
+    // Read the cached price of the next tick for the desired order direction
+    let (price, inverse_price) = orderbook.next_tick_price_pair(direction.opposite())?;
 
     let spot_price = match direction {
-        OrderDirection::Ask => price.inv().unwrap(),
+        OrderDirection::Ask => inverse_price,
         OrderDirection::Bid => price,
     };
 
@@ -79,6 +85,7 @@ pub(crate) fn spot_price(
 /// 2. The provided denom pair is not supported by the orderbook
 pub(crate) fn calc_out_amount_given_in(
     deps: Deps,
+    env: Env,
     token_in: Coin,
     token_out_denom: String,
     swap_fee: Decimal,
@@ -87,7 +94,7 @@ pub(crate) fn calc_out_amount_given_in(
     ensure_swap_fee(swap_fee)?;
 
     // Fetch orderbook
-    let orderbook = ORDERBOOK.load(deps.storage)?;
+    let orderbook = get_orderbook(deps.storage)?;
     // Determine order direction
     let direction = orderbook.direction_from_pair(token_in.denom, token_out_denom)?;
 
@@ -100,8 +107,12 @@ pub(crate) fn calc_out_amount_given_in(
     let mut mock_order = MarketOrder::new(token_in.amount, direction, Addr::unchecked("querier"));
 
     // Generate output coin given the input order by simulating a fill against current orderbook state
-    let order::PostMarketOrderState { output, .. } =
-        order::run_market_order_internal(deps.storage, &mut mock_order, tick_bound)?;
+    let order::PostMarketOrderState { output, .. } = order::run_market_order_internal(
+        deps.storage,
+        &mut mock_order,
+        tick_bound,
+        env.block.height,
+    )?;
 
     Ok(CalcOutAmtGivenInResponse {
         token_out: output.into(),
@@ -115,7 +126,7 @@ pub(crate) fn calc_out_amount_given_in(
 /// Errors if:
 /// 1. Summing total liquidity overflows Uint128
 pub(crate) fn total_pool_liquidity(deps: Deps) -> ContractResult<GetTotalPoolLiquidityResponse> {
-    let orderbook = ORDERBOOK.load(deps.storage)?;
+    let orderbook = get_orderbook(deps.storage)?;
 
     // Create tracking variables for both denoms
     let ask_amount = get_directional_liquidity(deps.storage, OrderDirection::Ask)?;
@@ -125,13 +136,13 @@ pub(crate) fn total_pool_liquidity(deps: Deps) -> ContractResult<GetTotalPoolLiq
         Uint128::try_from(ask_amount.to_uint_floor())
             .unwrap()
             .u128(),
-        orderbook.get_expected_denom(&OrderDirection::Ask),
+        orderbook.input_denom(&OrderDirection::Ask),
     );
     let bid_amount_coin = coin(
         Uint128::try_from(bid_amount.to_uint_floor())
             .unwrap()
             .u128(),
-        orderbook.get_expected_denom(&OrderDirection::Bid),
+        orderbook.input_denom(&OrderDirection::Bid),
     );
 
     // May return 0 amounts if there is no liquidity in the orderbook
@@ -185,12 +196,153 @@ pub(crate) fn all_ticks(
     })
 }
 
+/// Returns the ticks with remaining liquidity for the given direction, alongside each tick's price.
+///
+/// Ticks whose liquidity has been fully filled or cancelled are skipped. At most
+/// `MAX_TICK_SCAN_ITERATIONS` ticks are inspected per page, so a page may hold fewer than `limit`
+/// ticks while `next_start_after` is still set to the last tick inspected.
+pub(crate) fn active_ticks(
+    deps: Deps,
+    direction: OrderDirection,
+    start_after: Option<i64>,
+    limit: Option<u32>,
+) -> ContractResult<ActiveTicksResponse> {
+    let limit = limit.map_or(DEFAULT_PAGE_SIZE, u64::from);
+    let (active_ticks, next_start_after) =
+        get_active_ticks(deps.storage, direction, start_after, Some(limit))?;
+
+    let ticks = active_ticks
+        .into_iter()
+        .map(|(tick_id, remaining)| {
+            Ok(ActiveTick {
+                tick_id,
+                price: tick_to_price(tick_id)?,
+                remaining,
+            })
+        })
+        .collect::<ContractResult<Vec<ActiveTick>>>()?;
+
+    Ok(ActiveTicksResponse {
+        ticks,
+        next_start_after,
+    })
+}
+
+/// Returns the total resting liquidity for the given direction.
+///
+/// Liquidity is denominated in the direction's expected input denom.
+pub(crate) fn directional_liquidity(
+    deps: Deps,
+    direction: OrderDirection,
+) -> ContractResult<Decimal256> {
+    get_directional_liquidity(deps.storage, direction)
+}
+
+/// Returns the aggregate state of both sides of the orderbook.
+///
+/// The spread is the difference between the prices of the next ask and next bid ticks,
+/// and is only returned if both directions have resting liquidity.
+pub(crate) fn book_state(deps: Deps) -> ContractResult<BookStateResponse> {
+    let orderbook = get_orderbook(deps.storage)?;
+    let bid_liquidity = get_directional_liquidity(deps.storage, OrderDirection::Bid)?;
+    let ask_liquidity = get_directional_liquidity(deps.storage, OrderDirection::Ask)?;
+
+    let spread = if bid_liquidity.is_zero() || ask_liquidity.is_zero() {
+        None
+    } else {
+        let ask_price = orderbook.next_tick_price(OrderDirection::Ask)?;
+        let bid_price = orderbook.next_tick_price(OrderDirection::Bid)?;
+        Some(ask_price.checked_sub(bid_price)?)
+    };
+
+    Ok(BookStateResponse {
+        bid_liquidity,
+        ask_liquidity,
+        next_bid_tick: orderbook.next_bid_tick,
+        next_ask_tick: orderbook.next_ask_tick,
+        spread,
+    })
+}
+
+/// Returns the spread between the next ask and next bid ticks in basis points of their mid price,
+/// rounded down.
+///
+/// Like the spread in `book_state`, this is only returned if both directions have resting liquidity.
+pub(crate) fn spread_bps(deps: Deps) -> ContractResult<Option<u64>> {
+    let orderbook = get_orderbook(deps.storage)?;
+    let bid_liquidity = get_directional_liquidity(deps.storage, OrderDirection::Bid)?;
+    let ask_liquidity = get_directional_liquidity(deps.storage, OrderDirection::Ask)?;
+    if bid_liquidity.is_zero() || ask_liquidity.is_zero() {
+        return Ok(None);
+    }
+
+    let ask_price = orderbook.next_tick_price(OrderDirection::Ask)?;
+    let bid_price = orderbook.next_tick_price(OrderDirection::Bid)?;
+
+    // (ask - bid) / ((ask + bid) / 2) * 10000
+    let spread_bps = ask_price
+        .checked_sub(bid_price)?
+        .checked_mul(Decimal256::from_ratio(20_000u128, 1u128))?
+        .checked_div(ask_price.checked_add(bid_price)?)?
+        .to_uint_floor();
+
+    Ok(Some(Uint64::try_from(spread_bps)?.u64()))
+}
+
+/// Returns the time-weighted average mid price over `[start_time, end_time]`, with `end_time`
+/// defaulting to the current block time.
+///
+/// The average is the difference between the TWAP accumulator at both ends of the window divided
+/// by its length, so blocks without any changes to the book are weighted at the mid price they
+/// inherited.
+///
+/// Errors if:
+/// 1. `start_time` is not before `end_time`
+/// 2. `end_time` is after the current block time
+/// 3. No TWAP snapshot exists at or before `start_time`
+pub(crate) fn arithmetic_twap(
+    deps: Deps,
+    env: Env,
+    start_time: Timestamp,
+    end_time: Option<Timestamp>,
+) -> ContractResult<ArithmeticTwapResponse> {
+    let end_time = end_time.unwrap_or(env.block.time);
+    ensure!(
+        start_time < end_time,
+        ContractError::InvalidTwapWindow {
+            start_time,
+            end_time
+        }
+    );
+    ensure!(
+        end_time <= env.block.time,
+        ContractError::TwapEndTimeInFuture {
+            end_time,
+            block_time: env.block.time
+        }
+    );
+
+    let start_cumulative = get_twap_accumulator(deps.storage, start_time)?;
+    let end_cumulative = get_twap_accumulator(deps.storage, end_time)?;
+    let elapsed = Decimal256::from_ratio(end_time.nanos() - start_time.nanos(), 1u128);
+    let arithmetic_twap = end_cumulative
+        .checked_sub(start_cumulative)?
+        .checked_div(elapsed)?;
+
+    Ok(ArithmeticTwapResponse { arithmetic_twap })
+}
+
 /// Returns the current active status of the orderbook
 pub(crate) fn is_active(deps: Deps) -> ContractResult<bool> {
     let is_active = IS_ACTIVE.may_load(deps.storage)?;
     Ok(is_active.unwrap_or(true))
 }
 
+/// Returns the set of operations currently paused on the orderbook
+pub(crate) fn paused_ops(deps: Deps) -> ContractResult<PausedOps> {
+    get_paused_ops(deps.storage)
+}
+
 /// Returns zero as the swap fee/spread factor for the orderbook
 pub(crate) fn get_swap_fee() -> ContractResult<GetSwapFeeResponse> {
     Ok(GetSwapFeeResponse {
@@ -199,31 +351,241 @@ pub(crate) fn get_swap_fee() -> ContractResult<GetSwapFeeResponse> {
 }
 
 /// Returns all active orders for a given address
+///
+/// Orders are paginated in index order. If `sort_by` is provided only the returned page is sorted,
+/// ties keeping their index order.
 pub(crate) fn orders_by_owner(
     deps: Deps,
     owner: Addr,
     start_from: Option<(i64, u64)>,
     end_at: Option<(i64, u64)>,
     limit: Option<u64>,
+    sort_by: Option<OrderSort>,
 ) -> ContractResult<Vec<LimitOrder>> {
-    let orders = get_orders_by_owner(
+    let mut orders = get_orders_by_owner(
         deps.storage,
         FilterOwnerOrders::all(owner),
         start_from,
         end_at,
         limit,
     )?;
+
+    match sort_by {
+        None => {}
+        Some(OrderSort::TickAscending) => orders.sort_by_key(|order| order.tick_id),
+        Some(OrderSort::TickDescending) => {
+            orders.sort_by_key(|order| std::cmp::Reverse(order.tick_id))
+        }
+        Some(OrderSort::FillPercentage) => {
+            let mut sorted = orders
+                .into_iter()
+                .map(|order| Ok((fill_percentage(deps, &order)?, order)))
+                .collect::<ContractResult<Vec<(Decimal256, LimitOrder)>>>()?;
+            sorted.sort_by(|(a, _), (b, _)| b.cmp(a));
+            orders = sorted.into_iter().map(|(_, order)| order).collect();
+        }
+    }
+
     Ok(orders)
 }
 
+/// Returns the portion of an order's remaining quantity that has been filled, accounting for
+/// cancellations on its tick that are yet to be synced.
+fn fill_percentage(deps: Deps, order: &LimitOrder) -> ContractResult<Decimal256> {
+    if order.quantity.is_zero() {
+        return Ok(Decimal256::zero());
+    }
+    let tick_id = order.tick_id;
+    let tick_state = TICK_STATE
+        .may_load(deps.storage, tick_id)?
+        .ok_or(ContractError::InvalidTickId { tick_id })?;
+    let unrealized_cancels = get_unrealized_cancels(deps, tick_state.clone(), tick_id)?;
+    let amount_filled = synced_amount_filled(order, &tick_state, &unrealized_cancels)?;
+
+    Ok(Decimal256::from_ratio(amount_filled, order.quantity))
+}
+
 pub(crate) fn denoms(deps: Deps) -> ContractResult<DenomsResponse> {
-    let orderbook = ORDERBOOK.load(deps.storage)?;
+    let orderbook = get_orderbook(deps.storage)?;
     Ok(DenomsResponse {
         quote_denom: orderbook.quote_denom,
         base_denom: orderbook.base_denom,
     })
 }
 
+pub(crate) fn prune_reward(deps: Deps) -> ContractResult<PruneRewardResponse> {
+    let reward = PRUNE_REWARD.may_load(deps.storage)?;
+    let pool = match &reward {
+        Some(reward) => PRUNE_REWARD_POOL
+            .may_load(deps.storage, &reward.denom)?
+            .unwrap_or_default(),
+        None => Uint128::zero(),
+    };
+    Ok(PruneRewardResponse { reward, pool })
+}
+
+pub(crate) fn next_order_id(deps: Deps) -> ContractResult<u64> {
+    Ok(ORDER_ID.may_load(deps.storage)?.unwrap_or_default())
+}
+
+pub(crate) fn volume(deps: Deps) -> ContractResult<VolumeResponse> {
+    let orderbook = get_orderbook(deps.storage)?;
+    let quote_volume = get_volume(deps.storage, &orderbook.quote_denom)?;
+    let base_volume = get_volume(deps.storage, &orderbook.base_denom)?;
+    Ok(VolumeResponse {
+        quote_volume: coin_u256(quote_volume, &orderbook.quote_denom),
+        base_volume: coin_u256(base_volume, &orderbook.base_denom),
+    })
+}
+
+/// Estimates the work a market order would perform against the current orderbook state.
+///
+/// The order is simulated using the same fill path as swaps. Fills on a tick are allocated to its
+/// resting orders first in first out, after the amount already filled but not yet claimed, to count
+/// how many orders the fill reaches.
+///
+/// Errors if the simulated fill errors, e.g. due to insufficient liquidity.
+pub(crate) fn estimate_ticks_traversed(
+    deps: Deps,
+    env: Env,
+    quantity: Uint128,
+    direction: OrderDirection,
+    tick_bound: Option<i64>,
+) -> ContractResult<EstimateTicksTraversedResponse> {
+    let tick_bound = tick_bound.unwrap_or(match direction {
+        OrderDirection::Bid => MAX_TICK,
+        OrderDirection::Ask => MIN_TICK,
+    });
+
+    // Simulate the fill against current orderbook state
+    let mut mock_order = MarketOrder::new(quantity, direction, Addr::unchecked("querier"));
+    let order::PostMarketOrderState { tick_fills, .. } = order::run_market_order_internal(
+        deps.storage,
+        &mut mock_order,
+        tick_bound,
+        env.block.height,
+    )?;
+
+    let fill_direction = direction.opposite();
+    let mut orders_consumed: u64 = 0;
+    for (tick_id, fill_amount) in tick_fills.iter() {
+        let tick_values = TICK_STATE
+            .load(deps.storage, *tick_id)?
+            .get_values(fill_direction);
+        let quantities: Vec<Decimal256> = orders()
+            .prefix(*tick_id)
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .filter(|(_, order)| order.order_direction == fill_direction)
+            .map(|(_, order)| Decimal256::from_ratio(order.quantity, Uint256::one()))
+            .collect();
+
+        // Orders are only removed once claimed, so any resting quantity beyond the tick's
+        // remaining liquidity has already been filled
+        let resting_quantity = quantities
+            .iter()
+            .try_fold(Decimal256::zero(), |acc, quantity| {
+                acc.checked_add(*quantity)
+            })?;
+        let fill_start = resting_quantity.saturating_sub(tick_values.total_amount_of_liquidity);
+        let fill_end = fill_start.checked_add(*fill_amount)?;
+
+        let mut cursor = Decimal256::zero();
+        for quantity in quantities {
+            let order_end = cursor.checked_add(quantity)?;
+            if order_end > fill_start {
+                orders_consumed += 1;
+            }
+            cursor = order_end;
+            if cursor >= fill_end {
+                break;
+            }
+        }
+    }
+
+    Ok(EstimateTicksTraversedResponse {
+        ticks_traversed: tick_fills.len() as u32,
+        orders_consumed,
+    })
+}
+
+/// Simulates a market order against the current orderbook state, reporting the input consumed and
+/// output produced on each tick it fills against.
+///
+/// The order is simulated using the same fill path as swaps, so the reported totals match what a
+/// swap of `quantity` would fill at the current height.
+///
+/// Errors if the simulated fill errors, e.g. due to insufficient liquidity.
+pub(crate) fn market_fill_report(
+    deps: Deps,
+    env: Env,
+    quantity: Uint128,
+    direction: OrderDirection,
+    tick_bound: Option<i64>,
+) -> ContractResult<MarketFillReportResponse> {
+    let tick_bound = tick_bound.unwrap_or(match direction {
+        OrderDirection::Bid => MAX_TICK,
+        OrderDirection::Ask => MIN_TICK,
+    });
+
+    let mut mock_order = MarketOrder::new(quantity, direction, Addr::unchecked("querier"));
+    let order::PostMarketOrderState {
+        output,
+        fill_reports,
+        total_input,
+        ..
+    } = order::run_market_order_internal(
+        deps.storage,
+        &mut mock_order,
+        tick_bound,
+        env.block.height,
+    )?;
+
+    Ok(MarketFillReportResponse {
+        fills: fill_reports,
+        total_input,
+        total_output: output.amount,
+    })
+}
+
+/// Simulates placing a limit order against the current orderbook state without mutating it.
+///
+/// Errors as the placement would if placements are paused, the tick is out of bounds or the
+/// quantity is zero. A crossing order is instead reported through `would_cross`.
+pub(crate) fn simulate_place_limit(
+    deps: Deps,
+    tick_id: i64,
+    direction: OrderDirection,
+    quantity: Uint128,
+) -> ContractResult<SimulatePlaceLimitResponse> {
+    ensure_placements_not_paused(deps.storage)?;
+
+    let orderbook = get_orderbook(deps.storage)?;
+    ensure!(
+        orderbook.is_tick_in_bounds(tick_id),
+        ContractError::InvalidTickId { tick_id }
+    );
+    ensure!(
+        !quantity.is_zero(),
+        ContractError::InvalidQuantity { quantity }
+    );
+
+    let would_cross = match order::ensure_not_crossing(deps.storage, &orderbook, tick_id, direction)
+    {
+        Ok(()) => false,
+        Err(ContractError::CrossingLimitOrder { .. }) => true,
+        Err(err) => return Err(err),
+    };
+
+    Ok(SimulatePlaceLimitResponse {
+        required_funds: coin(quantity.u128(), orderbook.input_denom(&direction)),
+        placement_fee: get_placement_fee(deps.storage)?,
+        would_cross,
+        assigned_order_id: next_order_id(deps)?,
+    })
+}
+
 pub(crate) fn ticks_by_id(deps: Deps, tick_ids: Vec<i64>) -> ContractResult<TicksResponse> {
     let mut ticks: Vec<TickIdAndState> = vec![];
     for tick_id in tick_ids {
@@ -278,6 +640,218 @@ fn get_unrealized_cancels(
     })
 }
 
+/// Returns the closest tick to `from_tick` (inclusive) with resting liquidity for the given direction.
+///
+/// Asks are scanned upwards and bids downwards, matching the order in which market orders fill them.
+/// Returns `None` if that side of the book has no liquidity beyond `from_tick`.
+///
+/// Errors if:
+/// 1. `from_tick` is outside of the global tick range
+/// 2. More than `MAX_TICK_SCAN_ITERATIONS` ticks are inspected during the scan
+pub(crate) fn closest_tick_with_liquidity(
+    deps: Deps,
+    direction: OrderDirection,
+    from_tick: i64,
+) -> ContractResult<Option<i64>> {
+    ensure!(
+        (MIN_TICK..=MAX_TICK).contains(&from_tick),
+        ContractError::InvalidTickId { tick_id: from_tick }
+    );
+
+    get_next_tick_with_liquidity(
+        deps.storage,
+        direction,
+        from_tick,
+        Some(MAX_TICK_SCAN_ITERATIONS),
+    )
+}
+
+/// Rolls up every outstanding obligation of the orderbook by denom.
+///
+/// For each resting order, the filled portion is owed in the opposite denom as it would be paid
+/// out on claim (including any bounty and maker fee), and the unfilled portion is owed as a refund
+/// in the order's own denom. Unrealized cancels are included in each tick's ETAS so that fills match
+/// what a claim would observe after syncing the tick.
+pub(crate) fn solvency(deps: Deps) -> ContractResult<SolvencyResponse> {
+    let orderbook = get_orderbook(deps.storage)?;
+    let mut ask_denom_owed = Uint256::zero();
+    let mut bid_denom_owed = Uint256::zero();
+    count_obligations(deps, None, None, &mut ask_denom_owed, &mut bid_denom_owed)?;
+
+    Ok(SolvencyResponse {
+        obligations: vec![
+            DenomObligation {
+                denom: orderbook.input_denom(&OrderDirection::Ask),
+                direction: OrderDirection::Ask,
+                owed: ask_denom_owed,
+            },
+            DenomObligation {
+                denom: orderbook.input_denom(&OrderDirection::Bid),
+                direction: OrderDirection::Bid,
+                owed: bid_denom_owed,
+            },
+        ],
+    })
+}
+
+/// Adds the obligations of a page of resting orders, ordered by `(tick_id, order_id)`, to the
+/// running totals owed in the ask and bid denoms. Obligations are counted as in `solvency`.
+///
+/// Pages are keyed as in `export_book`. Returns the key of the last order counted if a full page
+/// was counted, or `None` once every order after `start_after` has been counted. Every remaining
+/// order is counted if no limit is provided.
+pub(crate) fn count_obligations(
+    deps: Deps,
+    start_after: Option<(i64, u64)>,
+    limit: Option<u32>,
+    ask_denom_owed: &mut Uint256,
+    bid_denom_owed: &mut Uint256,
+) -> ContractResult<Option<(i64, u64)>> {
+    let mut remaining = limit.map_or(usize::MAX, |limit| limit as usize);
+    let mut last_counted = None;
+
+    // Orders are walked tick by tick, as every tick with resting orders has tick state
+    let ticks = TICK_STATE.range(
+        deps.storage,
+        start_after.map(|(tick_id, _)| Bound::inclusive(tick_id)),
+        None,
+        Order::Ascending,
+    );
+    for maybe_tick in ticks {
+        if remaining == 0 {
+            break;
+        }
+        let (tick_id, tick_state) = maybe_tick?;
+        let tick_price = tick_to_price(tick_id)?;
+        let unrealized_cancels = get_unrealized_cancels(deps, tick_state.clone(), tick_id)?;
+
+        // Only the cursor's own tick is bounded by its order id
+        let min_order_id = match start_after {
+            Some((start_tick_id, order_id)) if start_tick_id == tick_id => {
+                Some(Bound::exclusive(order_id))
+            }
+            _ => None,
+        };
+        let tick_orders = orders()
+            .prefix(tick_id)
+            .range(deps.storage, min_order_id, None, Order::Ascending)
+            .take(remaining);
+        for maybe_order in tick_orders {
+            let (order_id, order) = maybe_order?;
+            remaining -= 1;
+            last_counted = Some((tick_id, order_id));
+
+            let amount_filled = synced_amount_filled(&order, &tick_state, &unrealized_cancels)?;
+            let amount_unfilled = Uint256::from(order.quantity.checked_sub(amount_filled)?);
+            let amount_claimable = amount_to_value(
+                order.order_direction,
+                amount_filled,
+                tick_price,
+                RoundingMode::Down,
+            )?;
+
+            match order.order_direction {
+                OrderDirection::Bid => {
+                    *bid_denom_owed = bid_denom_owed.checked_add(amount_unfilled)?;
+                    *ask_denom_owed = ask_denom_owed.checked_add(amount_claimable)?;
+                }
+                OrderDirection::Ask => {
+                    *ask_denom_owed = ask_denom_owed.checked_add(amount_unfilled)?;
+                    *bid_denom_owed = bid_denom_owed.checked_add(amount_claimable)?;
+                }
+            }
+        }
+    }
+
+    // A partial page means there are no further orders
+    Ok(if remaining == 0 { last_counted } else { None })
+}
+
+/// Returns the balance the contract is expected to hold in each denom.
+///
+/// This is the solvency rollup of order obligations, plus the funds held in the prune reward pool.
+/// The orderbook's denoms are always listed first, followed by any other prune reward denoms.
+pub(crate) fn expected_reserves(deps: Deps) -> ContractResult<Vec<Coin256>> {
+    let mut reserves: Vec<Coin256> = solvency(deps)?
+        .obligations
+        .into_iter()
+        .map(|obligation| coin_u256(obligation.owed, &obligation.denom))
+        .collect();
+
+    for maybe_pool in PRUNE_REWARD_POOL.range(deps.storage, None, None, Order::Ascending) {
+        let (denom, amount) = maybe_pool?;
+        match reserves.iter_mut().find(|reserve| reserve.denom == denom) {
+            Some(reserve) => {
+                reserve.amount = reserve.amount.checked_add(Uint256::from(amount))?;
+            }
+            None => reserves.push(coin_u256(amount, &denom)),
+        }
+    }
+
+    Ok(reserves)
+}
+
+/// Calculates the amount of an order that is filled, as a claim would observe it after syncing the tick.
+fn synced_amount_filled(
+    order: &LimitOrder,
+    tick_state: &TickState,
+    unrealized_cancels: &UnrealizedCancels,
+) -> ContractResult<Uint128> {
+    // Calculate the tick's ETAS as it would be after a sync
+    let unrealized = match order.order_direction {
+        OrderDirection::Bid => unrealized_cancels.bid_unrealized_cancels,
+        OrderDirection::Ask => unrealized_cancels.ask_unrealized_cancels,
+    };
+    let tick_etas = tick_state
+        .get_values(order.order_direction)
+        .effective_total_amount_swapped
+        .checked_add(unrealized)?;
+
+    let amount_filled_dec = tick_etas
+        .saturating_sub(order.etas)
+        .min(Decimal256::from_ratio(order.quantity, 1u128));
+    Ok(Uint128::try_from(
+        RoundingMode::Down.round(amount_filled_dec),
+    )?)
+}
+
+/// Returns a single order alongside the amount currently claimable from it and how much of it has been filled.
+///
+/// Returns `None` if the order does not exist, either because it was never placed or because it has
+/// been fully claimed or cancelled.
+pub(crate) fn order(
+    deps: Deps,
+    tick_id: i64,
+    order_id: u64,
+) -> ContractResult<Option<OrderResponse>> {
+    let Some(order) = orders().may_load(deps.storage, &(tick_id, order_id))? else {
+        return Ok(None);
+    };
+    let tick_state = TICK_STATE
+        .may_load(deps.storage, tick_id)?
+        .ok_or(ContractError::InvalidTickId { tick_id })?;
+    let unrealized_cancels = get_unrealized_cancels(deps, tick_state.clone(), tick_id)?;
+
+    let amount_filled = synced_amount_filled(&order, &tick_state, &unrealized_cancels)?;
+    let claimable = amount_to_value(
+        order.order_direction,
+        amount_filled,
+        tick_to_price(tick_id)?,
+        RoundingMode::Down,
+    )?;
+    let fill_percentage = if order.quantity.is_zero() {
+        Decimal256::zero()
+    } else {
+        Decimal256::from_ratio(amount_filled, order.quantity)
+    };
+
+    Ok(Some(OrderResponse {
+        order,
+        claimable,
+        fill_percentage,
+    }))
+}
+
 // Gets all ticks for the provided vector of IDs and retrieves the value of their sumtree root
 pub(crate) fn ticks_unrealized_cancels_by_id(
     deps: Deps,
@@ -331,3 +905,156 @@ pub(crate) fn orders_by_tick(
         orders,
     })
 }
+
+/// Returns a page of every resting order in the orderbook, ordered by `(tick_id, order_id)`.
+///
+/// Pages are keyed on the order's primary key, so orders placed or removed between pages do not
+/// shift the cursor. If a full page is returned, `next_start_after` is set to the key of the last
+/// order returned.
+pub(crate) fn export_book(
+    deps: Deps,
+    start_after: Option<(i64, u64)>,
+    limit: u32,
+) -> ContractResult<ExportBookResponse> {
+    let limit = limit as usize;
+    let mut exported: Vec<LimitOrder> = vec![];
+
+    // Orders are walked tick by tick, as every tick with resting orders has tick state
+    let tick_ids = TICK_STATE.keys(
+        deps.storage,
+        start_after.map(|(tick_id, _)| Bound::inclusive(tick_id)),
+        None,
+        Order::Ascending,
+    );
+    for maybe_tick_id in tick_ids {
+        if exported.len() >= limit {
+            break;
+        }
+        let tick_id = maybe_tick_id?;
+
+        // Only the cursor's own tick is bounded by its order id
+        let min_order_id = match start_after {
+            Some((start_tick_id, order_id)) if start_tick_id == tick_id => {
+                Some(Bound::exclusive(order_id))
+            }
+            _ => None,
+        };
+        let tick_orders = orders()
+            .prefix(tick_id)
+            .range(deps.storage, min_order_id, None, Order::Ascending)
+            .take(limit - exported.len())
+            .map(|res| res.map(|(_, order)| order))
+            .collect::<StdResult<Vec<LimitOrder>>>()?;
+        exported.extend(tick_orders);
+    }
+
+    // A partial page means there are no further orders
+    let next_start_after = if exported.len() < limit {
+        None
+    } else {
+        exported.last().map(|order| (order.tick_id, order.order_id))
+    };
+
+    Ok(ExportBookResponse {
+        orders: exported,
+        next_start_after,
+    })
+}
+
+/// Returns the sumtree prefix sum for a tick and direction at the given ETAS cutoff.
+///
+/// The walk starts from the tick's current `cumulative_realized_cancels`, exactly as a tick sync
+/// to `etas` would, so the result is the cumulative realized cancels the tick would hold after
+/// syncing to that cutoff. Returns zero if the tick has no sumtree for the direction.
+///
+/// Errors with `InvalidTickId` if the tick is outside the global tick range or has no state.
+pub(crate) fn prefix_sum(
+    deps: Deps,
+    tick_id: i64,
+    direction: OrderDirection,
+    etas: Decimal256,
+) -> ContractResult<Decimal256> {
+    ensure!(
+        (MIN_TICK..=MAX_TICK).contains(&tick_id),
+        ContractError::InvalidTickId { tick_id }
+    );
+    let tick_values = TICK_STATE
+        .may_load(deps.storage, tick_id)?
+        .ok_or(ContractError::InvalidTickId { tick_id })?
+        .get_values(direction);
+
+    let Some(root_node) = get_root_node_if_exists(deps.storage, tick_id, direction)? else {
+        return Ok(Decimal256::zero());
+    };
+
+    get_prefix_sum(
+        deps.storage,
+        root_node,
+        etas,
+        tick_values.cumulative_realized_cancels,
+    )
+}
+
+/// Returns the cumulative realized cancels for a tick and direction, as of the tick's current ETAS.
+///
+/// The stored `cumulative_realized_cancels` is only updated when the tick is synced, so this
+/// computes the value a sync would produce rather than returning the possibly stale stored one.
+///
+/// Errors with `InvalidTickId` if the tick is outside the global tick range or has no state.
+pub(crate) fn realized_cancels(
+    deps: Deps,
+    tick_id: i64,
+    direction: OrderDirection,
+) -> ContractResult<Decimal256> {
+    ensure!(
+        (MIN_TICK..=MAX_TICK).contains(&tick_id),
+        ContractError::InvalidTickId { tick_id }
+    );
+    let tick_values = TICK_STATE
+        .may_load(deps.storage, tick_id)?
+        .ok_or(ContractError::InvalidTickId { tick_id })?
+        .get_values(direction);
+
+    let Some(root_node) = get_root_node_if_exists(deps.storage, tick_id, direction)? else {
+        return Ok(tick_values.cumulative_realized_cancels);
+    };
+
+    get_prefix_sum(
+        deps.storage,
+        root_node,
+        tick_values.effective_total_amount_swapped,
+        tick_values.cumulative_realized_cancels,
+    )
+}
+
+/// Verifies the integrity of the sumtree on the given tick, returning true if it is valid.
+///
+/// As this walks the whole tree it is only served by debug builds, so that the query API is the
+/// same for every build.
+///
+/// Errors if:
+/// 1. The tree is corrupted, with `InvalidTreeState`
+/// 2. The contract is a release build, with `DebugQueryUnavailable`
+pub(crate) fn verify_tree(
+    deps: Deps,
+    tick_id: i64,
+    direction: OrderDirection,
+) -> ContractResult<bool> {
+    if cfg!(debug_assertions) {
+        crate::sumtree::tree::verify_tree(deps.storage, tick_id, direction)?;
+        Ok(true)
+    } else {
+        Err(ContractError::DebugQueryUnavailable)
+    }
+}
+
+/// Returns the claim history for a given address, oldest first
+pub(crate) fn fill_history(
+    deps: Deps,
+    owner: Addr,
+    start_after: Option<u64>,
+    limit: Option<u64>,
+) -> ContractResult<FillHistoryResponse> {
+    let fills = get_fill_history(deps.storage, &owner, start_after, limit)?;
+    Ok(FillHistoryResponse { fills })
+}
@@ -1,34 +1,122 @@
+use crate::constants::{
+    BATCHING_LAYER_COUNT, BATCHING_LAYER_WIDTH_FACTOR, DEFAULT_MAX_BATCH_CLAIM,
+    DEFAULT_MAX_CLAIM_BOUNTY, DEFAULT_MAX_ORDERS_PER_TICK, DEFAULT_MAX_TICKS_PER_ORDER,
+    MAX_FILL_HISTORY, MAX_TICK, MAX_TICK_SCAN_ITERATIONS, MIN_TICK, TWAP_RETENTION_PERIOD,
+};
 use crate::error::ContractResult;
-use crate::types::{FilterOwnerOrders, LimitOrder, OrderDirection, Orderbook, TickState};
+use crate::types::{
+    AssetType, CrossingTickPolicy, DerivedStateRebuild, DustAudit, FillRecord, FilterOwnerOrders,
+    LimitOrder, MakerFeeDiscount, OrderDirection, OrderNotFoundReason, Orderbook, PausedOps,
+    TickState, TwapRecord,
+};
 use crate::ContractError;
-use cosmwasm_std::{Addr, Decimal256, Order, StdResult, Storage};
+use cosmwasm_std::{
+    ensure, Addr, Coin, Decimal256, Order, OverflowError, OverflowOperation, StdResult, Storage,
+    Timestamp, Uint128, Uint256,
+};
 use cw_storage_plus::{Bound, Index, IndexList, IndexedMap, Item, Map, MultiIndex};
 
 // Counters for ID tracking
 pub const ORDER_ID: Item<u64> = Item::new("order_id");
 
 // Pagination constants for queries
-const DEFAULT_PAGE_SIZE: u64 = 100;
+pub(crate) const DEFAULT_PAGE_SIZE: u64 = 100;
 
 pub const ORDERBOOK: Item<Orderbook> = Item::new("orderbook");
 pub const TICK_STATE: Map<i64, TickState> = Map::new("tick_state");
 pub const DIRECTION_TOTAL_LIQUIDITY: Map<&str, Decimal256> = Map::new("direction_liquidity");
+// Key: (layer, direction as str, bucket index)
+// Only buckets holding liquidity are stored
+pub const LAYER_LIQUIDITY: Map<(u8, &str, i64), Decimal256> = Map::new("layer_liquidity");
+// Key: (tick_id, direction as str)
+// Number of orders stored on a tick for a given direction, including filled orders that are yet to be claimed
+pub const TICK_ORDER_COUNT: Map<(i64, &str), u64> = Map::new("tick_order_count");
+// Key: owner
+// Number of orders stored for an owner, including filled orders that are yet to be claimed
+pub const ORDER_COUNT: Map<&Addr, u32> = Map::new("order_count");
+// Key: (tick_id, direction as str, placed_at_height)
+// Resting quantity of the orders on a tick that were queued at a given height, used to find the
+// liquidity still within the activation delay without walking the tick's orders
+pub const QUEUED_LIQUIDITY: Map<(i64, &str, u64), Uint128> = Map::new("queued_liquidity");
+
+// Key: (owner, seq)
+pub const FILL_HISTORY: Map<(&Addr, u64), FillRecord> = Map::new("fill_history");
+pub const FILL_HISTORY_SEQ: Map<&Addr, u64> = Map::new("fill_history_seq");
+
+// Key: (sender, client_order_id)
+// Resting order placed by the sender with the given client order ID, evicted once the order is removed
+pub const CLIENT_ORDER_IDS: Map<(&Addr, &str), (i64, u64)> = Map::new("client_order_ids");
+// Key: (tick_id, order_id)
+// Reverse lookup of `CLIENT_ORDER_IDS` used to evict the mapping when the order is removed
+pub const ORDER_CLIENT_IDS: Map<&(i64, u64), (Addr, String)> = Map::new("order_client_ids");
+
+// Key: (tick_id, order_id)
+// Tombstones of removed orders, used to explain why a lookup of the order failed
+pub const REMOVED_ORDERS: Map<&(i64, u64), OrderNotFoundReason> = Map::new("removed_orders");
+
+// Key: (direction as str, tick_id, order_id)
+// Resting orders that opted into auto-settlement, with the minimum payout they are settled at
+pub const AUTO_SETTLE_ORDERS: Map<(&str, i64, u64), Uint128> = Map::new("auto_settle_orders");
+
+// Key: denom
+// Cumulative output paid out by market order fills in each denom
+pub const VOLUME: Map<&str, Uint256> = Map::new("volume");
+
+// Key: block time in nanoseconds
+// Snapshot of the TWAP accumulator, written by every block that changes the mid price
+pub const TWAP_ACCUMULATOR: Map<u64, TwapRecord> = Map::new("twap_accumulator");
+
+// Reward paid for pruning a tick, unset if pruning is unrewarded
+pub const PRUNE_REWARD: Item<Coin> = Item::new("prune_reward");
+// Key: denom
+// Funds set aside to pay prune rewards, separate from order funds
+pub const PRUNE_REWARD_POOL: Map<&str, Uint128> = Map::new("prune_reward_pool");
+
+// Set while a dust audit is in progress, removed by any placement or fill as these add obligations
+// to orders the audit may already have counted
+pub const DUST_AUDIT: Item<DustAudit> = Item::new("dust_audit");
+// Set by `migrate` while the state derived from the ticks and orders is rebuilt, removed once the
+// rebuild completes
+pub const DERIVED_STATE_REBUILD: Item<DerivedStateRebuild> = Item::new("derived_state_rebuild");
+
+// Key: denom
+// Only non-native denoms are stored
+pub const ASSET_TYPES: Map<&str, AssetType> = Map::new("asset_types");
 
 // Admin State
 pub const IS_ACTIVE: Item<bool> = Item::new("is_active");
+pub const PAUSED_OPS: Item<PausedOps> = Item::new("paused_ops");
 pub const MAKER_FEE: Item<Decimal256> = Item::new("maker_fee");
 pub const MAKER_FEE_RECIPIENT: Item<Addr> = Item::new("maker_fee_recipient");
+pub const MAX_ORDERS_PER_TICK: Item<u64> = Item::new("max_orders_per_tick");
+pub const MAX_BATCH_CLAIM: Item<u32> = Item::new("max_batch_claim");
+pub const MAX_CLAIM_BOUNTY: Item<Decimal256> = Item::new("max_claim_bounty");
+pub const MAX_PRICE_IMPACT: Item<Decimal256> = Item::new("max_price_impact");
+pub const MAX_TICKS_PER_ORDER: Item<u32> = Item::new("max_ticks_per_order");
+pub const MIN_FEE_TRANSFER: Item<Uint128> = Item::new("min_fee_transfer");
+// Smallest input accepted for a market order, unset if any non-zero input is accepted
+pub const MIN_MARKET_ORDER_SIZE: Item<Uint128> = Item::new("min_market_order_size");
+// Flat fee charged for placing a limit order, unset if placements are free
+pub const PLACEMENT_FEE: Item<Coin> = Item::new("placement_fee");
+pub const MAKER_FEE_DISCOUNT: Item<MakerFeeDiscount> = Item::new("maker_fee_discount");
+// Number of blocks after placement before a limit order can be filled, unset if orders are
+// fillable immediately
+pub const ACTIVATION_DELAY: Item<u64> = Item::new("activation_delay");
+pub const CROSSING_TICK_POLICY: Item<CrossingTickPolicy> = Item::new("crossing_tick_policy");
 
 pub struct OrderIndexes {
     // Index by owner; Generic types: MultiIndex<Index Key: owner, Input Data: LimitOrder, Map Key: (tick_id, order_id)>
     pub owner: MultiIndex<'static, Addr, LimitOrder, (i64, u64)>,
     // Index by tick and owner; Generic types: MultiIndex<Index Key: (tick_id, owner), Input Data: LimitOrder, Map Key: (tick_id, order_id)>
     pub tick_and_owner: MultiIndex<'static, (i64, Addr), LimitOrder, (i64, u64)>,
+    // Index by owner and direction; Generic types: MultiIndex<Index Key: (owner, direction), Input Data: LimitOrder, Map Key: (tick_id, order_id)>
+    pub owner_and_direction: MultiIndex<'static, (Addr, String), LimitOrder, (i64, u64)>,
 }
 
 impl IndexList<LimitOrder> for OrderIndexes {
     fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<LimitOrder>> + '_> {
-        let v: Vec<&dyn Index<LimitOrder>> = vec![&self.owner, &self.tick_and_owner];
+        let v: Vec<&dyn Index<LimitOrder>> =
+            vec![&self.owner, &self.tick_and_owner, &self.owner_and_direction];
         Box::new(v.into_iter())
     }
 }
@@ -48,20 +136,72 @@ pub fn orders() -> IndexedMap<'static, &'static (i64, u64), LimitOrder, OrderInd
                 "orders",
                 "orders_tick_and_owner",
             ),
+            owner_and_direction: MultiIndex::new(
+                |_, d: &LimitOrder| (d.owner.clone(), d.order_direction.to_string()),
+                "orders",
+                "orders_owner_and_direction",
+            ),
         },
     )
 }
 
+// Primary map of `orders()` with owned keys, so that orders can be ranged across ticks. Read only,
+// as writes must go through `orders()` to keep its indexes in sync
+const ORDERS_BY_KEY: Map<(i64, u64), LimitOrder> = Map::new("orders");
+
+/// Loads the orderbook, erroring with `OrderbookNotFound` if it has not been created yet
+pub fn get_orderbook(storage: &dyn Storage) -> ContractResult<Orderbook> {
+    ORDERBOOK
+        .may_load(storage)?
+        .ok_or(ContractError::OrderbookNotFound)
+}
+
+/// Saves the orderbook, refreshing the cached prices of any tick pointers that have moved.
+///
+/// All writes of the orderbook go through this function so the price cache is never left stale.
+pub fn save_orderbook(storage: &mut dyn Storage, orderbook: &mut Orderbook) -> ContractResult<()> {
+    orderbook.refresh_price_cache()?;
+    ORDERBOOK.save(storage, orderbook)?;
+    Ok(())
+}
+
 pub fn new_order_id(storage: &mut dyn Storage) -> Result<u64, ContractError> {
     let id = ORDER_ID.load(storage).unwrap_or_default();
     ORDER_ID.save(storage, &(id + 1))?;
     Ok(id)
 }
 
+/// Loads the order at the given key, erroring with `OrderNotFound` if it does not exist.
+///
+/// The error carries the reason the order is missing: IDs at or above the order ID counter were
+/// never placed, otherwise the order's tombstone records how it was removed.
+pub fn load_order(
+    storage: &dyn Storage,
+    tick_id: i64,
+    order_id: u64,
+) -> ContractResult<LimitOrder> {
+    let key = (tick_id, order_id);
+    if let Some(order) = orders().may_load(storage, &key)? {
+        return Ok(order);
+    }
+
+    let reason = if order_id >= ORDER_ID.may_load(storage)?.unwrap_or_default() {
+        Some(OrderNotFoundReason::NeverPlaced)
+    } else {
+        REMOVED_ORDERS.may_load(storage, &key)?
+    };
+    Err(ContractError::OrderNotFound {
+        tick_id,
+        order_id,
+        reason,
+    })
+}
+
 /// Retrieves a list of `LimitOrder` filtered by the specified `FilterOwnerOrders`.
 ///
 /// This function allows for filtering orders based on the owner's address, optionally further
-/// filtering by tick ID. It supports pagination through `min`, `max`, and `page_size` parameters.
+/// filtering by tick ID, order direction or a range of tick IDs. Each filter is served by an index
+/// scan rather than filtering in memory. It supports pagination through `min`, `max`, and `page_size` parameters.
 ///
 /// ## Arguments
 ///
@@ -83,8 +223,8 @@ pub fn get_orders_by_owner(
     page_size: Option<u64>,
 ) -> StdResult<Vec<LimitOrder>> {
     let page_size = page_size.unwrap_or(DEFAULT_PAGE_SIZE) as usize;
-    let min = min.map(Bound::exclusive);
-    let max = max.map(Bound::inclusive);
+    let mut min = min.map(Bound::exclusive);
+    let mut max = max.map(Bound::inclusive);
 
     // Define the prefix iterator based on the filter
     let iter = match filter {
@@ -92,6 +232,25 @@ pub fn get_orders_by_owner(
         FilterOwnerOrders::ByTick(tick_id, owner) => {
             orders().idx.tick_and_owner.prefix((tick_id, owner))
         }
+        FilterOwnerOrders::ByDirection(owner, direction) => orders()
+            .idx
+            .owner_and_direction
+            .prefix((owner, direction.to_string())),
+        FilterOwnerOrders::ByTickRange(owner, min_tick, max_tick) => {
+            // Order keys are sorted by tick ID, so the tick range is applied as bounds on the
+            // owner index, keeping whichever of the pagination and range bounds is tighter
+            let range_min = (min_tick, u64::MIN);
+            let range_max = (max_tick, u64::MAX);
+            min = match min {
+                Some(Bound::Exclusive((start, _))) if start >= range_min => min,
+                _ => Some(Bound::inclusive(range_min)),
+            };
+            max = match max {
+                Some(Bound::Inclusive((end, _))) if end <= range_max => max,
+                _ => Some(Bound::inclusive(range_max)),
+            };
+            orders().idx.owner.prefix(owner)
+        }
     };
 
     // Get orders based on pagination
@@ -105,6 +264,205 @@ pub fn get_orders_by_owner(
     Ok(orders)
 }
 
+/// Appends a claim to the owner's fill history.
+///
+/// Only the most recent `MAX_FILL_HISTORY` records are kept per owner, with the oldest
+/// record being removed once the limit is reached.
+pub fn record_fill(
+    storage: &mut dyn Storage,
+    owner: &Addr,
+    tick_id: i64,
+    order_id: u64,
+    amount: Uint256,
+    price: Decimal256,
+    block_height: u64,
+) -> ContractResult<()> {
+    let seq = FILL_HISTORY_SEQ
+        .may_load(storage, owner)?
+        .unwrap_or_default();
+    FILL_HISTORY_SEQ.save(storage, owner, &(seq + 1))?;
+
+    let record = FillRecord {
+        seq,
+        tick_id,
+        order_id,
+        amount,
+        price,
+        block_height,
+    };
+    FILL_HISTORY.save(storage, (owner, seq), &record)?;
+
+    if seq >= MAX_FILL_HISTORY {
+        FILL_HISTORY.remove(storage, (owner, seq - MAX_FILL_HISTORY));
+    }
+
+    Ok(())
+}
+
+/// Retrieves the fill history for an owner in ascending order of sequence number.
+///
+/// Supports pagination through `start_after` (exclusive) and `page_size`, which defaults to `DEFAULT_PAGE_SIZE`.
+pub fn get_fill_history(
+    storage: &dyn Storage,
+    owner: &Addr,
+    start_after: Option<u64>,
+    page_size: Option<u64>,
+) -> StdResult<Vec<FillRecord>> {
+    let page_size = page_size.unwrap_or(DEFAULT_PAGE_SIZE) as usize;
+
+    FILL_HISTORY
+        .prefix(owner)
+        .range(
+            storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(page_size)
+        .map(|item| item.map(|(_, record)| record))
+        .collect()
+}
+
+/// Gets the mid price between the next bid and next ask ticks.
+///
+/// Returns `None` if either direction has no resting liquidity, as there is no mid price to
+/// speak of.
+pub fn get_mid_price(storage: &dyn Storage) -> ContractResult<Option<Decimal256>> {
+    let bid_liquidity = get_directional_liquidity(storage, OrderDirection::Bid)?;
+    let ask_liquidity = get_directional_liquidity(storage, OrderDirection::Ask)?;
+    if bid_liquidity.is_zero() || ask_liquidity.is_zero() {
+        return Ok(None);
+    }
+
+    let orderbook = get_orderbook(storage)?;
+    let bid_price = orderbook.next_tick_price(OrderDirection::Bid)?;
+    let ask_price = orderbook.next_tick_price(OrderDirection::Ask)?;
+    Ok(Some(
+        bid_price
+            .checked_add(ask_price)?
+            .checked_div(Decimal256::from_ratio(2u128, 1u128))?,
+    ))
+}
+
+/// Records the mid price left by the current block in the TWAP accumulator.
+///
+/// A snapshot is only written when the mid price changes, as the accumulator can be extrapolated
+/// from the latest snapshot over blocks in which the book did not move. Repeated updates within a
+/// block overwrite that block's snapshot so that only the closing mid price is weighted. While
+/// either side of the book is empty, the last known mid price is carried forward.
+///
+/// Snapshots older than `TWAP_RETENTION_PERIOD` are pruned one at a time, always keeping the
+/// newest of them so the accumulator can still be extrapolated at the retention cutoff.
+pub fn update_twap_accumulator(storage: &mut dyn Storage, now: Timestamp) -> ContractResult<()> {
+    let Some(mid_price) = get_mid_price(storage)? else {
+        return Ok(());
+    };
+
+    let record = match get_latest_twap_record(storage, now)? {
+        Some(latest) if latest.mid_price == mid_price => return Ok(()),
+        Some(latest) if latest.timestamp == now => TwapRecord {
+            mid_price,
+            ..latest
+        },
+        Some(latest) => TwapRecord {
+            timestamp: now,
+            price_cumulative: latest.cumulative_at(now)?,
+            mid_price,
+        },
+        None => TwapRecord {
+            timestamp: now,
+            price_cumulative: Decimal256::zero(),
+            mid_price,
+        },
+    };
+    TWAP_ACCUMULATOR.save(storage, now.nanos(), &record)?;
+
+    let cutoff = now.minus_seconds(TWAP_RETENTION_PERIOD).nanos();
+    let expired = TWAP_ACCUMULATOR
+        .keys(
+            storage,
+            None,
+            Some(Bound::exclusive(cutoff)),
+            Order::Ascending,
+        )
+        .take(2)
+        .collect::<StdResult<Vec<u64>>>()?;
+    if expired.len() == 2 {
+        TWAP_ACCUMULATOR.remove(storage, expired[0]);
+    }
+
+    Ok(())
+}
+
+/// Gets the latest TWAP snapshot taken at or before the given time.
+pub fn get_latest_twap_record(
+    storage: &dyn Storage,
+    time: Timestamp,
+) -> ContractResult<Option<TwapRecord>> {
+    let record = TWAP_ACCUMULATOR
+        .range(
+            storage,
+            None,
+            Some(Bound::inclusive(time.nanos())),
+            Order::Descending,
+        )
+        .next()
+        .transpose()?
+        .map(|(_, record)| record);
+    Ok(record)
+}
+
+/// Gets the value of the TWAP accumulator at the given time.
+///
+/// Errors with `TwapUnavailable` if no snapshot was taken at or before the given time.
+pub fn get_twap_accumulator(storage: &dyn Storage, time: Timestamp) -> ContractResult<Decimal256> {
+    get_latest_twap_record(storage, time)?
+        .ok_or(ContractError::TwapUnavailable { time })?
+        .cumulative_at(time)
+}
+
+/// A page of `(tick_id, liquidity)` pairs, alongside the cursor to resume from.
+pub type ActiveTicksPage = (Vec<(i64, Decimal256)>, Option<i64>);
+
+/// Gets the ticks with remaining liquidity for the given direction, as `(tick_id, liquidity)`
+/// pairs in ascending tick order, alongside the cursor to resume the scan from.
+///
+/// Supports pagination through `start_after` (exclusive) and `page_size`, which defaults to `DEFAULT_PAGE_SIZE`.
+/// Ticks without liquidity are skipped but still count towards `MAX_TICK_SCAN_ITERATIONS`, so a
+/// page stops short once that many ticks have been inspected. The cursor is the last tick inspected,
+/// or `None` once every tick has been inspected.
+pub fn get_active_ticks(
+    storage: &dyn Storage,
+    direction: OrderDirection,
+    start_after: Option<i64>,
+    page_size: Option<u64>,
+) -> StdResult<ActiveTicksPage> {
+    let page_size = page_size.unwrap_or(DEFAULT_PAGE_SIZE) as usize;
+
+    let mut active_ticks = vec![];
+    let mut last_scanned = None;
+    let ticks = TICK_STATE.range(
+        storage,
+        start_after.map(Bound::exclusive),
+        None,
+        Order::Ascending,
+    );
+    for (scanned, maybe_tick) in ticks.enumerate() {
+        if active_ticks.len() >= page_size || scanned >= MAX_TICK_SCAN_ITERATIONS as usize {
+            return Ok((active_ticks, last_scanned));
+        }
+
+        let (tick_id, tick_state) = maybe_tick?;
+        last_scanned = Some(tick_id);
+
+        let liquidity = tick_state.get_values(direction).total_amount_of_liquidity;
+        if !liquidity.is_zero() {
+            active_ticks.push((tick_id, liquidity));
+        }
+    }
+    Ok((active_ticks, None))
+}
+
 /// Gets the currently stored total liquidity for the specified `OrderDirection`.
 ///
 /// Defaults to 0 for empty values.
@@ -119,6 +477,32 @@ pub fn get_directional_liquidity(
     Ok(current_liquidity)
 }
 
+/// Adds the output of a market order fill to the traded volume of the given denom.
+///
+/// Errors on Uint256 overflow.
+pub fn add_volume(storage: &mut dyn Storage, denom: &str, amount: Uint256) -> ContractResult<()> {
+    if amount.is_zero() {
+        return Ok(());
+    }
+    let current_volume = VOLUME.may_load(storage, denom)?.unwrap_or_default();
+    VOLUME.save(storage, denom, &current_volume.checked_add(amount)?)?;
+    Ok(())
+}
+
+/// Returns the cumulative traded volume of the given denom.
+pub fn get_volume(storage: &dyn Storage, denom: &str) -> ContractResult<Uint256> {
+    let volume = VOLUME.may_load(storage, denom)?.unwrap_or_default();
+    Ok(volume)
+}
+
+/// Restarts any dust audit in progress.
+///
+/// Must be called whenever obligations are added to resting orders, i.e. on every placement and
+/// fill, as the audit may already have counted the affected orders.
+pub fn reset_dust_audit(storage: &mut dyn Storage) {
+    DUST_AUDIT.remove(storage);
+}
+
 /// Adds the specified amount of liquidity to the specified `OrderDirection`'s total liquidity.
 ///
 /// Errors on Decimal256 overflow.
@@ -159,6 +543,215 @@ pub fn subtract_directional_liquidity(
     Ok(())
 }
 
+/// Returns the number of ticks covered by a single bucket in the given batching layer.
+#[inline]
+pub fn layer_bucket_width(layer: u8) -> i64 {
+    BATCHING_LAYER_WIDTH_FACTOR.pow(layer as u32 + 1)
+}
+
+/// Returns the index of the bucket containing `tick_id` in the given batching layer.
+#[inline]
+pub fn layer_bucket_index(layer: u8, tick_id: i64) -> i64 {
+    tick_id.div_euclid(layer_bucket_width(layer))
+}
+
+/// Returns the inclusive range of ticks covered by a bucket in the given batching layer.
+#[inline]
+pub fn layer_bucket_range(layer: u8, bucket_index: i64) -> (i64, i64) {
+    let width = layer_bucket_width(layer);
+    (bucket_index * width, bucket_index * width + width - 1)
+}
+
+/// Iterates the indexes of all buckets in the given batching layer that hold liquidity for the
+/// specified `OrderDirection` and overlap the inclusive tick range `[min_tick, max_tick]`.
+pub fn get_layer_buckets<'a>(
+    storage: &'a dyn Storage,
+    layer: u8,
+    direction: OrderDirection,
+    min_tick: i64,
+    max_tick: i64,
+    ordering: Order,
+) -> Box<dyn Iterator<Item = StdResult<i64>> + 'a> {
+    let direction_key = direction.to_string();
+    LAYER_LIQUIDITY.prefix((layer, &direction_key)).keys(
+        storage,
+        Some(Bound::inclusive(layer_bucket_index(layer, min_tick))),
+        Some(Bound::inclusive(layer_bucket_index(layer, max_tick))),
+        ordering,
+    )
+}
+
+/// Adds the specified amount of liquidity at `tick_id` to every batching layer for the specified `OrderDirection`.
+///
+/// Errors on Decimal256 overflow.
+pub fn add_layer_liquidity(
+    storage: &mut dyn Storage,
+    direction: OrderDirection,
+    tick_id: i64,
+    amount: Decimal256,
+) -> ContractResult<()> {
+    let direction_key = &direction.to_string();
+    for layer in 0..BATCHING_LAYER_COUNT {
+        let key = (
+            layer,
+            direction_key.as_str(),
+            layer_bucket_index(layer, tick_id),
+        );
+        let current_liquidity = LAYER_LIQUIDITY.may_load(storage, key)?.unwrap_or_default();
+        LAYER_LIQUIDITY.save(storage, key, &(current_liquidity.checked_add(amount)?))?;
+    }
+    Ok(())
+}
+
+/// Subtracts the specified amount of liquidity at `tick_id` from every batching layer for the specified `OrderDirection`.
+///
+/// Buckets left without liquidity are removed so that they are skipped by market orders.
+///
+/// Errors on Decimal256 underflow.
+pub fn subtract_layer_liquidity(
+    storage: &mut dyn Storage,
+    direction: OrderDirection,
+    tick_id: i64,
+    amount: Decimal256,
+) -> ContractResult<()> {
+    let direction_key = &direction.to_string();
+    for layer in 0..BATCHING_LAYER_COUNT {
+        let key = (
+            layer,
+            direction_key.as_str(),
+            layer_bucket_index(layer, tick_id),
+        );
+        let current_liquidity = LAYER_LIQUIDITY.may_load(storage, key)?.unwrap_or_default();
+        let new_liquidity = current_liquidity.checked_sub(amount)?;
+        if new_liquidity.is_zero() {
+            LAYER_LIQUIDITY.remove(storage, key);
+        } else {
+            LAYER_LIQUIDITY.save(storage, key, &new_liquidity)?;
+        }
+    }
+    Ok(())
+}
+
+/// Adds an order's resting quantity to the liquidity queued on its tick at its placement height.
+///
+/// Errors on Uint128 overflow.
+pub fn add_queued_liquidity(
+    storage: &mut dyn Storage,
+    order: &LimitOrder,
+    amount: Uint128,
+) -> ContractResult<()> {
+    let direction_key = &order.order_direction.to_string();
+    let key = (
+        order.tick_id,
+        direction_key.as_str(),
+        order.placed_at_height,
+    );
+    let current_liquidity = QUEUED_LIQUIDITY.may_load(storage, key)?.unwrap_or_default();
+    QUEUED_LIQUIDITY.save(storage, key, &current_liquidity.checked_add(amount)?)?;
+    Ok(())
+}
+
+/// Subtracts an amount of an order's resting quantity, whether claimed or cancelled, from the
+/// liquidity queued on its tick at its placement height.
+///
+/// Heights left without liquidity are removed. Errors on Uint128 underflow.
+pub fn subtract_queued_liquidity(
+    storage: &mut dyn Storage,
+    order: &LimitOrder,
+    amount: Uint128,
+) -> ContractResult<()> {
+    let direction_key = &order.order_direction.to_string();
+    let key = (
+        order.tick_id,
+        direction_key.as_str(),
+        order.placed_at_height,
+    );
+    let current_liquidity = QUEUED_LIQUIDITY.may_load(storage, key)?.unwrap_or_default();
+    let new_liquidity = current_liquidity.checked_sub(amount)?;
+    if new_liquidity.is_zero() {
+        QUEUED_LIQUIDITY.remove(storage, key);
+    } else {
+        QUEUED_LIQUIDITY.save(storage, key, &new_liquidity)?;
+    }
+    Ok(())
+}
+
+/// Returns the resting quantity on a tick side queued after `height`.
+///
+/// Only the heights after `height` are read, so when `height` is the activation cutoff the cost is
+/// bounded by the activation delay rather than the number of orders on the tick.
+pub fn get_liquidity_queued_after(
+    storage: &dyn Storage,
+    tick_id: i64,
+    direction: OrderDirection,
+    height: u64,
+) -> ContractResult<Decimal256> {
+    let direction_key = &direction.to_string();
+    let mut liquidity = Decimal256::zero();
+    for maybe_queued in QUEUED_LIQUIDITY
+        .prefix((tick_id, direction_key.as_str()))
+        .range(
+            storage,
+            Some(Bound::exclusive(height)),
+            None,
+            Order::Ascending,
+        )
+    {
+        let (_, amount) = maybe_queued?;
+        liquidity = liquidity.checked_add(Decimal256::from_ratio(amount, 1u128))?;
+    }
+    Ok(liquidity)
+}
+
+/// Finds the closest tick to `start_tick` (inclusive) holding liquidity for the specified `OrderDirection`,
+/// searching in the direction a market order would fill that side of the book.
+///
+/// Asks are searched upwards towards `MAX_TICK` and bids downwards towards `MIN_TICK`. The lowest
+/// batching layer is used to jump directly to buckets with liquidity, so empty ticks are skipped.
+///
+/// If `max_iterations` is provided, errors once more than that many ticks have been inspected.
+pub fn get_next_tick_with_liquidity(
+    storage: &dyn Storage,
+    direction: OrderDirection,
+    start_tick: i64,
+    max_iterations: Option<u32>,
+) -> ContractResult<Option<i64>> {
+    let (min_tick, max_tick, ordering) = match direction {
+        OrderDirection::Ask => (start_tick, MAX_TICK, Order::Ascending),
+        OrderDirection::Bid => (MIN_TICK, start_tick, Order::Descending),
+    };
+
+    let mut iterations: u32 = 0;
+    for maybe_bucket in get_layer_buckets(storage, 0, direction, min_tick, max_tick, ordering) {
+        let (bucket_min, bucket_max) = layer_bucket_range(0, maybe_bucket?);
+        for maybe_tick in TICK_STATE.range(
+            storage,
+            Some(Bound::inclusive(bucket_min.max(min_tick))),
+            Some(Bound::inclusive(bucket_max.min(max_tick))),
+            ordering,
+        ) {
+            iterations += 1;
+            if let Some(limit) = max_iterations {
+                ensure!(
+                    iterations <= limit,
+                    ContractError::TickScanLimitExceeded { limit }
+                );
+            }
+
+            let (tick_id, tick_state) = maybe_tick?;
+            if !tick_state
+                .get_values(direction)
+                .total_amount_of_liquidity
+                .is_zero()
+            {
+                return Ok(Some(tick_id));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
 /// Returns the current maker fee
 ///
 /// If none is set defaults to `Decimal256::zero()`
@@ -166,3 +759,389 @@ pub fn get_maker_fee(storage: &dyn Storage) -> ContractResult<Decimal256> {
     let fee = MAKER_FEE.load(storage).unwrap_or_default();
     Ok(fee)
 }
+
+/// Returns the smallest maker fee amount that is transferred to the maker fee recipient
+///
+/// If none is set defaults to `Uint128::zero()`, transferring any non-zero fee
+pub fn get_min_fee_transfer(storage: &dyn Storage) -> ContractResult<Uint128> {
+    Ok(MIN_FEE_TRANSFER.may_load(storage)?.unwrap_or_default())
+}
+
+/// Returns the smallest input accepted for a market order
+///
+/// If none is set defaults to `Uint128::zero()`, accepting any non-zero input
+pub fn get_min_market_order_size(storage: &dyn Storage) -> ContractResult<Uint128> {
+    Ok(MIN_MARKET_ORDER_SIZE.may_load(storage)?.unwrap_or_default())
+}
+
+/// Returns the flat fee charged for placing a limit order
+///
+/// If none is set, or the configured fee is zero, defaults to `None`
+pub fn get_placement_fee(storage: &dyn Storage) -> ContractResult<Option<Coin>> {
+    let fee = PLACEMENT_FEE.may_load(storage)?;
+    Ok(fee.filter(|fee| !fee.amount.is_zero()))
+}
+
+/// Returns the maker fee discount for long resting orders, `None` if there is no discount
+pub fn get_maker_fee_discount(storage: &dyn Storage) -> ContractResult<Option<MakerFeeDiscount>> {
+    let discount = MAKER_FEE_DISCOUNT.may_load(storage)?;
+    Ok(discount.filter(|discount| !discount.discount.is_zero()))
+}
+
+/// Returns the number of blocks after placement before a limit order can be filled
+///
+/// If none is set defaults to zero, making orders fillable immediately
+pub fn get_activation_delay(storage: &dyn Storage) -> ContractResult<u64> {
+    Ok(ACTIVATION_DELAY.may_load(storage)?.unwrap_or_default())
+}
+
+/// Returns how limit orders placed exactly on the best opposite tick are handled
+///
+/// If none is set defaults to `CrossingTickPolicy::Rest`
+pub fn get_crossing_tick_policy(storage: &dyn Storage) -> ContractResult<CrossingTickPolicy> {
+    Ok(CROSSING_TICK_POLICY.may_load(storage)?.unwrap_or_default())
+}
+
+/// Returns the asset type of the given denom
+///
+/// If none is set defaults to `AssetType::Native`
+pub fn get_asset_type(storage: &dyn Storage, denom: &str) -> ContractResult<AssetType> {
+    let asset_type = ASSET_TYPES.may_load(storage, denom)?.unwrap_or_default();
+    Ok(asset_type)
+}
+
+/// Returns the maximum number of orders allowed on a single tick for a given direction
+///
+/// If none is set defaults to `DEFAULT_MAX_ORDERS_PER_TICK`
+pub fn get_max_orders_per_tick(storage: &dyn Storage) -> ContractResult<u64> {
+    let limit = MAX_ORDERS_PER_TICK
+        .may_load(storage)?
+        .unwrap_or(DEFAULT_MAX_ORDERS_PER_TICK);
+    Ok(limit)
+}
+
+/// Returns the maximum number of ticks a single market order may fill against
+///
+/// If none is set defaults to `DEFAULT_MAX_TICKS_PER_ORDER`
+pub fn get_max_ticks_per_order(storage: &dyn Storage) -> ContractResult<u32> {
+    let limit = MAX_TICKS_PER_ORDER
+        .may_load(storage)?
+        .unwrap_or(DEFAULT_MAX_TICKS_PER_ORDER);
+    Ok(limit)
+}
+
+/// Returns the maximum claim bounty that may be attached to a limit order
+///
+/// If none is set defaults to `DEFAULT_MAX_CLAIM_BOUNTY`
+pub fn get_max_claim_bounty(storage: &dyn Storage) -> ContractResult<Decimal256> {
+    let max_claim_bounty = MAX_CLAIM_BOUNTY
+        .may_load(storage)?
+        .unwrap_or(DEFAULT_MAX_CLAIM_BOUNTY);
+    Ok(max_claim_bounty)
+}
+
+/// Records the client order ID an order was placed with so that repeated placements can be deduplicated.
+pub fn save_client_order_id(
+    storage: &mut dyn Storage,
+    sender: &Addr,
+    client_order_id: &str,
+    tick_id: i64,
+    order_id: u64,
+) -> ContractResult<()> {
+    CLIENT_ORDER_IDS.save(storage, (sender, client_order_id), &(tick_id, order_id))?;
+    ORDER_CLIENT_IDS.save(
+        storage,
+        &(tick_id, order_id),
+        &(sender.clone(), client_order_id.to_string()),
+    )?;
+    Ok(())
+}
+
+/// Removes the client order ID mapping for an order, if it was placed with one.
+///
+/// Called whenever an order is removed from state, freeing the client order ID for reuse.
+pub fn remove_client_order_id(
+    storage: &mut dyn Storage,
+    tick_id: i64,
+    order_id: u64,
+) -> ContractResult<()> {
+    let key = (tick_id, order_id);
+    if let Some((sender, client_order_id)) = ORDER_CLIENT_IDS.may_load(storage, &key)? {
+        CLIENT_ORDER_IDS.remove(storage, (&sender, &client_order_id));
+        ORDER_CLIENT_IDS.remove(storage, &key);
+    }
+    Ok(())
+}
+
+/// Returns the maximum fraction a market order may move the price by
+///
+/// If none is set market orders are unbounded
+pub fn get_max_price_impact(storage: &dyn Storage) -> ContractResult<Option<Decimal256>> {
+    Ok(MAX_PRICE_IMPACT.may_load(storage)?)
+}
+
+/// Returns the maximum number of orders processed by a single batch claim
+///
+/// If none is set defaults to `DEFAULT_MAX_BATCH_CLAIM`
+pub fn get_max_batch_claim(storage: &dyn Storage) -> ContractResult<u32> {
+    let limit = MAX_BATCH_CLAIM
+        .may_load(storage)?
+        .unwrap_or(DEFAULT_MAX_BATCH_CLAIM);
+    Ok(limit)
+}
+
+/// Returns the number of orders stored on the given tick for the given direction.
+pub fn get_tick_order_count(
+    storage: &dyn Storage,
+    tick_id: i64,
+    direction: OrderDirection,
+) -> ContractResult<u64> {
+    let count = TICK_ORDER_COUNT
+        .may_load(storage, (tick_id, &direction.to_string()))?
+        .unwrap_or_default();
+    Ok(count)
+}
+
+/// Increments the number of orders stored on the given tick for the given direction.
+///
+/// Errors if the tick already holds the maximum number of orders for the direction.
+pub fn increment_tick_order_count(
+    storage: &mut dyn Storage,
+    tick_id: i64,
+    direction: OrderDirection,
+) -> ContractResult<()> {
+    let limit = get_max_orders_per_tick(storage)?;
+    let count = get_tick_order_count(storage, tick_id, direction)?;
+    ensure!(
+        count < limit,
+        ContractError::TickOrderLimitReached { tick_id, limit }
+    );
+    TICK_ORDER_COUNT.save(storage, (tick_id, &direction.to_string()), &(count + 1))?;
+    Ok(())
+}
+
+/// Decrements the number of orders stored on the given tick for the given direction.
+///
+/// The entry is removed once the count reaches zero. Errors if the tick has no orders counted, as
+/// the count has then drifted from the stored orders.
+pub fn decrement_tick_order_count(
+    storage: &mut dyn Storage,
+    tick_id: i64,
+    direction: OrderDirection,
+) -> ContractResult<()> {
+    let direction_key = &direction.to_string();
+    let count = get_tick_order_count(storage, tick_id, direction)?;
+    let count = count
+        .checked_sub(1)
+        .ok_or_else(|| OverflowError::new(OverflowOperation::Sub, count, 1))?;
+    if count == 0 {
+        TICK_ORDER_COUNT.remove(storage, (tick_id, direction_key));
+    } else {
+        TICK_ORDER_COUNT.save(storage, (tick_id, direction_key), &count)?;
+    }
+    Ok(())
+}
+
+/// Returns the number of orders stored for the given owner.
+pub fn get_order_count(storage: &dyn Storage, owner: &Addr) -> ContractResult<u32> {
+    let count = ORDER_COUNT.may_load(storage, owner)?.unwrap_or_default();
+    Ok(count)
+}
+
+/// Increments the number of orders stored for the given owner.
+pub fn increment_order_count(storage: &mut dyn Storage, owner: &Addr) -> ContractResult<()> {
+    let count = get_order_count(storage, owner)?;
+    ORDER_COUNT.save(storage, owner, &(count + 1))?;
+    Ok(())
+}
+
+/// Decrements the number of orders stored for the given owner.
+///
+/// The entry is removed once the count reaches zero. Errors if the owner has no orders counted, as
+/// the count has then drifted from the stored orders.
+pub fn decrement_order_count(storage: &mut dyn Storage, owner: &Addr) -> ContractResult<()> {
+    let count = get_order_count(storage, owner)?;
+    let count = count
+        .checked_sub(1)
+        .ok_or_else(|| OverflowError::new(OverflowOperation::Sub, count, 1))?;
+    if count == 0 {
+        ORDER_COUNT.remove(storage, owner);
+    } else {
+        ORDER_COUNT.save(storage, owner, &count)?;
+    }
+    Ok(())
+}
+
+/// Starts a rebuild of the state derived from the ticks and orders: the tick batching layers, the
+/// per tick and per owner order counts, the queued liquidity and the order indexes.
+///
+/// Books created before this state was introduced hold ticks and orders that were never added to
+/// it, so it is rebuilt on migration rather than assumed to be in sync. Walking every tick and order
+/// could exceed the block gas limit, so the rebuild is advanced a page at a time by
+/// `advance_derived_state_rebuild`. Handlers relying on the derived state are blocked until then.
+pub fn start_derived_state_rebuild(storage: &mut dyn Storage) -> ContractResult<()> {
+    DERIVED_STATE_REBUILD.save(storage, &DerivedStateRebuild::Clearing)?;
+    Ok(())
+}
+
+/// Errors with `RebuildInProgress` until the rebuild started by `migrate` has completed.
+pub fn ensure_derived_state_rebuilt(storage: &dyn Storage) -> ContractResult<()> {
+    ensure!(
+        DERIVED_STATE_REBUILD.may_load(storage)?.is_none(),
+        ContractError::RebuildInProgress
+    );
+    Ok(())
+}
+
+/// Advances the rebuild of the derived state by up to `limit` steps, returning whether it has
+/// completed. Each step removes one stale entry, adds one tick's liquidity or adds one order.
+///
+/// The derived state stored before the migration is cleared first, then rebuilt from every tick
+/// and every order in key order, so each call resumes where the previous one stopped.
+pub fn advance_derived_state_rebuild(
+    storage: &mut dyn Storage,
+    limit: u32,
+) -> ContractResult<bool> {
+    let Some(mut rebuild) = DERIVED_STATE_REBUILD.may_load(storage)? else {
+        return Ok(true);
+    };
+
+    let mut steps = limit as usize;
+    while steps > 0 {
+        rebuild = match rebuild {
+            DerivedStateRebuild::Clearing => {
+                let removed = clear_derived_state(storage, steps)?;
+                if removed == steps {
+                    steps = 0;
+                    DerivedStateRebuild::Clearing
+                } else {
+                    steps -= removed;
+                    DerivedStateRebuild::Ticks { start_after: None }
+                }
+            }
+            DerivedStateRebuild::Ticks { start_after } => {
+                let ticks = TICK_STATE
+                    .range(
+                        storage,
+                        start_after.map(Bound::exclusive),
+                        None,
+                        Order::Ascending,
+                    )
+                    .take(steps)
+                    .collect::<StdResult<Vec<_>>>()?;
+                let visited = ticks.len();
+                let last_tick = ticks.last().map(|(tick_id, _)| *tick_id);
+                for (tick_id, tick_state) in ticks {
+                    for direction in [OrderDirection::Bid, OrderDirection::Ask] {
+                        let liquidity = tick_state.get_values(direction).total_amount_of_liquidity;
+                        if !liquidity.is_zero() {
+                            add_layer_liquidity(storage, direction, tick_id, liquidity)?;
+                        }
+                    }
+                }
+
+                if visited == steps {
+                    steps = 0;
+                    DerivedStateRebuild::Ticks {
+                        start_after: last_tick,
+                    }
+                } else {
+                    steps -= visited;
+                    DerivedStateRebuild::Orders { start_after: None }
+                }
+            }
+            DerivedStateRebuild::Orders { start_after } => {
+                let page = ORDERS_BY_KEY
+                    .range(
+                        storage,
+                        start_after.map(Bound::exclusive),
+                        None,
+                        Order::Ascending,
+                    )
+                    .take(steps)
+                    .map(|res| res.map(|(_, order)| order))
+                    .collect::<StdResult<Vec<LimitOrder>>>()?;
+                let visited = page.len();
+                let last_order = page.last().map(|order| (order.tick_id, order.order_id));
+                for order in page {
+                    // Books created before the per tick limit may exceed it, so the count is
+                    // written directly rather than through `increment_tick_order_count`
+                    let direction_key = &order.order_direction.to_string();
+                    let count =
+                        get_tick_order_count(storage, order.tick_id, order.order_direction)?;
+                    TICK_ORDER_COUNT.save(storage, (order.tick_id, direction_key), &(count + 1))?;
+                    increment_order_count(storage, &order.owner)?;
+                    add_queued_liquidity(storage, &order, order.quantity)?;
+                    // Saving the order again writes any index entries it is missing
+                    orders().save(storage, &(order.tick_id, order.order_id), &order)?;
+                }
+
+                if visited < steps {
+                    DERIVED_STATE_REBUILD.remove(storage);
+                    return Ok(true);
+                }
+                steps = 0;
+                DerivedStateRebuild::Orders {
+                    start_after: last_order,
+                }
+            }
+        };
+    }
+
+    DERIVED_STATE_REBUILD.save(storage, &rebuild)?;
+    Ok(false)
+}
+
+/// Removes up to `limit` entries of the derived state, returning the number removed.
+///
+/// The order indexes are not cleared, as saving each order again during the rebuild replaces its
+/// index entries.
+fn clear_derived_state(storage: &mut dyn Storage, limit: usize) -> StdResult<usize> {
+    let mut removed = 0;
+
+    let layer_keys = LAYER_LIQUIDITY
+        .keys(storage, None, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (layer, direction_key, bucket_index) in layer_keys {
+        LAYER_LIQUIDITY.remove(storage, (layer, &direction_key, bucket_index));
+        removed += 1;
+    }
+
+    let tick_count_keys = TICK_ORDER_COUNT
+        .keys(storage, None, None, Order::Ascending)
+        .take(limit - removed)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (tick_id, direction_key) in tick_count_keys {
+        TICK_ORDER_COUNT.remove(storage, (tick_id, &direction_key));
+        removed += 1;
+    }
+
+    let owner_count_keys = ORDER_COUNT
+        .keys(storage, None, None, Order::Ascending)
+        .take(limit - removed)
+        .collect::<StdResult<Vec<_>>>()?;
+    for owner in owner_count_keys {
+        ORDER_COUNT.remove(storage, &owner);
+        removed += 1;
+    }
+
+    let queued_keys = QUEUED_LIQUIDITY
+        .keys(storage, None, None, Order::Ascending)
+        .take(limit - removed)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (tick_id, direction_key, placed_at_height) in queued_keys {
+        QUEUED_LIQUIDITY.remove(storage, (tick_id, &direction_key, placed_at_height));
+        removed += 1;
+    }
+
+    Ok(removed)
+}
+
+/// Returns the set of currently paused operations
+///
+/// If none is set defaults to no operations being paused
+pub fn get_paused_ops(storage: &dyn Storage) -> ContractResult<PausedOps> {
+    let paused_ops = PAUSED_OPS.may_load(storage)?.unwrap_or_default();
+    Ok(paused_ops)
+}
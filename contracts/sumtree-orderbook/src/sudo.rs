@@ -1,36 +1,65 @@
 use cosmwasm_std::{
-    coin, ensure, entry_point, to_json_binary, BankMsg, Coin, Decimal, Deps, DepsMut, Env,
-    Response, SubMsg, Uint128, Uint256,
+    coin, ensure, entry_point, to_json_binary, BankMsg, Coin, Decimal, Decimal256, Deps, DepsMut,
+    Env, MessageInfo, Response, Storage, SubMsg, Uint128, Uint256,
 };
+use cw_utils::nonpayable;
 
 use crate::{
     auth,
-    constants::{EXPECTED_SWAP_FEE, MAX_TICK, MIN_TICK},
+    constants::{DEFAULT_DUST_AUDIT_LIMIT, EXPECTED_SWAP_FEE, MAX_TICK, MIN_TICK},
     error::ContractResult,
-    msg::{SudoMsg, SwapExactAmountInResponseData},
-    order::run_market_order,
-    state::{IS_ACTIVE, ORDERBOOK},
+    msg::{SudoMsg, SwapExactAmountInResponseData, SwapResult},
+    order::{
+        average_fill_price, ensure_market_order_size, ensure_price_impact_within_limit,
+        run_market_order, settle_auto_orders, transfer_msg,
+    },
+    orderbook::set_max_claim_bounty,
+    query,
+    state::{
+        ensure_derived_state_rebuilt, get_asset_type, get_max_ticks_per_order,
+        get_next_tick_with_liquidity, get_orderbook, get_paused_ops, update_twap_accumulator,
+        DUST_AUDIT, IS_ACTIVE, MAKER_FEE_RECIPIENT, PAUSED_OPS, PRUNE_REWARD_POOL,
+    },
+    tick_math::price_limit_to_tick_bound,
     types::{
-        coin_u256, Coin256, MarketOrder, MsgSend256, OrderDirection, REPLY_ID_REFUND,
-        REPLY_ID_SUDO_SWAP_EXACT_IN,
+        coin_u256, AssetType, Coin256, Cw20BalanceResponse, Cw20QueryMsg, DustAudit, MarketOrder,
+        MsgSend256, OrderDirection, PausedOps, REPLY_ID_REFUND, REPLY_ID_SUDO_SWAP_EXACT_IN,
     },
     ContractError,
 };
 
 #[cfg_attr(not(feature = "imported"), entry_point)]
-pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> ContractResult<Response> {
+pub fn sudo(mut deps: DepsMut, env: Env, msg: SudoMsg) -> ContractResult<Response> {
     // Ensure orderbook is active
     ensure_is_active(deps.as_ref())?;
 
-    match msg {
+    let is_swap = matches!(
+        msg,
+        SudoMsg::SwapExactAmountIn { .. }
+            | SudoMsg::SwapToTick { .. }
+            | SudoMsg::SwapExactAmountInWithRoute { .. }
+            | SudoMsg::SwapExactAmountOut { .. }
+    );
+
+    // Ensure market orders are not paused and the derived state they fill against is rebuilt
+    // Switches only apply to swap messages
+    if is_swap {
+        ensure_market_orders_not_paused(deps.storage)?;
+        ensure_derived_state_rebuilt(deps.storage)?;
+    }
+
+    let block_time = env.block.time;
+    let response = match msg {
         SudoMsg::SwapExactAmountIn {
             sender,
             token_in,
             token_out_denom,
             token_out_min_amount,
             swap_fee,
+            recipient,
+            max_slippage_ticks,
         } => dispatch_swap_exact_amount_in(
-            deps,
+            deps.branch(),
             env,
             sender,
             token_in,
@@ -38,6 +67,8 @@ pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> ContractResult<Response> {
             token_out_min_amount,
             swap_fee,
             None,
+            max_slippage_ticks,
+            recipient,
         ),
         SudoMsg::SwapToTick {
             sender,
@@ -46,8 +77,10 @@ pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> ContractResult<Response> {
             token_out_min_amount,
             swap_fee,
             target_tick,
+            recipient,
+            max_slippage_ticks,
         } => dispatch_swap_exact_amount_in(
-            deps,
+            deps.branch(),
             env,
             sender,
             token_in,
@@ -55,6 +88,29 @@ pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> ContractResult<Response> {
             token_out_min_amount,
             swap_fee,
             Some(target_tick),
+            max_slippage_ticks,
+            recipient,
+        ),
+        SudoMsg::SwapExactAmountInWithRoute {
+            sender,
+            token_in,
+            token_out_denom,
+            token_out_min_amount,
+            swap_fee,
+            tranches,
+            recipient,
+            max_slippage_ticks,
+        } => dispatch_swap_exact_amount_in_with_route(
+            deps.branch(),
+            env,
+            sender,
+            token_in,
+            token_out_denom,
+            token_out_min_amount,
+            swap_fee,
+            tranches,
+            max_slippage_ticks,
+            recipient,
         ),
         SudoMsg::SwapExactAmountOut {
             sender,
@@ -63,7 +119,7 @@ pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> ContractResult<Response> {
             token_out,
             swap_fee,
         } => dispatch_swap_exact_amount_out(
-            deps,
+            deps.branch(),
             sender,
             token_in_denom,
             token_in_max_amount,
@@ -88,15 +144,48 @@ pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> ContractResult<Response> {
         }
 
         // -- Active Switch --
-        SudoMsg::SetActive { active } => set_active(deps, active),
+        SudoMsg::SetActive { active } => set_active(deps.branch(), active),
+
+        // -- Pause Switch --
+        SudoMsg::SetPausedOps { paused_ops } => set_paused_ops(deps.branch(), paused_ops),
+
+        // -- Claim Bounty Cap --
+        SudoMsg::SetMaxClaimBounty { max_claim_bounty } => {
+            dispatch_set_max_claim_bounty(deps.branch(), max_claim_bounty)
+        }
+
+        // -- Dust Recovery --
+        SudoMsg::SweepDust {
+            denom,
+            recipient,
+            limit,
+        } => dispatch_sweep_dust(deps.branch(), env, denom, recipient, limit),
+    }?;
+
+    // Record the mid price left by this message for the TWAP oracle
+    // Only swaps can move the book, so other messages cannot change the mid price
+    if is_swap {
+        update_twap_accumulator(deps.storage, block_time)?;
     }
+
+    Ok(response)
 }
 
 /// Swaps the provided token in for the desired token out while restricting the possible minimum output.
 /// The swap is performed by first determining the orderbook to be used before generating a market order against that orderbook.
 /// Order direction is automatically determined by the token in/token out pairing.
 ///
-/// Errors if the amount provided by the swap does not meet the `token_out_min_amount` or if there is no orderbook for the provided pair.
+/// The swap is bounded by `target_tick` if provided, and by `max_slippage_ticks` past the best
+/// price at execution if provided, stopping at whichever is reached first. Input left unfilled by
+/// a bound is refunded.
+///
+/// The output is sent to the `recipient` if provided, allowing a contract swapping on a user's
+/// behalf to deliver the output directly to them. Any unfilled input is refunded to the sender.
+///
+/// The response data holds a `SwapResult` breaking down the fill for calling contracts.
+///
+/// Errors if the amount provided by the swap does not meet the `token_out_min_amount`, if there is no orderbook for the provided pair,
+/// if the input is below the minimum market order size or if the recipient is not a valid address.
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn dispatch_swap_exact_amount_in(
     deps: DepsMut,
@@ -107,42 +196,56 @@ pub(crate) fn dispatch_swap_exact_amount_in(
     token_out_min_amount: Uint128,
     swap_fee: Decimal,
     target_tick: Option<i64>,
+    max_slippage_ticks: Option<u32>,
+    recipient: Option<String>,
 ) -> ContractResult<Response> {
-    // Ensure the provided swap fee matches what is expected
-    ensure_swap_fee(swap_fee)?;
+    let order_direction =
+        validate_swap(deps.as_ref(), &token_in.denom, &token_out_denom, swap_fee)?;
+    let recipient = recipient
+        .map(|recipient| deps.api.addr_validate(&recipient))
+        .transpose()?;
 
-    let token_in_denom = token_in.denom.clone();
-
-    // Ensure in and out denoms are not equal
-    ensure!(
-        token_in_denom != token_out_denom,
-        ContractError::InvalidSwap {
-            error: "Input and output denoms cannot be the same".to_string()
-        }
-    );
-
-    // Load the orderbook for the provided pair
-    let orderbook = ORDERBOOK.load(deps.storage)?;
-
-    // Determine order direction based on token in/out denoms
-    let order_direction = orderbook.direction_from_pair(token_in_denom, token_out_denom.clone())?;
+    ensure_market_order_size(deps.storage, token_in.amount)?;
 
     // Generate market order to be run
-    let mut order = MarketOrder::new(
+    let mut order = MarketOrder::try_new(
         token_in.amount,
         order_direction,
         deps.api.addr_validate(&sender)?,
-    );
+    )?
+    .with_recipient(recipient);
 
-    // Market orders always run until either the input is filled or the orderbook is exhausted.
-    let tick_bound = target_tick.unwrap_or(match order_direction {
+    // Market orders always run until either the input is filled or the orderbook is exhausted,
+    // unless bounded by a target tick or a maximum slippage.
+    let mut tick_bound = target_tick.unwrap_or(match order_direction {
         OrderDirection::Bid => MAX_TICK,
         OrderDirection::Ask => MIN_TICK,
     });
+    if let Some(max_slippage_ticks) = max_slippage_ticks {
+        let slippage_bound =
+            slippage_tick_bound(deps.storage, order_direction, max_slippage_ticks)?;
+        tick_bound = match order_direction {
+            OrderDirection::Bid => tick_bound.min(slippage_bound),
+            OrderDirection::Ask => tick_bound.max(slippage_bound),
+        };
+    }
 
     // Run market order against orderbook
-    let (output, bank_msg) =
-        run_market_order(deps.storage, env.contract.address, &mut order, tick_bound)?;
+    let filled_direction = order_direction.opposite();
+    let start_tick = get_orderbook(deps.storage)?.next_tick(filled_direction);
+    let (output, avg_price, bank_msg, _, ticks_traversed, tick_pointer_event) =
+        run_market_order(deps.storage, &env, &mut order, tick_bound, None)?;
+
+    // Pay out the fills of auto-settling orders on the ticks filled against
+    let end_tick = get_orderbook(deps.storage)?.next_tick(filled_direction);
+    let (settlement_msgs, settlement_events) = settle_auto_orders(
+        deps.storage,
+        deps.api,
+        &env,
+        filled_direction,
+        start_tick,
+        end_tick,
+    )?;
 
     // Validate the output message against the order
     let MsgSend256 { amount, .. } = bank_msg.clone();
@@ -173,6 +276,9 @@ pub(crate) fn dispatch_swap_exact_amount_in(
 
     Ok(Response::default()
         .add_submessages(bank_msgs)
+        .add_submessages(settlement_msgs)
+        .add_events(tick_pointer_event)
+        .add_events(settlement_events)
         .add_attributes(vec![
             ("method", "swapExactAmountIn"),
             ("sender", &sender),
@@ -180,12 +286,266 @@ pub(crate) fn dispatch_swap_exact_amount_in(
             ("token_out_denom", &token_out_denom),
             ("token_out_min_amount", &token_out_min_amount.to_string()),
             ("output_quantity", &output.to_string()),
+            ("recipient", order.recipient().as_str()),
         ])
+        .add_attributes(avg_price.map(|price| ("avg_price", price.to_string())))
         .set_data(to_json_binary(&SwapExactAmountInResponseData {
             token_out_amount: output,
+            swap_result: SwapResult {
+                token_in: coin(
+                    token_in.amount.checked_sub(order.quantity)?.u128(),
+                    &token_in.denom,
+                ),
+                token_out: output_amt.clone(),
+                avg_price,
+                ticks_traversed,
+            },
         })?))
 }
 
+/// Converts a maximum slippage in ticks into an absolute tick bound, relative to the best tick
+/// holding liquidity opposite the order at execution time.
+///
+/// If the opposite side of the book is empty the bound is the tick pointer, leaving nothing to fill.
+fn slippage_tick_bound(
+    storage: &dyn Storage,
+    order_direction: OrderDirection,
+    max_slippage_ticks: u32,
+) -> ContractResult<i64> {
+    let filled_direction = order_direction.opposite();
+    let pointer = get_orderbook(storage)?.next_tick(filled_direction);
+    let best_tick =
+        get_next_tick_with_liquidity(storage, filled_direction, pointer, None)?.unwrap_or(pointer);
+
+    let max_slippage_ticks = i64::from(max_slippage_ticks);
+    Ok(match order_direction {
+        OrderDirection::Bid => best_tick.saturating_add(max_slippage_ticks).min(MAX_TICK),
+        OrderDirection::Ask => best_tick.saturating_sub(max_slippage_ticks).max(MIN_TICK),
+    })
+}
+
+/// Swaps the provided token in across a sequence of price bands, each tranche being a
+/// `(price_limit, max_quantity)` pair.
+///
+/// Tranches are filled in order, each spending at most `max_quantity` of the remaining input on
+/// ticks priced within its `price_limit` (oriented the same way as tick prices). Tranches whose
+/// band holds no liquidity are skipped, and a tranche whose band runs out of liquidity passes its
+/// leftover on to the next tranche. Any input left after all tranches is refunded.
+///
+/// The route is limited as a single market order: the ticks filled by all tranches count towards
+/// the maximum ticks per order, and the price impact is measured from the start of the route.
+/// Likewise `max_slippage_ticks` is measured from the best tick before the first tranche and bounds
+/// every tranche. The output is sent to the `recipient` if provided, refunds always go to the sender.
+///
+/// Errors if the total output does not meet the `token_out_min_amount`, if there is no orderbook for the provided pair,
+/// if the input is below the minimum market order size or if the route exceeds the maximum ticks
+/// per order or price impact.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn dispatch_swap_exact_amount_in_with_route(
+    deps: DepsMut,
+    env: Env,
+    sender: String,
+    token_in: Coin,
+    token_out_denom: String,
+    token_out_min_amount: Uint128,
+    swap_fee: Decimal,
+    tranches: Vec<(Decimal256, Uint128)>,
+    max_slippage_ticks: Option<u32>,
+    recipient: Option<String>,
+) -> ContractResult<Response> {
+    let order_direction =
+        validate_swap(deps.as_ref(), &token_in.denom, &token_out_denom, swap_fee)?;
+    let owner = deps.api.addr_validate(&sender)?;
+    let recipient = recipient
+        .map(|recipient| deps.api.addr_validate(&recipient))
+        .transpose()?
+        .unwrap_or_else(|| owner.clone());
+    ensure_market_order_size(deps.storage, token_in.amount)?;
+
+    // Slippage is measured once from the start of the route so that it bounds every tranche
+    let slippage_bound = max_slippage_ticks
+        .map(|max_slippage_ticks| {
+            slippage_tick_bound(deps.storage, order_direction, max_slippage_ticks)
+        })
+        .transpose()?;
+
+    let filled_direction = order_direction.opposite();
+    let start_orderbook = get_orderbook(deps.storage)?;
+    let start_tick = start_orderbook.next_tick(filled_direction);
+    let max_ticks_per_order = get_max_ticks_per_order(deps.storage)?;
+    let mut remaining = token_in.amount;
+    let mut total_output = Uint256::zero();
+    let mut ticks_traversed = 0u32;
+    let mut events = vec![];
+    for (price_limit, max_quantity) in tranches {
+        let quantity = remaining.min(max_quantity);
+        if quantity.is_zero() {
+            continue;
+        }
+        let Some(mut tick_bound) = price_limit_to_tick_bound(price_limit, order_direction)? else {
+            continue;
+        };
+        if let Some(slippage_bound) = slippage_bound {
+            tick_bound = match order_direction {
+                OrderDirection::Bid => tick_bound.min(slippage_bound),
+                OrderDirection::Ask => tick_bound.max(slippage_bound),
+            };
+        }
+
+        // Skip tranches whose band lies entirely before the best available price
+        let orderbook = get_orderbook(deps.storage)?;
+        let band_is_empty = match order_direction {
+            OrderDirection::Bid => tick_bound < orderbook.next_ask_tick,
+            OrderDirection::Ask => tick_bound > orderbook.next_bid_tick,
+        };
+        if band_is_empty {
+            continue;
+        }
+
+        // A tranche bounded at MIN_TICK or MAX_TICK can outrun the book, its leftover input then
+        // carries over to the next tranche or the refund
+        let mut order = MarketOrder::new(quantity, order_direction, owner.clone())
+            .with_allow_partial_fill(true);
+        let (output, _, _, _, tranche_ticks_traversed, tick_pointer_event) =
+            run_market_order(deps.storage, &env, &mut order, tick_bound, None)?;
+        total_output = total_output.checked_add(output)?;
+        ticks_traversed += tranche_ticks_traversed;
+        events.extend(tick_pointer_event);
+        remaining = remaining.checked_sub(quantity.checked_sub(order.quantity)?)?;
+
+        // Each tranche is only limited on its own, so the limit is also applied across the route
+        ensure!(
+            ticks_traversed <= max_ticks_per_order,
+            ContractError::TooManyTicks {
+                limit: max_ticks_per_order
+            }
+        );
+    }
+
+    // Each tranche's price impact is measured from the price it started at, so the impact of the
+    // whole route is checked against the price before the first tranche
+    ensure_price_impact_within_limit(
+        deps.storage,
+        filled_direction,
+        &start_orderbook,
+        &get_orderbook(deps.storage)?,
+    )?;
+
+    // Pay out the fills of auto-settling orders on the ticks filled against
+    let end_tick = get_orderbook(deps.storage)?.next_tick(filled_direction);
+    let (settlement_msgs, settlement_events) = settle_auto_orders(
+        deps.storage,
+        deps.api,
+        &env,
+        filled_direction,
+        start_tick,
+        end_tick,
+    )?;
+    events.extend(settlement_events);
+
+    let output = coin_u256(total_output, &token_out_denom);
+    validate_output_amount(
+        Uint256::from_uint128(token_in.amount),
+        Uint256::from_uint128(token_out_min_amount),
+        &coin_u256(token_in.amount, &token_in.denom),
+        &output,
+    )?;
+
+    let input_consumed = token_in.amount.checked_sub(remaining)?;
+    let mut bank_msgs = vec![];
+    if !total_output.is_zero() {
+        bank_msgs.push(SubMsg::reply_on_error(
+            MsgSend256 {
+                from_address: env.contract.address.to_string(),
+                to_address: recipient.to_string(),
+                amount: vec![output.clone()],
+            },
+            REPLY_ID_SUDO_SWAP_EXACT_IN,
+        ));
+    }
+    if !remaining.is_zero() {
+        bank_msgs.push(SubMsg::reply_on_error(
+            BankMsg::Send {
+                to_address: owner.to_string(),
+                amount: vec![coin(remaining.u128(), &token_in.denom)],
+            },
+            REPLY_ID_REFUND,
+        ));
+    }
+
+    Ok(Response::default()
+        .add_submessages(bank_msgs)
+        .add_submessages(settlement_msgs)
+        .add_events(events)
+        .add_attributes(vec![
+            ("method", "swapExactAmountInWithRoute"),
+            ("sender", &sender),
+            ("token_in", &token_in.to_string()),
+            ("token_out_denom", &token_out_denom),
+            ("token_out_min_amount", &token_out_min_amount.to_string()),
+            ("output_quantity", &total_output.to_string()),
+            ("refund_quantity", &remaining.to_string()),
+            ("recipient", recipient.as_str()),
+        ])
+        .set_data(to_json_binary(&SwapExactAmountInResponseData {
+            token_out_amount: total_output,
+            swap_result: SwapResult {
+                token_in: coin(input_consumed.u128(), &token_in.denom),
+                token_out: output,
+                avg_price: average_fill_price(
+                    order_direction,
+                    Uint256::from_uint128(input_consumed),
+                    total_output,
+                )?,
+                ticks_traversed,
+            },
+        })?))
+}
+
+/// Validates the parameters shared by all swaps, returning the direction of the resulting market order.
+///
+/// Errors if:
+/// 1. The swap fee does not match the expected swap fee
+/// 2. The in and out denoms are the same
+/// 3. The denoms do not match the orderbook's pair
+/// 4. Either denom is a CW20 denom
+fn validate_swap(
+    deps: Deps,
+    token_in_denom: &str,
+    token_out_denom: &str,
+    swap_fee: Decimal,
+) -> ContractResult<OrderDirection> {
+    // Ensure the provided swap fee matches what is expected
+    ensure_swap_fee(swap_fee)?;
+
+    // Ensure in and out denoms are not equal
+    ensure!(
+        token_in_denom != token_out_denom,
+        ContractError::InvalidSwap {
+            error: "Input and output denoms cannot be the same".to_string()
+        }
+    );
+
+    // Load the orderbook for the provided pair
+    let orderbook = get_orderbook(deps.storage)?;
+
+    // Determine order direction based on token in/out denoms
+    let order_direction =
+        orderbook.direction_from_pair(token_in_denom.to_string(), token_out_denom.to_string())?;
+
+    // Swaps are routed through the pool manager, which only handles native denoms
+    for denom in [token_in_denom, token_out_denom] {
+        ensure!(
+            get_asset_type(deps.storage, denom)? == AssetType::Native,
+            ContractError::InvalidSwap {
+                error: format!("Swaps are not supported for CW20 denom {denom}")
+            }
+        );
+    }
+
+    Ok(order_direction)
+}
+
 /// Temporarily unimplemented
 pub(crate) fn dispatch_swap_exact_amount_out(
     _deps: DepsMut,
@@ -276,3 +636,256 @@ pub(crate) fn ensure_is_active(deps: Deps) -> ContractResult<()> {
 
     Ok(())
 }
+
+/// Sets the operations that are paused on the orderbook.
+///
+/// Cancellations can never be paused so that users can always recover their funds.
+pub(crate) fn set_paused_ops(deps: DepsMut, paused_ops: PausedOps) -> ContractResult<Response> {
+    PAUSED_OPS.save(deps.storage, &paused_ops)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("method", "set_paused_ops"),
+        ("placements", &paused_ops.placements.to_string()),
+        ("market_orders", &paused_ops.market_orders.to_string()),
+        ("claims", &paused_ops.claims.to_string()),
+    ]))
+}
+
+/// Sets the upper bound for claim bounties on newly placed limit orders.
+///
+/// Orders placed before the update keep their existing bounty.
+pub(crate) fn dispatch_set_max_claim_bounty(
+    deps: DepsMut,
+    max_claim_bounty: Decimal256,
+) -> ContractResult<Response> {
+    let max_claim_bounty = set_max_claim_bounty(deps.storage, max_claim_bounty)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("method", "set_max_claim_bounty"),
+        ("max_claim_bounty", &max_claim_bounty.to_string()),
+    ]))
+}
+
+/// Sends the contract's balance of `denom` in excess of its expected reserves to `recipient`.
+///
+/// Recovers funds that cannot be attributed to any user, such as rounding dust or funds left by a
+/// failed reply. The expected reserves are the solvency rollup of every resting order plus the
+/// prune reward pool, counted by a dust audit up to `limit` orders per call. Nothing is swept until
+/// the audit has counted every order, so funds owed to users are never swept. As any placement or
+/// fill restarts the audit, placements and market orders may need to be paused to complete an
+/// audit on a busy book.
+///
+/// Errors if:
+/// 1. The recipient is not a valid address
+/// 2. The expected reserves or the contract's balance cannot be computed
+/// 3. The balance does not exceed the expected reserves for the denom
+pub(crate) fn dispatch_sweep_dust(
+    mut deps: DepsMut,
+    env: Env,
+    denom: String,
+    recipient: String,
+    limit: Option<u32>,
+) -> ContractResult<Response> {
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let Some(expected) = audit_expected_reserves(deps.branch(), &denom, limit)? else {
+        // Orders remain to be counted, the surplus is swept by a later call
+        return Ok(Response::default().add_attributes(vec![
+            ("method", "sudo_sweep_dust"),
+            ("denom", &denom),
+            ("recipient", recipient.as_str()),
+            ("audit", "in_progress"),
+        ]));
+    };
+    let surplus = surplus_above_reserves(deps.as_ref(), &env, &denom, expected)?;
+
+    Ok(Response::default()
+        .add_attributes(vec![
+            ("method", "sudo_sweep_dust"),
+            ("denom", &denom),
+            ("recipient", recipient.as_str()),
+            ("amount", &surplus.to_string()),
+            ("expected_reserves", &expected.to_string()),
+        ])
+        .add_message(transfer_msg(
+            deps.storage,
+            recipient.to_string(),
+            coin(surplus.u128(), denom),
+        )?))
+}
+
+/// Sends the contract's balance of `denom` in excess of its expected reserves to the maker fee
+/// recipient.
+///
+/// Unlike `SweepDust` this is callable by anyone, as the surplus can only ever go to the fixed
+/// maker fee recipient. The surplus is computed by the same dust audit as `SweepDust`, so funds
+/// owed to users are never donated.
+///
+/// Errors if:
+/// 1. Funds are sent with the message
+/// 2. The expected reserves or the contract's balance cannot be computed
+/// 3. The balance does not exceed the expected reserves for the denom
+pub(crate) fn donate_dust(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    denom: String,
+    limit: Option<u32>,
+) -> ContractResult<Response> {
+    nonpayable(&info)?;
+
+    let recipient = MAKER_FEE_RECIPIENT.load(deps.storage)?;
+    let Some(expected) = audit_expected_reserves(deps.branch(), &denom, limit)? else {
+        // Orders remain to be counted, the surplus is sent by a later call
+        return Ok(Response::default().add_attributes(vec![
+            ("method", "donateDust"),
+            ("sender", info.sender.as_str()),
+            ("denom", &denom),
+            ("audit", "in_progress"),
+        ]));
+    };
+    let surplus = surplus_above_reserves(deps.as_ref(), &env, &denom, expected)?;
+
+    Ok(Response::default()
+        .add_attributes(vec![
+            ("method", "donateDust"),
+            ("sender", info.sender.as_str()),
+            ("denom", &denom),
+            ("recipient", recipient.as_str()),
+            ("amount", &surplus.to_string()),
+            ("expected_reserves", &expected.to_string()),
+        ])
+        .add_message(transfer_msg(
+            deps.storage,
+            recipient.to_string(),
+            coin(surplus.u128(), denom),
+        )?))
+}
+
+/// Counts up to `limit` further orders towards the dust audit, returning the expected reserves of
+/// `denom` once every resting order has been counted, or `None` while orders remain to be counted.
+///
+/// The expected reserves are the obligations of every resting order in `denom`, as rolled up by the
+/// `Solvency` query, plus the prune reward pool. No order is owed denoms outside of the orderbook's
+/// pair, so these never require an audit. The audit is cleared once complete.
+fn audit_expected_reserves(
+    deps: DepsMut,
+    denom: &str,
+    limit: Option<u32>,
+) -> ContractResult<Option<Uint256>> {
+    let orderbook = get_orderbook(deps.storage)?;
+    let pool = Uint256::from(
+        PRUNE_REWARD_POOL
+            .may_load(deps.storage, denom)?
+            .unwrap_or_default(),
+    );
+    if denom != orderbook.base_denom && denom != orderbook.quote_denom {
+        return Ok(Some(pool));
+    }
+
+    // Resume the audit in progress, if any
+    let audit = DUST_AUDIT.may_load(deps.storage)?;
+    let start_after = audit.as_ref().map(|audit| audit.last_counted);
+    let (mut ask_denom_owed, mut bid_denom_owed) = audit
+        .map_or((Uint256::zero(), Uint256::zero()), |audit| {
+            (audit.ask_denom_owed, audit.bid_denom_owed)
+        });
+    // A zero limit would never make progress
+    let limit = limit.unwrap_or(DEFAULT_DUST_AUDIT_LIMIT).max(1);
+    let last_counted = query::count_obligations(
+        deps.as_ref(),
+        start_after,
+        Some(limit),
+        &mut ask_denom_owed,
+        &mut bid_denom_owed,
+    )?;
+    if let Some(last_counted) = last_counted {
+        DUST_AUDIT.save(
+            deps.storage,
+            &DustAudit {
+                last_counted,
+                ask_denom_owed,
+                bid_denom_owed,
+            },
+        )?;
+        return Ok(None);
+    }
+    DUST_AUDIT.remove(deps.storage);
+
+    let owed = if denom == orderbook.input_denom(&OrderDirection::Ask) {
+        ask_denom_owed
+    } else {
+        bid_denom_owed
+    };
+    Ok(Some(owed.checked_add(pool)?))
+}
+
+/// Returns the contract's balance of `denom` in excess of the `expected` reserves.
+///
+/// Errors with `NoSweepableSurplus` if the balance does not exceed the expected reserves.
+fn surplus_above_reserves(
+    deps: Deps,
+    env: &Env,
+    denom: &str,
+    expected: Uint256,
+) -> ContractResult<Uint128> {
+    let contract_address = env.contract.address.to_string();
+    let balance = match get_asset_type(deps.storage, denom)? {
+        AssetType::Native => deps.querier.query_balance(&contract_address, denom)?.amount,
+        AssetType::Cw20 => {
+            let response: Cw20BalanceResponse = deps.querier.query_wasm_smart(
+                denom,
+                &Cw20QueryMsg::Balance {
+                    address: contract_address,
+                },
+            )?;
+            response.balance
+        }
+    };
+
+    // Only the balance above the expected reserves is unattributed
+    let balance = Uint256::from(balance);
+    ensure!(
+        balance > expected,
+        ContractError::NoSweepableSurplus {
+            denom: denom.to_string(),
+            balance,
+            expected,
+        }
+    );
+    let surplus = Uint128::try_from(balance.checked_sub(expected)?)?;
+
+    Ok(surplus)
+}
+
+/// Asserts that limit order placements are not currently paused.
+///
+/// Errors if placements are paused in `PAUSED_OPS`.
+pub(crate) fn ensure_placements_not_paused(storage: &dyn Storage) -> ContractResult<()> {
+    ensure!(
+        !get_paused_ops(storage)?.placements,
+        ContractError::PlacementsPaused
+    );
+    Ok(())
+}
+
+/// Asserts that market orders are not currently paused.
+///
+/// Errors if market orders are paused in `PAUSED_OPS`.
+pub(crate) fn ensure_market_orders_not_paused(storage: &dyn Storage) -> ContractResult<()> {
+    ensure!(
+        !get_paused_ops(storage)?.market_orders,
+        ContractError::MarketOrdersPaused
+    );
+    Ok(())
+}
+
+/// Asserts that claims are not currently paused.
+///
+/// Errors if claims are paused in `PAUSED_OPS`.
+pub(crate) fn ensure_claims_not_paused(storage: &dyn Storage) -> ContractResult<()> {
+    ensure!(
+        !get_paused_ops(storage)?.claims,
+        ContractError::ClaimsPaused
+    );
+    Ok(())
+}
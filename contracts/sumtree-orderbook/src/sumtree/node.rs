@@ -517,6 +517,86 @@ impl TreeNode {
         Err(ContractError::NodeInsertionError)
     }
 
+    /// Inserts a given leaf in to the tree, merging it in to an existing leaf instead if their ranges
+    /// are exactly contiguous (see `merge_adjacent_leaf`).
+    ///
+    /// Must be called on the root node, as the search for a contiguous leaf starts from the current node.
+    pub fn insert_or_merge(
+        &mut self,
+        storage: &mut dyn Storage,
+        new_node: &mut TreeNode,
+    ) -> ContractResult<()> {
+        if self.merge_adjacent_leaf(storage, new_node)? {
+            return Ok(());
+        }
+        self.insert(storage, new_node)
+    }
+
+    /// Merges a new leaf in to an existing leaf whose range is exactly contiguous with it, returning
+    /// whether a merge took place.
+    ///
+    /// Contiguous leaves are always realized together when a tick is synced, as an ETAS that reaches
+    /// the lower leaf is rolled through it in to the upper leaf. Merging them therefore does not change
+    /// prefix sums, while keeping the node count and height of the tree down.
+    ///
+    /// The new node is not saved if it is merged.
+    pub fn merge_adjacent_leaf(
+        &mut self,
+        storage: &mut dyn Storage,
+        new_node: &TreeNode,
+    ) -> ContractResult<bool> {
+        ensure!(!new_node.is_internal(), ContractError::InvalidNodeType);
+        let Some(mut leaf) = self.find_adjacent_leaf(storage, new_node)? else {
+            return Ok(false);
+        };
+
+        // Extend the existing leaf to cover both ranges
+        let etas = leaf.get_min_range().min(new_node.get_min_range());
+        let value = leaf.get_value().checked_add(new_node.get_value())?;
+        leaf.node_type = NodeType::leaf(etas, value);
+        leaf.save(storage)?;
+
+        // Propagate the new value and range up the tree
+        if let Some(mut parent) = leaf.get_parent(storage)? {
+            parent.sync_range_and_value_up(storage)?;
+        }
+        self.sync(storage)?;
+
+        Ok(true)
+    }
+
+    /// Searches below the current node for a leaf whose range ends where the new node's range begins, or
+    /// begins where the new node's range ends.
+    ///
+    /// Only children whose range touches the new node's range are walked, so at most two paths are searched.
+    fn find_adjacent_leaf(
+        &self,
+        storage: &dyn Storage,
+        new_node: &TreeNode,
+    ) -> ContractResult<Option<TreeNode>> {
+        if !self.is_internal() {
+            let is_adjacent = self.get_max_range() == new_node.get_min_range()
+                || self.get_min_range() == new_node.get_max_range();
+            return Ok(is_adjacent.then(|| self.clone()));
+        }
+
+        for child in [self.get_left(storage)?, self.get_right(storage)?]
+            .into_iter()
+            .flatten()
+        {
+            let touches_range = child.get_min_range() <= new_node.get_max_range()
+                && child.get_max_range() >= new_node.get_min_range();
+            if !touches_range {
+                continue;
+            }
+            if let Some(leaf) = child.find_adjacent_leaf(storage, new_node)? {
+                return Ok(Some(leaf));
+            }
+        }
+
+        Ok(None)
+    }
+
     /// Splits a given node by generating a new parent internal node and assigning the current and new node as ordered children.
     /// Split nodes are ordered by ETAS in ascending order left to right.
     ///
@@ -714,6 +794,7 @@ impl TreeNode {
 
         // Perform the rotation.
         let mut left = maybe_left.unwrap();
+        self.ensure_acyclic(&left)?;
         left.parent = self.parent;
         self.parent = Some(left.key);
         self.left = left.right;
@@ -781,6 +862,7 @@ impl TreeNode {
 
         // Perform the rotation.
         let mut right = maybe_right.unwrap();
+        self.ensure_acyclic(&right)?;
         right.parent = self.parent;
         self.parent = Some(right.key);
         self.right = right.left;
@@ -825,6 +907,28 @@ impl TreeNode {
         Ok(())
     }
 
+    /// Ensures that a rotation of the current node around `child` cannot introduce a cycle.
+    ///
+    /// Rotations rewrite the pointers of both nodes, so a node referencing itself or a child
+    /// referencing the node as its own child would be persisted as a loop that later traversals
+    /// never exit. Errors with `TreeCorruption` if either is detected.
+    fn ensure_acyclic(&self, child: &TreeNode) -> ContractResult<()> {
+        let key = Some(self.key);
+        let self_referencing = self.left == key || self.right == key || self.parent == key;
+        let parent_is_child =
+            self.parent.is_some() && (self.parent == self.left || self.parent == self.right);
+        let child_cycle = child.key == self.key || child.left == key || child.right == key;
+        ensure!(
+            !self_referencing && !parent_is_child && !child_cycle,
+            ContractError::TreeCorruption {
+                tick_id: self.tick_id,
+                node_id: self.key,
+            }
+        );
+
+        Ok(())
+    }
+
     #[cfg(test)]
     pub fn with_children(self, left: Option<u64>, right: Option<u64>) -> Self {
         Self {
@@ -1935,6 +1935,109 @@ fn test_rotate_left() {
     }
 }
 
+struct RotateCycleTestCase {
+    name: &'static str,
+    nodes: Vec<TreeNode>,
+    rotate_left: bool,
+}
+
+#[test]
+fn test_rotate_cycle_guard() {
+    let tick_id = 1;
+    let direction = OrderDirection::Bid;
+    let internal = || NodeType::internal_uint256(2u32, (1u32, 3u32));
+    let leaf = || NodeType::leaf_uint256(1u32, 1u32);
+    let test_cases = vec![
+        RotateCycleTestCase {
+            name: "rotate right: node is its own left child",
+            nodes: vec![
+                TreeNode::new(tick_id, direction, 1, internal()).with_children(Some(1), Some(2)),
+                TreeNode::new(tick_id, direction, 2, leaf()).with_parent(1),
+            ],
+            rotate_left: false,
+        },
+        RotateCycleTestCase {
+            name: "rotate left: node is its own parent",
+            nodes: vec![
+                TreeNode::new(tick_id, direction, 1, internal())
+                    .with_children(None, Some(2))
+                    .with_parent(1),
+                TreeNode::new(tick_id, direction, 2, leaf()).with_parent(1),
+            ],
+            rotate_left: true,
+        },
+        RotateCycleTestCase {
+            name: "rotate right: left child points back to node",
+            nodes: vec![
+                TreeNode::new(tick_id, direction, 1, internal()).with_children(Some(2), None),
+                TreeNode::new(tick_id, direction, 2, internal())
+                    .with_children(None, Some(1))
+                    .with_parent(1),
+            ],
+            rotate_left: false,
+        },
+        RotateCycleTestCase {
+            name: "rotate left: right child points back to node",
+            nodes: vec![
+                TreeNode::new(tick_id, direction, 1, internal()).with_children(None, Some(2)),
+                TreeNode::new(tick_id, direction, 2, internal())
+                    .with_children(Some(1), None)
+                    .with_parent(1),
+            ],
+            rotate_left: true,
+        },
+        RotateCycleTestCase {
+            name: "rotate left: parent is also a child",
+            nodes: vec![
+                TreeNode::new(tick_id, direction, 1, internal())
+                    .with_children(None, Some(2))
+                    .with_parent(2),
+                TreeNode::new(tick_id, direction, 2, internal())
+                    .with_children(Some(1), None)
+                    .with_parent(1),
+            ],
+            rotate_left: true,
+        },
+    ];
+
+    for test in test_cases {
+        let mut deps = mock_dependencies();
+        for node in test.nodes.iter() {
+            NODES
+                .save(deps.as_mut().storage, &(tick_id, node.key), node)
+                .unwrap();
+        }
+
+        let mut node = test.nodes[0].clone();
+        let res = if test.rotate_left {
+            node.rotate_left(deps.as_mut().storage)
+        } else {
+            node.rotate_right(deps.as_mut().storage)
+        };
+
+        assert_eq!(
+            res,
+            Err(ContractError::TreeCorruption {
+                tick_id,
+                node_id: 1
+            }),
+            "{}",
+            test.name
+        );
+        // The malformed nodes are left untouched
+        for node in test.nodes {
+            assert_eq!(
+                NODES
+                    .load(deps.as_ref().storage, &(tick_id, node.key))
+                    .unwrap(),
+                node,
+                "{}",
+                test.name
+            );
+        }
+    }
+}
+
 struct RebalanceTestCase {
     name: &'static str,
     nodes: Vec<TreeNode>,
@@ -1,7 +1,10 @@
 use crate::sumtree::node::{generate_node_id, NodeType, TreeNode, NODES};
 use crate::sumtree::test::test_node::assert_internal_values;
-use crate::sumtree::tree::{get_or_init_root_node, get_prefix_sum, get_root_node, TREE};
+use crate::sumtree::tree::{
+    get_or_init_root_node, get_prefix_sum, get_root_node, verify_tree, TREE,
+};
 use crate::types::OrderDirection;
+use crate::ContractError;
 use cosmwasm_std::Storage;
 use cosmwasm_std::{testing::mock_dependencies, Decimal256};
 
@@ -316,3 +319,243 @@ pub fn insert_and_refetch(
         .unwrap();
     NODES.load(storage, &(tick_id, root_id)).unwrap()
 }
+
+// Selects the node an error is expected on given the root node, alongside the expected error
+type ExpectedTreeError = (fn(&TreeNode) -> u64, &'static str);
+
+struct VerifyTreeTestCase {
+    name: &'static str,
+    // Corrupts the tree given its root node
+    corrupt: fn(&mut dyn Storage, TreeNode),
+    expected_error: Option<ExpectedTreeError>,
+}
+
+#[test]
+fn test_verify_tree() {
+    let tick_id = 1;
+    let direction = OrderDirection::Bid;
+    let test_cases: Vec<VerifyTreeTestCase> = vec![
+        VerifyTreeTestCase {
+            name: "valid tree",
+            corrupt: |_, _| {},
+            expected_error: None,
+        },
+        VerifyTreeTestCase {
+            name: "stale accumulator",
+            corrupt: |storage, mut root| {
+                root.add_value(Decimal256::one()).unwrap();
+                root.save(storage).unwrap();
+            },
+            expected_error: Some((
+                |root| root.key,
+                "value does not equal the sum of its children",
+            )),
+        },
+        VerifyTreeTestCase {
+            name: "stale range",
+            corrupt: |storage, mut root| {
+                root.set_max_range(Decimal256::MAX).unwrap();
+                root.save(storage).unwrap();
+            },
+            expected_error: Some((
+                |root| root.key,
+                "range does not match the range of its children",
+            )),
+        },
+        VerifyTreeTestCase {
+            name: "stale weight",
+            corrupt: |storage, mut root| {
+                root.set_weight(root.get_weight() + 1).unwrap();
+                root.save(storage).unwrap();
+            },
+            expected_error: Some((
+                |root| root.key,
+                "weight does not match the height of its subtree",
+            )),
+        },
+        VerifyTreeTestCase {
+            name: "child points to wrong parent",
+            corrupt: |storage, root| {
+                let mut left = root.get_left(storage).unwrap().unwrap();
+                left.parent = root.right;
+                left.save(storage).unwrap();
+            },
+            expected_error: Some((
+                |root| root.left.unwrap(),
+                "parent pointer does not match parent's child pointer",
+            )),
+        },
+        VerifyTreeTestCase {
+            name: "missing child",
+            corrupt: |storage, root| {
+                NODES.remove(storage, &(root.tick_id, root.right.unwrap()));
+            },
+            expected_error: Some((|root| root.key, "child node does not exist")),
+        },
+        VerifyTreeTestCase {
+            name: "overlapping children",
+            corrupt: |storage, mut root| {
+                // Swapping the children keeps every value intact but breaks ordering
+                (root.left, root.right) = (root.right, root.left);
+                root.save(storage).unwrap();
+            },
+            expected_error: Some((
+                |root| root.key,
+                "left child range overlaps right child range",
+            )),
+        },
+    ];
+
+    for test in test_cases {
+        let mut deps = mock_dependencies();
+
+        // An uninitialized tree is valid
+        verify_tree(deps.as_ref().storage, tick_id, direction).unwrap();
+
+        // Build a tree with two internal nodes below the root
+        let mut tree = get_or_init_root_node(deps.as_mut().storage, tick_id, direction).unwrap();
+        for (etas, value) in [(1u128, 1u128), (2, 1), (3, 1), (4, 1)] {
+            tree = insert_and_refetch(
+                deps.as_mut().storage,
+                tick_id,
+                direction,
+                &NodeType::leaf_uint256(etas, value),
+            );
+        }
+        verify_tree(deps.as_ref().storage, tick_id, direction).unwrap();
+
+        // System under test: corrupt the tree and verify it
+        (test.corrupt)(deps.as_mut().storage, tree.clone());
+        let res = verify_tree(deps.as_ref().storage, tick_id, direction);
+
+        match test.expected_error {
+            Some((node_id, error)) => assert_eq!(
+                res.unwrap_err(),
+                ContractError::InvalidTreeState {
+                    tick_id,
+                    node_id: node_id(&tree),
+                    error: error.to_string(),
+                },
+                "{}: did not receive expected error",
+                test.name
+            ),
+            None => assert!(res.is_ok(), "{}: {:?}", test.name, res.unwrap_err()),
+        }
+    }
+}
+
+struct InsertOrMergeTestCase {
+    name: &'static str,
+    nodes: Vec<NodeType>,
+    expected_leaves: Vec<NodeType>,
+}
+
+#[test]
+fn test_insert_or_merge() {
+    let direction = OrderDirection::Bid;
+    let (merged_tick_id, unmerged_tick_id) = (1, 2);
+    let test_cases: Vec<InsertOrMergeTestCase> = vec![
+        InsertOrMergeTestCase {
+            name: "cancellation in three contiguous pieces",
+            nodes: vec![
+                NodeType::leaf_uint256(10u128, 10u128),
+                NodeType::leaf_uint256(20u128, 10u128),
+                NodeType::leaf_uint256(30u128, 10u128),
+            ],
+            expected_leaves: vec![NodeType::leaf_uint256(10u128, 30u128)],
+        },
+        InsertOrMergeTestCase {
+            name: "contiguous piece inserted below existing leaf",
+            nodes: vec![
+                NodeType::leaf_uint256(20u128, 10u128),
+                NodeType::leaf_uint256(10u128, 10u128),
+            ],
+            expected_leaves: vec![NodeType::leaf_uint256(10u128, 20u128)],
+        },
+        InsertOrMergeTestCase {
+            name: "piece filling a gap merges with the leaf below",
+            nodes: vec![
+                NodeType::leaf_uint256(30u128, 10u128),
+                NodeType::leaf_uint256(10u128, 10u128),
+                NodeType::leaf_uint256(20u128, 10u128),
+            ],
+            expected_leaves: vec![
+                NodeType::leaf_uint256(10u128, 20u128),
+                NodeType::leaf_uint256(30u128, 10u128),
+            ],
+        },
+        InsertOrMergeTestCase {
+            name: "non-contiguous pieces are not merged",
+            nodes: vec![
+                NodeType::leaf_uint256(10u128, 10u128),
+                NodeType::leaf_uint256(25u128, 10u128),
+                NodeType::leaf_uint256(40u128, 10u128),
+            ],
+            expected_leaves: vec![
+                NodeType::leaf_uint256(10u128, 10u128),
+                NodeType::leaf_uint256(25u128, 10u128),
+                NodeType::leaf_uint256(40u128, 10u128),
+            ],
+        },
+    ];
+
+    for test in test_cases {
+        let mut deps = mock_dependencies();
+
+        // Build the same nodes in to a merged and an unmerged tree
+        for node in test.nodes.iter() {
+            let mut tree =
+                get_or_init_root_node(deps.as_mut().storage, merged_tick_id, direction).unwrap();
+            let node_id = generate_node_id(deps.as_mut().storage, merged_tick_id).unwrap();
+            let mut new_node = TreeNode::new(merged_tick_id, direction, node_id, node.clone());
+
+            // System under test: insert or merge the new node
+            tree.insert_or_merge(deps.as_mut().storage, &mut new_node)
+                .unwrap();
+
+            insert_and_refetch(deps.as_mut().storage, unmerged_tick_id, direction, node);
+        }
+
+        // Assert the merged tree is valid and holds the expected leaves
+        verify_tree(deps.as_ref().storage, merged_tick_id, direction).unwrap();
+        let merged_tree = get_root_node(deps.as_ref().storage, merged_tick_id, direction).unwrap();
+        let mut leaves: Vec<NodeType> = merged_tree
+            .traverse(deps.as_ref().storage)
+            .unwrap()
+            .into_iter()
+            .filter(|node| !node.is_internal())
+            .map(|node| node.node_type)
+            .collect();
+        leaves.sort_by_key(|node| match node {
+            NodeType::Leaf { etas, .. } => *etas,
+            NodeType::Internal { .. } => unreachable!(),
+        });
+        assert_eq!(leaves, test.expected_leaves, "{}", test.name);
+
+        // Assert that merging does not change prefix sums
+        let unmerged_tree =
+            get_root_node(deps.as_ref().storage, unmerged_tick_id, direction).unwrap();
+        for target_etas in (0..=60u128).step_by(5) {
+            let target_etas = Decimal256::from_ratio(target_etas, 1u128);
+            let merged_sum = get_prefix_sum(
+                deps.as_ref().storage,
+                merged_tree.clone(),
+                target_etas,
+                Decimal256::zero(),
+            )
+            .unwrap();
+            let unmerged_sum = get_prefix_sum(
+                deps.as_ref().storage,
+                unmerged_tree.clone(),
+                target_etas,
+                Decimal256::zero(),
+            )
+            .unwrap();
+            assert_eq!(
+                merged_sum, unmerged_sum,
+                "{}: prefix sum at {} changed by merging",
+                test.name, target_etas
+            );
+        }
+    }
+}
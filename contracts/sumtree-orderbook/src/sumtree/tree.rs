@@ -1,11 +1,25 @@
 use super::node::{generate_node_id, NodeType, TreeNode, NODES};
-use crate::{error::ContractResult, types::OrderDirection};
-use cosmwasm_std::{Decimal256, Storage};
+use crate::{error::ContractResult, types::OrderDirection, ContractError};
+use cosmwasm_std::{ensure, Decimal256, Storage};
 use cw_storage_plus::Map;
 
 // Key: (tick_id, direction as str)
 pub const TREE: Map<&(i64, &str), u64> = Map::new("tree");
 
+/// Retrieves the root node of a specific tick's sumtree, returning `None` if no tree has been
+/// saved for the tick and direction.
+pub fn get_root_node_if_exists(
+    storage: &dyn Storage,
+    tick_id: i64,
+    direction: OrderDirection,
+) -> ContractResult<Option<TreeNode>> {
+    let Some(root_id) = TREE.may_load(storage, &(tick_id, &direction.to_string()))? else {
+        return Ok(None);
+    };
+    // Trees initialized to sync a tick without any cancellations never save their root
+    Ok(NODES.may_load(storage, &(tick_id, root_id))?)
+}
+
 #[allow(dead_code)]
 /// Retrieves the root node of a specific book and tick from storage.
 pub fn get_root_node(
@@ -40,6 +54,34 @@ pub fn get_or_init_root_node(
     Ok(tree)
 }
 
+/// Removes the sumtree of a specific book and tick, deleting every node in it.
+///
+/// Returns whether a tree existed to be removed.
+pub fn remove_tree(
+    storage: &mut dyn Storage,
+    tick_id: i64,
+    direction: OrderDirection,
+) -> ContractResult<bool> {
+    let direction_key = direction.to_string();
+    let Some(root_id) = TREE.may_load(storage, &(tick_id, &direction_key))? else {
+        return Ok(false);
+    };
+
+    let mut node_ids = vec![root_id];
+    while let Some(node_id) = node_ids.pop() {
+        // Trees initialized to sync a tick without any cancellations never save their root
+        let Some(node) = NODES.may_load(storage, &(tick_id, node_id))? else {
+            continue;
+        };
+        node_ids.extend(node.left);
+        node_ids.extend(node.right);
+        NODES.remove(storage, &(tick_id, node_id));
+    }
+    TREE.remove(storage, &(tick_id, &direction_key));
+
+    Ok(true)
+}
+
 #[allow(dead_code)]
 /// Calculates the prefix sum of values in the sumtree up to a target ETAS.
 pub fn get_prefix_sum(
@@ -209,3 +251,138 @@ fn prefix_sum_walk(
         Ok(current_sum)
     }
 }
+
+/// Verifies the integrity of the sumtree for a specific book and tick.
+///
+/// Walks every node in the tree, checking that:
+/// 1. Each internal node's value is the sum of its children's values
+/// 2. Each internal node's range spans exactly the ranges of its children
+/// 3. Each internal node's weight is the height of its subtree
+/// 4. The ranges of sibling nodes do not overlap
+/// 5. Parent and child pointers agree in both directions
+///
+/// A tree that was never initialized, or whose root has been pruned, is considered valid.
+/// As this is a full walk of the tree, it is intended for tests and debugging only.
+pub fn verify_tree(
+    storage: &dyn Storage,
+    tick_id: i64,
+    direction: OrderDirection,
+) -> ContractResult<()> {
+    let Some(root_id) = TREE.may_load(storage, &(tick_id, &direction.to_string()))? else {
+        return Ok(());
+    };
+    // The root is removed from storage once its last child is deleted
+    let Some(root) = NODES.may_load(storage, &(tick_id, root_id))? else {
+        return Ok(());
+    };
+
+    ensure!(
+        root.parent.is_none(),
+        invalid_tree_state(&root, "root node has a parent")
+    );
+    verify_node(storage, &root, direction)?;
+
+    Ok(())
+}
+
+// verify_node recursively verifies a node and everything below it, returning the height of the subtree.
+fn verify_node(
+    storage: &dyn Storage,
+    node: &TreeNode,
+    direction: OrderDirection,
+) -> ContractResult<u64> {
+    ensure!(
+        node.direction == direction,
+        invalid_tree_state(node, "node direction does not match tree")
+    );
+
+    if !node.is_internal() {
+        ensure!(
+            !node.has_child(),
+            invalid_tree_state(node, "leaf node has children")
+        );
+        return Ok(1);
+    }
+
+    // Only an empty root may be an internal node without children
+    if !node.has_child() {
+        ensure!(
+            node.parent.is_none(),
+            invalid_tree_state(node, "childless internal node")
+        );
+        ensure!(
+            node.get_value().is_zero(),
+            invalid_tree_state(node, "childless internal node has a value")
+        );
+        return Ok(1);
+    }
+
+    let left = load_child(storage, node, node.left)?;
+    let right = load_child(storage, node, node.right)?;
+    let children: Vec<&TreeNode> = left.iter().chain(right.iter()).collect();
+
+    // Sibling ranges must not overlap
+    if let (Some(left), Some(right)) = (&left, &right) {
+        ensure!(
+            left.below_range(right.clone()),
+            invalid_tree_state(node, "left child range overlaps right child range")
+        );
+    }
+
+    let mut value = Decimal256::zero();
+    let mut min = Decimal256::MAX;
+    let mut max = Decimal256::MIN;
+    let mut height = 0;
+    for child in children {
+        value = value.checked_add(child.get_value())?;
+        min = min.min(child.get_min_range());
+        max = max.max(child.get_max_range());
+        height = height.max(verify_node(storage, child, direction)?);
+    }
+
+    ensure!(
+        node.get_value() == value,
+        invalid_tree_state(node, "value does not equal the sum of its children")
+    );
+    ensure!(
+        node.get_min_range() == min && node.get_max_range() == max,
+        invalid_tree_state(node, "range does not match the range of its children")
+    );
+    ensure!(
+        node.get_weight() == height + 1,
+        invalid_tree_state(node, "weight does not match the height of its subtree")
+    );
+
+    Ok(height + 1)
+}
+
+// load_child loads a child of the given node, ensuring it exists and points back to the node as its parent.
+fn load_child(
+    storage: &dyn Storage,
+    node: &TreeNode,
+    child_id: Option<u64>,
+) -> ContractResult<Option<TreeNode>> {
+    let Some(child_id) = child_id else {
+        return Ok(None);
+    };
+    let child = NODES
+        .may_load(storage, &(node.tick_id, child_id))?
+        .ok_or_else(|| invalid_tree_state(node, "child node does not exist"))?;
+    ensure!(
+        child.parent == Some(node.key),
+        invalid_tree_state(
+            &child,
+            "parent pointer does not match parent's child pointer"
+        )
+    );
+
+    Ok(Some(child))
+}
+
+fn invalid_tree_state(node: &TreeNode, error: &str) -> ContractError {
+    ContractError::InvalidTreeState {
+        tick_id: node.tick_id,
+        node_id: node.key,
+        error: error.to_string(),
+    }
+}
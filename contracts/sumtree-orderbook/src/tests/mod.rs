@@ -1,10 +1,16 @@
 mod mock_querier;
 pub mod test_auth;
+mod test_coin;
 mod test_constants;
+pub mod test_cw20;
 pub mod test_instantiate;
+mod test_migrate;
+mod test_market_order;
 pub mod test_order;
+mod test_order_direction;
 pub mod test_orderbook;
 pub mod test_query;
+pub mod test_reply;
 pub mod test_state;
 pub mod test_sudo;
 pub mod test_tick;
@@ -1,7 +1,7 @@
 use cosmwasm_std::{
-    from_json,
+    coin, from_json,
     testing::{mock_dependencies, mock_env, mock_info},
-    Addr, Decimal256,
+    Addr, Coin, Decimal256, Uint128,
 };
 
 use crate::{
@@ -11,10 +11,20 @@ use crate::{
         dispatch_renounce_adminship, dispatch_transfer_admin, ADMIN, ADMIN_OFFER, MODERATOR,
         MODERATOR_OFFER,
     },
-    constants::MAX_MAKER_FEE_PERCENTAGE,
+    constants::{
+        DEFAULT_MAX_BATCH_CLAIM, DEFAULT_MAX_CLAIM_BOUNTY, DEFAULT_MAX_ORDERS_PER_TICK,
+        DEFAULT_MAX_TICKS_PER_ORDER, MAX_MAKER_FEE_PERCENTAGE,
+    },
     contract::{execute, query},
     msg::{AuthExecuteMsg, AuthQueryMsg, ExecuteMsg, QueryMsg},
-    state::{get_maker_fee, IS_ACTIVE, MAKER_FEE, MAKER_FEE_RECIPIENT},
+    state::{
+        get_activation_delay, get_crossing_tick_policy, get_maker_fee, get_maker_fee_discount,
+        get_max_batch_claim, get_max_claim_bounty, get_max_orders_per_tick, get_max_price_impact,
+        get_max_ticks_per_order, get_min_fee_transfer, get_min_market_order_size, get_paused_ops,
+        get_placement_fee, ACTIVATION_DELAY, IS_ACTIVE, MAKER_FEE, MAKER_FEE_RECIPIENT,
+        MAX_PRICE_IMPACT, MIN_FEE_TRANSFER, MIN_MARKET_ORDER_SIZE, PLACEMENT_FEE, PRUNE_REWARD,
+    },
+    types::{CrossingTickPolicy, MakerFeeDiscount, PausedOps},
     ContractError,
 };
 
@@ -1043,3 +1053,1317 @@ fn test_set_maker_fee_recipient() {
         );
     }
 }
+
+struct SetMaxOrdersPerTickTestCase {
+    name: &'static str,
+    sender: &'static str,
+    max_orders_per_tick: u64,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_set_max_orders_per_tick() {
+    let current_admin = "admin";
+    let current_moderator = "moderator";
+
+    let test_cases = vec![
+        SetMaxOrdersPerTickTestCase {
+            name: "valid limit set by admin",
+            sender: current_admin,
+            max_orders_per_tick: 10,
+            expected_error: None,
+        },
+        SetMaxOrdersPerTickTestCase {
+            name: "invalid limit set by moderator",
+            sender: current_moderator,
+            max_orders_per_tick: 10,
+            expected_error: Some(ContractError::Unauthorized {}),
+        },
+        SetMaxOrdersPerTickTestCase {
+            name: "invalid limit set by unauthorized user",
+            sender: "user",
+            max_orders_per_tick: 10,
+            expected_error: Some(ContractError::Unauthorized {}),
+        },
+        SetMaxOrdersPerTickTestCase {
+            name: "zero limit",
+            sender: current_admin,
+            max_orders_per_tick: 0,
+            expected_error: Some(ContractError::InvalidMaxOrdersPerTick),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(test.sender, &[]);
+
+        ADMIN
+            .save(deps.as_mut().storage, &Addr::unchecked(current_admin))
+            .unwrap();
+        MODERATOR
+            .save(deps.as_mut().storage, &Addr::unchecked(current_moderator))
+            .unwrap();
+        let msg = ExecuteMsg::Auth(AuthExecuteMsg::SetMaxOrdersPerTick {
+            max_orders_per_tick: test.max_orders_per_tick,
+        });
+
+        // -- System under test --
+        let res = execute(deps.as_mut(), env, info, msg);
+
+        // -- Test Assertions --
+        if let Some(err) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                err,
+                "{}: did not receive expected error",
+                test.name
+            );
+            assert_eq!(
+                get_max_orders_per_tick(deps.as_ref().storage).unwrap(),
+                DEFAULT_MAX_ORDERS_PER_TICK,
+                "{}: limit was incorrectly set",
+                test.name
+            );
+            continue;
+        }
+
+        res.unwrap();
+
+        let new_limit = get_max_orders_per_tick(deps.as_ref().storage).unwrap();
+
+        assert_eq!(
+            test.max_orders_per_tick, new_limit,
+            "{}: limit did not update correctly",
+            test.name
+        );
+    }
+}
+
+struct SetMaxTicksPerOrderTestCase {
+    name: &'static str,
+    sender: &'static str,
+    max_ticks_per_order: u32,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_set_max_ticks_per_order() {
+    let current_admin = "admin";
+    let current_moderator = "moderator";
+
+    let test_cases = vec![
+        SetMaxTicksPerOrderTestCase {
+            name: "valid limit set by admin",
+            sender: current_admin,
+            max_ticks_per_order: 10,
+            expected_error: None,
+        },
+        SetMaxTicksPerOrderTestCase {
+            name: "invalid limit set by moderator",
+            sender: current_moderator,
+            max_ticks_per_order: 10,
+            expected_error: Some(ContractError::Unauthorized {}),
+        },
+        SetMaxTicksPerOrderTestCase {
+            name: "invalid limit set by unauthorized user",
+            sender: "user",
+            max_ticks_per_order: 10,
+            expected_error: Some(ContractError::Unauthorized {}),
+        },
+        SetMaxTicksPerOrderTestCase {
+            name: "zero limit",
+            sender: current_admin,
+            max_ticks_per_order: 0,
+            expected_error: Some(ContractError::InvalidMaxTicksPerOrder),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(test.sender, &[]);
+
+        ADMIN
+            .save(deps.as_mut().storage, &Addr::unchecked(current_admin))
+            .unwrap();
+        MODERATOR
+            .save(deps.as_mut().storage, &Addr::unchecked(current_moderator))
+            .unwrap();
+        let msg = ExecuteMsg::Auth(AuthExecuteMsg::SetMaxTicksPerOrder {
+            max_ticks_per_order: test.max_ticks_per_order,
+        });
+
+        // -- System under test --
+        let res = execute(deps.as_mut(), env, info, msg);
+
+        // -- Test Assertions --
+        if let Some(err) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                err,
+                "{}: did not receive expected error",
+                test.name
+            );
+            assert_eq!(
+                get_max_ticks_per_order(deps.as_ref().storage).unwrap(),
+                DEFAULT_MAX_TICKS_PER_ORDER,
+                "{}: limit was incorrectly set",
+                test.name
+            );
+            continue;
+        }
+
+        res.unwrap();
+
+        let new_limit = get_max_ticks_per_order(deps.as_ref().storage).unwrap();
+
+        assert_eq!(
+            test.max_ticks_per_order, new_limit,
+            "{}: limit did not update correctly",
+            test.name
+        );
+    }
+}
+
+struct SetMaxBatchClaimTestCase {
+    name: &'static str,
+    sender: &'static str,
+    max_batch_claim: u32,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_set_max_batch_claim() {
+    let current_admin = "admin";
+    let current_moderator = "moderator";
+
+    let test_cases = vec![
+        SetMaxBatchClaimTestCase {
+            name: "valid limit set by admin",
+            sender: current_admin,
+            max_batch_claim: 10,
+            expected_error: None,
+        },
+        SetMaxBatchClaimTestCase {
+            name: "invalid limit set by moderator",
+            sender: current_moderator,
+            max_batch_claim: 10,
+            expected_error: Some(ContractError::Unauthorized {}),
+        },
+        SetMaxBatchClaimTestCase {
+            name: "invalid limit set by unauthorized user",
+            sender: "user",
+            max_batch_claim: 10,
+            expected_error: Some(ContractError::Unauthorized {}),
+        },
+        SetMaxBatchClaimTestCase {
+            name: "zero limit",
+            sender: current_admin,
+            max_batch_claim: 0,
+            expected_error: Some(ContractError::InvalidMaxBatchClaim),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(test.sender, &[]);
+
+        ADMIN
+            .save(deps.as_mut().storage, &Addr::unchecked(current_admin))
+            .unwrap();
+        MODERATOR
+            .save(deps.as_mut().storage, &Addr::unchecked(current_moderator))
+            .unwrap();
+        let msg = ExecuteMsg::Auth(AuthExecuteMsg::SetMaxBatchClaim {
+            max_batch_claim: test.max_batch_claim,
+        });
+
+        // -- System under test --
+        let res = execute(deps.as_mut(), env, info, msg);
+
+        // -- Test Assertions --
+        if let Some(err) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                err,
+                "{}: did not receive expected error",
+                test.name
+            );
+            assert_eq!(
+                get_max_batch_claim(deps.as_ref().storage).unwrap(),
+                DEFAULT_MAX_BATCH_CLAIM,
+                "{}: limit was incorrectly set",
+                test.name
+            );
+            continue;
+        }
+
+        res.unwrap();
+
+        let new_limit = get_max_batch_claim(deps.as_ref().storage).unwrap();
+
+        assert_eq!(
+            test.max_batch_claim, new_limit,
+            "{}: limit did not update correctly",
+            test.name
+        );
+    }
+}
+
+struct SetPruneRewardTestCase {
+    name: &'static str,
+    sender: &'static str,
+    reward: Option<Coin>,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_set_prune_reward() {
+    let current_admin = "admin";
+    let current_moderator = "moderator";
+    let initial_reward = coin(10, "uosmo");
+
+    let test_cases = vec![
+        SetPruneRewardTestCase {
+            name: "valid reward set by admin",
+            sender: current_admin,
+            reward: Some(coin(5, "uion")),
+            expected_error: None,
+        },
+        SetPruneRewardTestCase {
+            name: "reward disabled by admin",
+            sender: current_admin,
+            reward: None,
+            expected_error: None,
+        },
+        SetPruneRewardTestCase {
+            name: "invalid reward set by moderator",
+            sender: current_moderator,
+            reward: Some(coin(5, "uion")),
+            expected_error: Some(ContractError::Unauthorized {}),
+        },
+        SetPruneRewardTestCase {
+            name: "invalid reward set by unauthorized user",
+            sender: "user",
+            reward: None,
+            expected_error: Some(ContractError::Unauthorized {}),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(test.sender, &[]);
+
+        ADMIN
+            .save(deps.as_mut().storage, &Addr::unchecked(current_admin))
+            .unwrap();
+        MODERATOR
+            .save(deps.as_mut().storage, &Addr::unchecked(current_moderator))
+            .unwrap();
+        PRUNE_REWARD
+            .save(deps.as_mut().storage, &initial_reward)
+            .unwrap();
+        let msg = ExecuteMsg::Auth(AuthExecuteMsg::SetPruneReward {
+            reward: test.reward.clone(),
+        });
+
+        // -- System under test --
+        let res = execute(deps.as_mut(), env, info, msg);
+
+        // -- Test Assertions --
+        if let Some(err) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                err,
+                "{}: did not receive expected error",
+                test.name
+            );
+            assert_eq!(
+                PRUNE_REWARD.may_load(deps.as_ref().storage).unwrap(),
+                Some(initial_reward.clone()),
+                "{}: reward was incorrectly set",
+                test.name
+            );
+            continue;
+        }
+
+        res.unwrap();
+
+        assert_eq!(
+            PRUNE_REWARD.may_load(deps.as_ref().storage).unwrap(),
+            test.reward,
+            "{}: reward did not update correctly",
+            test.name
+        );
+    }
+}
+
+struct SetMaxPriceImpactTestCase {
+    name: &'static str,
+    sender: &'static str,
+    max_price_impact: Option<Decimal256>,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_set_max_price_impact() {
+    let current_admin = "admin";
+    let current_moderator = "moderator";
+    let initial_max_price_impact = Decimal256::percent(10);
+
+    let test_cases = vec![
+        SetMaxPriceImpactTestCase {
+            name: "valid max price impact set by admin",
+            sender: current_admin,
+            max_price_impact: Some(Decimal256::percent(5)),
+            expected_error: None,
+        },
+        SetMaxPriceImpactTestCase {
+            name: "max price impact removed by admin",
+            sender: current_admin,
+            max_price_impact: None,
+            expected_error: None,
+        },
+        SetMaxPriceImpactTestCase {
+            name: "invalid max price impact set by moderator",
+            sender: current_moderator,
+            max_price_impact: Some(Decimal256::percent(5)),
+            expected_error: Some(ContractError::Unauthorized {}),
+        },
+        SetMaxPriceImpactTestCase {
+            name: "invalid max price impact set by unauthorized user",
+            sender: "user",
+            max_price_impact: None,
+            expected_error: Some(ContractError::Unauthorized {}),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(test.sender, &[]);
+
+        ADMIN
+            .save(deps.as_mut().storage, &Addr::unchecked(current_admin))
+            .unwrap();
+        MODERATOR
+            .save(deps.as_mut().storage, &Addr::unchecked(current_moderator))
+            .unwrap();
+        MAX_PRICE_IMPACT
+            .save(deps.as_mut().storage, &initial_max_price_impact)
+            .unwrap();
+        let msg = ExecuteMsg::Auth(AuthExecuteMsg::SetMaxPriceImpact {
+            max_price_impact: test.max_price_impact,
+        });
+
+        // -- System under test --
+        let res = execute(deps.as_mut(), env, info, msg);
+
+        // -- Test Assertions --
+        if let Some(err) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                err,
+                "{}: did not receive expected error",
+                test.name
+            );
+            assert_eq!(
+                get_max_price_impact(deps.as_ref().storage).unwrap(),
+                Some(initial_max_price_impact),
+                "{}: max price impact was incorrectly set",
+                test.name
+            );
+            continue;
+        }
+
+        res.unwrap();
+
+        assert_eq!(
+            get_max_price_impact(deps.as_ref().storage).unwrap(),
+            test.max_price_impact,
+            "{}: max price impact did not update correctly",
+            test.name
+        );
+    }
+}
+
+struct SetMinFeeTransferTestCase {
+    name: &'static str,
+    sender: &'static str,
+    min_fee_transfer: Uint128,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_set_min_fee_transfer() {
+    let current_admin = "admin";
+    let current_moderator = "moderator";
+    let initial_min_fee_transfer = Uint128::from(10u128);
+
+    let test_cases = vec![
+        SetMinFeeTransferTestCase {
+            name: "valid min fee transfer set by admin",
+            sender: current_admin,
+            min_fee_transfer: Uint128::from(5u128),
+            expected_error: None,
+        },
+        SetMinFeeTransferTestCase {
+            name: "min fee transfer cleared by admin",
+            sender: current_admin,
+            min_fee_transfer: Uint128::zero(),
+            expected_error: None,
+        },
+        SetMinFeeTransferTestCase {
+            name: "invalid min fee transfer set by moderator",
+            sender: current_moderator,
+            min_fee_transfer: Uint128::from(5u128),
+            expected_error: Some(ContractError::Unauthorized {}),
+        },
+        SetMinFeeTransferTestCase {
+            name: "invalid min fee transfer set by unauthorized user",
+            sender: "user",
+            min_fee_transfer: Uint128::from(5u128),
+            expected_error: Some(ContractError::Unauthorized {}),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(test.sender, &[]);
+
+        ADMIN
+            .save(deps.as_mut().storage, &Addr::unchecked(current_admin))
+            .unwrap();
+        MODERATOR
+            .save(deps.as_mut().storage, &Addr::unchecked(current_moderator))
+            .unwrap();
+        MIN_FEE_TRANSFER
+            .save(deps.as_mut().storage, &initial_min_fee_transfer)
+            .unwrap();
+        let msg = ExecuteMsg::Auth(AuthExecuteMsg::SetMinFeeTransfer {
+            min_fee_transfer: test.min_fee_transfer,
+        });
+
+        // -- System under test --
+        let res = execute(deps.as_mut(), env, info, msg);
+
+        // -- Test Assertions --
+        if let Some(err) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                err,
+                "{}: did not receive expected error",
+                test.name
+            );
+            assert_eq!(
+                get_min_fee_transfer(deps.as_ref().storage).unwrap(),
+                initial_min_fee_transfer,
+                "{}: min fee transfer was incorrectly set",
+                test.name
+            );
+            continue;
+        }
+
+        res.unwrap();
+
+        assert_eq!(
+            get_min_fee_transfer(deps.as_ref().storage).unwrap(),
+            test.min_fee_transfer,
+            "{}: min fee transfer did not update correctly",
+            test.name
+        );
+    }
+}
+
+struct SetMinMarketOrderSizeTestCase {
+    name: &'static str,
+    sender: &'static str,
+    min_market_order_size: Uint128,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_set_min_market_order_size() {
+    let current_admin = "admin";
+    let current_moderator = "moderator";
+    let initial_min_market_order_size = Uint128::from(10u128);
+
+    let test_cases = vec![
+        SetMinMarketOrderSizeTestCase {
+            name: "valid min market order size set by admin",
+            sender: current_admin,
+            min_market_order_size: Uint128::from(5u128),
+            expected_error: None,
+        },
+        SetMinMarketOrderSizeTestCase {
+            name: "min market order size cleared by admin",
+            sender: current_admin,
+            min_market_order_size: Uint128::zero(),
+            expected_error: None,
+        },
+        SetMinMarketOrderSizeTestCase {
+            name: "invalid min market order size set by moderator",
+            sender: current_moderator,
+            min_market_order_size: Uint128::from(5u128),
+            expected_error: Some(ContractError::Unauthorized {}),
+        },
+        SetMinMarketOrderSizeTestCase {
+            name: "invalid min market order size set by unauthorized user",
+            sender: "user",
+            min_market_order_size: Uint128::from(5u128),
+            expected_error: Some(ContractError::Unauthorized {}),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(test.sender, &[]);
+
+        ADMIN
+            .save(deps.as_mut().storage, &Addr::unchecked(current_admin))
+            .unwrap();
+        MODERATOR
+            .save(deps.as_mut().storage, &Addr::unchecked(current_moderator))
+            .unwrap();
+        MIN_MARKET_ORDER_SIZE
+            .save(deps.as_mut().storage, &initial_min_market_order_size)
+            .unwrap();
+        let msg = ExecuteMsg::Auth(AuthExecuteMsg::SetMinMarketOrderSize {
+            min_market_order_size: test.min_market_order_size,
+        });
+
+        // -- System under test --
+        let res = execute(deps.as_mut(), env, info, msg);
+
+        // -- Test Assertions --
+        if let Some(err) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                err,
+                "{}: did not receive expected error",
+                test.name
+            );
+            assert_eq!(
+                get_min_market_order_size(deps.as_ref().storage).unwrap(),
+                initial_min_market_order_size,
+                "{}: min market order size was incorrectly set",
+                test.name
+            );
+            continue;
+        }
+
+        res.unwrap();
+
+        assert_eq!(
+            get_min_market_order_size(deps.as_ref().storage).unwrap(),
+            test.min_market_order_size,
+            "{}: min market order size did not update correctly",
+            test.name
+        );
+    }
+}
+
+struct SetActivationDelayTestCase {
+    name: &'static str,
+    sender: &'static str,
+    activation_delay: u64,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_set_activation_delay() {
+    let current_admin = "admin";
+    let current_moderator = "moderator";
+    let initial_activation_delay = 10u64;
+
+    let test_cases = vec![
+        SetActivationDelayTestCase {
+            name: "valid activation delay set by admin",
+            sender: current_admin,
+            activation_delay: 5,
+            expected_error: None,
+        },
+        SetActivationDelayTestCase {
+            name: "activation delay cleared by admin",
+            sender: current_admin,
+            activation_delay: 0,
+            expected_error: None,
+        },
+        SetActivationDelayTestCase {
+            name: "invalid activation delay set by moderator",
+            sender: current_moderator,
+            activation_delay: 5,
+            expected_error: Some(ContractError::Unauthorized {}),
+        },
+        SetActivationDelayTestCase {
+            name: "invalid activation delay set by unauthorized user",
+            sender: "user",
+            activation_delay: 5,
+            expected_error: Some(ContractError::Unauthorized {}),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(test.sender, &[]);
+
+        ADMIN
+            .save(deps.as_mut().storage, &Addr::unchecked(current_admin))
+            .unwrap();
+        MODERATOR
+            .save(deps.as_mut().storage, &Addr::unchecked(current_moderator))
+            .unwrap();
+        ACTIVATION_DELAY
+            .save(deps.as_mut().storage, &initial_activation_delay)
+            .unwrap();
+        let msg = ExecuteMsg::Auth(AuthExecuteMsg::SetActivationDelay {
+            activation_delay: test.activation_delay,
+        });
+
+        // -- System under test --
+        let res = execute(deps.as_mut(), env.clone(), info, msg);
+
+        // -- Test Assertions --
+        if let Some(err) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                err,
+                "{}: did not receive expected error",
+                test.name
+            );
+            assert_eq!(
+                get_activation_delay(deps.as_ref().storage).unwrap(),
+                initial_activation_delay,
+                "{}: activation delay was incorrectly set",
+                test.name
+            );
+            continue;
+        }
+
+        res.unwrap();
+
+        let queried: u64 =
+            from_json(query(deps.as_ref(), env, QueryMsg::ActivationDelay {}).unwrap()).unwrap();
+        assert_eq!(
+            queried, test.activation_delay,
+            "{}: activation delay did not update correctly",
+            test.name
+        );
+    }
+}
+
+struct SetPausedOpsTestCase {
+    name: &'static str,
+    sender: &'static str,
+    paused_ops: PausedOps,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_set_paused_ops() {
+    let current_admin = "admin";
+    let current_moderator = "moderator";
+    let paused_ops = PausedOps {
+        placements: true,
+        market_orders: false,
+        claims: true,
+    };
+
+    let test_cases = vec![
+        SetPausedOpsTestCase {
+            name: "valid paused ops set by admin",
+            sender: current_admin,
+            paused_ops,
+            expected_error: None,
+        },
+        SetPausedOpsTestCase {
+            name: "valid paused ops set by moderator",
+            sender: current_moderator,
+            paused_ops,
+            expected_error: None,
+        },
+        SetPausedOpsTestCase {
+            name: "invalid paused ops set by unauthorized user",
+            sender: "user",
+            paused_ops,
+            expected_error: Some(ContractError::Unauthorized {}),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(test.sender, &[]);
+
+        ADMIN
+            .save(deps.as_mut().storage, &Addr::unchecked(current_admin))
+            .unwrap();
+        MODERATOR
+            .save(deps.as_mut().storage, &Addr::unchecked(current_moderator))
+            .unwrap();
+        let msg = ExecuteMsg::Auth(AuthExecuteMsg::SetPausedOps {
+            paused_ops: test.paused_ops,
+        });
+
+        // -- System under test --
+        let res = execute(deps.as_mut(), env, info, msg);
+
+        // -- Test Assertions --
+        if let Some(err) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                err,
+                "{}: did not receive expected error",
+                test.name
+            );
+            assert_eq!(
+                get_paused_ops(deps.as_ref().storage).unwrap(),
+                PausedOps::default(),
+                "{}: paused ops were incorrectly set",
+                test.name
+            );
+            continue;
+        }
+
+        res.unwrap();
+
+        assert_eq!(
+            get_paused_ops(deps.as_ref().storage).unwrap(),
+            test.paused_ops,
+            "{}: paused ops did not update correctly",
+            test.name
+        );
+    }
+}
+
+struct SetMaxClaimBountyTestCase {
+    name: &'static str,
+    sender: &'static str,
+    max_claim_bounty: Decimal256,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_set_max_claim_bounty() {
+    let current_admin = "admin";
+    let current_moderator = "moderator";
+
+    let test_cases = vec![
+        SetMaxClaimBountyTestCase {
+            name: "valid max claim bounty set by admin",
+            sender: current_admin,
+            max_claim_bounty: Decimal256::percent(5),
+            expected_error: None,
+        },
+        SetMaxClaimBountyTestCase {
+            name: "invalid max claim bounty set by admin",
+            sender: current_admin,
+            max_claim_bounty: Decimal256::percent(101),
+            expected_error: Some(ContractError::InvalidMaxClaimBounty {
+                max_claim_bounty: Decimal256::percent(101),
+            }),
+        },
+        SetMaxClaimBountyTestCase {
+            name: "invalid max claim bounty set by moderator",
+            sender: current_moderator,
+            max_claim_bounty: Decimal256::percent(5),
+            expected_error: Some(ContractError::Unauthorized {}),
+        },
+        SetMaxClaimBountyTestCase {
+            name: "invalid max claim bounty set by unauthorized user",
+            sender: "user",
+            max_claim_bounty: Decimal256::percent(5),
+            expected_error: Some(ContractError::Unauthorized {}),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(test.sender, &[]);
+
+        ADMIN
+            .save(deps.as_mut().storage, &Addr::unchecked(current_admin))
+            .unwrap();
+        MODERATOR
+            .save(deps.as_mut().storage, &Addr::unchecked(current_moderator))
+            .unwrap();
+        let msg = ExecuteMsg::Auth(AuthExecuteMsg::SetMaxClaimBounty {
+            max_claim_bounty: test.max_claim_bounty,
+        });
+
+        // -- System under test --
+        let res = execute(deps.as_mut(), env, info, msg);
+
+        // -- Test Assertions --
+        if let Some(err) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                err,
+                "{}: did not receive expected error",
+                test.name
+            );
+            assert_eq!(
+                get_max_claim_bounty(deps.as_ref().storage).unwrap(),
+                DEFAULT_MAX_CLAIM_BOUNTY,
+                "{}: max claim bounty was incorrectly set",
+                test.name
+            );
+            continue;
+        }
+
+        res.unwrap();
+
+        assert_eq!(
+            get_max_claim_bounty(deps.as_ref().storage).unwrap(),
+            test.max_claim_bounty,
+            "{}: max claim bounty did not update correctly",
+            test.name
+        );
+    }
+}
+
+struct PrivilegedExecuteTestCase {
+    name: &'static str,
+    msg: AuthExecuteMsg,
+    moderator_allowed: bool,
+}
+
+#[test]
+fn test_privileged_execute_unauthorized() {
+    let current_admin = "admin";
+    let current_moderator = "moderator";
+
+    let test_cases = vec![
+        PrivilegedExecuteTestCase {
+            name: "transfer admin",
+            msg: AuthExecuteMsg::TransferAdmin {
+                new_admin: Addr::unchecked("new_admin"),
+            },
+            moderator_allowed: false,
+        },
+        PrivilegedExecuteTestCase {
+            name: "cancel admin transfer",
+            msg: AuthExecuteMsg::CancelAdminTransfer {},
+            moderator_allowed: false,
+        },
+        PrivilegedExecuteTestCase {
+            name: "renounce adminship",
+            msg: AuthExecuteMsg::RenounceAdminship {},
+            moderator_allowed: false,
+        },
+        PrivilegedExecuteTestCase {
+            name: "offer moderator",
+            msg: AuthExecuteMsg::OfferModerator {
+                new_moderator: Addr::unchecked("new_moderator"),
+            },
+            moderator_allowed: false,
+        },
+        PrivilegedExecuteTestCase {
+            name: "set active",
+            msg: AuthExecuteMsg::SetActive { active: false },
+            moderator_allowed: true,
+        },
+        PrivilegedExecuteTestCase {
+            name: "set paused ops",
+            msg: AuthExecuteMsg::SetPausedOps {
+                paused_ops: PausedOps::default(),
+            },
+            moderator_allowed: true,
+        },
+        PrivilegedExecuteTestCase {
+            name: "set maker fee",
+            msg: AuthExecuteMsg::SetMakerFee {
+                fee: Decimal256::zero(),
+            },
+            moderator_allowed: false,
+        },
+        PrivilegedExecuteTestCase {
+            name: "set maker fee recipient",
+            msg: AuthExecuteMsg::SetMakerFeeRecipient {
+                recipient: Addr::unchecked("recipient"),
+            },
+            moderator_allowed: false,
+        },
+        PrivilegedExecuteTestCase {
+            name: "set max orders per tick",
+            msg: AuthExecuteMsg::SetMaxOrdersPerTick {
+                max_orders_per_tick: 1,
+            },
+            moderator_allowed: false,
+        },
+        PrivilegedExecuteTestCase {
+            name: "set max batch claim",
+            msg: AuthExecuteMsg::SetMaxBatchClaim { max_batch_claim: 1 },
+            moderator_allowed: false,
+        },
+        PrivilegedExecuteTestCase {
+            name: "set prune reward",
+            msg: AuthExecuteMsg::SetPruneReward { reward: None },
+            moderator_allowed: false,
+        },
+        PrivilegedExecuteTestCase {
+            name: "set max price impact",
+            msg: AuthExecuteMsg::SetMaxPriceImpact {
+                max_price_impact: None,
+            },
+            moderator_allowed: false,
+        },
+        PrivilegedExecuteTestCase {
+            name: "set max claim bounty",
+            msg: AuthExecuteMsg::SetMaxClaimBounty {
+                max_claim_bounty: Decimal256::zero(),
+            },
+            moderator_allowed: false,
+        },
+        PrivilegedExecuteTestCase {
+            name: "set min fee transfer",
+            msg: AuthExecuteMsg::SetMinFeeTransfer {
+                min_fee_transfer: Uint128::zero(),
+            },
+            moderator_allowed: false,
+        },
+        PrivilegedExecuteTestCase {
+            name: "set placement fee",
+            msg: AuthExecuteMsg::SetPlacementFee { fee: None },
+            moderator_allowed: false,
+        },
+    ];
+
+    for test in test_cases {
+        let senders = [
+            ("user", false),
+            (current_moderator, test.moderator_allowed),
+            (current_admin, true),
+        ];
+        for (sender, allowed) in senders {
+            // -- Test Setup --
+            let mut deps = mock_dependencies();
+            ADMIN
+                .save(deps.as_mut().storage, &Addr::unchecked(current_admin))
+                .unwrap();
+            MODERATOR
+                .save(deps.as_mut().storage, &Addr::unchecked(current_moderator))
+                .unwrap();
+
+            // -- System under test --
+            let res = execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(sender, &[]),
+                ExecuteMsg::Auth(test.msg.clone()),
+            );
+
+            // -- Test Assertions --
+            if allowed {
+                assert!(res.is_ok(), "{}: {} was rejected", test.name, sender);
+            } else {
+                assert_eq!(
+                    res.unwrap_err(),
+                    ContractError::Unauthorized {},
+                    "{}: {} was not rejected",
+                    test.name,
+                    sender
+                );
+            }
+        }
+    }
+}
+
+struct SetPlacementFeeTestCase {
+    name: &'static str,
+    sender: &'static str,
+    placement_fee: Option<Coin>,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_set_placement_fee() {
+    let current_admin = "admin";
+    let current_moderator = "moderator";
+    let initial_placement_fee = coin(10u128, "uosmo");
+
+    let test_cases = vec![
+        SetPlacementFeeTestCase {
+            name: "valid placement fee set by admin",
+            sender: current_admin,
+            placement_fee: Some(coin(5u128, "uosmo")),
+            expected_error: None,
+        },
+        SetPlacementFeeTestCase {
+            name: "placement fee removed by admin",
+            sender: current_admin,
+            placement_fee: None,
+            expected_error: None,
+        },
+        SetPlacementFeeTestCase {
+            name: "invalid placement fee set by moderator",
+            sender: current_moderator,
+            placement_fee: Some(coin(5u128, "uosmo")),
+            expected_error: Some(ContractError::Unauthorized {}),
+        },
+        SetPlacementFeeTestCase {
+            name: "invalid placement fee set by unauthorized user",
+            sender: "user",
+            placement_fee: Some(coin(5u128, "uosmo")),
+            expected_error: Some(ContractError::Unauthorized {}),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(test.sender, &[]);
+
+        ADMIN
+            .save(deps.as_mut().storage, &Addr::unchecked(current_admin))
+            .unwrap();
+        MODERATOR
+            .save(deps.as_mut().storage, &Addr::unchecked(current_moderator))
+            .unwrap();
+        PLACEMENT_FEE
+            .save(deps.as_mut().storage, &initial_placement_fee)
+            .unwrap();
+        let msg = ExecuteMsg::Auth(AuthExecuteMsg::SetPlacementFee {
+            fee: test.placement_fee.clone(),
+        });
+
+        // -- System under test --
+        let res = execute(deps.as_mut(), env, info, msg);
+
+        // -- Test Assertions --
+        if let Some(err) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                err,
+                "{}: did not receive expected error",
+                test.name
+            );
+            assert_eq!(
+                get_placement_fee(deps.as_ref().storage).unwrap(),
+                Some(initial_placement_fee.clone()),
+                "{}: placement fee was incorrectly set",
+                test.name
+            );
+            continue;
+        }
+
+        res.unwrap();
+
+        assert_eq!(
+            get_placement_fee(deps.as_ref().storage).unwrap(),
+            test.placement_fee,
+            "{}: placement fee did not update correctly",
+            test.name
+        );
+    }
+}
+
+struct SetMakerFeeDiscountTestCase {
+    name: &'static str,
+    sender: &'static str,
+    discount: Option<MakerFeeDiscount>,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_set_maker_fee_discount() {
+    let current_admin = "admin";
+    let current_moderator = "moderator";
+    let discount = |min_blocks: u64, discount: Decimal256| MakerFeeDiscount {
+        min_blocks,
+        discount,
+    };
+
+    let test_cases = vec![
+        SetMakerFeeDiscountTestCase {
+            name: "valid discount set by admin",
+            sender: current_admin,
+            discount: Some(discount(100, Decimal256::percent(50))),
+            expected_error: None,
+        },
+        SetMakerFeeDiscountTestCase {
+            name: "full discount set by admin",
+            sender: current_admin,
+            discount: Some(discount(100, Decimal256::one())),
+            expected_error: None,
+        },
+        SetMakerFeeDiscountTestCase {
+            name: "discount removed by admin",
+            sender: current_admin,
+            discount: None,
+            expected_error: None,
+        },
+        SetMakerFeeDiscountTestCase {
+            name: "discount above one",
+            sender: current_admin,
+            discount: Some(discount(100, Decimal256::percent(101))),
+            expected_error: Some(ContractError::InvalidMakerFeeDiscount {
+                discount: Decimal256::percent(101),
+            }),
+        },
+        SetMakerFeeDiscountTestCase {
+            name: "discount set by moderator",
+            sender: current_moderator,
+            discount: Some(discount(100, Decimal256::percent(50))),
+            expected_error: Some(ContractError::Unauthorized {}),
+        },
+        SetMakerFeeDiscountTestCase {
+            name: "discount set by unauthorized user",
+            sender: "user",
+            discount: Some(discount(100, Decimal256::percent(50))),
+            expected_error: Some(ContractError::Unauthorized {}),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(test.sender, &[]);
+
+        ADMIN
+            .save(deps.as_mut().storage, &Addr::unchecked(current_admin))
+            .unwrap();
+        MODERATOR
+            .save(deps.as_mut().storage, &Addr::unchecked(current_moderator))
+            .unwrap();
+        let msg = ExecuteMsg::Auth(AuthExecuteMsg::SetMakerFeeDiscount {
+            discount: test.discount.clone(),
+        });
+
+        // -- System under test --
+        let res = execute(deps.as_mut(), env.clone(), info, msg);
+
+        // -- Test Assertions --
+        if let Some(err) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                err,
+                "{}: did not receive expected error",
+                test.name
+            );
+            assert_eq!(
+                get_maker_fee_discount(deps.as_ref().storage).unwrap(),
+                None,
+                "{}: discount was incorrectly set",
+                test.name
+            );
+            continue;
+        }
+
+        res.unwrap();
+
+        let queried: Option<MakerFeeDiscount> =
+            from_json(query(deps.as_ref(), env, QueryMsg::MakerFeeDiscount {}).unwrap()).unwrap();
+        assert_eq!(
+            queried, test.discount,
+            "{}: discount did not update correctly",
+            test.name
+        );
+    }
+}
+
+struct SetCrossingTickPolicyTestCase {
+    name: &'static str,
+    sender: &'static str,
+    policy: CrossingTickPolicy,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_set_crossing_tick_policy() {
+    let current_admin = "admin";
+    let current_moderator = "moderator";
+
+    let test_cases = vec![
+        SetCrossingTickPolicyTestCase {
+            name: "reject policy set by admin",
+            sender: current_admin,
+            policy: CrossingTickPolicy::Reject,
+            expected_error: None,
+        },
+        SetCrossingTickPolicyTestCase {
+            name: "rest policy set by admin",
+            sender: current_admin,
+            policy: CrossingTickPolicy::Rest,
+            expected_error: None,
+        },
+        SetCrossingTickPolicyTestCase {
+            name: "invalid policy set by moderator",
+            sender: current_moderator,
+            policy: CrossingTickPolicy::Reject,
+            expected_error: Some(ContractError::Unauthorized {}),
+        },
+        SetCrossingTickPolicyTestCase {
+            name: "invalid policy set by unauthorized user",
+            sender: "user",
+            policy: CrossingTickPolicy::Reject,
+            expected_error: Some(ContractError::Unauthorized {}),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(test.sender, &[]);
+
+        ADMIN
+            .save(deps.as_mut().storage, &Addr::unchecked(current_admin))
+            .unwrap();
+        MODERATOR
+            .save(deps.as_mut().storage, &Addr::unchecked(current_moderator))
+            .unwrap();
+        let msg = ExecuteMsg::Auth(AuthExecuteMsg::SetCrossingTickPolicy {
+            policy: test.policy,
+        });
+
+        // -- System under test --
+        let res = execute(deps.as_mut(), env.clone(), info, msg);
+
+        // -- Test Assertions --
+        if let Some(err) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                err,
+                "{}: did not receive expected error",
+                test.name
+            );
+            assert_eq!(
+                get_crossing_tick_policy(deps.as_ref().storage).unwrap(),
+                CrossingTickPolicy::Rest,
+                "{}: crossing tick policy was incorrectly set",
+                test.name
+            );
+            continue;
+        }
+
+        res.unwrap();
+
+        let queried: CrossingTickPolicy =
+            from_json(query(deps.as_ref(), env, QueryMsg::CrossingTickPolicy {}).unwrap()).unwrap();
+        assert_eq!(
+            queried, test.policy,
+            "{}: crossing tick policy did not update correctly",
+            test.name
+        );
+    }
+}
@@ -0,0 +1,80 @@
+use crate::types::{coin_u256, MsgSend256};
+
+use super::test_utils::format_test_name;
+
+struct TryMergeTestCase {
+    name: &'static str,
+    msg: MsgSend256,
+    other: MsgSend256,
+    expected_merged: bool,
+    expected_msg: MsgSend256,
+}
+
+#[test]
+fn test_msg_send_try_merge() {
+    let send = |to: &str, amount: u128, denom: &str| MsgSend256 {
+        from_address: "contract".to_string(),
+        to_address: to.to_string(),
+        amount: vec![coin_u256(amount, denom)],
+    };
+    let test_cases = vec![
+        TryMergeTestCase {
+            name: "same recipient and denom",
+            msg: send("owner", 10, "base"),
+            other: send("owner", 5, "base"),
+            expected_merged: true,
+            expected_msg: send("owner", 15, "base"),
+        },
+        TryMergeTestCase {
+            name: "different denom",
+            msg: send("owner", 10, "base"),
+            other: send("owner", 5, "quote"),
+            expected_merged: false,
+            expected_msg: send("owner", 10, "base"),
+        },
+        TryMergeTestCase {
+            name: "different recipient",
+            msg: send("owner", 10, "base"),
+            other: send("claimer", 5, "base"),
+            expected_merged: false,
+            expected_msg: send("owner", 10, "base"),
+        },
+        TryMergeTestCase {
+            name: "different sender",
+            msg: send("owner", 10, "base"),
+            other: MsgSend256 {
+                from_address: "other_contract".to_string(),
+                ..send("owner", 5, "base")
+            },
+            expected_merged: false,
+            expected_msg: send("owner", 10, "base"),
+        },
+        TryMergeTestCase {
+            name: "multi coin message",
+            msg: MsgSend256 {
+                amount: vec![coin_u256(10u128, "base"), coin_u256(1u128, "quote")],
+                ..send("owner", 0, "base")
+            },
+            other: send("owner", 5, "base"),
+            expected_merged: false,
+            expected_msg: MsgSend256 {
+                amount: vec![coin_u256(10u128, "base"), coin_u256(1u128, "quote")],
+                ..send("owner", 0, "base")
+            },
+        },
+    ];
+
+    for test in test_cases {
+        let mut msg = test.msg.clone();
+
+        let merged = msg.try_merge(&test.other).unwrap();
+
+        assert_eq!(
+            merged,
+            test.expected_merged,
+            "{}",
+            format_test_name(test.name)
+        );
+        assert_eq!(msg, test.expected_msg, "{}", format_test_name(test.name));
+    }
+}
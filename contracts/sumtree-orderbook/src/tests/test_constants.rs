@@ -1,5 +1,7 @@
 pub(crate) const BASE_DENOM: &str = "base";
 pub(crate) const QUOTE_DENOM: &str = "quote";
+// Address of a CW20 token contract
+pub(crate) const CW20_DENOM: &str = "cw20token";
 pub(crate) const DEFAULT_OWNER: &str = "owner";
 pub(crate) const DEFAULT_SENDER: &str = "sender";
 
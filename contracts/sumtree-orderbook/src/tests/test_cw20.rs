@@ -0,0 +1,509 @@
+use cosmwasm_std::{
+    coin,
+    testing::{mock_env, mock_info},
+    to_json_binary, Addr, BankMsg, Coin, CosmosMsg, SubMsg, Uint128, Uint256, WasmMsg,
+};
+use cw_utils::PaymentError;
+
+use super::{
+    mock_querier::mock_dependencies_custom,
+    test_constants::{CW20_DENOM, DEFAULT_OWNER, DEFAULT_SENDER, QUOTE_DENOM},
+    test_utils::format_test_name,
+};
+use crate::{
+    constants::{MAX_TICK, MIN_TICK},
+    msg::{Cw20HookMsg, Cw20ReceiveMsg},
+    order::{
+        cancel_limit, claim_limit, place_limit, receive_cw20, replace_order, run_market_order,
+    },
+    orderbook::create_orderbook,
+    state::{orders, ASSET_TYPES},
+    types::{
+        coin_u256, AssetType, Cw20ExecuteMsg, MarketOrder, MsgSend256, OrderDirection,
+        REPLY_ID_CLAIM, REPLY_ID_REFUND,
+    },
+    ContractError,
+};
+
+struct ReceiveCw20TestCase {
+    name: &'static str,
+    token: &'static str,
+    funds: Vec<Coin>,
+    order_direction: OrderDirection,
+    amount: Uint128,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_receive_cw20_place_limit() {
+    let test_cases = vec![
+        ReceiveCw20TestCase {
+            name: "valid ask funded by cw20",
+            token: CW20_DENOM,
+            funds: vec![],
+            order_direction: OrderDirection::Ask,
+            amount: Uint128::from(100u128),
+            expected_error: None,
+        },
+        ReceiveCw20TestCase {
+            name: "tokens sent from the wrong contract",
+            token: "othertoken",
+            funds: vec![],
+            order_direction: OrderDirection::Ask,
+            amount: Uint128::from(100u128),
+            expected_error: Some(ContractError::InvalidPaymentAsset {
+                denom: CW20_DENOM.to_string(),
+            }),
+        },
+        ReceiveCw20TestCase {
+            name: "cw20 sent for native side",
+            token: CW20_DENOM,
+            funds: vec![],
+            order_direction: OrderDirection::Bid,
+            amount: Uint128::from(100u128),
+            expected_error: Some(ContractError::InvalidPaymentAsset {
+                denom: QUOTE_DENOM.to_string(),
+            }),
+        },
+        ReceiveCw20TestCase {
+            name: "native funds attached",
+            token: CW20_DENOM,
+            funds: vec![coin(100u128, QUOTE_DENOM)],
+            order_direction: OrderDirection::Ask,
+            amount: Uint128::from(100u128),
+            expected_error: Some(ContractError::PaymentError(PaymentError::NonPayable {})),
+        },
+        ReceiveCw20TestCase {
+            name: "zero amount",
+            token: CW20_DENOM,
+            funds: vec![],
+            order_direction: OrderDirection::Ask,
+            amount: Uint128::zero(),
+            expected_error: Some(ContractError::InvalidQuantity {
+                quantity: Uint128::zero(),
+            }),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        ASSET_TYPES
+            .save(deps.as_mut().storage, CW20_DENOM, &AssetType::Cw20)
+            .unwrap();
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            CW20_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        // -- System under test --
+        let receive_msg = Cw20ReceiveMsg {
+            sender: DEFAULT_SENDER.to_string(),
+            amount: test.amount,
+            msg: to_json_binary(&Cw20HookMsg::PlaceLimit {
+                tick_id: 0,
+                order_direction: test.order_direction,
+                claim_bounty: None,
+                owner: None,
+                client_order_id: None,
+                memo: None,
+                auto_settle: None,
+            })
+            .unwrap(),
+        };
+        let res = receive_cw20(
+            deps.as_mut(),
+            env,
+            mock_info(test.token, &test.funds),
+            receive_msg,
+        );
+
+        // -- Post Test Assertions --
+        if let Some(expected_error) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+
+        res.unwrap();
+        let order = orders().load(deps.as_ref().storage, &(0, 0)).unwrap();
+        assert_eq!(
+            order.owner,
+            Addr::unchecked(DEFAULT_SENDER),
+            "{}",
+            format_test_name(test.name)
+        );
+        assert_eq!(
+            order.quantity,
+            test.amount,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+#[test]
+fn test_place_limit_native_funds_for_cw20() {
+    // -- Test Setup --
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    ASSET_TYPES
+        .save(deps.as_mut().storage, CW20_DENOM, &AssetType::Cw20)
+        .unwrap();
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        CW20_DENOM.to_string(),
+        None,
+        None,
+    )
+    .unwrap();
+
+    // -- System under test --
+    let res = place_limit(
+        &mut deps.as_mut(),
+        env,
+        mock_info(DEFAULT_SENDER, &[coin(100u128, CW20_DENOM)]),
+        0,
+        OrderDirection::Ask,
+        Uint128::from(100u128),
+        None,
+        None,
+        None,
+        None,
+        None,
+    );
+
+    // -- Post Test Assertions --
+    assert_eq!(
+        res.unwrap_err(),
+        ContractError::InvalidPaymentAsset {
+            denom: CW20_DENOM.to_string()
+        }
+    );
+}
+
+struct Cw20PayoutTestCase {
+    name: &'static str,
+    order_direction: OrderDirection,
+    cancel: bool,
+    expected_msg: CosmosMsg,
+    expected_reply_id: u64,
+}
+
+#[test]
+fn test_cw20_payouts() {
+    let env = mock_env();
+    let quantity = 100u128;
+    let cw20_transfer = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: CW20_DENOM.to_string(),
+        msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: DEFAULT_OWNER.to_string(),
+            amount: Uint128::from(quantity),
+        })
+        .unwrap(),
+        funds: vec![],
+    });
+    let test_cases = vec![
+        Cw20PayoutTestCase {
+            name: "cancel refunds cw20",
+            order_direction: OrderDirection::Ask,
+            cancel: true,
+            expected_msg: cw20_transfer.clone(),
+            expected_reply_id: REPLY_ID_REFUND,
+        },
+        Cw20PayoutTestCase {
+            name: "cancel refunds native",
+            order_direction: OrderDirection::Bid,
+            cancel: true,
+            expected_msg: CosmosMsg::Bank(BankMsg::Send {
+                to_address: DEFAULT_OWNER.to_string(),
+                amount: vec![coin(quantity, QUOTE_DENOM)],
+            }),
+            expected_reply_id: REPLY_ID_REFUND,
+        },
+        Cw20PayoutTestCase {
+            name: "claim pays out cw20",
+            order_direction: OrderDirection::Bid,
+            cancel: false,
+            expected_msg: cw20_transfer,
+            expected_reply_id: REPLY_ID_CLAIM,
+        },
+        Cw20PayoutTestCase {
+            name: "claim pays out native",
+            order_direction: OrderDirection::Ask,
+            cancel: false,
+            expected_msg: MsgSend256 {
+                from_address: env.contract.address.to_string(),
+                to_address: DEFAULT_OWNER.to_string(),
+                amount: vec![coin_u256(quantity, QUOTE_DENOM)],
+            }
+            .into(),
+            expected_reply_id: REPLY_ID_CLAIM,
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        ASSET_TYPES
+            .save(deps.as_mut().storage, CW20_DENOM, &AssetType::Cw20)
+            .unwrap();
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            CW20_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        // Asks are funded through the CW20 hook, bids with native funds
+        match test.order_direction {
+            OrderDirection::Ask => receive_cw20(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(CW20_DENOM, &[]),
+                Cw20ReceiveMsg {
+                    sender: DEFAULT_OWNER.to_string(),
+                    amount: Uint128::from(quantity),
+                    msg: to_json_binary(&Cw20HookMsg::PlaceLimit {
+                        tick_id: 0,
+                        order_direction: OrderDirection::Ask,
+                        claim_bounty: None,
+                        owner: None,
+                        client_order_id: None,
+                        memo: None,
+                        auto_settle: None,
+                    })
+                    .unwrap(),
+                },
+            ),
+            OrderDirection::Bid => place_limit(
+                &mut deps.as_mut(),
+                env.clone(),
+                mock_info(DEFAULT_OWNER, &[coin(quantity, QUOTE_DENOM)]),
+                0,
+                OrderDirection::Bid,
+                Uint128::from(quantity),
+                None,
+                None,
+                None,
+                None,
+                None,
+            ),
+        }
+        .unwrap();
+
+        // -- System under test --
+        let res = if test.cancel {
+            cancel_limit(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(DEFAULT_OWNER, &[]),
+                0,
+                0,
+            )
+            .unwrap()
+        } else {
+            // Fill the order entirely at a price of 1
+            let mut market_order = MarketOrder::new(
+                Uint128::from(quantity),
+                test.order_direction.opposite(),
+                Addr::unchecked(DEFAULT_SENDER),
+            );
+            let tick_bound = match market_order.order_direction {
+                OrderDirection::Bid => MAX_TICK,
+                OrderDirection::Ask => MIN_TICK,
+            };
+            let (output, ..) = run_market_order(
+                deps.as_mut().storage,
+                &env,
+                &mut market_order,
+                tick_bound,
+                None,
+            )
+            .unwrap();
+            assert_eq!(
+                output,
+                Uint256::from(quantity),
+                "{}",
+                format_test_name(test.name)
+            );
+
+            claim_limit(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(DEFAULT_SENDER, &[]),
+                0,
+                0,
+                false,
+            )
+            .unwrap()
+        };
+
+        // -- Post Test Assertions --
+        assert_eq!(res.messages.len(), 1, "{}", format_test_name(test.name));
+        assert_eq!(
+            res.messages[0].msg,
+            test.expected_msg,
+            "{}",
+            format_test_name(test.name)
+        );
+        assert_eq!(
+            res.messages[0].id,
+            test.expected_reply_id,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct ReplaceOrderCw20TestCase {
+    name: &'static str,
+    sent: Vec<Coin>,
+    new_quantity: Uint128,
+    expected_refund: Option<u128>,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_replace_order_cw20() {
+    let quantity = Uint128::from(100u128);
+    let test_cases = vec![
+        ReplaceOrderCw20TestCase {
+            name: "move cw20 order",
+            sent: vec![],
+            new_quantity: quantity,
+            expected_refund: None,
+            expected_error: None,
+        },
+        ReplaceOrderCw20TestCase {
+            name: "move cw20 order and decrease quantity",
+            sent: vec![],
+            new_quantity: Uint128::from(60u128),
+            expected_refund: Some(40),
+            expected_error: None,
+        },
+        ReplaceOrderCw20TestCase {
+            name: "increase cw20 quantity",
+            sent: vec![],
+            new_quantity: Uint128::from(150u128),
+            expected_refund: None,
+            expected_error: Some(ContractError::InsufficientFunds {
+                sent: quantity,
+                required: Uint128::from(150u128),
+            }),
+        },
+        ReplaceOrderCw20TestCase {
+            name: "native funds attached",
+            sent: vec![coin(50, QUOTE_DENOM)],
+            new_quantity: quantity,
+            expected_refund: None,
+            expected_error: Some(ContractError::PaymentError(PaymentError::NonPayable {})),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        ASSET_TYPES
+            .save(deps.as_mut().storage, CW20_DENOM, &AssetType::Cw20)
+            .unwrap();
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            CW20_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        receive_cw20(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(CW20_DENOM, &[]),
+            Cw20ReceiveMsg {
+                sender: DEFAULT_OWNER.to_string(),
+                amount: quantity,
+                msg: to_json_binary(&Cw20HookMsg::PlaceLimit {
+                    tick_id: 0,
+                    order_direction: OrderDirection::Ask,
+                    claim_bounty: None,
+                    owner: None,
+                    client_order_id: None,
+                    memo: None,
+                    auto_settle: None,
+                })
+                .unwrap(),
+            },
+        )
+        .unwrap();
+
+        // -- System under test --
+        let res = replace_order(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(DEFAULT_OWNER, &test.sent),
+            0,
+            0,
+            10,
+            test.new_quantity,
+        );
+
+        // -- Post Test Assertions --
+        if let Some(expected_error) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+        let res = res.unwrap();
+
+        let order = orders().load(deps.as_ref().storage, &(10, 1)).unwrap();
+        assert_eq!(
+            order.quantity,
+            test.new_quantity,
+            "{}",
+            format_test_name(test.name)
+        );
+
+        // A decrease in quantity is refunded in the CW20
+        let expected_messages: Vec<SubMsg> = test
+            .expected_refund
+            .map(|refund| {
+                SubMsg::reply_on_error(
+                    WasmMsg::Execute {
+                        contract_addr: CW20_DENOM.to_string(),
+                        msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                            recipient: DEFAULT_OWNER.to_string(),
+                            amount: Uint128::from(refund),
+                        })
+                        .unwrap(),
+                        funds: vec![],
+                    },
+                    REPLY_ID_REFUND,
+                )
+            })
+            .into_iter()
+            .collect();
+        assert_eq!(
+            res.messages,
+            expected_messages,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
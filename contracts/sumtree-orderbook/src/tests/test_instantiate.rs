@@ -1,13 +1,24 @@
 use cosmwasm_std::{
     coin,
     testing::{mock_env, mock_info},
+    Addr, CosmosMsg, Decimal256, Uint128,
 };
+use osmosis_std::types::cosmos::bank::v1beta1::MsgSend;
 
 use super::{
     mock_querier::mock_dependencies_custom,
-    test_constants::{BASE_DENOM, DEFAULT_SENDER, QUOTE_DENOM},
+    test_constants::{BASE_DENOM, CW20_DENOM, DEFAULT_OWNER, DEFAULT_SENDER, QUOTE_DENOM},
+    test_utils::OrderOperation,
+};
+use crate::{
+    constants::{DEFAULT_MAKER_FEE, DEFAULT_MAKER_FEE_RECIPIENT, MAX_MAKER_FEE_PERCENTAGE},
+    contract::instantiate,
+    msg::InstantiateMsg,
+    order::claim_limit,
+    state::{get_maker_fee, MAKER_FEE_RECIPIENT},
+    types::{AssetType, LimitOrder, MarketOrder, OrderDirection, REPLY_ID_MAKER_FEE},
+    ContractError,
 };
-use crate::{contract::instantiate, msg::InstantiateMsg, ContractError};
 
 struct InstantiateTestCase {
     name: &'static str,
@@ -23,6 +34,26 @@ fn test_instantiate() {
             msg: InstantiateMsg {
                 quote_denom: QUOTE_DENOM.to_string(),
                 base_denom: BASE_DENOM.to_string(),
+                maker_fee: None,
+                maker_fee_recipient: None,
+                min_tick: None,
+                max_tick: None,
+                base_asset_type: None,
+                quote_asset_type: None,
+            },
+            expected_error: None,
+        },
+        InstantiateTestCase {
+            name: "valid instantiate with maker fee config",
+            msg: InstantiateMsg {
+                quote_denom: QUOTE_DENOM.to_string(),
+                base_denom: BASE_DENOM.to_string(),
+                maker_fee: Some(Decimal256::percent(1)),
+                maker_fee_recipient: Some(Addr::unchecked("fee_recipient")),
+                min_tick: None,
+                max_tick: None,
+                base_asset_type: None,
+                quote_asset_type: None,
             },
             expected_error: None,
         },
@@ -32,9 +63,94 @@ fn test_instantiate() {
                 // Same denom for both quote and base
                 quote_denom: QUOTE_DENOM.to_string(),
                 base_denom: QUOTE_DENOM.to_string(),
+                maker_fee: None,
+                maker_fee_recipient: None,
+                min_tick: None,
+                max_tick: None,
+                base_asset_type: None,
+                quote_asset_type: None,
             },
             expected_error: Some(ContractError::DuplicateDenoms {}),
         },
+        InstantiateTestCase {
+            name: "maker fee above maximum",
+            msg: InstantiateMsg {
+                quote_denom: QUOTE_DENOM.to_string(),
+                base_denom: BASE_DENOM.to_string(),
+                maker_fee: Some(
+                    MAX_MAKER_FEE_PERCENTAGE
+                        .checked_add(Decimal256::percent(1))
+                        .unwrap(),
+                ),
+                maker_fee_recipient: None,
+                min_tick: None,
+                max_tick: None,
+                base_asset_type: None,
+                quote_asset_type: None,
+            },
+            expected_error: Some(ContractError::InvalidMakerFee),
+        },
+        InstantiateTestCase {
+            name: "invalid maker fee recipient",
+            msg: InstantiateMsg {
+                quote_denom: QUOTE_DENOM.to_string(),
+                base_denom: BASE_DENOM.to_string(),
+                maker_fee: None,
+                maker_fee_recipient: Some(Addr::unchecked("0")),
+                min_tick: None,
+                max_tick: None,
+                base_asset_type: None,
+                quote_asset_type: None,
+            },
+            expected_error: Some(ContractError::InvalidMakerFeeRecipient),
+        },
+        InstantiateTestCase {
+            name: "valid instantiate with cw20 base",
+            msg: InstantiateMsg {
+                quote_denom: QUOTE_DENOM.to_string(),
+                base_denom: CW20_DENOM.to_string(),
+                maker_fee: None,
+                maker_fee_recipient: None,
+                min_tick: None,
+                max_tick: None,
+                base_asset_type: Some(AssetType::Cw20),
+                quote_asset_type: None,
+            },
+            expected_error: None,
+        },
+        InstantiateTestCase {
+            name: "cw20 denom without asset type",
+            msg: InstantiateMsg {
+                quote_denom: QUOTE_DENOM.to_string(),
+                base_denom: CW20_DENOM.to_string(),
+                maker_fee: None,
+                maker_fee_recipient: None,
+                min_tick: None,
+                max_tick: None,
+                base_asset_type: None,
+                quote_asset_type: None,
+            },
+            // Without an asset type the denom is checked for a bank supply
+            expected_error: Some(ContractError::InvalidDenom {
+                denom: CW20_DENOM.to_string(),
+            }),
+        },
+        InstantiateTestCase {
+            name: "cw20 denom is not a valid address",
+            msg: InstantiateMsg {
+                quote_denom: "Invalid Token".to_string(),
+                base_denom: BASE_DENOM.to_string(),
+                maker_fee: None,
+                maker_fee_recipient: None,
+                min_tick: None,
+                max_tick: None,
+                base_asset_type: None,
+                quote_asset_type: Some(AssetType::Cw20),
+            },
+            expected_error: Some(ContractError::InvalidDenom {
+                denom: "Invalid Token".to_string(),
+            }),
+        },
     ];
 
     for test in test_cases {
@@ -42,6 +158,12 @@ fn test_instantiate() {
         let mut deps = mock_dependencies_custom();
         let env = mock_env();
         let info = mock_info(DEFAULT_SENDER, &[coin(100u128, BASE_DENOM)]);
+        let expected_maker_fee = test.msg.maker_fee.unwrap_or(DEFAULT_MAKER_FEE);
+        let expected_recipient = test
+            .msg
+            .maker_fee_recipient
+            .clone()
+            .unwrap_or(Addr::unchecked(DEFAULT_MAKER_FEE_RECIPIENT));
 
         // -- System under test --
         let res = instantiate(deps.as_mut(), env, info, test.msg);
@@ -63,5 +185,93 @@ fn test_instantiate() {
             test.name,
             res.unwrap_err()
         );
+
+        assert_eq!(
+            get_maker_fee(deps.as_ref().storage).unwrap(),
+            expected_maker_fee,
+            "{}: maker fee was not set correctly",
+            test.name
+        );
+        assert_eq!(
+            MAKER_FEE_RECIPIENT.load(deps.as_ref().storage).unwrap(),
+            expected_recipient,
+            "{}: maker fee recipient was not set correctly",
+            test.name
+        );
+    }
+}
+
+#[test]
+fn test_instantiate_maker_fee_applied_on_claim() {
+    // -- Test Setup --
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let recipient = "fee_recipient";
+    let quantity = Uint128::from(1000u128);
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(DEFAULT_SENDER, &[]),
+        InstantiateMsg {
+            quote_denom: QUOTE_DENOM.to_string(),
+            base_denom: BASE_DENOM.to_string(),
+            maker_fee: Some(Decimal256::percent(1)),
+            maker_fee_recipient: Some(Addr::unchecked(recipient)),
+            min_tick: None,
+            max_tick: None,
+            base_asset_type: None,
+            quote_asset_type: None,
+        },
+    )
+    .unwrap();
+
+    // Place an ask at price 1 and fill it completely
+    let operations = vec![
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            0,
+            OrderDirection::Ask,
+            Addr::unchecked(DEFAULT_OWNER),
+            quantity,
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::RunMarket(MarketOrder::new(
+            quantity,
+            OrderDirection::Bid,
+            Addr::unchecked(DEFAULT_SENDER),
+        )),
+    ];
+    for operation in operations {
+        operation
+            .run(deps.as_mut(), env.clone(), mock_info(DEFAULT_SENDER, &[]))
+            .unwrap();
     }
+
+    // -- System under test --
+    let res = claim_limit(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(DEFAULT_SENDER, &[]),
+        0,
+        0,
+        false,
+    )
+    .unwrap();
+
+    // -- Post Test Assertions --
+    let maker_fee_msg = res
+        .messages
+        .iter()
+        .find(|msg| msg.id == REPLY_ID_MAKER_FEE)
+        .expect("maker fee message was not sent");
+    let CosmosMsg::Stargate { value, .. } = &maker_fee_msg.msg else {
+        panic!("unexpected maker fee message: {:?}", maker_fee_msg);
+    };
+    let maker_fee_send = MsgSend::try_from(value.clone()).unwrap();
+    assert_eq!(maker_fee_send.to_address, recipient);
+    assert_eq!(maker_fee_send.amount.len(), 1);
+    assert_eq!(maker_fee_send.amount[0].denom, QUOTE_DENOM);
+    assert_eq!(maker_fee_send.amount[0].amount, "10");
 }
@@ -0,0 +1,71 @@
+use cosmwasm_std::{Addr, Uint128};
+
+use crate::types::{MarketOrder, OrderDirection};
+use crate::ContractError;
+
+use super::test_utils::format_test_name;
+
+struct TryNewMarketOrderTestCase {
+    name: &'static str,
+    quantity: Uint128,
+    order_direction: OrderDirection,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_market_order_try_new() {
+    let owner = Addr::unchecked("owner");
+    let test_cases = vec![
+        TryNewMarketOrderTestCase {
+            name: "valid bid",
+            quantity: Uint128::from(100u128),
+            order_direction: OrderDirection::Bid,
+            expected_error: None,
+        },
+        TryNewMarketOrderTestCase {
+            name: "valid ask",
+            quantity: Uint128::one(),
+            order_direction: OrderDirection::Ask,
+            expected_error: None,
+        },
+        TryNewMarketOrderTestCase {
+            name: "zero quantity bid",
+            quantity: Uint128::zero(),
+            order_direction: OrderDirection::Bid,
+            expected_error: Some(ContractError::InvalidQuantity {
+                quantity: Uint128::zero(),
+            }),
+        },
+        TryNewMarketOrderTestCase {
+            name: "zero quantity ask",
+            quantity: Uint128::zero(),
+            order_direction: OrderDirection::Ask,
+            expected_error: Some(ContractError::InvalidQuantity {
+                quantity: Uint128::zero(),
+            }),
+        },
+    ];
+
+    for test in test_cases {
+        let res = MarketOrder::try_new(test.quantity, test.order_direction, owner.clone());
+
+        match test.expected_error {
+            Some(expected_error) => {
+                assert_eq!(
+                    res.unwrap_err(),
+                    expected_error,
+                    "{}",
+                    format_test_name(test.name)
+                );
+            }
+            None => {
+                assert_eq!(
+                    res.unwrap(),
+                    MarketOrder::new(test.quantity, test.order_direction, owner.clone()),
+                    "{}",
+                    format_test_name(test.name)
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,419 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    testing::{mock_env, mock_info},
+    Addr, Decimal256, DepsMut, Env, Order, Storage, Timestamp, Uint128,
+};
+use cw_storage_plus::{Item, Map};
+
+use super::{
+    mock_querier::mock_dependencies_custom,
+    test_constants::{BASE_DENOM, DEFAULT_OWNER, DEFAULT_SENDER, QUOTE_DENOM},
+    test_utils::{format_test_name, OrderOperation},
+};
+use crate::{
+    constants::{MAX_TICK, MIN_TICK},
+    contract::{execute, migrate},
+    msg::{ExecuteMsg, MigrateMsg},
+    orderbook::create_orderbook,
+    state::{
+        get_orderbook, orders, LAYER_LIQUIDITY, ORDER_COUNT, QUEUED_LIQUIDITY, TICK_ORDER_COUNT,
+    },
+    types::{LimitOrder, MarketOrder, OrderDirection},
+    ContractError,
+};
+
+type LayerSnapshot = Vec<((u8, String, i64), Decimal256)>;
+type TickCountSnapshot = Vec<((i64, String), u64)>;
+type OwnerCountSnapshot = Vec<(Addr, u32)>;
+type QueuedSnapshot = Vec<((i64, String, u64), Uint128)>;
+type IndexSnapshot = Vec<(Vec<u8>, Vec<u8>)>;
+
+/// Orderbook layout stored before the tick range and price cache were introduced
+#[cw_serde]
+struct LegacyOrderbook {
+    quote_denom: String,
+    base_denom: String,
+    current_tick: i64,
+    next_bid_tick: i64,
+    next_ask_tick: i64,
+}
+
+/// Limit order layout stored before placement heights, pegs, memos and auto-settlement were
+/// introduced
+#[cw_serde]
+struct LegacyLimitOrder {
+    tick_id: i64,
+    order_id: u64,
+    order_direction: OrderDirection,
+    owner: Addr,
+    quantity: Uint128,
+    etas: Decimal256,
+    claim_bounty: Option<Decimal256>,
+    placed_quantity: Uint128,
+    placed_at: Timestamp,
+}
+
+const LEGACY_ORDERBOOK: Item<LegacyOrderbook> = Item::new("orderbook");
+const LEGACY_ORDERS: Map<(i64, u64), LegacyLimitOrder> = Map::new("orders");
+
+// Storage written by the contract before the upgrade, anything else is removed to recreate it
+const LEGACY_ITEMS: [&str; 10] = [
+    "order_id",
+    "orderbook",
+    "is_active",
+    "maker_fee",
+    "maker_fee_recipient",
+    "admin",
+    "admin_offer",
+    "moderator",
+    "moderator_offer",
+    "contract_info",
+];
+const LEGACY_MAPS: [&str; 8] = [
+    "tick_state",
+    "direction_liquidity",
+    "orders",
+    "orders_owner",
+    "orders_tick_and_owner",
+    "nodes",
+    "node_id",
+    "tree",
+];
+
+/// Returns the prefix of every key stored in the map with the given namespace.
+fn map_prefix(namespace: &str) -> Vec<u8> {
+    [
+        (namespace.len() as u16).to_be_bytes().as_slice(),
+        namespace.as_bytes(),
+    ]
+    .concat()
+}
+
+/// Rewrites the storage of a book built by the current contract into the layout written before
+/// the upgrade: the orderbook and orders lose the fields added since, and all other state is removed.
+fn downgrade_to_legacy_storage(storage: &mut dyn Storage) {
+    let orderbook = get_orderbook(storage).unwrap();
+    LEGACY_ORDERBOOK
+        .save(
+            storage,
+            &LegacyOrderbook {
+                quote_denom: orderbook.quote_denom,
+                base_denom: orderbook.base_denom,
+                current_tick: orderbook.current_tick,
+                next_bid_tick: orderbook.next_bid_tick,
+                next_ask_tick: orderbook.next_ask_tick,
+            },
+        )
+        .unwrap();
+
+    let stored_orders: Vec<LimitOrder> = orders()
+        .idx
+        .owner
+        .range(storage, None, None, Order::Ascending)
+        .map(|res| res.unwrap().1)
+        .collect();
+    for order in stored_orders {
+        LEGACY_ORDERS
+            .save(
+                storage,
+                (order.tick_id, order.order_id),
+                &LegacyLimitOrder {
+                    tick_id: order.tick_id,
+                    order_id: order.order_id,
+                    order_direction: order.order_direction,
+                    owner: order.owner,
+                    quantity: order.quantity,
+                    etas: order.etas,
+                    claim_bounty: order.claim_bounty,
+                    placed_quantity: order.placed_quantity,
+                    placed_at: order.placed_at,
+                },
+            )
+            .unwrap();
+    }
+
+    let legacy_prefixes: Vec<Vec<u8>> = LEGACY_MAPS.iter().map(|ns| map_prefix(ns)).collect();
+    let removed_keys: Vec<Vec<u8>> = storage
+        .range(None, None, Order::Ascending)
+        .map(|(key, _)| key)
+        .filter(|key| {
+            !LEGACY_ITEMS
+                .iter()
+                .any(|ns| key.as_slice() == ns.as_bytes())
+                && !legacy_prefixes.iter().any(|prefix| key.starts_with(prefix))
+        })
+        .collect();
+    for key in removed_keys {
+        storage.remove(&key);
+    }
+}
+
+/// Reads every entry of the state derived from the ticks and orders.
+fn derived_state(
+    storage: &dyn Storage,
+) -> (
+    LayerSnapshot,
+    TickCountSnapshot,
+    OwnerCountSnapshot,
+    QueuedSnapshot,
+    IndexSnapshot,
+) {
+    let index_prefix = map_prefix("orders_owner_and_direction");
+    (
+        LAYER_LIQUIDITY
+            .range(storage, None, None, Order::Ascending)
+            .collect::<Result<_, _>>()
+            .unwrap(),
+        TICK_ORDER_COUNT
+            .range(storage, None, None, Order::Ascending)
+            .collect::<Result<_, _>>()
+            .unwrap(),
+        ORDER_COUNT
+            .range(storage, None, None, Order::Ascending)
+            .collect::<Result<_, _>>()
+            .unwrap(),
+        QUEUED_LIQUIDITY
+            .range(storage, None, None, Order::Ascending)
+            .collect::<Result<_, _>>()
+            .unwrap(),
+        storage
+            .range(Some(&index_prefix), None, Order::Ascending)
+            .take_while(|(key, _)| key.starts_with(&index_prefix))
+            .collect(),
+    )
+}
+
+/// Calls `RebuildDerivedState` until the rebuild completes, returning the number of calls made.
+fn complete_rebuild(mut deps: DepsMut, env: Env, limit: Option<u32>) -> u32 {
+    for calls in 1..=1000 {
+        let res = execute(
+            deps.branch(),
+            env.clone(),
+            mock_info(DEFAULT_SENDER, &[]),
+            ExecuteMsg::RebuildDerivedState { limit },
+        )
+        .unwrap();
+        if res
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "rebuild" && attr.value == "complete")
+        {
+            return calls;
+        }
+    }
+    panic!("rebuild did not complete");
+}
+
+struct MigrateTestCase {
+    name: &'static str,
+    // Recreates the storage written before the upgrade, without any derived state
+    legacy_storage: bool,
+    // Steps taken by each rebuild call
+    limit: Option<u32>,
+}
+
+#[test]
+fn test_migrate_rebuilds_derived_state() {
+    let owner_a = Addr::unchecked(DEFAULT_OWNER);
+    let owner_b = Addr::unchecked("owner_b");
+    let quantity = Uint128::from(10u128);
+    let place = |tick_id: i64, order_id: u64, direction: OrderDirection, owner: &Addr| {
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            tick_id,
+            order_id,
+            direction,
+            owner.clone(),
+            quantity,
+            Decimal256::zero(),
+            None,
+        ))
+    };
+    let book = vec![
+        place(-20, 0, OrderDirection::Bid, &owner_a),
+        place(-10, 1, OrderDirection::Bid, &owner_b),
+        place(10, 2, OrderDirection::Ask, &owner_a),
+        place(10, 3, OrderDirection::Ask, &owner_b),
+        place(20, 4, OrderDirection::Ask, &owner_a),
+    ];
+    // Fills, claims and cancels touching orders placed before the upgrade
+    let post_upgrade = vec![
+        OrderOperation::RunMarket(MarketOrder::new(
+            Uint128::from(15u128),
+            OrderDirection::Bid,
+            owner_b.clone(),
+        )),
+        OrderOperation::RunMarket(MarketOrder::new(
+            Uint128::from(5u128),
+            OrderDirection::Ask,
+            owner_a.clone(),
+        )),
+        OrderOperation::Claim((10, 2)),
+        OrderOperation::Cancel((-20, 0)),
+        OrderOperation::Cancel((20, 4)),
+    ];
+
+    let test_cases = vec![
+        MigrateTestCase {
+            name: "book stored before the upgrade",
+            legacy_storage: true,
+            limit: Some(3),
+        },
+        MigrateTestCase {
+            name: "book stored before the upgrade, single call",
+            legacy_storage: true,
+            limit: None,
+        },
+        MigrateTestCase {
+            name: "book already in sync",
+            legacy_storage: false,
+            limit: Some(3),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(DEFAULT_SENDER, &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        for op in book.clone() {
+            op.run(deps.as_mut(), env.clone(), info.clone()).unwrap();
+        }
+        let mut expected = derived_state(deps.as_ref().storage);
+
+        if test.legacy_storage {
+            downgrade_to_legacy_storage(deps.as_mut().storage);
+            // Legacy orders have no placement height and are queued as if placed at genesis
+            for ((_, _, placed_at_height), _) in expected.3.iter_mut() {
+                *placed_at_height = 0;
+            }
+        }
+
+        // -- System under test --
+        migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap();
+
+        // -- Post Test Assertions --
+        // Handlers are blocked until the rebuild completes
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(owner_a.as_str(), &[]),
+            ExecuteMsg::CancelLimit {
+                tick_id: 20,
+                order_id: 4,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::RebuildInProgress,
+            "{}",
+            format_test_name(test.name)
+        );
+
+        let calls = complete_rebuild(deps.as_mut(), env.clone(), test.limit);
+        if test.limit.is_some() {
+            assert!(calls > 1, "{}", format_test_name(test.name));
+        }
+        assert_eq!(
+            derived_state(deps.as_ref().storage),
+            expected,
+            "{}",
+            format_test_name(test.name)
+        );
+
+        // Orders placed before the upgrade can be filled, claimed and cancelled
+        for op in post_upgrade.clone() {
+            op.run(deps.as_mut(), env.clone(), info.clone()).unwrap();
+        }
+
+        // The derived state kept up to date by those operations matches a fresh rebuild
+        let updated = derived_state(deps.as_ref().storage);
+        migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap();
+        complete_rebuild(deps.as_mut(), env.clone(), None);
+        assert_eq!(
+            derived_state(deps.as_ref().storage),
+            updated,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+#[test]
+fn test_migrate_legacy_orderbook() {
+    // -- Test Setup --
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let info = mock_info(DEFAULT_SENDER, &[]);
+    let owner = Addr::unchecked(DEFAULT_OWNER);
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+        None,
+        None,
+    )
+    .unwrap();
+    for (tick_id, order_id, direction) in
+        [(-10, 0, OrderDirection::Bid), (10, 1, OrderDirection::Ask)]
+    {
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            tick_id,
+            order_id,
+            direction,
+            owner.clone(),
+            Uint128::from(10u128),
+            Decimal256::zero(),
+            None,
+        ))
+        .run(deps.as_mut(), env.clone(), info.clone())
+        .unwrap();
+    }
+
+    // Overwrite the orderbook with the layout stored before the upgrade
+    let orderbook = get_orderbook(deps.as_ref().storage).unwrap();
+    let legacy_orderbook = LegacyOrderbook {
+        quote_denom: orderbook.quote_denom.clone(),
+        base_denom: orderbook.base_denom.clone(),
+        current_tick: orderbook.current_tick,
+        next_bid_tick: orderbook.next_bid_tick,
+        next_ask_tick: orderbook.next_ask_tick,
+    };
+    LEGACY_ORDERBOOK
+        .save(deps.as_mut().storage, &legacy_orderbook)
+        .unwrap();
+
+    // -- System under test --
+    migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap();
+    complete_rebuild(deps.as_mut(), env.clone(), None);
+
+    // -- Post Test Assertions --
+    // The legacy orderbook loads with every tick in range
+    let migrated = get_orderbook(deps.as_ref().storage).unwrap();
+    assert_eq!(migrated.min_tick, MIN_TICK);
+    assert_eq!(migrated.max_tick, MAX_TICK);
+    assert_eq!(migrated.next_bid_tick, legacy_orderbook.next_bid_tick);
+    assert_eq!(migrated.next_ask_tick, legacy_orderbook.next_ask_tick);
+
+    // Handlers loading the orderbook keep working
+    for op in [
+        OrderOperation::RunMarket(MarketOrder::new(
+            Uint128::from(5u128),
+            OrderDirection::Bid,
+            owner.clone(),
+        )),
+        OrderOperation::Claim((10, 1)),
+        OrderOperation::Cancel((-10, 0)),
+    ] {
+        op.run(deps.as_mut(), env.clone(), info.clone()).unwrap();
+    }
+}
@@ -1,25 +1,29 @@
-use std::str::FromStr;
+use std::{collections::HashMap, str::FromStr};
 
 use crate::{
-    constants::{MAX_TICK, MIN_TICK}, error::ContractError, order::*, orderbook::*, state::*, sumtree::{
+    constants::{BATCHING_LAYER_COUNT, DEFAULT_MAX_ORDERS_PER_TICK, MAX_CLIENT_ORDER_ID_LENGTH, MAX_MEMO_LENGTH, MAX_TICK, MIN_TICK}, contract::execute, error::ContractError, msg::{BatchClaimResponseData, ExecuteMsg, PlaceLimitResponseData}, order::*, orderbook::*, query, state::*, sumtree::{
         node::{NodeType, TreeNode}, test::test_node::print_tree, tree::{get_or_init_root_node, get_root_node}
     },
+    tick_math::tick_to_price,
     tests::{mock_querier::mock_dependencies_custom, test_utils::{decimal256_from_u128, place_multiple_limit_orders}},
     types::{
-        coin_u256, FilterOwnerOrders, LimitOrder, MarketOrder, MsgSend256, OrderDirection, Orderbook, TickState, TickValues, REPLY_ID_CLAIM, REPLY_ID_CLAIM_BOUNTY, REPLY_ID_MAKER_FEE, REPLY_ID_REFUND
+        coin_u256, CrossingTickPolicy, FilterOwnerOrders, LimitOrder, MakerFeeDiscount, MarketOrder, MsgSend256, OrderDirection, OrderNotFoundReason, Orderbook, PegConfig, PegReference, TickState, TickValues, REPLY_ID_CLAIM, REPLY_ID_CLAIM_BOUNTY, REPLY_ID_MAKER_FEE, REPLY_ID_REFUND
     },
 };
 use cosmwasm_std::{
-    coin, Addr, BankMsg, Coin, Empty, SubMsg, Uint128, Uint256
+    coin, from_json, testing::MockApi, Addr, Api, Attribute, BankMsg, Coin, CosmosMsg, Empty, Event, Order, SubMsg, Uint128, Uint256
 };
 use cosmwasm_std::{
     testing::{mock_env, mock_info},
     Decimal256,
 };
+use cw_storage_plus::Bound;
 use cw_utils::PaymentError;
+use osmosis_std::types::cosmos::bank::v1beta1::MsgSend;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
 use super::{test_constants::{DEFAULT_OWNER, DEFAULT_SENDER, BASE_DENOM, QUOTE_DENOM, LARGE_POSITIVE_TICK, LARGE_NEGATIVE_TICK}, test_utils::{
-    format_test_name, generate_limit_orders, OrderOperation,
+    format_test_name, generate_limit_orders, verify_all_trees, OrderOperation,
 }};
 
 struct PlaceLimitTestCase {
@@ -212,7 +216,7 @@ fn test_place_limit() {
         let info = mock_info(DEFAULT_OWNER, &coin_vec);
 
         // Create an orderbook to operate on
-        create_orderbook(deps.as_mut(), QUOTE_DENOM.to_string(), BASE_DENOM.to_string()).unwrap();
+        create_orderbook(deps.as_mut(), QUOTE_DENOM.to_string(), BASE_DENOM.to_string(), None, None).unwrap();
 
         // --- System under test ---
 
@@ -224,6 +228,10 @@ fn test_place_limit() {
             test.order_direction,
             test.quantity,
             test.claim_bounty,
+            None,
+            None,
+            None,
+            None,
         );
 
         // --- Assertions ---
@@ -285,8 +293,20 @@ fn test_place_limit() {
             format_test_name(test.name)
         );
 
-        // Retrieve the order from storage to verify it was saved correctly
+        // The assigned order ID is returned in the response data
         let expected_order_id = 0;
+        let data: PlaceLimitResponseData = from_json(response.data.unwrap()).unwrap();
+        assert_eq!(
+            data,
+            PlaceLimitResponseData {
+                tick_id: test.tick_id,
+                order_id: expected_order_id,
+            },
+            "{}",
+            format_test_name(test.name)
+        );
+
+        // Retrieve the order from storage to verify it was saved correctly
         let order = orders()
             .load(&deps.storage, &(test.tick_id, expected_order_id))
             .unwrap();
@@ -412,6 +432,7 @@ fn test_cancel_limit() {
             expected_error: Some(ContractError::OrderNotFound {
                 tick_id: 1,
                 order_id: 0,
+                reason: Some(OrderNotFoundReason::NeverPlaced),
             }),
             owner: DEFAULT_OWNER,
             sender: None,
@@ -428,7 +449,7 @@ fn test_cancel_limit() {
         let info = mock_info(test.sender.unwrap_or(test.owner), test.sent.as_slice());
 
         // Create an orderbook to operate on
-        create_orderbook(deps.as_mut(), QUOTE_DENOM.to_string(), BASE_DENOM.to_string()).unwrap();
+        create_orderbook(deps.as_mut(), QUOTE_DENOM.to_string(), BASE_DENOM.to_string(), None, None).unwrap();
 
         if test.place_order {
             let place_info = mock_info(
@@ -443,6 +464,10 @@ fn test_cancel_limit() {
                 test.order_direction,
                 test.quantity,
                 None,
+                None,
+                None,
+                None,
+                None,
             )
             .unwrap();
         }
@@ -862,7 +887,36 @@ fn test_run_market_order() {
             expected_output: Uint256::zero(),
             expected_tick_etas: vec![(-1500000, Decimal256::zero())],
             expected_tick_pointers: vec![(OrderDirection::Ask, -1500000)],
-            expected_error: Some(ContractError::InvalidTickId { tick_id: MIN_TICK }),
+            expected_error: Some(ContractError::TickBoundCrossesCurrent {
+                tick_bound: MIN_TICK,
+                current_tick: -1500000,
+            }),
+        },
+        RunMarketOrderTestCase {
+            name: "invalid tick bound due to ask direction",
+            placed_order: MarketOrder::new(
+                Uint128::new(1000),
+                OrderDirection::Ask,
+                Addr::unchecked(DEFAULT_SENDER),
+            ),
+            // We expect the target tick for a market ask to be below the current tick,
+            // but this is above.
+            tick_bound: MAX_TICK,
+            // Orders to fill against
+            orders: generate_limit_orders(
+                &[-1500000],
+                // 1000 units of liquidity total
+                10,
+                default_quantity,
+                OrderDirection::Bid,
+            ),
+            expected_output: Uint256::zero(),
+            expected_tick_etas: vec![(-1500000, Decimal256::zero())],
+            expected_tick_pointers: vec![(OrderDirection::Bid, -1500000)],
+            expected_error: Some(ContractError::TickBoundCrossesCurrent {
+                tick_bound: MAX_TICK,
+                current_tick: -1500000,
+            }),
         },
         RunMarketOrderTestCase {
             name: "insufficient liquidity on orderbook",
@@ -886,6 +940,60 @@ fn test_run_market_order() {
             expected_tick_pointers: vec![],
             expected_error: Some(ContractError::InsufficientLiquidity {}),
         },
+        RunMarketOrderTestCase {
+            name: "bid at MIN_TICK with input below price precision",
+            placed_order: MarketOrder::new(
+                Uint128::one(),
+                OrderDirection::Bid,
+                Addr::unchecked(DEFAULT_SENDER),
+            ),
+            tick_bound: MAX_TICK,
+            // Orders we expect to not get touched
+            orders: generate_limit_orders(&[MIN_TICK], 1, Uint128::new(10), OrderDirection::Ask),
+            expected_output: Uint256::zero(),
+            expected_tick_etas: vec![(MIN_TICK, Decimal256::zero())],
+            expected_tick_pointers: vec![(OrderDirection::Ask, MIN_TICK)],
+            // 1 * 0.000000000001 rounds down to zero, so the order cannot be filled
+            expected_error: Some(ContractError::PriceUnderflow { tick_id: MIN_TICK }),
+        },
+        RunMarketOrderTestCase {
+            name: "bid at MIN_TICK with smallest input producing output",
+            placed_order: MarketOrder::new(
+                Uint128::new(1_000_000_000_000),
+                OrderDirection::Bid,
+                Addr::unchecked(DEFAULT_SENDER),
+            ),
+            tick_bound: MAX_TICK,
+            orders: generate_limit_orders(&[MIN_TICK], 1, Uint128::new(10), OrderDirection::Ask),
+            // 1,000,000,000,000 * 0.000000000001 = 1 unit of output
+            expected_output: Uint256::from_u128(1),
+            expected_tick_etas: vec![(MIN_TICK, decimal256_from_u128(Uint128::one()))],
+            expected_tick_pointers: vec![(OrderDirection::Ask, MIN_TICK)],
+            expected_error: None,
+        },
+        RunMarketOrderTestCase {
+            name: "ask at MIN_TICK with smallest input",
+            placed_order: MarketOrder::new(
+                Uint128::one(),
+                OrderDirection::Ask,
+                Addr::unchecked(DEFAULT_SENDER),
+            ),
+            tick_bound: MIN_TICK,
+            orders: generate_limit_orders(
+                &[MIN_TICK],
+                1,
+                Uint128::new(1_000_000_000_000),
+                OrderDirection::Bid,
+            ),
+            // 1 / 0.000000000001 = 1,000,000,000,000 units of output
+            expected_output: Uint256::from_u128(1_000_000_000_000),
+            expected_tick_etas: vec![(
+                MIN_TICK,
+                decimal256_from_u128(Uint128::new(1_000_000_000_000)),
+            )],
+            expected_tick_pointers: vec![(OrderDirection::Bid, MIN_TICK)],
+            expected_error: None,
+        },
     ];
 
     for test in test_cases {
@@ -900,6 +1008,8 @@ fn test_run_market_order() {
             deps.as_mut(),
             QUOTE_DENOM.to_string(),
             BASE_DENOM.to_string(),
+            None,
+            None,
         )
         .unwrap();
 
@@ -920,7 +1030,7 @@ fn test_run_market_order() {
         // --- System under test ---
 
         let mut market_order = test.placed_order.clone();
-        let response = run_market_order(deps.as_mut().storage, env.contract.address.clone(), &mut market_order, test.tick_bound);
+        let response = run_market_order(deps.as_mut().storage, &env, &mut market_order, test.tick_bound, None);
 
         // --- Assertions ---
 
@@ -939,6 +1049,37 @@ fn test_run_market_order() {
         // Assert no error
         let response = response.unwrap();
 
+        // The average fill price should lie between the prices of the first and last filled ticks.
+        // Each filled tick may round the input taken up by one unit in favor of the orderbook,
+        // which can worsen the realized price slightly beyond the tick prices.
+        let filled_tick_prices: Vec<Decimal256> = test
+            .expected_tick_etas
+            .iter()
+            .filter(|(_, etas)| !etas.is_zero())
+            .map(|(tick_id, _)| tick_to_price(*tick_id).unwrap())
+            .collect();
+        match response.1 {
+            Some(avg_price) => {
+                let min_price = *filled_tick_prices.iter().min().unwrap();
+                let max_price = *filled_tick_prices.iter().max().unwrap();
+                let output = Decimal256::from_ratio(response.0, 1u128);
+                let rounding = Decimal256::from_ratio(filled_tick_prices.len() as u128, 1u128);
+                let (lower_bound, upper_bound) = match test.placed_order.order_direction {
+                    OrderDirection::Bid => (output / (output / min_price + rounding), max_price),
+                    OrderDirection::Ask => (min_price, max_price + rounding / output),
+                };
+                assert!(
+                    lower_bound <= avg_price && avg_price <= upper_bound,
+                    "{}: average price {} not within [{}, {}]",
+                    format_test_name(test.name),
+                    avg_price,
+                    lower_bound,
+                    upper_bound
+                );
+            }
+            None => assert!(filled_tick_prices.is_empty(), "{}", format_test_name(test.name)),
+        }
+
         // Assert expected tick ETAS values are correct.
         // This should run regardless of whether we error or not.
         for (tick_id, expected_etas) in test.expected_tick_etas {
@@ -999,7 +1140,7 @@ fn test_run_market_order() {
             "{}",
             format_test_name(test.name)
         );
-        assert_eq!(expected_msg, response.1, "{}", format_test_name(test.name));
+        assert_eq!(expected_msg, response.2, "{}", format_test_name(test.name));
     }
 }
 
@@ -1546,6 +1687,8 @@ fn test_run_market_order_moving_tick() {
             deps.as_mut(),
             QUOTE_DENOM.to_string(),
             BASE_DENOM.to_string(),
+            None,
+            None,
         )
         .unwrap();
 
@@ -1576,6 +1719,271 @@ fn test_run_market_order_moving_tick() {
     }
 }
 
+struct OverlappingTickTestCase {
+    name: &'static str,
+    market_order: MarketOrder,
+    expected_output: Uint256,
+    // (bid, ask) liquidity remaining on the overlapping tick
+    expected_liquidity: (u128, u128),
+    // (bid_tick, ask_tick)
+    expected_tick_pointers: (i64, i64),
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_run_market_order_overlapping_tick() {
+    let env = mock_env();
+    let info = mock_info(DEFAULT_SENDER, &[]);
+    let overlapping_tick = 0;
+    let test_cases = vec![
+        OverlappingTickTestCase {
+            name: "market bid fills only the asks on the tick",
+            market_order: MarketOrder::new(
+                Uint128::from(10u128),
+                OrderDirection::Bid,
+                Addr::unchecked("buyer"),
+            ),
+            expected_output: Uint256::from(10u128),
+            expected_liquidity: (10, 0),
+            expected_tick_pointers: (overlapping_tick, overlapping_tick),
+            expected_error: None,
+        },
+        OverlappingTickTestCase {
+            name: "market ask fills only the bids on the tick",
+            market_order: MarketOrder::new(
+                Uint128::from(10u128),
+                OrderDirection::Ask,
+                Addr::unchecked("seller"),
+            ),
+            expected_output: Uint256::from(10u128),
+            expected_liquidity: (0, 10),
+            expected_tick_pointers: (overlapping_tick, overlapping_tick),
+            expected_error: None,
+        },
+        OverlappingTickTestCase {
+            name: "market bid does not count the bids on the tick",
+            market_order: MarketOrder::new(
+                Uint128::from(15u128),
+                OrderDirection::Bid,
+                Addr::unchecked("buyer"),
+            ),
+            expected_output: Uint256::zero(),
+            expected_liquidity: (10, 10),
+            expected_tick_pointers: (overlapping_tick, overlapping_tick),
+            expected_error: Some(ContractError::InsufficientLiquidity),
+        },
+        OverlappingTickTestCase {
+            name: "market ask does not count the asks on the tick",
+            market_order: MarketOrder::new(
+                Uint128::from(15u128),
+                OrderDirection::Ask,
+                Addr::unchecked("seller"),
+            ),
+            expected_output: Uint256::zero(),
+            expected_liquidity: (10, 10),
+            expected_tick_pointers: (overlapping_tick, overlapping_tick),
+            expected_error: Some(ContractError::InsufficientLiquidity),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        // A bid and an ask resting on the same tick
+        for direction in [OrderDirection::Ask, OrderDirection::Bid] {
+            OrderOperation::PlaceLimit(LimitOrder::new(
+                overlapping_tick,
+                0,
+                direction,
+                Addr::unchecked(info.sender.as_str()),
+                Uint128::from(10u128),
+                Decimal256::zero(),
+                None,
+            ))
+            .run(deps.as_mut(), env.clone(), info.clone())
+            .unwrap();
+        }
+
+        // -- System under test --
+        let mut market_order = test.market_order.clone();
+        let tick_bound = match market_order.order_direction {
+            OrderDirection::Bid => MAX_TICK,
+            OrderDirection::Ask => MIN_TICK,
+        };
+        let res = run_market_order(
+            deps.as_mut().storage,
+            &env,
+            &mut market_order,
+            tick_bound,
+            None,
+        );
+
+        // -- Post Test Assertions --
+        if let Some(expected_error) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            );
+        } else {
+            assert_eq!(
+                res.unwrap().0,
+                test.expected_output,
+                "{}",
+                format_test_name(test.name)
+            );
+        }
+
+        let tick_state = TICK_STATE
+            .load(deps.as_ref().storage, overlapping_tick)
+            .unwrap();
+        assert_eq!(
+            (
+                tick_state
+                    .get_values(OrderDirection::Bid)
+                    .total_amount_of_liquidity,
+                tick_state
+                    .get_values(OrderDirection::Ask)
+                    .total_amount_of_liquidity,
+            ),
+            (
+                decimal256_from_u128(test.expected_liquidity.0),
+                decimal256_from_u128(test.expected_liquidity.1),
+            ),
+            "{}",
+            format_test_name(test.name)
+        );
+
+        let orderbook = ORDERBOOK.load(deps.as_ref().storage).unwrap();
+        assert_eq!(
+            (orderbook.next_bid_tick, orderbook.next_ask_tick),
+            test.expected_tick_pointers,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct MinPriceFillTestCase {
+    name: &'static str,
+    resting_direction: OrderDirection,
+    resting_quantity: Uint128,
+    market_quantity: Uint128,
+    expected_output: Uint256,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_run_market_order_min_price() {
+    let env = mock_env();
+    let info = mock_info(DEFAULT_SENDER, &[]);
+    // At MIN_TICK the price is 10^-12, the smallest representable price
+    let test_cases = vec![
+        MinPriceFillTestCase {
+            name: "market ask against bid at min price",
+            resting_direction: OrderDirection::Bid,
+            resting_quantity: Uint128::from(1_000_000_000_000u128),
+            market_quantity: Uint128::from(1u128),
+            expected_output: Uint256::from(1_000_000_000_000u128),
+            expected_error: None,
+        },
+        MinPriceFillTestCase {
+            name: "market bid against ask at min price",
+            resting_direction: OrderDirection::Ask,
+            resting_quantity: Uint128::from(1u128),
+            market_quantity: Uint128::from(1_000_000_000_000u128),
+            expected_output: Uint256::one(),
+            expected_error: None,
+        },
+        MinPriceFillTestCase {
+            name: "market bid too small to fill at min price",
+            resting_direction: OrderDirection::Ask,
+            resting_quantity: Uint128::from(1u128),
+            market_quantity: Uint128::from(999_999_999_999u128),
+            expected_output: Uint256::zero(),
+            expected_error: Some(ContractError::PriceUnderflow { tick_id: MIN_TICK }),
+        },
+        MinPriceFillTestCase {
+            name: "market ask output exceeding liquidity at min price",
+            resting_direction: OrderDirection::Bid,
+            resting_quantity: Uint128::MAX,
+            market_quantity: Uint128::MAX,
+            expected_output: Uint256::zero(),
+            expected_error: Some(ContractError::InsufficientLiquidity),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            MIN_TICK,
+            0,
+            test.resting_direction,
+            Addr::unchecked(info.sender.as_str()),
+            test.resting_quantity,
+            Decimal256::zero(),
+            None,
+        ))
+        .run(deps.as_mut(), env.clone(), info.clone())
+        .unwrap();
+
+        // -- System under test --
+        let mut market_order = MarketOrder::new(
+            test.market_quantity,
+            test.resting_direction.opposite(),
+            Addr::unchecked("taker"),
+        );
+        let tick_bound = match market_order.order_direction {
+            OrderDirection::Bid => MAX_TICK,
+            OrderDirection::Ask => MIN_TICK,
+        };
+        let res = run_market_order(
+            deps.as_mut().storage,
+            &env,
+            &mut market_order,
+            tick_bound,
+            None,
+        );
+
+        // -- Post Test Assertions --
+        if let Some(expected_error) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            );
+        } else {
+            assert_eq!(
+                res.unwrap().0,
+                test.expected_output,
+                "{}",
+                format_test_name(test.name)
+            );
+        }
+    }
+}
+
 struct ClaimOrderTestCase {
     name: &'static str,
     operations: Vec<OrderOperation>,
@@ -2626,6 +3034,7 @@ fn test_claim_order() {
             expected_error: Some(ContractError::OrderNotFound {
                 tick_id: valid_tick_id,
                 order_id: 1,
+                reason: Some(OrderNotFoundReason::NeverPlaced),
             }),
         },
         ClaimOrderTestCase {
@@ -2659,6 +3068,50 @@ fn test_claim_order() {
             expected_error: Some(ContractError::OrderNotFound {
                 tick_id: valid_tick_id,
                 order_id: 0,
+                reason: Some(OrderNotFoundReason::Cancelled),
+            }),
+        },
+        ClaimOrderTestCase {
+            name: "invalid order id (placed on another tick)",
+            sender: sender.clone(),
+            operations: vec![
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    valid_tick_id,
+                    0,
+                    OrderDirection::Ask,
+                    sender.clone(),
+                    Uint128::from(10u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    valid_tick_id + 1,
+                    1,
+                    OrderDirection::Ask,
+                    sender.clone(),
+                    Uint128::from(10u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+            ],
+            order_id: 1,
+
+            tick_id: valid_tick_id,
+            expected_bank_msg: Some(SubMsg::reply_on_error(
+                MsgSend256 {
+                    from_address: "cosmos2contract".to_string(),
+                    to_address: sender.to_string(),
+                    amount: vec![coin_u256(Uint256::from(5u128), QUOTE_DENOM)],
+                },
+                REPLY_ID_CLAIM,
+            )),
+            expected_bounty_msg: None,
+            expected_order_state: None,
+            // The ID exists elsewhere, so there is no telling whether it was mistyped
+            expected_error: Some(ContractError::OrderNotFound {
+                tick_id: valid_tick_id,
+                order_id: 1,
+                reason: None,
             }),
         },
         ClaimOrderTestCase {
@@ -2811,6 +3264,8 @@ fn test_claim_order() {
             deps.as_mut(),
             QUOTE_DENOM.to_string(),
             BASE_DENOM.to_string(),
+            None,
+            None,
         )
         .unwrap();
 
@@ -2842,6 +3297,7 @@ fn test_claim_order() {
             info,
             test.tick_id,
             test.order_id,
+            false,
         );
 
         if let Some(err) = test.expected_error {
@@ -2885,7 +3341,7 @@ fn test_claim_order() {
         // Order in state may have been removed
         assert_eq!(
             maybe_order,
-            test.expected_order_state.map(|o| o.with_placed_at(env.block.time)),
+            test.expected_order_state.map(|o| o.with_placed_at(&env.block)),
             "{}",
             format_test_name(test.name)
         );
@@ -3530,7 +3986,9 @@ fn test_claim_order_moving_tick() {
             deps.as_mut(),
             QUOTE_DENOM.to_string(),
             BASE_DENOM.to_string(),
-        )
+            None,
+            None,
+        )
         .unwrap();
 
         // Run setup operations
@@ -3547,6 +4005,7 @@ fn test_claim_order_moving_tick() {
             info,
             test.tick_id,
             test.order_id,
+            false,
         );
 
         if let Some(err) = test.expected_error {
@@ -3571,7 +4030,7 @@ fn test_claim_order_moving_tick() {
         // Order in state may have been removed
         assert_eq!(
             maybe_order,
-            test.expected_order_state.map(|o| o.with_placed_at(env.block.time)),
+            test.expected_order_state.map(|o| o.with_placed_at(&env.block)),
             "{}",
             format_test_name(test.name)
         );
@@ -3735,6 +4194,134 @@ fn test_batch_claim_order() {
             )]),
             expected_error: None,
         },
+        BatchClaimOrderTestCase {
+            name: "Batch claim coalesces sends to the same recipient",
+            operations: vec![
+                // Place two bids for the same owner
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    0,
+                    0,
+                    OrderDirection::Bid,
+                    owner.clone(),
+                    Uint128::from(100u128),
+                    Decimal256::zero(),
+                    Some(Decimal256::percent(1)),
+                )),
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    0,
+                    1,
+                    OrderDirection::Bid,
+                    owner.clone(),
+                    Uint128::from(100u128),
+                    Decimal256::zero(),
+                    Some(Decimal256::percent(1)),
+                )),
+                // Fully fill both orders
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(200u128),
+                    OrderDirection::Ask,
+                    owner.clone(),
+                )),
+            ],
+            // (tick_id, order_id) pairs
+            orders: vec![(0, 0), (0, 1)],
+            expected_messages: vec![
+                // Both principals are sent in a single message
+                SubMsg::reply_on_error(
+                    MsgSend256 {
+                        from_address: "cosmos2contract".to_string(),
+                        to_address: owner.to_string(),
+                        amount: vec![coin_u256(99u128 + 99u128, BASE_DENOM)],
+                    },
+                    REPLY_ID_CLAIM,
+                ),
+                // Both bounties are sent in a single message
+                SubMsg::reply_on_error(
+                    MsgSend256 {
+                        from_address: "cosmos2contract".to_string(),
+                        to_address: sender.to_string(),
+                        amount: vec![coin_u256(1u128 + 1u128, BASE_DENOM)],
+                    },
+                    REPLY_ID_CLAIM_BOUNTY,
+                ),
+            ],
+            // Orders are fully filled & claimed, so they should be removed from state
+            expected_order_states: None,
+            expected_error: None,
+        },
+        BatchClaimOrderTestCase {
+            name: "Batch claim routes principal to each owner and bounties to the claimer",
+            operations: vec![
+                // Place three bids across two owners
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    0,
+                    0,
+                    OrderDirection::Bid,
+                    owner.clone(),
+                    Uint128::from(100u128),
+                    Decimal256::zero(),
+                    Some(Decimal256::percent(1)),
+                )),
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    0,
+                    1,
+                    OrderDirection::Bid,
+                    Addr::unchecked("owner2"),
+                    Uint128::from(200u128),
+                    Decimal256::zero(),
+                    Some(Decimal256::percent(1)),
+                )),
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    0,
+                    2,
+                    OrderDirection::Bid,
+                    owner.clone(),
+                    Uint128::from(100u128),
+                    Decimal256::zero(),
+                    Some(Decimal256::percent(1)),
+                )),
+                // Fully fill all orders
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(400u128),
+                    OrderDirection::Ask,
+                    owner.clone(),
+                )),
+            ],
+            // (tick_id, order_id) pairs
+            orders: vec![(0, 0), (0, 1), (0, 2)],
+            expected_messages: vec![
+                // Principal for both of the first owner's orders
+                SubMsg::reply_on_error(
+                    MsgSend256 {
+                        from_address: "cosmos2contract".to_string(),
+                        to_address: owner.to_string(),
+                        amount: vec![coin_u256(99u128 + 99u128, BASE_DENOM)],
+                    },
+                    REPLY_ID_CLAIM,
+                ),
+                // All three bounties go to the claimer regardless of order owner
+                SubMsg::reply_on_error(
+                    MsgSend256 {
+                        from_address: "cosmos2contract".to_string(),
+                        to_address: sender.to_string(),
+                        amount: vec![coin_u256(1u128 + 2u128 + 1u128, BASE_DENOM)],
+                    },
+                    REPLY_ID_CLAIM_BOUNTY,
+                ),
+                // Principal for the second owner's order
+                SubMsg::reply_on_error(
+                    MsgSend256 {
+                        from_address: "cosmos2contract".to_string(),
+                        to_address: "owner2".to_string(),
+                        amount: vec![coin_u256(198u128, BASE_DENOM)],
+                    },
+                    REPLY_ID_CLAIM,
+                ),
+            ],
+            // Orders are fully filled & claimed, so they should be removed from state
+            expected_order_states: None,
+            expected_error: None,
+        },
     ];
 
     for test in test_cases {
@@ -3746,6 +4333,8 @@ fn test_batch_claim_order() {
             deps.as_mut(),
             QUOTE_DENOM.to_string(),
             BASE_DENOM.to_string(),
+            None,
+            None,
         )
         .unwrap();
 
@@ -3760,7 +4349,7 @@ fn test_batch_claim_order() {
         let info = mock_info(sender.as_str(), &[]);
 
         // Batch claim orders
-        let res = batch_claim_limits(deps.as_mut(), info.clone(), env.clone(), test.orders.clone());
+        let res = batch_claim_limits(deps.as_mut(), info.clone(), env.clone(), test.orders.clone(), false);
 
         if let Some(err) = test.expected_error {
             assert_eq!(res, Err(err), "{}", format_test_name(test.name));
@@ -3806,7 +4395,7 @@ fn test_batch_claim_order() {
                     .find(|order| order.tick_id == *tick_id && order.order_id == *order_id)
             });
             assert_eq!(
-                expected_order_state.cloned().map(|o| o.with_placed_at(env.block.time)),
+                expected_order_state.cloned().map(|o| o.with_placed_at(&env.block)),
                 maybe_order,
                 "{} for order_id {} and tick_id {}",
                 format_test_name(test.name),
@@ -4010,6 +4599,8 @@ fn test_directional_liquidity() {
             deps.as_mut(),
             QUOTE_DENOM.to_string(),
             BASE_DENOM.to_string(),
+            None,
+            None,
         )
         .unwrap();
 
@@ -4160,6 +4751,9 @@ fn test_maker_fee() {
 
         // Save the placed order
         orders().save(deps.as_mut().storage, &(test.placed_order.tick_id, test.placed_order.order_id), &test.placed_order).unwrap();
+        increment_tick_order_count(deps.as_mut().storage, test.placed_order.tick_id, test.placed_order.order_direction).unwrap();
+        increment_order_count(deps.as_mut().storage, &test.placed_order.owner).unwrap();
+        add_queued_liquidity(deps.as_mut().storage, &test.placed_order, test.placed_order.quantity).unwrap();
 
         // Save the expected maker fee
         if let Some(maker_fee) = test.maker_fee {
@@ -4186,11 +4780,13 @@ fn test_maker_fee() {
 
         // -- System Under Test --
         let result = claim_order(
-            deps.as_mut().storage,
-            env.contract.address.clone(),
+            &mut deps.storage,
+            &deps.api,
+            &env,
             sender.clone(),
             test.placed_order.tick_id,
             test.placed_order.order_id,
+            false,
         );
 
         // -- Post test assertions --
@@ -4226,6 +4822,120 @@ fn test_maker_fee() {
 
 }
 
+struct MakerFeeDiscountTestCase {
+    name: &'static str,
+    discount: Option<MakerFeeDiscount>,
+    // Blocks between placing and claiming the order
+    blocks_rested: u64,
+    expected_claim: u128,
+    expected_maker_fee: Option<u128>,
+}
+
+#[test]
+fn test_maker_fee_discount() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let maker_fee_recipient = Addr::unchecked("maker");
+    let half_off = MakerFeeDiscount {
+        min_blocks: 100,
+        discount: Decimal256::percent(50),
+    };
+    let test_cases = vec![
+        MakerFeeDiscountTestCase {
+            name: "no discount configured",
+            discount: None,
+            blocks_rested: 1000,
+            expected_claim: 98,
+            expected_maker_fee: Some(2),
+        },
+        MakerFeeDiscountTestCase {
+            name: "claimed before the threshold",
+            discount: Some(half_off.clone()),
+            blocks_rested: 99,
+            expected_claim: 98,
+            expected_maker_fee: Some(2),
+        },
+        MakerFeeDiscountTestCase {
+            name: "claimed at the threshold",
+            discount: Some(half_off.clone()),
+            blocks_rested: 100,
+            expected_claim: 99,
+            expected_maker_fee: Some(1),
+        },
+        MakerFeeDiscountTestCase {
+            name: "claimed after the threshold",
+            discount: Some(half_off),
+            blocks_rested: 1000,
+            expected_claim: 99,
+            expected_maker_fee: Some(1),
+        },
+        MakerFeeDiscountTestCase {
+            name: "fee fully waived",
+            discount: Some(MakerFeeDiscount {
+                min_blocks: 100,
+                discount: Decimal256::one(),
+            }),
+            blocks_rested: 100,
+            expected_claim: 100,
+            expected_maker_fee: None,
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        create_orderbook(deps.as_mut(), QUOTE_DENOM.to_string(), BASE_DENOM.to_string(), None, None).unwrap();
+        MAKER_FEE.save(deps.as_mut().storage, &Decimal256::percent(2)).unwrap();
+        MAKER_FEE_RECIPIENT.save(deps.as_mut().storage, &maker_fee_recipient).unwrap();
+        set_maker_fee_discount(deps.as_mut().storage, test.discount).unwrap();
+
+        place_limit(
+            &mut deps.as_mut(),
+            env.clone(),
+            mock_info(sender.as_str(), &[coin(100, QUOTE_DENOM)]),
+            0,
+            OrderDirection::Bid,
+            Uint128::from(100u128),
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        // The order is filled in the same block it is placed
+        OrderOperation::RunMarket(MarketOrder::new(Uint128::from(100u128), OrderDirection::Ask, Addr::unchecked("seller")))
+            .run(deps.as_mut(), env.clone(), mock_info(sender.as_str(), &[]))
+            .unwrap();
+
+        // -- System Under Test --
+        let mut claim_env = env.clone();
+        claim_env.block.height += test.blocks_rested;
+        let (_, msgs, _) = claim_order(&mut deps.storage, &deps.api, &claim_env, sender.clone(), 0, 0, false).unwrap();
+
+        // -- Post test assertions --
+        let mut expected_msgs = vec![SubMsg::reply_on_error(
+            MsgSend256 {
+                from_address: env.contract.address.to_string(),
+                to_address: sender.to_string(),
+                amount: vec![coin_u256(test.expected_claim, BASE_DENOM)],
+            },
+            REPLY_ID_CLAIM,
+        )];
+        if let Some(maker_fee) = test.expected_maker_fee {
+            expected_msgs.push(SubMsg::reply_on_error(
+                MsgSend256 {
+                    from_address: env.contract.address.to_string(),
+                    to_address: maker_fee_recipient.to_string(),
+                    amount: vec![coin_u256(maker_fee, BASE_DENOM)],
+                },
+                REPLY_ID_MAKER_FEE,
+            ));
+        }
+        assert_eq!(msgs, expected_msgs, "{}", format_test_name(test.name));
+    }
+}
+
 
 #[test]
 fn test_cancelled_orders() {
@@ -4234,7 +4944,7 @@ fn test_cancelled_orders() {
     let env = mock_env();
     let info = mock_info(sender.as_str(), &[]);
 
-    create_orderbook(deps.as_mut(), QUOTE_DENOM.to_string(), BASE_DENOM.to_string()).unwrap();
+    create_orderbook(deps.as_mut(), QUOTE_DENOM.to_string(), BASE_DENOM.to_string(), None, None).unwrap();
 
     // Place 10 orders and cancel every order that is not evenly divisible by 3
     // This leaves orders 0, 3, 6 and 9 uncancelled
@@ -4299,3 +5009,4750 @@ fn test_cancelled_orders() {
     assert_eq!(tick_state.get_values(OrderDirection::Bid).total_amount_of_liquidity, Decimal256::zero());
     assert_eq!(tick_state.get_values(OrderDirection::Bid).cumulative_total_value, Decimal256::from_ratio(22u128, 1u128));
 }
+
+#[test]
+fn test_run_market_order_batching_differential() {
+    let seed: u64 = 1234567890;
+    let num_iterations = 10;
+    let num_rounds = 20;
+    let mut rng = StdRng::seed_from_u64(seed);
+    let env = mock_env();
+    let owner = Addr::unchecked(DEFAULT_OWNER);
+
+    for iteration in 0..num_iterations {
+        let mut deps = mock_dependencies_custom();
+        create_orderbook(deps.as_mut(), QUOTE_DENOM.to_string(), BASE_DENOM.to_string(), None, None).unwrap();
+        let mut placed_orders: Vec<(i64, u64)> = vec![];
+
+        for round in 0..num_rounds {
+            let test_name = format!("Differential run {} round {}", iteration + 1, round + 1);
+
+            // Place a random set of orders, mixing clustered ticks near zero with sparse ticks far apart
+            for _ in 0..rng.gen_range(1..=10) {
+                let direction = if rng.gen_bool(0.5) { OrderDirection::Bid } else { OrderDirection::Ask };
+                let distance = if rng.gen_bool(0.5) { rng.gen_range(0..100) } else { rng.gen_range(0..5_000_000) };
+                let tick_id = match direction {
+                    OrderDirection::Bid => -distance,
+                    OrderDirection::Ask => distance,
+                };
+                let quantity = Uint128::from(rng.gen_range(1..=1_000u128));
+                let order_id = ORDER_ID.load(deps.as_ref().storage).unwrap_or_default();
+                let info = mock_info(owner.as_str(), &[coin(quantity.u128(), match direction {
+                    OrderDirection::Bid => QUOTE_DENOM,
+                    OrderDirection::Ask => BASE_DENOM,
+                })]);
+                place_limit(&mut deps.as_mut(), env.clone(), info, tick_id, direction, quantity, None, None, None, None, None).unwrap();
+                placed_orders.push((tick_id, order_id));
+            }
+
+            // Randomly cancel some resting orders, leaving empty ticks behind
+            for (tick_id, order_id) in placed_orders.clone() {
+                if rng.gen_bool(0.2) {
+                    let info = mock_info(owner.as_str(), &[]);
+                    // Orders may have been filled, in which case the cancel fails
+                    let _ = cancel_limit(deps.as_mut(), env.clone(), info, tick_id, order_id);
+                }
+            }
+
+            // Compare the batched and unbatched fills for a random market order in each direction
+            for direction in [OrderDirection::Bid, OrderDirection::Ask] {
+                let quantity = Uint128::from(rng.gen_range(1..=5_000u128));
+                let tick_bound = if rng.gen_bool(0.5) {
+                    match direction {
+                        OrderDirection::Bid => MAX_TICK,
+                        OrderDirection::Ask => MIN_TICK,
+                    }
+                } else {
+                    rng.gen_range(-5_000_000..5_000_000)
+                };
+
+                let mut batched_order = MarketOrder::new(quantity, direction, owner.clone());
+                let batched = run_market_order_internal(deps.as_ref().storage, &mut batched_order, tick_bound, env.block.height);
+                let mut unbatched_order = MarketOrder::new(quantity, direction, owner.clone());
+                let unbatched = run_market_order_internal_unbatched(deps.as_ref().storage, &mut unbatched_order, tick_bound, env.block.height);
+
+                assert_eq!(batched_order, unbatched_order, "{}", format_test_name(&test_name));
+                match (batched, unbatched) {
+                    (Ok(batched), Ok(unbatched)) => {
+                        assert_eq!(batched.output, unbatched.output, "{}", format_test_name(&test_name));
+                        assert_eq!(batched.tick_fills, unbatched.tick_fills, "{}", format_test_name(&test_name));
+                        assert_eq!(batched.updated_orderbook, unbatched.updated_orderbook, "{}", format_test_name(&test_name));
+
+                        // Empty ticks that are visited are written back unchanged, so only compare updates that change state
+                        let changed_tick_updates = |tick_updates: Vec<(i64, TickState)>| -> Vec<(i64, TickState)> {
+                            tick_updates
+                                .into_iter()
+                                .filter(|(tick_id, tick_state)| &TICK_STATE.load(deps.as_ref().storage, *tick_id).unwrap() != tick_state)
+                                .collect()
+                        };
+                        assert_eq!(changed_tick_updates(batched.tick_updates), changed_tick_updates(unbatched.tick_updates), "{}", format_test_name(&test_name));
+                    }
+                    (batched, unbatched) => assert_eq!(batched.err(), unbatched.err(), "{}", format_test_name(&test_name)),
+                }
+            }
+
+            // Run a partial market order against state so that later rounds operate on partially filled ticks
+            let direction = if rng.gen_bool(0.5) { OrderDirection::Bid } else { OrderDirection::Ask };
+            let mut order = MarketOrder::new(Uint128::from(rng.gen_range(1..=2_000u128)), direction, owner.clone());
+            let _ = run_market_order(deps.as_mut().storage, &env, &mut order, match direction {
+                OrderDirection::Bid => 5_000_000,
+                OrderDirection::Ask => -5_000_000,
+            }, None);
+
+            // Every batching layer bucket should hold exactly the liquidity of the ticks it covers
+            for direction in [OrderDirection::Bid, OrderDirection::Ask] {
+                for layer in 0..BATCHING_LAYER_COUNT {
+                    let buckets: Vec<i64> = get_layer_buckets(deps.as_ref().storage, layer, direction, MIN_TICK, MAX_TICK, Order::Ascending)
+                        .map(|bucket| bucket.unwrap())
+                        .collect();
+                    for bucket in buckets.iter() {
+                        let (min_tick, max_tick) = layer_bucket_range(layer, *bucket);
+                        let expected_liquidity = TICK_STATE
+                            .range(deps.as_ref().storage, Some(Bound::inclusive(min_tick)), Some(Bound::inclusive(max_tick)), Order::Ascending)
+                            .map(|tick| tick.unwrap().1.get_values(direction).total_amount_of_liquidity)
+                            .fold(Decimal256::zero(), |acc, liquidity| acc + liquidity);
+                        let layer_liquidity = LAYER_LIQUIDITY.load(deps.as_ref().storage, (layer, &direction.to_string(), *bucket)).unwrap();
+                        assert_eq!(layer_liquidity, expected_liquidity, "{}", format_test_name(&test_name));
+                    }
+
+                    // Ticks holding liquidity must always be covered by a bucket
+                    for tick in TICK_STATE.range(deps.as_ref().storage, None, None, Order::Ascending) {
+                        let (tick_id, tick_state) = tick.unwrap();
+                        if !tick_state.get_values(direction).total_amount_of_liquidity.is_zero() {
+                            assert!(buckets.contains(&layer_bucket_index(layer, tick_id)), "{}", format_test_name(&test_name));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Subtracts the amounts sent by the given bank messages from the tracked contract balances.
+fn apply_outgoing_sends(balances: &mut HashMap<String, i128>, msgs: &[SubMsg]) {
+    for msg in msgs {
+        let coins: Vec<(String, u128)> = match &msg.msg {
+            CosmosMsg::Bank(BankMsg::Send { amount, .. }) => amount.iter().map(|c| (c.denom.clone(), c.amount.u128())).collect(),
+            CosmosMsg::Stargate { value, .. } => MsgSend::try_from(value.clone()).unwrap().amount.into_iter().map(|c| (c.denom, c.amount.parse().unwrap())).collect(),
+            _ => panic!("unexpected message: {:?}", msg),
+        };
+        for (denom, amount) in coins {
+            *balances.entry(denom).or_default() -= amount as i128;
+        }
+    }
+}
+
+#[test]
+fn test_rounding_never_loses_value() {
+    let seed: u64 = 1234567890;
+    let num_iterations = 20;
+    let env = mock_env();
+    let claimer = Addr::unchecked(DEFAULT_SENDER);
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    for iteration in 0..num_iterations {
+        let test_name = format!("Rounding run {}", iteration + 1);
+        let mut deps = mock_dependencies_custom();
+        create_orderbook(deps.as_mut(), QUOTE_DENOM.to_string(), BASE_DENOM.to_string(), None, None).unwrap();
+        set_maker_fee(deps.as_mut().storage, Decimal256::permille(rng.gen_range(0..=50))).unwrap();
+
+        // Expected bank balance of the contract, tracked from deposits and emitted sends
+        let mut balances: HashMap<String, i128> = HashMap::new();
+        let mut placed_orders: Vec<(i64, u64)> = vec![];
+
+        for _ in 0..rng.gen_range(1..=100) {
+            match rng.gen_range(0..4) {
+                // Place an order on a tick with an awkward price and a random claim bounty
+                0 => {
+                    let direction = if rng.gen_bool(0.5) { OrderDirection::Bid } else { OrderDirection::Ask };
+                    // Cluster most orders on a few ticks so fills are split across several orders
+                    let distance = if rng.gen_bool(0.8) { [0, 1, 7, 2000, 4010000, 17765433][rng.gen_range(0..6)] } else { rng.gen_range(0..20_000_000) };
+                    let tick_id = match direction {
+                        OrderDirection::Bid => -distance,
+                        OrderDirection::Ask => distance,
+                    };
+                    let quantity = rng.gen_range(1..=1_000u128);
+                    let claim_bounty = if rng.gen_bool(0.5) { Some(Decimal256::permille(rng.gen_range(0..=10))) } else { None };
+                    let denom = match direction {
+                        OrderDirection::Bid => QUOTE_DENOM,
+                        OrderDirection::Ask => BASE_DENOM,
+                    };
+                    let order_id = ORDER_ID.load(deps.as_ref().storage).unwrap_or_default();
+                    let info = mock_info(DEFAULT_OWNER, &[coin(quantity, denom)]);
+                    place_limit(&mut deps.as_mut(), env.clone(), info, tick_id, direction, Uint128::from(quantity), claim_bounty, None, None, None, None).unwrap();
+                    *balances.entry(denom.to_string()).or_default() += quantity as i128;
+                    placed_orders.push((tick_id, order_id));
+                }
+                // Run a market order, crediting only the input it consumed
+                1 => {
+                    let direction = if rng.gen_bool(0.5) { OrderDirection::Bid } else { OrderDirection::Ask };
+                    let (tick_bound, input_denom) = match direction {
+                        OrderDirection::Bid => (MAX_TICK, QUOTE_DENOM),
+                        OrderDirection::Ask => (MIN_TICK, BASE_DENOM),
+                    };
+                    let quantity = Uint128::from(rng.gen_range(1..=200u128));
+                    let mut order = MarketOrder::new(quantity, direction, claimer.clone());
+                    if let Ok((_, _, output_msg, ..)) = run_market_order(deps.as_mut().storage, &env, &mut order, tick_bound, None) {
+                        *balances.entry(input_denom.to_string()).or_default() += quantity.checked_sub(order.quantity).unwrap().u128() as i128;
+                        apply_outgoing_sends(&mut balances, &[SubMsg::new(output_msg)]);
+                    }
+                }
+                // Cancel a random order, which may already be filled
+                2 => {
+                    if let Some((tick_id, order_id)) = placed_orders.get(rng.gen_range(0..placed_orders.len().max(1))) {
+                        let info = mock_info(DEFAULT_OWNER, &[]);
+                        if let Ok(res) = cancel_limit(deps.as_mut(), env.clone(), info, *tick_id, *order_id) {
+                            apply_outgoing_sends(&mut balances, &res.messages);
+                        }
+                    }
+                }
+                // Claim a random order, which may be unfilled
+                _ => {
+                    if let Some((tick_id, order_id)) = placed_orders.get(rng.gen_range(0..placed_orders.len().max(1))) {
+                        let info = mock_info(claimer.as_str(), &[]);
+                        if let Ok(res) = claim_limit(deps.as_mut(), env.clone(), info, *tick_id, *order_id, false) {
+                            apply_outgoing_sends(&mut balances, &res.messages);
+                        }
+                    }
+                }
+            }
+
+            for (denom, balance) in balances.iter() {
+                assert!(*balance >= 0, "{}: {} balance is negative: {}", format_test_name(&test_name), denom, balance);
+            }
+        }
+
+        // The reported obligations must be covered by the contract's balance
+        let obligations = query::solvency(deps.as_ref()).unwrap().obligations;
+        for obligation in obligations.iter() {
+            let balance = balances.get(&obligation.denom).copied().unwrap_or_default();
+            assert!(balance >= Uint128::try_from(obligation.owed).unwrap().u128() as i128, "{}: {} balance {} does not cover obligations {}", format_test_name(&test_name), obligation.denom, balance, obligation.owed);
+        }
+        let balances_before_settlement = balances.clone();
+
+        // Settle every outstanding obligation by claiming all filled amounts and refunding the rest.
+        // The contract must be able to cover all of them.
+        for (tick_id, order_id) in placed_orders {
+            let info = mock_info(claimer.as_str(), &[]);
+            if let Ok(res) = claim_limit(deps.as_mut(), env.clone(), info, tick_id, order_id, false) {
+                apply_outgoing_sends(&mut balances, &res.messages);
+            }
+            if orders().has(deps.as_ref().storage, &(tick_id, order_id)) {
+                let info = mock_info(DEFAULT_OWNER, &[]);
+                let res = cancel_limit(deps.as_mut(), env.clone(), info, tick_id, order_id).unwrap();
+                apply_outgoing_sends(&mut balances, &res.messages);
+            }
+        }
+
+        for (denom, balance) in balances.iter() {
+            assert!(*balance >= 0, "{}: {} balance is negative after settlement: {}", format_test_name(&test_name), denom, balance);
+        }
+
+        // Settlement must pay out exactly the reported obligations
+        for obligation in obligations {
+            let paid_out = balances_before_settlement.get(&obligation.denom).copied().unwrap_or_default() - balances.get(&obligation.denom).copied().unwrap_or_default();
+            assert_eq!(paid_out, Uint128::try_from(obligation.owed).unwrap().u128() as i128, "{}: {} obligations do not match settlement", format_test_name(&test_name), obligation.denom);
+        }
+    }
+}
+
+struct CancelTickPointerTestCase {
+    name: &'static str,
+    operations: Vec<OrderOperation>,
+    cancels: Vec<(i64, u64)>,
+    expected_next_bid_tick: i64,
+    expected_next_ask_tick: i64,
+    // Market order run after the cancellations, alongside the ticks we expect it to touch
+    market_order: Option<MarketOrder>,
+    expected_touched_ticks: Vec<i64>,
+}
+
+#[test]
+fn test_cancel_advances_tick_pointer() {
+    let owner = Addr::unchecked(DEFAULT_OWNER);
+    let place = |tick_id: i64, order_id: u64, direction: OrderDirection| {
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            tick_id,
+            order_id,
+            direction,
+            owner.clone(),
+            Uint128::from(10u128),
+            Decimal256::zero(),
+            None,
+        ))
+    };
+    let test_cases = vec![
+        CancelTickPointerTestCase {
+            name: "ask pointer advances past fully cancelled tick",
+            operations: vec![
+                place(10, 0, OrderDirection::Ask),
+                place(10, 1, OrderDirection::Ask),
+                place(20, 2, OrderDirection::Ask),
+            ],
+            cancels: vec![(10, 0), (10, 1)],
+            expected_next_bid_tick: MIN_TICK,
+            expected_next_ask_tick: 20,
+            market_order: Some(MarketOrder::new(
+                Uint128::from(5u128),
+                OrderDirection::Bid,
+                owner.clone(),
+            )),
+            expected_touched_ticks: vec![20],
+        },
+        CancelTickPointerTestCase {
+            name: "bid pointer advances past fully cancelled tick",
+            operations: vec![
+                place(-10, 0, OrderDirection::Bid),
+                place(-20, 1, OrderDirection::Bid),
+            ],
+            cancels: vec![(-10, 0)],
+            expected_next_bid_tick: -20,
+            expected_next_ask_tick: MAX_TICK,
+            market_order: Some(MarketOrder::new(
+                Uint128::from(5u128),
+                OrderDirection::Ask,
+                owner.clone(),
+            )),
+            expected_touched_ticks: vec![-20],
+        },
+        CancelTickPointerTestCase {
+            name: "pointer advances across distant ticks",
+            operations: vec![
+                place(-5, 0, OrderDirection::Ask),
+                place(LARGE_POSITIVE_TICK, 1, OrderDirection::Ask),
+            ],
+            cancels: vec![(-5, 0)],
+            expected_next_bid_tick: MIN_TICK,
+            expected_next_ask_tick: LARGE_POSITIVE_TICK,
+            market_order: None,
+            expected_touched_ticks: vec![],
+        },
+        CancelTickPointerTestCase {
+            name: "partial cancellation does not move pointer",
+            operations: vec![
+                place(10, 0, OrderDirection::Ask),
+                place(10, 1, OrderDirection::Ask),
+                place(20, 2, OrderDirection::Ask),
+            ],
+            cancels: vec![(10, 0)],
+            expected_next_bid_tick: MIN_TICK,
+            expected_next_ask_tick: 10,
+            market_order: None,
+            expected_touched_ticks: vec![],
+        },
+        CancelTickPointerTestCase {
+            name: "cancelling a tick behind the pointer does not move pointer",
+            operations: vec![
+                place(10, 0, OrderDirection::Ask),
+                place(20, 1, OrderDirection::Ask),
+            ],
+            cancels: vec![(20, 1)],
+            expected_next_bid_tick: MIN_TICK,
+            expected_next_ask_tick: 10,
+            market_order: None,
+            expected_touched_ticks: vec![],
+        },
+        CancelTickPointerTestCase {
+            name: "pointer resets when side of book is emptied",
+            operations: vec![
+                place(10, 0, OrderDirection::Ask),
+                place(-10, 1, OrderDirection::Bid),
+            ],
+            cancels: vec![(10, 0), (-10, 1)],
+            expected_next_bid_tick: MIN_TICK,
+            expected_next_ask_tick: MAX_TICK,
+            market_order: None,
+            expected_touched_ticks: vec![],
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(owner.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        for operation in test.operations {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        // -- System under test --
+        for (tick_id, order_id) in test.cancels {
+            cancel_limit(deps.as_mut(), env.clone(), info.clone(), tick_id, order_id).unwrap();
+        }
+
+        // -- Post test assertions --
+        let orderbook = ORDERBOOK.load(deps.as_ref().storage).unwrap();
+        assert_eq!(
+            orderbook.next_bid_tick,
+            test.expected_next_bid_tick,
+            "{}: next bid tick did not match",
+            format_test_name(test.name)
+        );
+        assert_eq!(
+            orderbook.next_ask_tick,
+            test.expected_next_ask_tick,
+            "{}: next ask tick did not match",
+            format_test_name(test.name)
+        );
+
+        // Ensure a subsequent market order only touches ticks with liquidity
+        if let Some(mut market_order) = test.market_order {
+            let tick_bound = match market_order.order_direction {
+                OrderDirection::Bid => MAX_TICK,
+                OrderDirection::Ask => MIN_TICK,
+            };
+            let PostMarketOrderState { tick_updates, .. } = run_market_order_internal(
+                deps.as_ref().storage,
+                &mut market_order,
+                tick_bound,
+                env.block.height,
+            )
+            .unwrap();
+            let touched_ticks: Vec<i64> = tick_updates
+                .into_iter()
+                .map(|(tick_id, _)| tick_id)
+                .collect();
+            assert_eq!(
+                touched_ticks,
+                test.expected_touched_ticks,
+                "{}: market order touched unexpected ticks",
+                format_test_name(test.name)
+            );
+        }
+    }
+}
+
+#[test]
+fn test_undeliverable_recipient() {
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let malformed_owner = Addr::unchecked("Not A Valid Address");
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+        None,
+        None,
+    )
+    .unwrap();
+
+    // Place two orders on the same tick and fill the first
+    for _ in 0..2 {
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            0,
+            OrderDirection::Ask,
+            Addr::unchecked(DEFAULT_OWNER),
+            Uint128::from(10u128),
+            Decimal256::zero(),
+            None,
+        ))
+        .run(deps.as_mut(), env.clone(), mock_info(DEFAULT_OWNER, &[]))
+        .unwrap();
+    }
+    OrderOperation::RunMarket(MarketOrder::new(
+        Uint128::from(10u128),
+        OrderDirection::Bid,
+        Addr::unchecked(DEFAULT_SENDER),
+    ))
+    .run(deps.as_mut(), env.clone(), mock_info(DEFAULT_SENDER, &[]))
+    .unwrap();
+
+    // Corrupt the stored owner of both orders
+    for order_id in 0..2 {
+        let mut order = orders()
+            .load(deps.as_ref().storage, &(0, order_id))
+            .unwrap();
+        order.owner = malformed_owner.clone();
+        orders()
+            .save(deps.as_mut().storage, &(0, order_id), &order)
+            .unwrap();
+    }
+    let orders_before = get_orders_by_owner(
+        deps.as_ref().storage,
+        FilterOwnerOrders::all(malformed_owner.clone()),
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+    let expected_error = ContractError::UndeliverableRecipient {
+        address: malformed_owner.to_string(),
+    };
+
+    // Claims to the malformed owner are rejected
+    let res = claim_limit(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(DEFAULT_SENDER, &[]),
+        0,
+        0,
+        false,
+    );
+    assert_eq!(res.unwrap_err(), expected_error);
+
+    // Batch claims skip the order without generating any sends
+    let res = batch_claim_limits(
+        deps.as_mut(),
+        mock_info(DEFAULT_SENDER, &[]),
+        env.clone(),
+        vec![(0, 0)],
+        false,
+    )
+    .unwrap();
+    assert!(res.messages.is_empty());
+
+    // Refunds to the malformed owner are rejected
+    let res = cancel_limit(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(malformed_owner.as_str(), &[]),
+        0,
+        1,
+    );
+    assert_eq!(res.unwrap_err(), expected_error);
+
+    // Order state is left untouched
+    let orders_after = get_orders_by_owner(
+        deps.as_ref().storage,
+        FilterOwnerOrders::all(malformed_owner.clone()),
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+    assert_eq!(orders_before, orders_after);
+}
+
+struct TransferOrderTestCase {
+    name: &'static str,
+    operations: Vec<OrderOperation>,
+    tick_id: i64,
+    order_id: u64,
+    sender: &'static str,
+    new_owner: &'static str,
+    partially_filled: bool,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_transfer_order() {
+    let new_owner = "new_owner";
+    let default_order = LimitOrder::new(
+        0,
+        0,
+        OrderDirection::Ask,
+        Addr::unchecked(DEFAULT_OWNER),
+        Uint128::from(10u128),
+        Decimal256::zero(),
+        None,
+    );
+    let test_cases = vec![
+        TransferOrderTestCase {
+            name: "valid transfer",
+            operations: vec![OrderOperation::PlaceLimit(default_order.clone())],
+            tick_id: 0,
+            order_id: 0,
+            sender: DEFAULT_OWNER,
+            new_owner,
+            partially_filled: false,
+            expected_error: None,
+        },
+        TransferOrderTestCase {
+            name: "valid transfer of partially filled order",
+            operations: vec![
+                OrderOperation::PlaceLimit(default_order.clone()),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(5u128),
+                    OrderDirection::Bid,
+                    Addr::unchecked(DEFAULT_SENDER),
+                )),
+            ],
+            tick_id: 0,
+            order_id: 0,
+            sender: DEFAULT_OWNER,
+            new_owner,
+            partially_filled: true,
+            expected_error: None,
+        },
+        TransferOrderTestCase {
+            name: "sender is not owner",
+            operations: vec![OrderOperation::PlaceLimit(default_order.clone())],
+            tick_id: 0,
+            order_id: 0,
+            sender: DEFAULT_SENDER,
+            new_owner,
+            partially_filled: false,
+            expected_error: Some(ContractError::Unauthorized {}),
+        },
+        TransferOrderTestCase {
+            name: "invalid new owner",
+            operations: vec![OrderOperation::PlaceLimit(default_order.clone())],
+            tick_id: 0,
+            order_id: 0,
+            sender: DEFAULT_OWNER,
+            new_owner: "Invalid Owner",
+            partially_filled: false,
+            expected_error: Some(ContractError::Std(
+                MockApi::default()
+                    .addr_validate("Invalid Owner")
+                    .unwrap_err(),
+            )),
+        },
+        TransferOrderTestCase {
+            name: "order does not exist",
+            operations: vec![],
+            tick_id: 0,
+            order_id: 0,
+            sender: DEFAULT_OWNER,
+            new_owner,
+            partially_filled: false,
+            expected_error: Some(ContractError::OrderNotFound {
+                tick_id: 0,
+                order_id: 0,
+                reason: Some(OrderNotFoundReason::NeverPlaced),
+            }),
+        },
+        TransferOrderTestCase {
+            name: "fully filled and claimed order",
+            operations: vec![
+                OrderOperation::PlaceLimit(default_order.clone()),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(10u128),
+                    OrderDirection::Bid,
+                    Addr::unchecked(DEFAULT_SENDER),
+                )),
+                OrderOperation::Claim((0, 0)),
+            ],
+            tick_id: 0,
+            order_id: 0,
+            sender: DEFAULT_OWNER,
+            new_owner,
+            partially_filled: false,
+            expected_error: Some(ContractError::OrderNotFound {
+                tick_id: 0,
+                order_id: 0,
+                reason: Some(OrderNotFoundReason::Claimed),
+            }),
+        },
+    ];
+
+    for test in test_cases {
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        for operation in test.operations {
+            operation
+                .run(deps.as_mut(), env.clone(), mock_info(DEFAULT_SENDER, &[]))
+                .unwrap();
+        }
+
+        let res = transfer_order(
+            deps.as_mut(),
+            mock_info(test.sender, &[]),
+            test.tick_id,
+            test.order_id,
+            Addr::unchecked(test.new_owner),
+        );
+
+        if let Some(expected_error) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+        res.unwrap();
+
+        // The order is now owned by the new owner
+        let order = orders()
+            .load(deps.as_ref().storage, &(test.tick_id, test.order_id))
+            .unwrap();
+        assert_eq!(
+            order.owner,
+            Addr::unchecked(test.new_owner),
+            "{}",
+            format_test_name(test.name)
+        );
+
+        // The owner index no longer returns the order for the previous owner
+        let previous_owner_orders = get_orders_by_owner(
+            deps.as_ref().storage,
+            FilterOwnerOrders::all(Addr::unchecked(test.sender)),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert!(
+            previous_owner_orders.is_empty(),
+            "{}",
+            format_test_name(test.name)
+        );
+        let new_owner_orders = get_orders_by_owner(
+            deps.as_ref().storage,
+            FilterOwnerOrders::by_tick(test.tick_id, Addr::unchecked(test.new_owner)),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            new_owner_orders,
+            vec![order.clone()],
+            "{}",
+            format_test_name(test.name)
+        );
+
+        // The previous owner can no longer cancel the order
+        let err = cancel_limit(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(test.sender, &[]),
+            test.tick_id,
+            test.order_id,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::Unauthorized {},
+            "{}",
+            format_test_name(test.name)
+        );
+
+        // Any remaining payout is sent to the new owner
+        let res = if test.partially_filled {
+            claim_limit(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(DEFAULT_SENDER, &[]),
+                test.tick_id,
+                test.order_id,
+                false,
+            )
+        } else {
+            cancel_limit(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(test.new_owner, &[]),
+                test.tick_id,
+                test.order_id,
+            )
+        }
+        .unwrap();
+        let recipients: Vec<String> = res
+            .messages
+            .iter()
+            .filter_map(|msg| match &msg.msg {
+                CosmosMsg::Bank(BankMsg::Send { to_address, .. }) => Some(to_address.clone()),
+                CosmosMsg::Stargate { value, .. } => {
+                    Some(MsgSend::try_from(value.clone()).unwrap().to_address)
+                }
+                _ => None,
+            })
+            .collect();
+        assert!(
+            !recipients.is_empty() && recipients.iter().all(|addr| addr == test.new_owner),
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct MaxOrdersPerTickTestCase {
+    name: &'static str,
+    max_orders_per_tick: u64,
+    operations: Vec<OrderOperation>,
+    tick_id: i64,
+    order_direction: OrderDirection,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_max_orders_per_tick() {
+    let limit = 3;
+    let quantity = Uint128::from(10u128);
+    let test_cases = vec![
+        MaxOrdersPerTickTestCase {
+            name: "tick below limit",
+            max_orders_per_tick: limit,
+            operations: vec![OrderOperation::PlaceLimitMulti((
+                vec![0],
+                (limit - 1) as usize,
+                quantity,
+                OrderDirection::Ask,
+            ))],
+            tick_id: 0,
+            order_direction: OrderDirection::Ask,
+            expected_error: None,
+        },
+        MaxOrdersPerTickTestCase {
+            name: "tick at limit",
+            max_orders_per_tick: limit,
+            operations: vec![OrderOperation::PlaceLimitMulti((
+                vec![0],
+                limit as usize,
+                quantity,
+                OrderDirection::Ask,
+            ))],
+            tick_id: 0,
+            order_direction: OrderDirection::Ask,
+            expected_error: Some(ContractError::TickOrderLimitReached { tick_id: 0, limit }),
+        },
+        MaxOrdersPerTickTestCase {
+            name: "limit is tracked per direction",
+            max_orders_per_tick: limit,
+            operations: vec![OrderOperation::PlaceLimitMulti((
+                vec![0],
+                limit as usize,
+                quantity,
+                OrderDirection::Ask,
+            ))],
+            tick_id: 0,
+            order_direction: OrderDirection::Bid,
+            expected_error: None,
+        },
+        MaxOrdersPerTickTestCase {
+            name: "limit is tracked per tick",
+            max_orders_per_tick: limit,
+            operations: vec![OrderOperation::PlaceLimitMulti((
+                vec![0],
+                limit as usize,
+                quantity,
+                OrderDirection::Ask,
+            ))],
+            tick_id: 1,
+            order_direction: OrderDirection::Ask,
+            expected_error: None,
+        },
+        MaxOrdersPerTickTestCase {
+            name: "cancel frees a slot",
+            max_orders_per_tick: limit,
+            operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![0],
+                    limit as usize,
+                    quantity,
+                    OrderDirection::Ask,
+                )),
+                OrderOperation::Cancel((0, 0)),
+            ],
+            tick_id: 0,
+            order_direction: OrderDirection::Ask,
+            expected_error: None,
+        },
+        MaxOrdersPerTickTestCase {
+            name: "partial claim does not free a slot",
+            max_orders_per_tick: limit,
+            operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![0],
+                    limit as usize,
+                    quantity,
+                    OrderDirection::Ask,
+                )),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(5u128),
+                    OrderDirection::Bid,
+                    Addr::unchecked(DEFAULT_SENDER),
+                )),
+                OrderOperation::Claim((0, 0)),
+            ],
+            tick_id: 0,
+            order_direction: OrderDirection::Ask,
+            expected_error: Some(ContractError::TickOrderLimitReached { tick_id: 0, limit }),
+        },
+        MaxOrdersPerTickTestCase {
+            name: "full claim frees a slot",
+            max_orders_per_tick: limit,
+            operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![0],
+                    limit as usize,
+                    quantity,
+                    OrderDirection::Ask,
+                )),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    quantity,
+                    OrderDirection::Bid,
+                    Addr::unchecked(DEFAULT_SENDER),
+                )),
+                OrderOperation::Claim((0, 0)),
+            ],
+            tick_id: 0,
+            order_direction: OrderDirection::Ask,
+            expected_error: None,
+        },
+        MaxOrdersPerTickTestCase {
+            name: "default limit",
+            max_orders_per_tick: DEFAULT_MAX_ORDERS_PER_TICK,
+            operations: vec![OrderOperation::PlaceLimitMulti((
+                vec![0],
+                limit as usize,
+                quantity,
+                OrderDirection::Ask,
+            ))],
+            tick_id: 0,
+            order_direction: OrderDirection::Ask,
+            expected_error: None,
+        },
+    ];
+
+    for test in test_cases {
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        if test.max_orders_per_tick != DEFAULT_MAX_ORDERS_PER_TICK {
+            set_max_orders_per_tick(deps.as_mut().storage, test.max_orders_per_tick).unwrap();
+        }
+
+        for operation in test.operations {
+            operation
+                .run(deps.as_mut(), env.clone(), mock_info(DEFAULT_OWNER, &[]))
+                .unwrap();
+        }
+
+        let orderbook = ORDERBOOK.load(deps.as_ref().storage).unwrap();
+        let count_before =
+            get_tick_order_count(deps.as_ref().storage, test.tick_id, test.order_direction)
+                .unwrap();
+        let res = OrderOperation::PlaceLimit(LimitOrder::new(
+            test.tick_id,
+            0,
+            test.order_direction,
+            Addr::unchecked(DEFAULT_OWNER),
+            quantity,
+            Decimal256::zero(),
+            None,
+        ))
+        .run(deps.as_mut(), env.clone(), mock_info(DEFAULT_OWNER, &[]));
+        let count_after =
+            get_tick_order_count(deps.as_ref().storage, test.tick_id, test.order_direction)
+                .unwrap();
+
+        if let Some(expected_error) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            );
+            assert_eq!(count_before, count_after, "{}", format_test_name(test.name));
+            assert_eq!(
+                ORDERBOOK.load(deps.as_ref().storage).unwrap(),
+                orderbook,
+                "{}",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+
+        res.unwrap();
+        assert_eq!(
+            count_before + 1,
+            count_after,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct RunMarketOrderMaxTicksTestCase {
+    name: &'static str,
+    quantity: Uint128,
+    max_ticks: Option<u32>,
+    expected_output: Uint256,
+    expected_stopped_at_tick: Option<i64>,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_run_market_order_max_ticks() {
+    let tick_ids = vec![0, 1, 2, 3, 4];
+    let quantity_per_tick = Uint128::from(10u128);
+    let test_cases = vec![
+        RunMarketOrderMaxTicksTestCase {
+            name: "no tick limit",
+            quantity: Uint128::from(50u128),
+            max_ticks: None,
+            expected_output: Uint256::from(50u128),
+            expected_stopped_at_tick: None,
+            expected_error: None,
+        },
+        RunMarketOrderMaxTicksTestCase {
+            name: "stopped after exactly max ticks",
+            quantity: Uint128::from(50u128),
+            max_ticks: Some(2),
+            expected_output: Uint256::from(20u128),
+            expected_stopped_at_tick: Some(1),
+            expected_error: None,
+        },
+        RunMarketOrderMaxTicksTestCase {
+            name: "stopped mid book",
+            quantity: Uint128::from(50u128),
+            max_ticks: Some(4),
+            expected_output: Uint256::from(40u128),
+            expected_stopped_at_tick: Some(3),
+            expected_error: None,
+        },
+        RunMarketOrderMaxTicksTestCase {
+            name: "order completes on the last allowed tick",
+            quantity: Uint128::from(20u128),
+            max_ticks: Some(2),
+            expected_output: Uint256::from(20u128),
+            expected_stopped_at_tick: None,
+            expected_error: None,
+        },
+        RunMarketOrderMaxTicksTestCase {
+            name: "order completes before the tick limit",
+            quantity: Uint128::from(15u128),
+            max_ticks: Some(4),
+            expected_output: Uint256::from(15u128),
+            expected_stopped_at_tick: None,
+            expected_error: None,
+        },
+        RunMarketOrderMaxTicksTestCase {
+            name: "insufficient liquidity within tick limit",
+            quantity: Uint128::from(100u128),
+            max_ticks: Some(10),
+            expected_output: Uint256::zero(),
+            expected_stopped_at_tick: None,
+            expected_error: Some(ContractError::InsufficientLiquidity),
+        },
+        RunMarketOrderMaxTicksTestCase {
+            name: "zero max ticks",
+            quantity: Uint128::from(50u128),
+            max_ticks: Some(0),
+            expected_output: Uint256::zero(),
+            expected_stopped_at_tick: None,
+            expected_error: Some(ContractError::InvalidSwap {
+                error: "Max ticks cannot be zero".to_string(),
+            }),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        OrderOperation::PlaceLimitMulti((
+            tick_ids.clone(),
+            1,
+            quantity_per_tick,
+            OrderDirection::Ask,
+        ))
+        .run(deps.as_mut(), env.clone(), mock_info(DEFAULT_OWNER, &[]))
+        .unwrap();
+
+        // -- System under test --
+        let mut order = MarketOrder::new(
+            test.quantity,
+            OrderDirection::Bid,
+            Addr::unchecked(DEFAULT_SENDER),
+        );
+        let res = run_market_order(
+            deps.as_mut().storage,
+            &env,
+            &mut order,
+            MAX_TICK,
+            test.max_ticks,
+        );
+
+        // -- Post Test Assertions --
+        if let Some(expected_error) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+
+        let (output, _, _, stopped_at_tick, ..) = res.unwrap();
+        assert_eq!(
+            output,
+            test.expected_output,
+            "{}",
+            format_test_name(test.name)
+        );
+        assert_eq!(
+            stopped_at_tick,
+            test.expected_stopped_at_tick,
+            "{}",
+            format_test_name(test.name)
+        );
+
+        // The unfilled input is left on the order
+        let remaining_input = test
+            .quantity
+            .checked_sub(Uint128::try_from(output).unwrap())
+            .unwrap();
+        assert_eq!(
+            order.quantity,
+            remaining_input,
+            "{}",
+            format_test_name(test.name)
+        );
+
+        let Some(stopped_at_tick) = stopped_at_tick else {
+            continue;
+        };
+
+        // The tick pointer is left on the tick the fill stopped at so the order can be resumed from it
+        let orderbook = ORDERBOOK.load(deps.as_ref().storage).unwrap();
+        assert_eq!(
+            orderbook.next_ask_tick,
+            stopped_at_tick,
+            "{}",
+            format_test_name(test.name)
+        );
+
+        // Resuming with the remaining input fills the rest of the order
+        let mut resumed_order = MarketOrder::new(
+            remaining_input,
+            OrderDirection::Bid,
+            Addr::unchecked(DEFAULT_SENDER),
+        );
+        let (resumed_output, _, _, resumed_stopped_at_tick, ..) = run_market_order(
+            deps.as_mut().storage,
+            &env,
+            &mut resumed_order,
+            MAX_TICK,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            output.checked_add(resumed_output).unwrap(),
+            Uint256::from_uint128(test.quantity),
+            "{}",
+            format_test_name(test.name)
+        );
+        assert!(
+            resumed_stopped_at_tick.is_none(),
+            "{}",
+            format_test_name(test.name)
+        );
+        assert!(
+            resumed_order.quantity.is_zero(),
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct MaxTicksPerOrderTestCase {
+    name: &'static str,
+    max_ticks_per_order: u32,
+    quantity: Uint128,
+    max_ticks: Option<u32>,
+    expected_output: Uint256,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_max_ticks_per_order() {
+    let tick_ids = vec![0, 1, 2, 3, 4];
+    let quantity_per_tick = Uint128::from(10u128);
+    let test_cases = vec![
+        MaxTicksPerOrderTestCase {
+            name: "order within the cap",
+            max_ticks_per_order: 5,
+            quantity: Uint128::from(50u128),
+            max_ticks: None,
+            expected_output: Uint256::from(50u128),
+            expected_error: None,
+        },
+        MaxTicksPerOrderTestCase {
+            name: "order completes on the last allowed tick",
+            max_ticks_per_order: 3,
+            quantity: Uint128::from(30u128),
+            max_ticks: None,
+            expected_output: Uint256::from(30u128),
+            expected_error: None,
+        },
+        MaxTicksPerOrderTestCase {
+            name: "order spanning more ticks than the cap reverts",
+            max_ticks_per_order: 3,
+            quantity: Uint128::from(40u128),
+            max_ticks: None,
+            expected_output: Uint256::zero(),
+            expected_error: Some(ContractError::TooManyTicks { limit: 3 }),
+        },
+        MaxTicksPerOrderTestCase {
+            name: "resumable tick limit below the cap stops without reverting",
+            max_ticks_per_order: 3,
+            quantity: Uint128::from(50u128),
+            max_ticks: Some(2),
+            expected_output: Uint256::from(20u128),
+            expected_error: None,
+        },
+        MaxTicksPerOrderTestCase {
+            name: "resumable tick limit above the cap still reverts",
+            max_ticks_per_order: 3,
+            quantity: Uint128::from(50u128),
+            max_ticks: Some(5),
+            expected_output: Uint256::zero(),
+            expected_error: Some(ContractError::TooManyTicks { limit: 3 }),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        set_max_ticks_per_order(deps.as_mut().storage, test.max_ticks_per_order).unwrap();
+        OrderOperation::PlaceLimitMulti((
+            tick_ids.clone(),
+            1,
+            quantity_per_tick,
+            OrderDirection::Ask,
+        ))
+        .run(deps.as_mut(), env.clone(), mock_info(DEFAULT_OWNER, &[]))
+        .unwrap();
+
+        // -- System under test --
+        let mut order = MarketOrder::new(
+            test.quantity,
+            OrderDirection::Bid,
+            Addr::unchecked(DEFAULT_SENDER),
+        );
+        let res = run_market_order(
+            deps.as_mut().storage,
+            &env,
+            &mut order,
+            MAX_TICK,
+            test.max_ticks,
+        );
+
+        // -- Post Test Assertions --
+        if let Some(expected_error) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            );
+
+            // The revert leaves the book untouched
+            let orderbook = ORDERBOOK.load(deps.as_ref().storage).unwrap();
+            assert_eq!(orderbook.next_ask_tick, 0, "{}", format_test_name(test.name));
+            continue;
+        }
+
+        let (output, ..) = res.unwrap();
+        assert_eq!(
+            output,
+            test.expected_output,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+#[test]
+fn test_place_limit_response_data() {
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+        None,
+        None,
+    )
+    .unwrap();
+
+    // Order IDs are allocated sequentially across ticks and directions
+    let placements = vec![
+        (0, OrderDirection::Ask, BASE_DENOM),
+        (-10, OrderDirection::Bid, QUOTE_DENOM),
+        (0, OrderDirection::Ask, BASE_DENOM),
+        (LARGE_POSITIVE_TICK, OrderDirection::Ask, BASE_DENOM),
+    ];
+    for (expected_order_id, (tick_id, order_direction, denom)) in placements.into_iter().enumerate()
+    {
+        let response = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(DEFAULT_OWNER, &[coin(100u128, denom)]),
+            ExecuteMsg::PlaceLimit {
+                tick_id,
+                order_direction,
+                quantity: Uint128::from(100u128),
+                claim_bounty: None,
+                owner: None,
+                client_order_id: None,
+                memo: None,
+                auto_settle: None,
+            },
+        )
+        .unwrap();
+
+        let data: PlaceLimitResponseData = from_json(response.data.unwrap()).unwrap();
+        assert_eq!(
+            data,
+            PlaceLimitResponseData {
+                tick_id,
+                order_id: expected_order_id as u64,
+            }
+        );
+
+        // The returned identifiers reference the stored order
+        let order = orders()
+            .load(deps.as_ref().storage, &(data.tick_id, data.order_id))
+            .unwrap();
+        assert_eq!(order.order_direction, order_direction);
+    }
+}
+
+struct PlaceLimitOnBehalfTestCase {
+    name: &'static str,
+    sender: &'static str,
+    owner: Option<&'static str>,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_place_limit_on_behalf() {
+    let vault = "vault";
+    let test_cases = vec![
+        PlaceLimitOnBehalfTestCase {
+            name: "no owner provided",
+            sender: DEFAULT_SENDER,
+            owner: None,
+            expected_error: None,
+        },
+        PlaceLimitOnBehalfTestCase {
+            name: "owner is sender",
+            sender: DEFAULT_SENDER,
+            owner: Some(DEFAULT_SENDER),
+            expected_error: None,
+        },
+        PlaceLimitOnBehalfTestCase {
+            name: "owner differs from sender",
+            sender: vault,
+            owner: Some(DEFAULT_OWNER),
+            expected_error: None,
+        },
+        PlaceLimitOnBehalfTestCase {
+            name: "invalid owner",
+            sender: vault,
+            owner: Some("Invalid Owner"),
+            expected_error: Some(ContractError::Std(
+                MockApi::default()
+                    .addr_validate("Invalid Owner")
+                    .unwrap_err(),
+            )),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let tick_id = 0;
+        let quantity = Uint128::from(100u128);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        // -- System under test --
+        let res = place_limit(
+            &mut deps.as_mut(),
+            env.clone(),
+            mock_info(test.sender, &[coin(quantity.u128(), BASE_DENOM)]),
+            tick_id,
+            OrderDirection::Ask,
+            quantity,
+            None,
+            test.owner.map(Addr::unchecked),
+            None,
+            None,
+            None,
+        );
+
+        // -- Post Test Assertions --
+        if let Some(expected_error) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+
+        let res = res.unwrap();
+        let expected_owner = test.owner.unwrap_or(test.sender);
+        assert!(
+            res.attributes
+                .contains(&Attribute::new("owner", expected_owner)),
+            "{}",
+            format_test_name(test.name)
+        );
+        assert!(
+            res.attributes
+                .contains(&Attribute::new("sender", test.sender)),
+            "{}",
+            format_test_name(test.name)
+        );
+
+        let order = orders().load(deps.as_ref().storage, &(tick_id, 0)).unwrap();
+        assert_eq!(
+            order.owner,
+            Addr::unchecked(expected_owner),
+            "{}",
+            format_test_name(test.name)
+        );
+
+        // Only the owner may cancel an order placed on their behalf
+        if expected_owner != test.sender {
+            let err = cancel_limit(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(test.sender, &[]),
+                tick_id,
+                0,
+            )
+            .unwrap_err();
+            assert_eq!(
+                err,
+                ContractError::Unauthorized {},
+                "{}",
+                format_test_name(test.name)
+            );
+        }
+
+        // Cancelling refunds the owner
+        let res = cancel_limit(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(expected_owner, &[]),
+            tick_id,
+            0,
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::reply_on_error(
+                BankMsg::Send {
+                    to_address: expected_owner.to_string(),
+                    amount: vec![coin(quantity.u128(), BASE_DENOM)],
+                },
+                REPLY_ID_REFUND,
+            )],
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct PlaceLimitTickBoundsTestCase {
+    name: &'static str,
+    tick_id: i64,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_place_limit_custom_tick_bounds() {
+    let min_tick = -100;
+    let max_tick = 100;
+    let test_cases = vec![
+        PlaceLimitTickBoundsTestCase {
+            name: "tick within bounds",
+            tick_id: 0,
+            expected_error: None,
+        },
+        PlaceLimitTickBoundsTestCase {
+            name: "tick on min bound",
+            tick_id: min_tick,
+            expected_error: None,
+        },
+        PlaceLimitTickBoundsTestCase {
+            name: "tick on max bound",
+            tick_id: max_tick,
+            expected_error: None,
+        },
+        PlaceLimitTickBoundsTestCase {
+            name: "tick below min bound",
+            tick_id: min_tick - 1,
+            expected_error: Some(ContractError::InvalidTickId {
+                tick_id: min_tick - 1,
+            }),
+        },
+        PlaceLimitTickBoundsTestCase {
+            name: "tick above max bound",
+            tick_id: max_tick + 1,
+            expected_error: Some(ContractError::InvalidTickId {
+                tick_id: max_tick + 1,
+            }),
+        },
+        PlaceLimitTickBoundsTestCase {
+            name: "tick valid globally but outside bounds",
+            tick_id: LARGE_POSITIVE_TICK,
+            expected_error: Some(ContractError::InvalidTickId {
+                tick_id: LARGE_POSITIVE_TICK,
+            }),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let quantity = Uint128::from(100u128);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            Some(min_tick),
+            Some(max_tick),
+        )
+        .unwrap();
+
+        // -- System under test --
+        let res = place_limit(
+            &mut deps.as_mut(),
+            env,
+            mock_info(DEFAULT_SENDER, &[coin(quantity.u128(), BASE_DENOM)]),
+            test.tick_id,
+            OrderDirection::Ask,
+            quantity,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+
+        // -- Post Test Assertions --
+        if let Some(err) = test.expected_error {
+            assert_eq!(res.unwrap_err(), err, "{}", format_test_name(test.name));
+
+            // Verify no order was placed
+            assert!(
+                orders()
+                    .may_load(deps.as_ref().storage, &(test.tick_id, 0))
+                    .unwrap()
+                    .is_none(),
+                "{}",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+
+        res.unwrap();
+        let order = orders()
+            .load(deps.as_ref().storage, &(test.tick_id, 0))
+            .unwrap();
+        assert_eq!(order.quantity, quantity, "{}", format_test_name(test.name));
+    }
+}
+
+#[test]
+fn test_cancel_contiguous_orders_merges_leaves() {
+    // -- Test Setup --
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let info = mock_info(DEFAULT_SENDER, &[]);
+    let tick_id = 0;
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+        None,
+        None,
+    )
+    .unwrap();
+    OrderOperation::PlaceLimitMulti((vec![tick_id], 4, Uint128::from(10u128), OrderDirection::Ask))
+        .run(deps.as_mut(), env.clone(), info.clone())
+        .unwrap();
+
+    // -- System under test --
+    // Cancel the first three orders, which cover a contiguous ETAS range
+    for order_id in 0..3 {
+        OrderOperation::Cancel((tick_id, order_id))
+            .run(deps.as_mut(), env.clone(), info.clone())
+            .unwrap();
+    }
+
+    // -- Post Test Assertions --
+    // The cancellations are held in a single merged leaf
+    let tree = get_root_node(deps.as_ref().storage, tick_id, OrderDirection::Ask).unwrap();
+    let leaves: Vec<NodeType> = tree
+        .traverse(deps.as_ref().storage)
+        .unwrap()
+        .into_iter()
+        .filter(|node| !node.is_internal())
+        .map(|node| node.node_type)
+        .collect();
+    assert_eq!(
+        leaves,
+        vec![NodeType::leaf(
+            Decimal256::zero(),
+            decimal256_from_u128(30u128)
+        )]
+    );
+
+    // A market order larger than the remaining order only fills the remaining order
+    OrderOperation::RunMarket(MarketOrder::new(
+        Uint128::from(10u128),
+        OrderDirection::Bid,
+        Addr::unchecked(DEFAULT_SENDER),
+    ))
+    .run(deps.as_mut(), env.clone(), info.clone())
+    .unwrap();
+    let order = query::order(deps.as_ref(), tick_id, 3).unwrap().unwrap();
+    assert_eq!(order.claimable, Uint256::from(10u128));
+    assert_eq!(order.fill_percentage, Decimal256::one());
+}
+
+struct CancelledLiquidityTestCase {
+    name: &'static str,
+    cancelled_order_ids: Vec<u64>,
+    quantity: Uint128,
+    expected_output: Uint256,
+    // ((tick_id, order_id), fill percentage) for each remaining order
+    expected_fills: Vec<((i64, u64), Decimal256)>,
+}
+
+#[test]
+fn test_run_market_order_cancelled_liquidity() {
+    let owner = Addr::unchecked(DEFAULT_OWNER);
+    let test_cases = vec![
+        CancelledLiquidityTestCase {
+            name: "first order on tick cancelled",
+            cancelled_order_ids: vec![0],
+            // 10 quote fills the remaining order on tick 0, 5 quote fills 10 base at a price of 2
+            quantity: Uint128::from(15u128),
+            expected_output: Uint256::from(20u128),
+            expected_fills: vec![
+                ((0, 1), Decimal256::one()),
+                ((LARGE_POSITIVE_TICK, 2), Decimal256::percent(50)),
+            ],
+        },
+        CancelledLiquidityTestCase {
+            name: "second order on tick cancelled",
+            cancelled_order_ids: vec![1],
+            quantity: Uint128::from(15u128),
+            expected_output: Uint256::from(20u128),
+            expected_fills: vec![
+                ((0, 0), Decimal256::one()),
+                ((LARGE_POSITIVE_TICK, 2), Decimal256::percent(50)),
+            ],
+        },
+        CancelledLiquidityTestCase {
+            name: "all orders on tick cancelled",
+            cancelled_order_ids: vec![0, 1],
+            quantity: Uint128::from(5u128),
+            expected_output: Uint256::from(10u128),
+            expected_fills: vec![((LARGE_POSITIVE_TICK, 2), Decimal256::percent(50))],
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(DEFAULT_SENDER, &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        // Two orders on tick 0 and one order on a tick with a price of 2
+        let mut operations = vec![
+            OrderOperation::PlaceLimit(LimitOrder::new(
+                0,
+                0,
+                OrderDirection::Ask,
+                owner.clone(),
+                Uint128::from(10u128),
+                Decimal256::zero(),
+                None,
+            )),
+            OrderOperation::PlaceLimit(LimitOrder::new(
+                0,
+                1,
+                OrderDirection::Ask,
+                owner.clone(),
+                Uint128::from(10u128),
+                Decimal256::zero(),
+                None,
+            )),
+            OrderOperation::PlaceLimit(LimitOrder::new(
+                LARGE_POSITIVE_TICK,
+                2,
+                OrderDirection::Ask,
+                owner.clone(),
+                Uint128::from(20u128),
+                Decimal256::zero(),
+                None,
+            )),
+        ];
+        for order_id in test.cancelled_order_ids {
+            operations.push(OrderOperation::Cancel((0, order_id)));
+        }
+        for operation in operations {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        // -- System under test --
+        let mut order = MarketOrder::new(
+            test.quantity,
+            OrderDirection::Bid,
+            Addr::unchecked(DEFAULT_SENDER),
+        );
+        let (output, ..) = run_market_order(
+            deps.as_mut().storage,
+            &env,
+            &mut order,
+            MAX_TICK,
+            None,
+        )
+        .unwrap();
+
+        // -- Post Test Assertions --
+        assert_eq!(
+            output,
+            test.expected_output,
+            "{}",
+            format_test_name(test.name)
+        );
+
+        // The pointer advances past the tick with only cancelled liquidity left
+        let orderbook = ORDERBOOK.load(deps.as_ref().storage).unwrap();
+        assert_eq!(
+            orderbook.next_ask_tick,
+            LARGE_POSITIVE_TICK,
+            "{}",
+            format_test_name(test.name)
+        );
+        let tick_values = TICK_STATE
+            .load(deps.as_ref().storage, 0)
+            .unwrap()
+            .get_values(OrderDirection::Ask);
+        assert!(
+            tick_values.total_amount_of_liquidity.is_zero(),
+            "{}",
+            format_test_name(test.name)
+        );
+
+        // Remaining orders are filled as expected, after accounting for cancellations
+        for ((tick_id, order_id), expected_fill) in test.expected_fills {
+            let res = query::order(deps.as_ref(), tick_id, order_id)
+                .unwrap()
+                .unwrap();
+            assert_eq!(
+                res.fill_percentage,
+                expected_fill,
+                "{}: order {}",
+                format_test_name(test.name),
+                order_id
+            );
+        }
+    }
+}
+
+struct ClaimBountyTestCase {
+    name: &'static str,
+    quantity: u128,
+    claim_bounty: Decimal256,
+    expected_owner_amount: u128,
+    expected_bounty: u128,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_claim_bounty_never_exceeds_fill() {
+    let claimer = "claimer";
+    let test_cases = vec![
+        ClaimBountyTestCase {
+            name: "1% bounty on 1 unit fill rounds to zero",
+            quantity: 1,
+            claim_bounty: Decimal256::percent(1),
+            expected_owner_amount: 1,
+            expected_bounty: 0,
+            expected_error: None,
+        },
+        ClaimBountyTestCase {
+            name: "1% bounty rounds down in favor of the owner",
+            quantity: 199,
+            claim_bounty: Decimal256::percent(1),
+            expected_owner_amount: 198,
+            expected_bounty: 1,
+            expected_error: None,
+        },
+        ClaimBountyTestCase {
+            name: "bounty equal to the fill",
+            quantity: 10,
+            claim_bounty: Decimal256::one(),
+            expected_owner_amount: 0,
+            expected_bounty: 10,
+            expected_error: None,
+        },
+        ClaimBountyTestCase {
+            name: "bounty above the fill",
+            quantity: 10,
+            claim_bounty: Decimal256::percent(101),
+            expected_owner_amount: 0,
+            expected_bounty: 0,
+            expected_error: Some(ContractError::ClaimBountyExceedsFill {
+                claim_bounty: Decimal256::percent(101),
+            }),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(DEFAULT_SENDER, &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        let quantity = Uint128::from(test.quantity);
+        let operations = vec![
+            OrderOperation::PlaceLimit(LimitOrder::new(
+                0,
+                0,
+                OrderDirection::Ask,
+                Addr::unchecked(DEFAULT_OWNER),
+                quantity,
+                Decimal256::zero(),
+                Some(Decimal256::percent(1)),
+            )),
+            OrderOperation::RunMarket(MarketOrder::new(
+                quantity,
+                OrderDirection::Bid,
+                Addr::unchecked(DEFAULT_SENDER),
+            )),
+        ];
+        for operation in operations {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        // Bounties above 1% are rejected on placement, so they are written directly to state
+        let mut order = orders().load(deps.as_ref().storage, &(0, 0)).unwrap();
+        order.claim_bounty = Some(test.claim_bounty);
+        orders()
+            .save(deps.as_mut().storage, &(0, 0), &order)
+            .unwrap();
+
+        // -- System under test --
+        let res = claim_limit(deps.as_mut(), env.clone(), mock_info(claimer, &[]), 0, 0, false);
+
+        // -- Post Test Assertions --
+        if let Some(expected_error) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+
+        let mut paid: HashMap<String, u128> = HashMap::new();
+        for msg in res.unwrap().messages {
+            let CosmosMsg::Stargate { value, .. } = msg.msg else {
+                panic!("unexpected message: {:?}", msg);
+            };
+            let send = MsgSend::try_from(value).unwrap();
+            *paid.entry(send.to_address).or_default() +=
+                send.amount[0].amount.parse::<u128>().unwrap();
+        }
+        assert_eq!(
+            paid.get(DEFAULT_OWNER).copied().unwrap_or_default(),
+            test.expected_owner_amount,
+            "{}",
+            format_test_name(test.name)
+        );
+        assert_eq!(
+            paid.get(claimer).copied().unwrap_or_default(),
+            test.expected_bounty,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct PlaceLimitTickPointerTestCase {
+    name: &'static str,
+    // (tick_id, direction) of each order placed, in order
+    placements: Vec<(i64, OrderDirection)>,
+    expected_next_bid_tick: i64,
+    expected_next_ask_tick: i64,
+}
+
+#[test]
+fn test_place_limit_tick_pointer() {
+    let test_cases = vec![
+        PlaceLimitTickPointerTestCase {
+            name: "empty book",
+            placements: vec![],
+            expected_next_bid_tick: MIN_TICK,
+            expected_next_ask_tick: MAX_TICK,
+        },
+        PlaceLimitTickPointerTestCase {
+            name: "single distant ask on fresh book",
+            placements: vec![(LARGE_POSITIVE_TICK, OrderDirection::Ask)],
+            expected_next_bid_tick: MIN_TICK,
+            expected_next_ask_tick: LARGE_POSITIVE_TICK,
+        },
+        PlaceLimitTickPointerTestCase {
+            name: "single distant bid on fresh book",
+            placements: vec![(LARGE_NEGATIVE_TICK, OrderDirection::Bid)],
+            expected_next_bid_tick: LARGE_NEGATIVE_TICK,
+            expected_next_ask_tick: MAX_TICK,
+        },
+        PlaceLimitTickPointerTestCase {
+            name: "more aggressive ask moves pointer",
+            placements: vec![
+                (LARGE_POSITIVE_TICK, OrderDirection::Ask),
+                (100, OrderDirection::Ask),
+            ],
+            expected_next_bid_tick: MIN_TICK,
+            expected_next_ask_tick: 100,
+        },
+        PlaceLimitTickPointerTestCase {
+            name: "less aggressive ask does not move pointer",
+            placements: vec![
+                (100, OrderDirection::Ask),
+                (LARGE_POSITIVE_TICK, OrderDirection::Ask),
+            ],
+            expected_next_bid_tick: MIN_TICK,
+            expected_next_ask_tick: 100,
+        },
+        PlaceLimitTickPointerTestCase {
+            name: "more aggressive bid moves pointer",
+            placements: vec![
+                (LARGE_NEGATIVE_TICK, OrderDirection::Bid),
+                (-100, OrderDirection::Bid),
+            ],
+            expected_next_bid_tick: -100,
+            expected_next_ask_tick: MAX_TICK,
+        },
+        PlaceLimitTickPointerTestCase {
+            name: "less aggressive bid does not move pointer",
+            placements: vec![
+                (-100, OrderDirection::Bid),
+                (LARGE_NEGATIVE_TICK, OrderDirection::Bid),
+            ],
+            expected_next_bid_tick: -100,
+            expected_next_ask_tick: MAX_TICK,
+        },
+        PlaceLimitTickPointerTestCase {
+            name: "both sides placed on fresh book",
+            placements: vec![
+                (LARGE_POSITIVE_TICK, OrderDirection::Ask),
+                (LARGE_NEGATIVE_TICK, OrderDirection::Bid),
+            ],
+            expected_next_bid_tick: LARGE_NEGATIVE_TICK,
+            expected_next_ask_tick: LARGE_POSITIVE_TICK,
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        // -- System under test --
+        for (order_id, (tick_id, direction)) in test.placements.into_iter().enumerate() {
+            OrderOperation::PlaceLimit(LimitOrder::new(
+                tick_id,
+                order_id as u64,
+                direction,
+                Addr::unchecked(DEFAULT_OWNER),
+                Uint128::from(10u128),
+                Decimal256::zero(),
+                None,
+            ))
+            .run(deps.as_mut(), env.clone(), mock_info(DEFAULT_SENDER, &[]))
+            .unwrap();
+        }
+
+        // -- Post Test Assertions --
+        let orderbook = ORDERBOOK.load(deps.as_ref().storage).unwrap();
+        assert_eq!(
+            orderbook.next_bid_tick,
+            test.expected_next_bid_tick,
+            "{}",
+            format_test_name(test.name)
+        );
+        assert_eq!(
+            orderbook.next_ask_tick,
+            test.expected_next_ask_tick,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+struct BatchClaimLimitTestCase {
+    name: &'static str,
+    max_batch_claim: Option<u32>,
+    orders: Vec<(i64, u64)>,
+    expected_claimed: Vec<(i64, u64)>,
+    expected_unprocessed: Vec<(i64, u64)>,
+}
+
+#[test]
+fn test_batch_claim_order_limit() {
+    let owner = Addr::unchecked(DEFAULT_OWNER);
+    let test_cases = vec![
+        BatchClaimLimitTestCase {
+            name: "batch within default limit",
+            max_batch_claim: None,
+            orders: vec![(0, 0), (0, 1), (0, 2)],
+            expected_claimed: vec![(0, 0), (0, 1), (0, 2)],
+            expected_unprocessed: vec![],
+        },
+        BatchClaimLimitTestCase {
+            name: "batch exceeding configured limit",
+            max_batch_claim: Some(2),
+            orders: vec![(0, 2), (0, 0), (0, 1)],
+            expected_claimed: vec![(0, 2), (0, 0)],
+            expected_unprocessed: vec![(0, 1)],
+        },
+        BatchClaimLimitTestCase {
+            name: "batch exactly at configured limit",
+            max_batch_claim: Some(3),
+            orders: vec![(0, 0), (0, 1), (0, 2)],
+            expected_claimed: vec![(0, 0), (0, 1), (0, 2)],
+            expected_unprocessed: vec![],
+        },
+        BatchClaimLimitTestCase {
+            name: "remainder is returned in the order provided",
+            max_batch_claim: Some(1),
+            orders: vec![(0, 1), (0, 2), (0, 0)],
+            expected_claimed: vec![(0, 1)],
+            expected_unprocessed: vec![(0, 2), (0, 0)],
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(owner.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        if let Some(max_batch_claim) = test.max_batch_claim {
+            set_max_batch_claim(deps.as_mut().storage, max_batch_claim).unwrap();
+        }
+
+        // Place three asks and fill all of them
+        let operations = vec![
+            OrderOperation::PlaceLimitMulti((
+                vec![0],
+                3,
+                Uint128::from(10u128),
+                OrderDirection::Ask,
+            )),
+            OrderOperation::RunMarket(MarketOrder::new(
+                Uint128::from(30u128),
+                OrderDirection::Bid,
+                owner.clone(),
+            )),
+        ];
+        for operation in operations {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        // -- System under test --
+        let res = batch_claim_limits(
+            deps.as_mut(),
+            mock_info(DEFAULT_SENDER, &[]),
+            env.clone(),
+            test.orders.clone(),
+            false,
+        )
+        .unwrap();
+
+        // -- Post Test Assertions --
+        let data: BatchClaimResponseData = from_json(res.data.unwrap()).unwrap();
+        assert_eq!(
+            data.unprocessed,
+            test.expected_unprocessed,
+            "{}",
+            format_test_name(test.name)
+        );
+
+        // Claimed orders are fully filled and removed from state while the rest remain claimable
+        for key in test.expected_claimed {
+            assert!(
+                orders().may_load(deps.as_ref().storage, &key).unwrap().is_none(),
+                "{}: order {:?} was not claimed",
+                format_test_name(test.name),
+                key
+            );
+        }
+        for key in test.expected_unprocessed {
+            assert!(
+                orders().may_load(deps.as_ref().storage, &key).unwrap().is_some(),
+                "{}: order {:?} was unexpectedly claimed",
+                format_test_name(test.name),
+                key
+            );
+        }
+    }
+}
+struct TickPointerMovedEventTestCase {
+    name: &'static str,
+    operations: Vec<OrderOperation>,
+    market_order: MarketOrder,
+    // (direction, old_tick, new_tick, old_price, new_price)
+    expected_event: Option<(OrderDirection, i64, i64, &'static str, &'static str)>,
+}
+
+#[test]
+fn test_tick_pointer_moved_event() {
+    let env = mock_env();
+    let info = mock_info(DEFAULT_SENDER, &[]);
+    let buyer = Addr::unchecked("buyer");
+    let test_cases = vec![
+        TickPointerMovedEventTestCase {
+            name: "positive tick movement on filled market bid",
+            operations: vec![OrderOperation::PlaceLimitMulti((
+                vec![0, 1, 2],
+                1,
+                Uint128::from(10u128),
+                OrderDirection::Ask,
+            ))],
+            // Fills tick 0 and half of tick 1
+            market_order: MarketOrder::new(
+                Uint128::from(15u128),
+                OrderDirection::Bid,
+                buyer.clone(),
+            ),
+            expected_event: Some((OrderDirection::Ask, 0, 1, "1", "1.000001")),
+        },
+        TickPointerMovedEventTestCase {
+            name: "negative tick movement on filled market ask",
+            operations: vec![OrderOperation::PlaceLimitMulti((
+                vec![-2, -1, 0],
+                1,
+                Uint128::from(10u128),
+                OrderDirection::Bid,
+            ))],
+            // Fills tick 0 and half of tick -1
+            market_order: MarketOrder::new(
+                Uint128::from(15u128),
+                OrderDirection::Ask,
+                buyer.clone(),
+            ),
+            expected_event: Some((OrderDirection::Bid, 0, -1, "1", "0.9999999")),
+        },
+        TickPointerMovedEventTestCase {
+            name: "movement across an empty tick range",
+            operations: vec![OrderOperation::PlaceLimitMulti((
+                vec![0, LARGE_POSITIVE_TICK],
+                1,
+                Uint128::from(10u128),
+                OrderDirection::Ask,
+            ))],
+            // 10 quote fills tick 0, then 5 quote at a price of 2 fills 10 base
+            market_order: MarketOrder::new(
+                Uint128::from(15u128),
+                OrderDirection::Bid,
+                buyer.clone(),
+            ),
+            expected_event: Some((OrderDirection::Ask, 0, LARGE_POSITIVE_TICK, "1", "2")),
+        },
+        TickPointerMovedEventTestCase {
+            name: "no movement on partial fill of current tick",
+            operations: vec![OrderOperation::PlaceLimitMulti((
+                vec![0, 1],
+                1,
+                Uint128::from(10u128),
+                OrderDirection::Ask,
+            ))],
+            market_order: MarketOrder::new(
+                Uint128::from(5u128),
+                OrderDirection::Bid,
+                buyer.clone(),
+            ),
+            expected_event: None,
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        for operation in test.operations {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        // -- System under test --
+        let mut market_order = test.market_order;
+        let tick_bound = match market_order.order_direction {
+            OrderDirection::Bid => MAX_TICK,
+            OrderDirection::Ask => MIN_TICK,
+        };
+        let (.., event) = run_market_order(
+            deps.as_mut().storage,
+            &env,
+            &mut market_order,
+            tick_bound,
+            None,
+        )
+        .unwrap();
+
+        // -- Post Test Assertions --
+        let expected_event =
+            test.expected_event
+                .map(|(direction, old_tick, new_tick, old_price, new_price)| {
+                    Event::new("tick_pointer_moved").add_attributes(vec![
+                        ("direction", direction.to_string()),
+                        ("old_tick", old_tick.to_string()),
+                        ("new_tick", new_tick.to_string()),
+                        ("old_price", old_price.to_string()),
+                        ("new_price", new_price.to_string()),
+                    ])
+                });
+        assert_eq!(event, expected_event, "{}", format_test_name(test.name));
+    }
+}
+struct ClaimInconsistencyTestCase {
+    name: &'static str,
+    corrupt_tick_values: fn(&mut TickValues),
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_claim_inconsistency_guard() {
+    let test_cases = vec![
+        ClaimInconsistencyTestCase {
+            name: "consistent tick state",
+            corrupt_tick_values: |_| {},
+            expected_error: None,
+        },
+        ClaimInconsistencyTestCase {
+            name: "cumulative total value below order end",
+            corrupt_tick_values: |values| {
+                values.cumulative_total_value = decimal256_from_u128(5u128);
+            },
+            expected_error: Some(ContractError::ClaimInconsistency {
+                tick_id: 0,
+                order_id: 0,
+            }),
+        },
+        ClaimInconsistencyTestCase {
+            name: "realized cancels exceed ETAS",
+            corrupt_tick_values: |values| {
+                values.cumulative_realized_cancels = decimal256_from_u128(20u128);
+            },
+            expected_error: Some(ContractError::ClaimInconsistency {
+                tick_id: 0,
+                order_id: 0,
+            }),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(DEFAULT_SENDER, &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        let quantity = Uint128::from(10u128);
+        let operations = vec![
+            OrderOperation::PlaceLimit(LimitOrder::new(
+                0,
+                0,
+                OrderDirection::Ask,
+                Addr::unchecked(DEFAULT_OWNER),
+                quantity,
+                Decimal256::zero(),
+                None,
+            )),
+            OrderOperation::RunMarket(MarketOrder::new(
+                quantity,
+                OrderDirection::Bid,
+                Addr::unchecked(DEFAULT_SENDER),
+            )),
+        ];
+        for operation in operations {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        // Corrupt the already synced tick values so the claim's tick sync is skipped
+        let mut tick_state = TICK_STATE.load(deps.as_ref().storage, 0).unwrap();
+        let mut values = tick_state.get_values(OrderDirection::Ask);
+        values.last_tick_sync_etas = values.effective_total_amount_swapped;
+        (test.corrupt_tick_values)(&mut values);
+        tick_state.set_values(OrderDirection::Ask, values);
+        TICK_STATE
+            .save(deps.as_mut().storage, 0, &tick_state)
+            .unwrap();
+
+        // -- System under test --
+        let res = claim_order(
+            &mut deps.storage,
+            &deps.api,
+            &env,
+            Addr::unchecked(DEFAULT_SENDER),
+            0,
+            0,
+            false,
+        );
+
+        // -- Post Test Assertions --
+        if let Some(expected_error) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            );
+
+            // Order must be left untouched rather than paid out
+            let order = orders().load(deps.as_ref().storage, &(0, 0)).unwrap();
+            assert_eq!(order.quantity, quantity, "{}", format_test_name(test.name));
+            continue;
+        }
+
+        let (amount, ..) = res.unwrap();
+        assert_eq!(
+            amount,
+            Uint256::from(10u128),
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}struct ZeroOutputTestCase {
+    name: &'static str,
+    operations: Vec<OrderOperation>,
+    market_order: MarketOrder,
+    expected_output: Uint256,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_market_order_zero_output() {
+    let env = mock_env();
+    let info = mock_info(DEFAULT_SENDER, &[]);
+    let seller = Addr::unchecked("seller");
+    // Fully fills the bid at `LARGE_POSITIVE_TICK`, leaving the bid tick pointer on a tick with no
+    // liquidity where an ask of 1 truncates to zero output at a price of 2
+    let filled_large_positive_tick = vec![
+        OrderOperation::PlaceLimitMulti((
+            vec![0, LARGE_POSITIVE_TICK],
+            1,
+            Uint128::from(10u128),
+            OrderDirection::Bid,
+        )),
+        OrderOperation::RunMarket(MarketOrder::new(
+            Uint128::from(20u128),
+            OrderDirection::Ask,
+            seller.clone(),
+        )),
+    ];
+    let test_cases = vec![
+        ZeroOutputTestCase {
+            name: "input consumed for zero output",
+            operations: filled_large_positive_tick.clone(),
+            market_order: MarketOrder::new(Uint128::one(), OrderDirection::Ask, seller.clone()),
+            expected_output: Uint256::zero(),
+            expected_error: Some(ContractError::ZeroOutput {
+                input_consumed: Uint128::one(),
+            }),
+        },
+        ZeroOutputTestCase {
+            name: "input consumed for zero output with lossy fills allowed",
+            operations: filled_large_positive_tick.clone(),
+            market_order: MarketOrder::new(Uint128::one(), OrderDirection::Ask, seller.clone())
+                .with_allow_zero_output(true),
+            expected_output: Uint256::zero(),
+            expected_error: None,
+        },
+        ZeroOutputTestCase {
+            name: "non-zero output",
+            operations: filled_large_positive_tick,
+            // Passes over the empty tick and fills at tick 0
+            market_order: MarketOrder::new(
+                Uint128::from(4u128),
+                OrderDirection::Ask,
+                seller.clone(),
+            ),
+            expected_output: Uint256::from(4u128),
+            expected_error: None,
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        for operation in test.operations {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        // -- System under test --
+        let mut market_order = test.market_order;
+        let res = run_market_order(
+            deps.as_mut().storage,
+            &env,
+            &mut market_order,
+            MIN_TICK,
+            None,
+        );
+
+        // -- Post Test Assertions --
+        if let Some(expected_error) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+
+        let (output, ..) = res.unwrap();
+        assert_eq!(
+            output,
+            test.expected_output,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+struct WaiveBountyTestCase {
+    name: &'static str,
+    claimer: &'static str,
+    waive_bounty: bool,
+    batch: bool,
+    // (recipient, amount) of each bank send, in order
+    expected_sends: Vec<(&'static str, u128)>,
+}
+
+#[test]
+fn test_claim_waive_bounty() {
+    let claimer = "claimer";
+    let test_cases = vec![
+        WaiveBountyTestCase {
+            name: "owner self-claims with waiver",
+            claimer: DEFAULT_OWNER,
+            waive_bounty: true,
+            batch: false,
+            expected_sends: vec![(DEFAULT_OWNER, 100)],
+        },
+        WaiveBountyTestCase {
+            name: "owner self-claims without waiver",
+            claimer: DEFAULT_OWNER,
+            waive_bounty: false,
+            batch: false,
+            expected_sends: vec![(DEFAULT_OWNER, 99), (DEFAULT_OWNER, 1)],
+        },
+        WaiveBountyTestCase {
+            name: "third party claims",
+            claimer,
+            waive_bounty: false,
+            batch: false,
+            expected_sends: vec![(DEFAULT_OWNER, 99), (claimer, 1)],
+        },
+        WaiveBountyTestCase {
+            name: "third party cannot waive bounty",
+            claimer,
+            waive_bounty: true,
+            batch: false,
+            expected_sends: vec![(DEFAULT_OWNER, 99), (claimer, 1)],
+        },
+        WaiveBountyTestCase {
+            name: "owner batch claims with waiver",
+            claimer: DEFAULT_OWNER,
+            waive_bounty: true,
+            batch: true,
+            expected_sends: vec![(DEFAULT_OWNER, 100)],
+        },
+        WaiveBountyTestCase {
+            name: "third party batch claims with waiver",
+            claimer,
+            waive_bounty: true,
+            batch: true,
+            expected_sends: vec![(DEFAULT_OWNER, 99), (claimer, 1)],
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(DEFAULT_SENDER, &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        let quantity = Uint128::from(100u128);
+        let operations = vec![
+            OrderOperation::PlaceLimit(LimitOrder::new(
+                0,
+                0,
+                OrderDirection::Ask,
+                Addr::unchecked(DEFAULT_OWNER),
+                quantity,
+                Decimal256::zero(),
+                Some(Decimal256::percent(1)),
+            )),
+            OrderOperation::RunMarket(MarketOrder::new(
+                quantity,
+                OrderDirection::Bid,
+                Addr::unchecked(DEFAULT_SENDER),
+            )),
+        ];
+        for operation in operations {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        // -- System under test --
+        let claim_info = mock_info(test.claimer, &[]);
+        let res = if test.batch {
+            batch_claim_limits(
+                deps.as_mut(),
+                claim_info,
+                env.clone(),
+                vec![(0, 0)],
+                test.waive_bounty,
+            )
+        } else {
+            claim_limit(
+                deps.as_mut(),
+                env.clone(),
+                claim_info,
+                0,
+                0,
+                test.waive_bounty,
+            )
+        }
+        .unwrap();
+
+        // -- Post Test Assertions --
+        let sends: Vec<(String, u128)> = res
+            .messages
+            .into_iter()
+            .map(|msg| {
+                let CosmosMsg::Stargate { value, .. } = msg.msg else {
+                    panic!("unexpected message: {:?}", msg);
+                };
+                let send = MsgSend::try_from(value).unwrap();
+                (
+                    send.to_address,
+                    send.amount[0].amount.parse::<u128>().unwrap(),
+                )
+            })
+            .collect();
+        let expected_sends: Vec<(String, u128)> = test
+            .expected_sends
+            .into_iter()
+            .map(|(recipient, amount)| (recipient.to_string(), amount))
+            .collect();
+        assert_eq!(sends, expected_sends, "{}", format_test_name(test.name));
+    }
+}
+struct ClaimAndCancelTestCase {
+    name: &'static str,
+    filled: Uint128,
+    expected_sends: Vec<(&'static str, &'static str, u128)>,
+}
+
+#[test]
+fn test_claim_and_cancel() {
+    let test_cases = vec![
+        ClaimAndCancelTestCase {
+            name: "partially filled order",
+            filled: Uint128::from(400u128),
+            // Claim (less 1% bounty), bounty to the owner as claimer, then the refund
+            expected_sends: vec![
+                (DEFAULT_OWNER, QUOTE_DENOM, 396),
+                (DEFAULT_OWNER, QUOTE_DENOM, 4),
+                (DEFAULT_OWNER, BASE_DENOM, 600),
+            ],
+        },
+        ClaimAndCancelTestCase {
+            name: "unfilled order",
+            filled: Uint128::zero(),
+            expected_sends: vec![(DEFAULT_OWNER, BASE_DENOM, 1000)],
+        },
+        ClaimAndCancelTestCase {
+            name: "fully filled order",
+            filled: Uint128::from(1000u128),
+            expected_sends: vec![
+                (DEFAULT_OWNER, QUOTE_DENOM, 990),
+                (DEFAULT_OWNER, QUOTE_DENOM, 10),
+            ],
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(DEFAULT_SENDER, &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        let mut operations = vec![OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            0,
+            OrderDirection::Ask,
+            Addr::unchecked(DEFAULT_OWNER),
+            Uint128::from(1000u128),
+            Decimal256::zero(),
+            Some(Decimal256::percent(1)),
+        ))];
+        if !test.filled.is_zero() {
+            operations.push(OrderOperation::RunMarket(MarketOrder::new(
+                test.filled,
+                OrderDirection::Bid,
+                Addr::unchecked(DEFAULT_SENDER),
+            )));
+        }
+        for operation in operations {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        // Only the owner may claim and cancel their order
+        let err = claim_and_cancel(deps.as_mut(), env.clone(), info.clone(), 0, 0).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::Unauthorized {},
+            "{}",
+            format_test_name(test.name)
+        );
+
+        // -- System under test --
+        let res = claim_and_cancel(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(DEFAULT_OWNER, &[]),
+            0,
+            0,
+        )
+        .unwrap();
+
+        // -- Post Test Assertions --
+        let sends: Vec<(String, String, u128)> = res
+            .messages
+            .into_iter()
+            .map(|msg| match msg.msg {
+                CosmosMsg::Stargate { value, .. } => {
+                    let send = MsgSend::try_from(value).unwrap();
+                    (
+                        send.to_address,
+                        send.amount[0].denom.clone(),
+                        send.amount[0].amount.parse::<u128>().unwrap(),
+                    )
+                }
+                CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => {
+                    (to_address, amount[0].denom.clone(), amount[0].amount.u128())
+                }
+                msg => panic!("unexpected message: {:?}", msg),
+            })
+            .collect();
+        let expected_sends: Vec<(String, String, u128)> = test
+            .expected_sends
+            .into_iter()
+            .map(|(recipient, denom, amount)| (recipient.to_string(), denom.to_string(), amount))
+            .collect();
+        assert_eq!(sends, expected_sends, "{}", format_test_name(test.name));
+
+        // The order is always removed, either by the claim or by the cancellation
+        assert!(
+            orders().may_load(deps.as_ref().storage, &(0, 0)).unwrap().is_none(),
+            "{}",
+            format_test_name(test.name)
+        );
+        let tick_values = TICK_STATE
+            .load(deps.as_ref().storage, 0)
+            .unwrap()
+            .get_values(OrderDirection::Ask);
+        assert!(
+            tick_values.total_amount_of_liquidity.is_zero(),
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct ReplaceOrderTestCase {
+    name: &'static str,
+    sender: &'static str,
+    sent: Vec<Coin>,
+    new_tick_id: i64,
+    new_quantity: Uint128,
+    expected_refund: Option<u128>,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_replace_order() {
+    let quantity = Uint128::from(100u128);
+    let test_cases = vec![
+        ReplaceOrderTestCase {
+            name: "move order to a higher tick",
+            sender: DEFAULT_OWNER,
+            sent: vec![],
+            new_tick_id: 10,
+            new_quantity: quantity,
+            expected_refund: None,
+            expected_error: None,
+        },
+        ReplaceOrderTestCase {
+            name: "move order and increase quantity",
+            sender: DEFAULT_OWNER,
+            sent: vec![coin(50, BASE_DENOM)],
+            new_tick_id: 10,
+            new_quantity: Uint128::from(150u128),
+            expected_refund: None,
+            expected_error: None,
+        },
+        ReplaceOrderTestCase {
+            name: "move order and decrease quantity",
+            sender: DEFAULT_OWNER,
+            sent: vec![],
+            new_tick_id: 10,
+            new_quantity: Uint128::from(60u128),
+            expected_refund: Some(40),
+            expected_error: None,
+        },
+        ReplaceOrderTestCase {
+            name: "increase quantity without funds",
+            sender: DEFAULT_OWNER,
+            sent: vec![],
+            new_tick_id: 10,
+            new_quantity: Uint128::from(150u128),
+            expected_refund: None,
+            expected_error: Some(ContractError::InsufficientFunds {
+                sent: quantity,
+                required: Uint128::from(150u128),
+            }),
+        },
+        ReplaceOrderTestCase {
+            name: "replacement crosses the book",
+            sender: DEFAULT_OWNER,
+            sent: vec![],
+            new_tick_id: -20,
+            new_quantity: quantity,
+            expected_refund: None,
+            expected_error: Some(ContractError::CrossingLimitOrder {
+                tick_id: -20,
+                best_opposite_tick: -10,
+            }),
+        },
+        ReplaceOrderTestCase {
+            name: "sender is not the owner",
+            sender: DEFAULT_SENDER,
+            sent: vec![],
+            new_tick_id: 10,
+            new_quantity: quantity,
+            expected_refund: None,
+            expected_error: Some(ContractError::Unauthorized {}),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        let operations = vec![
+            OrderOperation::PlaceLimit(LimitOrder::new(
+                0,
+                0,
+                OrderDirection::Ask,
+                Addr::unchecked(DEFAULT_OWNER),
+                quantity,
+                Decimal256::zero(),
+                Some(Decimal256::percent(1)),
+            )),
+            OrderOperation::PlaceLimit(LimitOrder::new(
+                -10,
+                1,
+                OrderDirection::Bid,
+                Addr::unchecked(DEFAULT_SENDER),
+                quantity,
+                Decimal256::zero(),
+                None,
+            )),
+        ];
+        for operation in operations {
+            operation
+                .run(deps.as_mut(), env.clone(), mock_info(DEFAULT_OWNER, &[]))
+                .unwrap();
+        }
+
+        // -- System under test --
+        let res = replace_order(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(test.sender, &test.sent),
+            0,
+            0,
+            test.new_tick_id,
+            test.new_quantity,
+        );
+
+        // -- Post Test Assertions --
+        if let Some(expected_error) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+        let res = res.unwrap();
+
+        // The replacement is placed under a new ID on the new tick
+        let data: PlaceLimitResponseData = from_json(res.data.unwrap()).unwrap();
+        assert_eq!(
+            data,
+            PlaceLimitResponseData {
+                tick_id: test.new_tick_id,
+                order_id: 2,
+            },
+            "{}",
+            format_test_name(test.name)
+        );
+        assert!(
+            orders().may_load(deps.as_ref().storage, &(0, 0)).unwrap().is_none(),
+            "{}",
+            format_test_name(test.name)
+        );
+        assert_eq!(
+            load_order(deps.as_ref().storage, 0, 0).unwrap_err(),
+            ContractError::OrderNotFound {
+                tick_id: 0,
+                order_id: 0,
+                reason: Some(OrderNotFoundReason::Replaced {
+                    tick_id: test.new_tick_id,
+                    order_id: 2,
+                }),
+            },
+            "{}",
+            format_test_name(test.name)
+        );
+        let order = orders()
+            .load(deps.as_ref().storage, &(test.new_tick_id, 2))
+            .unwrap();
+        assert_eq!(order.owner, Addr::unchecked(DEFAULT_OWNER));
+        assert_eq!(order.quantity, test.new_quantity);
+        assert_eq!(order.claim_bounty, Some(Decimal256::percent(1)));
+
+        // Liquidity has moved from the old tick to the new one
+        let old_tick_values = TICK_STATE
+            .load(deps.as_ref().storage, 0)
+            .unwrap()
+            .get_values(OrderDirection::Ask);
+        assert!(
+            old_tick_values.total_amount_of_liquidity.is_zero(),
+            "{}",
+            format_test_name(test.name)
+        );
+        let new_tick_values = TICK_STATE
+            .load(deps.as_ref().storage, test.new_tick_id)
+            .unwrap()
+            .get_values(OrderDirection::Ask);
+        assert_eq!(
+            new_tick_values.total_amount_of_liquidity,
+            Decimal256::from_ratio(test.new_quantity, 1u128),
+            "{}",
+            format_test_name(test.name)
+        );
+
+        // Only a decrease in quantity is refunded
+        let expected_messages: Vec<SubMsg> = test
+            .expected_refund
+            .map(|refund| {
+                SubMsg::reply_on_error(
+                    BankMsg::Send {
+                        to_address: DEFAULT_OWNER.to_string(),
+                        amount: vec![coin(refund, BASE_DENOM)],
+                    },
+                    REPLY_ID_REFUND,
+                )
+            })
+            .into_iter()
+            .collect();
+        assert_eq!(
+            res.messages,
+            expected_messages,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+#[test]
+fn test_same_tick_fill_priority() {
+    // -- Test Setup --
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let quantity = Uint128::from(100u128);
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+        None,
+        None,
+    )
+    .unwrap();
+
+    // Owners are placed in reverse lexicographic order so that neither the owner index nor the
+    // order key iteration matches placement order by coincidence of naming
+    let owners = ["owner_c", "owner_b", "owner_a"];
+    for owner in owners {
+        place_limit(
+            &mut deps.as_mut(),
+            env.clone(),
+            mock_info(owner, &[coin(quantity.u128(), BASE_DENOM)]),
+            0,
+            OrderDirection::Ask,
+            quantity,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    }
+
+    // -- System under test --
+    // Fill one and a half orders' worth of the tick
+    let mut market_order = MarketOrder::new(
+        Uint128::from(150u128),
+        OrderDirection::Bid,
+        Addr::unchecked(DEFAULT_SENDER),
+    );
+    run_market_order(
+        deps.as_mut().storage,
+        &env,
+        &mut market_order,
+        MAX_TICK,
+        None,
+    )
+    .unwrap();
+
+    // -- Post Test Assertions --
+    // Orders are consumed in placement order: the first fully, the second by half
+    let expected_fills = [
+        Decimal256::one(),
+        Decimal256::percent(50),
+        Decimal256::zero(),
+    ];
+    for (order_id, (owner, expected_fill)) in owners.iter().zip(expected_fills).enumerate() {
+        let res = query::order(deps.as_ref(), 0, order_id as u64)
+            .unwrap()
+            .unwrap();
+        assert_eq!(res.order.owner, Addr::unchecked(*owner));
+        assert_eq!(res.fill_percentage, expected_fill, "order {order_id}");
+    }
+
+    // Claims follow the same priority
+    let res = claim_limit(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(owners[0], &[]),
+        0,
+        0,
+        false,
+    )
+    .unwrap();
+    assert!(res.events[0]
+        .attributes
+        .contains(&Attribute::new("amount_claimed", "100")));
+    let err = claim_limit(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(owners[2], &[]),
+        0,
+        2,
+        false,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::ZeroClaim);
+}
+
+struct MaxPriceImpactTestCase {
+    name: &'static str,
+    operations: Vec<OrderOperation>,
+    max_price_impact: Option<Decimal256>,
+    market_order: MarketOrder,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_max_price_impact() {
+    let env = mock_env();
+    let info = mock_info(DEFAULT_SENDER, &[]);
+    let taker = Addr::unchecked("taker");
+    // Thin book where filling past tick 0 jumps straight to a price of 2 (asks) or 0.5 (bids)
+    let thin_asks = vec![OrderOperation::PlaceLimitMulti((
+        vec![0, LARGE_POSITIVE_TICK],
+        1,
+        Uint128::from(10u128),
+        OrderDirection::Ask,
+    ))];
+    let thin_bids = vec![OrderOperation::PlaceLimitMulti((
+        vec![LARGE_NEGATIVE_TICK, 0],
+        1,
+        Uint128::from(10u128),
+        OrderDirection::Bid,
+    ))];
+    let test_cases = vec![
+        MaxPriceImpactTestCase {
+            name: "unbounded by default",
+            operations: thin_asks.clone(),
+            max_price_impact: None,
+            market_order: MarketOrder::new(
+                Uint128::from(15u128),
+                OrderDirection::Bid,
+                taker.clone(),
+            ),
+            expected_error: None,
+        },
+        MaxPriceImpactTestCase {
+            name: "large bid trips breaker",
+            operations: thin_asks.clone(),
+            max_price_impact: Some(Decimal256::percent(50)),
+            market_order: MarketOrder::new(
+                Uint128::from(15u128),
+                OrderDirection::Bid,
+                taker.clone(),
+            ),
+            expected_error: Some(ContractError::PriceImpactTooHigh {
+                impact: Decimal256::one(),
+            }),
+        },
+        MaxPriceImpactTestCase {
+            name: "impact equal to the maximum",
+            operations: thin_asks.clone(),
+            max_price_impact: Some(Decimal256::one()),
+            market_order: MarketOrder::new(
+                Uint128::from(15u128),
+                OrderDirection::Bid,
+                taker.clone(),
+            ),
+            expected_error: None,
+        },
+        MaxPriceImpactTestCase {
+            name: "small bid within the best tick",
+            operations: thin_asks,
+            max_price_impact: Some(Decimal256::zero()),
+            market_order: MarketOrder::new(
+                Uint128::from(5u128),
+                OrderDirection::Bid,
+                taker.clone(),
+            ),
+            expected_error: None,
+        },
+        MaxPriceImpactTestCase {
+            name: "large ask trips breaker",
+            operations: thin_bids,
+            max_price_impact: Some(Decimal256::percent(10)),
+            market_order: MarketOrder::new(
+                Uint128::from(15u128),
+                OrderDirection::Ask,
+                taker.clone(),
+            ),
+            expected_error: Some(ContractError::PriceImpactTooHigh {
+                impact: Decimal256::percent(50),
+            }),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        for operation in test.operations {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+        set_max_price_impact(deps.as_mut().storage, test.max_price_impact).unwrap();
+        let orderbook = ORDERBOOK.load(deps.as_ref().storage).unwrap();
+
+        // -- System under test --
+        let mut market_order = test.market_order;
+        let tick_bound = match market_order.order_direction {
+            OrderDirection::Bid => MAX_TICK,
+            OrderDirection::Ask => MIN_TICK,
+        };
+        let res = run_market_order(
+            deps.as_mut().storage,
+            &env,
+            &mut market_order,
+            tick_bound,
+            None,
+        );
+
+        // -- Post Test Assertions --
+        if let Some(expected_error) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            );
+
+            // Tick pointers are left untouched
+            assert_eq!(
+                ORDERBOOK.load(deps.as_ref().storage).unwrap(),
+                orderbook,
+                "{}",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+
+        res.unwrap();
+    }
+}
+struct ClientOrderIdTestCase {
+    name: &'static str,
+    first_client_order_id: Option<&'static str>,
+    second_sender: &'static str,
+    second_client_order_id: Option<String>,
+    operations_between: Vec<OrderOperation>,
+    expect_duplicate: bool,
+    expected_order_count: usize,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_place_limit_client_order_id() {
+    let quantity = Uint128::from(100u128);
+    let test_cases = vec![
+        ClientOrderIdTestCase {
+            name: "repeated client order id returns existing order",
+            first_client_order_id: Some("order-1"),
+            second_sender: DEFAULT_OWNER,
+            second_client_order_id: Some("order-1".to_string()),
+            operations_between: vec![],
+            expect_duplicate: true,
+            expected_order_count: 1,
+            expected_error: None,
+        },
+        ClientOrderIdTestCase {
+            name: "distinct client order ids place separate orders",
+            first_client_order_id: Some("order-1"),
+            second_sender: DEFAULT_OWNER,
+            second_client_order_id: Some("order-2".to_string()),
+            operations_between: vec![],
+            expect_duplicate: false,
+            expected_order_count: 2,
+            expected_error: None,
+        },
+        ClientOrderIdTestCase {
+            name: "no client order id places separate orders",
+            first_client_order_id: None,
+            second_sender: DEFAULT_OWNER,
+            second_client_order_id: None,
+            operations_between: vec![],
+            expect_duplicate: false,
+            expected_order_count: 2,
+            expected_error: None,
+        },
+        ClientOrderIdTestCase {
+            name: "client order ids are scoped per sender",
+            first_client_order_id: Some("order-1"),
+            second_sender: DEFAULT_SENDER,
+            second_client_order_id: Some("order-1".to_string()),
+            operations_between: vec![],
+            expect_duplicate: false,
+            expected_order_count: 2,
+            expected_error: None,
+        },
+        ClientOrderIdTestCase {
+            name: "client order id freed after cancel",
+            first_client_order_id: Some("order-1"),
+            second_sender: DEFAULT_OWNER,
+            second_client_order_id: Some("order-1".to_string()),
+            operations_between: vec![OrderOperation::Cancel((0, 0))],
+            expect_duplicate: false,
+            expected_order_count: 1,
+            expected_error: None,
+        },
+        ClientOrderIdTestCase {
+            name: "client order id freed after full claim",
+            first_client_order_id: Some("order-1"),
+            second_sender: DEFAULT_OWNER,
+            second_client_order_id: Some("order-1".to_string()),
+            operations_between: vec![
+                OrderOperation::RunMarket(MarketOrder::new(
+                    quantity,
+                    OrderDirection::Bid,
+                    Addr::unchecked(DEFAULT_SENDER),
+                )),
+                OrderOperation::Claim((0, 0)),
+            ],
+            expect_duplicate: false,
+            expected_order_count: 1,
+            expected_error: None,
+        },
+        ClientOrderIdTestCase {
+            name: "client order id kept after partial claim",
+            first_client_order_id: Some("order-1"),
+            second_sender: DEFAULT_OWNER,
+            second_client_order_id: Some("order-1".to_string()),
+            operations_between: vec![
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(50u128),
+                    OrderDirection::Bid,
+                    Addr::unchecked(DEFAULT_SENDER),
+                )),
+                OrderOperation::Claim((0, 0)),
+            ],
+            expect_duplicate: true,
+            expected_order_count: 1,
+            expected_error: None,
+        },
+        ClientOrderIdTestCase {
+            name: "empty client order id",
+            first_client_order_id: Some("order-1"),
+            second_sender: DEFAULT_OWNER,
+            second_client_order_id: Some(String::new()),
+            operations_between: vec![],
+            expect_duplicate: false,
+            expected_order_count: 1,
+            expected_error: Some(ContractError::InvalidClientOrderId {
+                max_length: MAX_CLIENT_ORDER_ID_LENGTH,
+            }),
+        },
+        ClientOrderIdTestCase {
+            name: "client order id too long",
+            first_client_order_id: Some("order-1"),
+            second_sender: DEFAULT_OWNER,
+            second_client_order_id: Some("a".repeat(MAX_CLIENT_ORDER_ID_LENGTH + 1)),
+            operations_between: vec![],
+            expect_duplicate: false,
+            expected_order_count: 1,
+            expected_error: Some(ContractError::InvalidClientOrderId {
+                max_length: MAX_CLIENT_ORDER_ID_LENGTH,
+            }),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        place_limit(
+            &mut deps.as_mut(),
+            env.clone(),
+            mock_info(DEFAULT_OWNER, &[coin(quantity.u128(), BASE_DENOM)]),
+            0,
+            OrderDirection::Ask,
+            quantity,
+            None,
+            None,
+            test.first_client_order_id.map(str::to_string),
+            None,
+            None,
+        )
+        .unwrap();
+
+        for operation in test.operations_between {
+            operation
+                .run(deps.as_mut(), env.clone(), mock_info(DEFAULT_OWNER, &[]))
+                .unwrap();
+        }
+        let next_order_id = ORDER_ID.load(deps.as_ref().storage).unwrap();
+
+        // -- System under test --
+        let funds = coin(quantity.u128(), BASE_DENOM);
+        let res = place_limit(
+            &mut deps.as_mut(),
+            env.clone(),
+            mock_info(test.second_sender, std::slice::from_ref(&funds)),
+            0,
+            OrderDirection::Ask,
+            quantity,
+            None,
+            None,
+            test.second_client_order_id.clone(),
+            None,
+            None,
+        );
+
+        // -- Post Test Assertions --
+        let order_count = orders()
+            .prefix(0)
+            .range(deps.as_ref().storage, None, None, Order::Ascending)
+            .count();
+        assert_eq!(
+            order_count,
+            test.expected_order_count,
+            "{}",
+            format_test_name(test.name)
+        );
+
+        if let Some(expected_error) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+
+        let res = res.unwrap();
+        let data: PlaceLimitResponseData = from_json(res.data.unwrap()).unwrap();
+        if test.expect_duplicate {
+            // The existing order is returned and the funds are refunded
+            assert_eq!(
+                data,
+                PlaceLimitResponseData {
+                    tick_id: 0,
+                    order_id: 0
+                },
+                "{}",
+                format_test_name(test.name)
+            );
+            assert_eq!(
+                res.messages,
+                vec![SubMsg::reply_on_error(
+                    BankMsg::Send {
+                        to_address: test.second_sender.to_string(),
+                        amount: vec![funds],
+                    },
+                    REPLY_ID_REFUND,
+                )],
+                "{}",
+                format_test_name(test.name)
+            );
+            assert_eq!(
+                ORDER_ID.load(deps.as_ref().storage).unwrap(),
+                next_order_id,
+                "{}",
+                format_test_name(test.name)
+            );
+        } else {
+            assert_eq!(
+                data,
+                PlaceLimitResponseData {
+                    tick_id: 0,
+                    order_id: next_order_id
+                },
+                "{}",
+                format_test_name(test.name)
+            );
+            assert!(res.messages.is_empty(), "{}", format_test_name(test.name));
+        }
+    }
+}
+struct PlaceLimitMemoTestCase {
+    name: &'static str,
+    memo: Option<String>,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_place_limit_memo() {
+    let quantity = Uint128::from(100u128);
+    let test_cases = vec![
+        PlaceLimitMemoTestCase {
+            name: "no memo",
+            memo: None,
+            expected_error: None,
+        },
+        PlaceLimitMemoTestCase {
+            name: "memo stored with order",
+            memo: Some("rebalance #42".to_string()),
+            expected_error: None,
+        },
+        PlaceLimitMemoTestCase {
+            name: "memo at max length",
+            memo: Some("a".repeat(MAX_MEMO_LENGTH)),
+            expected_error: None,
+        },
+        PlaceLimitMemoTestCase {
+            name: "memo too long",
+            memo: Some("a".repeat(MAX_MEMO_LENGTH + 1)),
+            expected_error: Some(ContractError::MemoTooLong {
+                max_length: MAX_MEMO_LENGTH,
+            }),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        // -- System under test --
+        let res = place_limit(
+            &mut deps.as_mut(),
+            env,
+            mock_info(DEFAULT_OWNER, &[coin(quantity.u128(), BASE_DENOM)]),
+            0,
+            OrderDirection::Ask,
+            quantity,
+            None,
+            None,
+            None,
+            test.memo.clone(),
+            None,
+        );
+
+        // -- Post Test Assertions --
+        if let Some(expected_error) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            );
+            assert!(
+                orders()
+                    .may_load(deps.as_ref().storage, &(0, 0))
+                    .unwrap()
+                    .is_none(),
+                "{}",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+        res.unwrap();
+
+        let order = query::order(deps.as_ref(), 0, 0).unwrap().unwrap().order;
+        assert_eq!(order.memo, test.memo, "{}", format_test_name(test.name));
+    }
+}
+
+struct PlaceLimitCrossingTestCase {
+    name: &'static str,
+    operations: Vec<OrderOperation>,
+    tick_id: i64,
+    order_direction: OrderDirection,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_place_limit_crossing() {
+    let quantity = Uint128::from(100u128);
+    let owner = Addr::unchecked(DEFAULT_OWNER);
+    let test_cases = vec![
+        PlaceLimitCrossingTestCase {
+            name: "bid above best ask",
+            operations: vec![OrderOperation::PlaceLimit(LimitOrder::new(
+                0,
+                0,
+                OrderDirection::Ask,
+                owner.clone(),
+                quantity,
+                Decimal256::zero(),
+                None,
+            ))],
+            tick_id: 1,
+            order_direction: OrderDirection::Bid,
+            expected_error: Some(ContractError::CrossingLimitOrder {
+                tick_id: 1,
+                best_opposite_tick: 0,
+            }),
+        },
+        PlaceLimitCrossingTestCase {
+            name: "ask below best bid",
+            operations: vec![OrderOperation::PlaceLimit(LimitOrder::new(
+                0,
+                0,
+                OrderDirection::Bid,
+                owner.clone(),
+                quantity,
+                Decimal256::zero(),
+                None,
+            ))],
+            tick_id: -1,
+            order_direction: OrderDirection::Ask,
+            expected_error: Some(ContractError::CrossingLimitOrder {
+                tick_id: -1,
+                best_opposite_tick: 0,
+            }),
+        },
+        PlaceLimitCrossingTestCase {
+            name: "bid on best ask tick",
+            operations: vec![OrderOperation::PlaceLimit(LimitOrder::new(
+                0,
+                0,
+                OrderDirection::Ask,
+                owner.clone(),
+                quantity,
+                Decimal256::zero(),
+                None,
+            ))],
+            tick_id: 0,
+            order_direction: OrderDirection::Bid,
+            expected_error: None,
+        },
+        PlaceLimitCrossingTestCase {
+            name: "ask above best bid",
+            operations: vec![OrderOperation::PlaceLimit(LimitOrder::new(
+                0,
+                0,
+                OrderDirection::Bid,
+                owner.clone(),
+                quantity,
+                Decimal256::zero(),
+                None,
+            ))],
+            tick_id: 1,
+            order_direction: OrderDirection::Ask,
+            expected_error: None,
+        },
+        PlaceLimitCrossingTestCase {
+            name: "bid above emptied ask tick",
+            operations: vec![
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    0,
+                    0,
+                    OrderDirection::Ask,
+                    owner.clone(),
+                    quantity,
+                    Decimal256::zero(),
+                    None,
+                )),
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    LARGE_POSITIVE_TICK,
+                    1,
+                    OrderDirection::Ask,
+                    owner.clone(),
+                    quantity,
+                    Decimal256::zero(),
+                    None,
+                )),
+                // Fully fills tick 0, leaving the ask pointer on the emptied tick
+                OrderOperation::RunMarket(MarketOrder::new(
+                    quantity,
+                    OrderDirection::Bid,
+                    owner.clone(),
+                )),
+            ],
+            tick_id: 1,
+            order_direction: OrderDirection::Bid,
+            expected_error: None,
+        },
+        PlaceLimitCrossingTestCase {
+            name: "bid above ask behind emptied ask tick",
+            operations: vec![
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    0,
+                    0,
+                    OrderDirection::Ask,
+                    owner.clone(),
+                    quantity,
+                    Decimal256::zero(),
+                    None,
+                )),
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    1,
+                    1,
+                    OrderDirection::Ask,
+                    owner.clone(),
+                    quantity,
+                    Decimal256::zero(),
+                    None,
+                )),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    quantity,
+                    OrderDirection::Bid,
+                    owner.clone(),
+                )),
+            ],
+            tick_id: 2,
+            order_direction: OrderDirection::Bid,
+            expected_error: Some(ContractError::CrossingLimitOrder {
+                tick_id: 2,
+                best_opposite_tick: 1,
+            }),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        for operation in test.operations {
+            operation
+                .run(deps.as_mut(), env.clone(), mock_info(DEFAULT_OWNER, &[]))
+                .unwrap();
+        }
+
+        // -- System under test --
+        let denom = match test.order_direction {
+            OrderDirection::Bid => QUOTE_DENOM,
+            OrderDirection::Ask => BASE_DENOM,
+        };
+        let res = place_limit(
+            &mut deps.as_mut(),
+            env.clone(),
+            mock_info(DEFAULT_SENDER, &[coin(quantity.u128(), denom)]),
+            test.tick_id,
+            test.order_direction,
+            quantity,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+
+        // -- Post Test Assertions --
+        match test.expected_error {
+            Some(expected_error) => assert_eq!(
+                res.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            ),
+            None => assert!(res.is_ok(), "{}", format_test_name(test.name)),
+        }
+    }
+}
+
+struct CrossingTickPolicyTestCase {
+    name: &'static str,
+    operations: Vec<OrderOperation>,
+    policy: CrossingTickPolicy,
+    tick_id: i64,
+    order_direction: OrderDirection,
+    // (next_bid_tick, next_ask_tick) after the placement
+    expected_pointers: (i64, i64),
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_place_limit_crossing_tick_policy() {
+    let quantity = Uint128::from(100u128);
+    let owner = Addr::unchecked(DEFAULT_OWNER);
+    let place = |tick_id: i64, order_id: u64, direction: OrderDirection| {
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            tick_id,
+            order_id,
+            direction,
+            owner.clone(),
+            quantity,
+            Decimal256::zero(),
+            None,
+        ))
+    };
+    // Asks on ticks 0 and 10, with tick 0 fully filled leaving the ask pointer on the emptied tick
+    let emptied_ask_tick = vec![
+        place(0, 0, OrderDirection::Ask),
+        place(10, 1, OrderDirection::Ask),
+        OrderOperation::RunMarket(MarketOrder::new(
+            quantity,
+            OrderDirection::Bid,
+            owner.clone(),
+        )),
+    ];
+
+    let test_cases = vec![
+        CrossingTickPolicyTestCase {
+            name: "bid on best ask tick rests",
+            operations: vec![place(0, 0, OrderDirection::Ask)],
+            policy: CrossingTickPolicy::Rest,
+            tick_id: 0,
+            order_direction: OrderDirection::Bid,
+            expected_pointers: (0, 0),
+            expected_error: None,
+        },
+        CrossingTickPolicyTestCase {
+            name: "ask on best bid tick rests",
+            operations: vec![place(0, 0, OrderDirection::Bid)],
+            policy: CrossingTickPolicy::Rest,
+            tick_id: 0,
+            order_direction: OrderDirection::Ask,
+            expected_pointers: (0, 0),
+            expected_error: None,
+        },
+        CrossingTickPolicyTestCase {
+            name: "bid on best ask tick rejected",
+            operations: vec![place(0, 0, OrderDirection::Ask)],
+            policy: CrossingTickPolicy::Reject,
+            tick_id: 0,
+            order_direction: OrderDirection::Bid,
+            expected_pointers: (MIN_TICK, 0),
+            expected_error: Some(ContractError::CrossingLimitOrder {
+                tick_id: 0,
+                best_opposite_tick: 0,
+            }),
+        },
+        CrossingTickPolicyTestCase {
+            name: "ask on best bid tick rejected",
+            operations: vec![place(0, 0, OrderDirection::Bid)],
+            policy: CrossingTickPolicy::Reject,
+            tick_id: 0,
+            order_direction: OrderDirection::Ask,
+            expected_pointers: (0, MAX_TICK),
+            expected_error: Some(ContractError::CrossingLimitOrder {
+                tick_id: 0,
+                best_opposite_tick: 0,
+            }),
+        },
+        CrossingTickPolicyTestCase {
+            name: "bid below best ask allowed under reject policy",
+            operations: vec![place(0, 0, OrderDirection::Ask)],
+            policy: CrossingTickPolicy::Reject,
+            tick_id: -1,
+            order_direction: OrderDirection::Bid,
+            expected_pointers: (-1, 0),
+            expected_error: None,
+        },
+        CrossingTickPolicyTestCase {
+            name: "bid on best ask tick behind emptied ask tick rests",
+            operations: emptied_ask_tick.clone(),
+            policy: CrossingTickPolicy::Rest,
+            tick_id: 10,
+            order_direction: OrderDirection::Bid,
+            // The ask pointer is brought up to the bid rather than left crossed behind it
+            expected_pointers: (10, 10),
+            expected_error: None,
+        },
+        CrossingTickPolicyTestCase {
+            name: "bid between emptied ask tick and best ask",
+            operations: emptied_ask_tick.clone(),
+            policy: CrossingTickPolicy::Rest,
+            tick_id: 5,
+            order_direction: OrderDirection::Bid,
+            expected_pointers: (5, 5),
+            expected_error: None,
+        },
+        CrossingTickPolicyTestCase {
+            name: "bid on best ask tick behind emptied ask tick rejected",
+            operations: emptied_ask_tick,
+            policy: CrossingTickPolicy::Reject,
+            tick_id: 10,
+            order_direction: OrderDirection::Bid,
+            expected_pointers: (MIN_TICK, 0),
+            expected_error: Some(ContractError::CrossingLimitOrder {
+                tick_id: 10,
+                best_opposite_tick: 10,
+            }),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        set_crossing_tick_policy(deps.as_mut().storage, test.policy).unwrap();
+
+        for operation in test.operations {
+            operation
+                .run(deps.as_mut(), env.clone(), mock_info(DEFAULT_OWNER, &[]))
+                .unwrap();
+        }
+
+        // -- System under test --
+        let denom = match test.order_direction {
+            OrderDirection::Bid => QUOTE_DENOM,
+            OrderDirection::Ask => BASE_DENOM,
+        };
+        let res = place_limit(
+            &mut deps.as_mut(),
+            env.clone(),
+            mock_info(DEFAULT_SENDER, &[coin(quantity.u128(), denom)]),
+            test.tick_id,
+            test.order_direction,
+            quantity,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+
+        // -- Post Test Assertions --
+        match test.expected_error {
+            Some(expected_error) => assert_eq!(
+                res.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            ),
+            None => assert!(res.is_ok(), "{}", format_test_name(test.name)),
+        }
+
+        let orderbook = get_orderbook(deps.as_ref().storage).unwrap();
+        assert_eq!(
+            (orderbook.next_bid_tick, orderbook.next_ask_tick),
+            test.expected_pointers,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct MinFeeTransferTestCase {
+    name: &'static str,
+    min_fee_transfer: Option<Uint128>,
+    expected_claimed: u128,
+    expected_maker_fee: Option<u128>,
+}
+
+#[test]
+fn test_min_fee_transfer() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let maker_fee_recipient = Addr::unchecked("maker");
+    let env = mock_env();
+    // A 2% maker fee on a 100 unit claim charges a fee of 2
+    let test_cases = vec![
+        MinFeeTransferTestCase {
+            name: "no threshold",
+            min_fee_transfer: None,
+            expected_claimed: 98,
+            expected_maker_fee: Some(2),
+        },
+        MinFeeTransferTestCase {
+            name: "fee above threshold",
+            min_fee_transfer: Some(Uint128::one()),
+            expected_claimed: 98,
+            expected_maker_fee: Some(2),
+        },
+        MinFeeTransferTestCase {
+            name: "fee at threshold",
+            min_fee_transfer: Some(Uint128::from(2u128)),
+            expected_claimed: 98,
+            expected_maker_fee: Some(2),
+        },
+        MinFeeTransferTestCase {
+            name: "fee below threshold",
+            min_fee_transfer: Some(Uint128::from(3u128)),
+            expected_claimed: 100,
+            expected_maker_fee: None,
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let placed_order = LimitOrder::new(
+            0,
+            0,
+            OrderDirection::Bid,
+            sender.clone(),
+            Uint128::from(100u128),
+            Decimal256::zero(),
+            None,
+        );
+        ORDERBOOK
+            .save(
+                deps.as_mut().storage,
+                &Orderbook::new(QUOTE_DENOM.to_string(), BASE_DENOM.to_string(), 0, 0, 0),
+            )
+            .unwrap();
+        orders()
+            .save(deps.as_mut().storage, &(0, 0), &placed_order)
+            .unwrap();
+        increment_tick_order_count(deps.as_mut().storage, 0, placed_order.order_direction).unwrap();
+        increment_order_count(deps.as_mut().storage, &placed_order.owner).unwrap();
+        add_queued_liquidity(deps.as_mut().storage, &placed_order, placed_order.quantity).unwrap();
+        MAKER_FEE
+            .save(deps.as_mut().storage, &Decimal256::percent(2))
+            .unwrap();
+        MAKER_FEE_RECIPIENT
+            .save(deps.as_mut().storage, &maker_fee_recipient)
+            .unwrap();
+        if let Some(min_fee_transfer) = test.min_fee_transfer {
+            set_min_fee_transfer(deps.as_mut().storage, min_fee_transfer).unwrap();
+        }
+
+        // Fill the placed order
+        let mut tick_state = TickState::default();
+        let quantity = decimal256_from_u128(placed_order.quantity);
+        tick_state.set_values(
+            OrderDirection::Bid,
+            TickValues {
+                total_amount_of_liquidity: quantity,
+                cumulative_total_value: quantity,
+                effective_total_amount_swapped: quantity,
+                cumulative_realized_cancels: Decimal256::zero(),
+                last_tick_sync_etas: quantity,
+            },
+        );
+        TICK_STATE
+            .save(deps.as_mut().storage, 0, &tick_state)
+            .unwrap();
+
+        // -- System Under Test --
+        let (_, msgs, _) = claim_order(
+            &mut deps.storage,
+            &deps.api,
+            &env,
+            sender.clone(),
+            0,
+            0,
+            false,
+        )
+        .unwrap();
+
+        // -- Post test assertions --
+        let mut expected_msgs = vec![SubMsg::reply_on_error(
+            MsgSend256 {
+                from_address: env.contract.address.to_string(),
+                to_address: sender.to_string(),
+                amount: vec![coin_u256(test.expected_claimed, BASE_DENOM)],
+            },
+            REPLY_ID_CLAIM,
+        )];
+        if let Some(maker_fee) = test.expected_maker_fee {
+            expected_msgs.push(SubMsg::reply_on_error(
+                MsgSend256 {
+                    from_address: env.contract.address.to_string(),
+                    to_address: maker_fee_recipient.to_string(),
+                    amount: vec![coin_u256(maker_fee, BASE_DENOM)],
+                },
+                REPLY_ID_MAKER_FEE,
+            ));
+        }
+        assert_eq!(msgs, expected_msgs, "{}", format_test_name(test.name));
+    }
+}
+struct PlacementFeeTestCase {
+    name: &'static str,
+    placement_fee: Option<Coin>,
+    sent: Vec<Coin>,
+    expected_fee_msg: Option<Coin>,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_place_limit_placement_fee() {
+    let quantity = Uint128::from(100u128);
+    let test_cases = vec![
+        PlacementFeeTestCase {
+            name: "no placement fee configured",
+            placement_fee: None,
+            sent: vec![coin(100, BASE_DENOM)],
+            expected_fee_msg: None,
+            expected_error: None,
+        },
+        PlacementFeeTestCase {
+            name: "zero placement fee",
+            placement_fee: Some(coin(0, "uosmo")),
+            sent: vec![coin(100, BASE_DENOM)],
+            expected_fee_msg: None,
+            expected_error: None,
+        },
+        PlacementFeeTestCase {
+            name: "placement fee in separate denom",
+            placement_fee: Some(coin(5, "uosmo")),
+            sent: vec![coin(100, BASE_DENOM), coin(5, "uosmo")],
+            expected_fee_msg: Some(coin(5, "uosmo")),
+            expected_error: None,
+        },
+        PlacementFeeTestCase {
+            name: "placement fee in order denom",
+            placement_fee: Some(coin(5, BASE_DENOM)),
+            sent: vec![coin(105, BASE_DENOM)],
+            expected_fee_msg: Some(coin(5, BASE_DENOM)),
+            expected_error: None,
+        },
+        PlacementFeeTestCase {
+            name: "missing placement fee",
+            placement_fee: Some(coin(5, "uosmo")),
+            sent: vec![coin(100, BASE_DENOM)],
+            expected_fee_msg: None,
+            expected_error: Some(ContractError::InsufficientPlacementFee {
+                denom: "uosmo".to_string(),
+                sent: Uint128::zero(),
+                required: Uint128::from(5u128),
+            }),
+        },
+        PlacementFeeTestCase {
+            name: "insufficient placement fee in separate denom",
+            placement_fee: Some(coin(5, "uosmo")),
+            sent: vec![coin(100, BASE_DENOM), coin(4, "uosmo")],
+            expected_fee_msg: None,
+            expected_error: Some(ContractError::InsufficientPlacementFee {
+                denom: "uosmo".to_string(),
+                sent: Uint128::from(4u128),
+                required: Uint128::from(5u128),
+            }),
+        },
+        PlacementFeeTestCase {
+            name: "insufficient placement fee in order denom",
+            placement_fee: Some(coin(5, BASE_DENOM)),
+            sent: vec![coin(102, BASE_DENOM)],
+            expected_fee_msg: None,
+            expected_error: Some(ContractError::InsufficientPlacementFee {
+                denom: BASE_DENOM.to_string(),
+                sent: Uint128::from(2u128),
+                required: Uint128::from(5u128),
+            }),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(DEFAULT_OWNER, &test.sent);
+
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        set_placement_fee(deps.as_mut().storage, test.placement_fee).unwrap();
+
+        // -- System under test --
+        let res = place_limit(
+            &mut deps.as_mut(),
+            env,
+            info,
+            0,
+            OrderDirection::Ask,
+            quantity,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+
+        // -- Post Test Assertions --
+        if let Some(expected_error) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            );
+            assert!(
+                orders()
+                    .may_load(deps.as_ref().storage, &(0, 0))
+                    .unwrap()
+                    .is_none(),
+                "{}",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+
+        let res = res.unwrap();
+
+        // The order is placed for the full quantity regardless of the fee
+        let order = orders().load(deps.as_ref().storage, &(0, 0)).unwrap();
+        assert_eq!(order.quantity, quantity, "{}", format_test_name(test.name));
+
+        // The fee is forwarded to the maker fee recipient
+        let expected_msgs: Vec<SubMsg> = test
+            .expected_fee_msg
+            .into_iter()
+            .map(|fee| {
+                SubMsg::new(BankMsg::Send {
+                    to_address: MAKER_FEE_RECIPIENT
+                        .load(deps.as_ref().storage)
+                        .unwrap()
+                        .to_string(),
+                    amount: vec![fee],
+                })
+            })
+            .collect();
+        assert_eq!(
+            res.messages,
+            expected_msgs,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+struct PlacePeggedLimitTestCase {
+    name: &'static str,
+    pre_operations: Vec<OrderOperation>,
+    order_direction: OrderDirection,
+    peg: PegConfig,
+    expected_tick_id: i64,
+    expected_error: Option<ContractError>,
+}
+
+fn place_single(tick_id: i64, direction: OrderDirection) -> OrderOperation {
+    OrderOperation::PlaceLimitMulti((vec![tick_id], 1, Uint128::from(10u128), direction))
+}
+
+fn peg(reference: PegReference, offset_ticks: i64) -> PegConfig {
+    PegConfig {
+        reference,
+        offset_ticks,
+    }
+}
+
+#[test]
+fn test_place_pegged_limit() {
+    let quantity = Uint128::from(10u128);
+    let test_cases = vec![
+        PlacePeggedLimitTestCase {
+            name: "bid joins best bid",
+            pre_operations: vec![place_single(-10, OrderDirection::Bid)],
+            order_direction: OrderDirection::Bid,
+            peg: peg(PegReference::BestBid, 0),
+            expected_tick_id: -10,
+            expected_error: None,
+        },
+        PlacePeggedLimitTestCase {
+            name: "bid behind best ask",
+            pre_operations: vec![place_single(100, OrderDirection::Ask)],
+            order_direction: OrderDirection::Bid,
+            peg: peg(PegReference::BestAsk, -5),
+            expected_tick_id: 95,
+            expected_error: None,
+        },
+        PlacePeggedLimitTestCase {
+            name: "ask behind best ask",
+            pre_operations: vec![
+                place_single(100, OrderDirection::Ask),
+                place_single(200, OrderDirection::Ask),
+            ],
+            order_direction: OrderDirection::Ask,
+            peg: peg(PegReference::BestAsk, 3),
+            expected_tick_id: 103,
+            expected_error: None,
+        },
+        PlacePeggedLimitTestCase {
+            name: "empty reference",
+            pre_operations: vec![place_single(100, OrderDirection::Ask)],
+            order_direction: OrderDirection::Bid,
+            peg: peg(PegReference::BestBid, 0),
+            expected_tick_id: 0,
+            expected_error: Some(ContractError::EmptyPegReference {
+                reference: PegReference::BestBid,
+            }),
+        },
+        PlacePeggedLimitTestCase {
+            name: "peg above max tick",
+            pre_operations: vec![place_single(MAX_TICK, OrderDirection::Ask)],
+            order_direction: OrderDirection::Ask,
+            peg: peg(PegReference::BestAsk, 1),
+            expected_tick_id: 0,
+            expected_error: Some(ContractError::PegOutOfBounds {
+                reference_tick: MAX_TICK,
+                offset_ticks: 1,
+            }),
+        },
+        PlacePeggedLimitTestCase {
+            name: "peg offset overflows",
+            pre_operations: vec![place_single(100, OrderDirection::Ask)],
+            order_direction: OrderDirection::Ask,
+            peg: peg(PegReference::BestAsk, i64::MAX),
+            expected_tick_id: 0,
+            expected_error: Some(ContractError::PegOutOfBounds {
+                reference_tick: 100,
+                offset_ticks: i64::MAX,
+            }),
+        },
+        PlacePeggedLimitTestCase {
+            name: "peg crosses the book",
+            pre_operations: vec![place_single(100, OrderDirection::Ask)],
+            order_direction: OrderDirection::Bid,
+            peg: peg(PegReference::BestAsk, 1),
+            expected_tick_id: 0,
+            expected_error: Some(ContractError::CrossingLimitOrder {
+                tick_id: 101,
+                best_opposite_tick: 100,
+            }),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(DEFAULT_OWNER, &[]);
+
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        for op in test.pre_operations {
+            op.run(deps.as_mut(), env.clone(), info.clone()).unwrap();
+        }
+
+        let denom = match test.order_direction {
+            OrderDirection::Bid => QUOTE_DENOM,
+            OrderDirection::Ask => BASE_DENOM,
+        };
+        let info = mock_info(DEFAULT_SENDER, &[coin(quantity.u128(), denom)]);
+
+        // -- System under test --
+        let res = place_pegged_limit(
+            deps.as_mut(),
+            env,
+            info,
+            test.order_direction,
+            quantity,
+            test.peg.clone(),
+            None,
+        );
+
+        // -- Post Test Assertions --
+        if let Some(expected_error) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+
+        let data: PlaceLimitResponseData = from_json(res.unwrap().data.unwrap()).unwrap();
+        assert_eq!(
+            data.tick_id,
+            test.expected_tick_id,
+            "{}",
+            format_test_name(test.name)
+        );
+        let order = orders()
+            .load(deps.as_ref().storage, &(data.tick_id, data.order_id))
+            .unwrap();
+        assert_eq!(order.peg, Some(test.peg), "{}", format_test_name(test.name));
+    }
+}
+
+struct RepriceTestCase {
+    name: &'static str,
+    pre_operations: Vec<OrderOperation>,
+    order_direction: OrderDirection,
+    peg: PegConfig,
+    operations: Vec<OrderOperation>,
+    expected_tick_id: i64,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_reprice() {
+    let quantity = Uint128::from(10u128);
+    let test_cases = vec![
+        RepriceTestCase {
+            name: "reference unchanged",
+            pre_operations: vec![place_single(-10, OrderDirection::Bid)],
+            order_direction: OrderDirection::Bid,
+            peg: peg(PegReference::BestBid, -2),
+            operations: vec![],
+            expected_tick_id: -12,
+            expected_error: None,
+        },
+        RepriceTestCase {
+            name: "reference moves up",
+            pre_operations: vec![place_single(-10, OrderDirection::Bid)],
+            order_direction: OrderDirection::Bid,
+            peg: peg(PegReference::BestBid, -2),
+            operations: vec![place_single(-5, OrderDirection::Bid)],
+            expected_tick_id: -7,
+            expected_error: None,
+        },
+        RepriceTestCase {
+            name: "reference moves onto existing liquidity",
+            pre_operations: vec![
+                place_single(-10, OrderDirection::Bid),
+                place_single(-3, OrderDirection::Bid),
+                place_single(100, OrderDirection::Ask),
+            ],
+            order_direction: OrderDirection::Bid,
+            peg: peg(PegReference::BestBid, -7),
+            // Pegged to -3 rather than -10 as it is the best bid
+            operations: vec![OrderOperation::Cancel((-3, 1))],
+            expected_tick_id: -17,
+            expected_error: None,
+        },
+        RepriceTestCase {
+            name: "own liquidity is not a reference",
+            pre_operations: vec![
+                place_single(-10, OrderDirection::Bid),
+                place_single(-20, OrderDirection::Bid),
+            ],
+            order_direction: OrderDirection::Bid,
+            peg: peg(PegReference::BestBid, 0),
+            operations: vec![OrderOperation::Cancel((-10, 0))],
+            expected_tick_id: -20,
+            expected_error: None,
+        },
+        RepriceTestCase {
+            name: "pegged to opposite side",
+            pre_operations: vec![
+                place_single(100, OrderDirection::Ask),
+                place_single(200, OrderDirection::Ask),
+            ],
+            order_direction: OrderDirection::Bid,
+            peg: peg(PegReference::BestAsk, -10),
+            operations: vec![OrderOperation::Cancel((100, 0))],
+            expected_tick_id: 190,
+            expected_error: None,
+        },
+        RepriceTestCase {
+            name: "unclaimed fills",
+            pre_operations: vec![place_single(-10, OrderDirection::Bid)],
+            order_direction: OrderDirection::Bid,
+            peg: peg(PegReference::BestBid, -2),
+            operations: vec![OrderOperation::RunMarket(MarketOrder::new(
+                Uint128::from(15u128),
+                OrderDirection::Ask,
+                Addr::unchecked(DEFAULT_SENDER),
+            ))],
+            expected_tick_id: 0,
+            expected_error: Some(ContractError::RepriceFilledOrder),
+        },
+        RepriceTestCase {
+            name: "claimed fills",
+            pre_operations: vec![place_single(-10, OrderDirection::Bid)],
+            order_direction: OrderDirection::Bid,
+            peg: peg(PegReference::BestBid, -2),
+            operations: vec![
+                place_single(-20, OrderDirection::Bid),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(15u128),
+                    OrderDirection::Ask,
+                    Addr::unchecked(DEFAULT_SENDER),
+                )),
+                OrderOperation::Claim((-12, 1)),
+            ],
+            // The best bid is the order itself, leaving -20 as the reference
+            expected_tick_id: -22,
+            expected_error: None,
+        },
+        RepriceTestCase {
+            name: "reference emptied",
+            pre_operations: vec![place_single(-10, OrderDirection::Bid)],
+            order_direction: OrderDirection::Bid,
+            peg: peg(PegReference::BestBid, -2),
+            operations: vec![OrderOperation::Cancel((-10, 0))],
+            expected_tick_id: 0,
+            expected_error: Some(ContractError::EmptyPegReference {
+                reference: PegReference::BestBid,
+            }),
+        },
+        RepriceTestCase {
+            name: "reprice crosses the book",
+            pre_operations: vec![
+                place_single(-10, OrderDirection::Bid),
+                place_single(0, OrderDirection::Ask),
+            ],
+            order_direction: OrderDirection::Bid,
+            peg: peg(PegReference::BestBid, 5),
+            operations: vec![place_single(-3, OrderDirection::Bid)],
+            expected_tick_id: 0,
+            expected_error: Some(ContractError::CrossingLimitOrder {
+                tick_id: 2,
+                best_opposite_tick: 0,
+            }),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(DEFAULT_OWNER, &[]);
+
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        for op in test.pre_operations {
+            op.run(deps.as_mut(), env.clone(), info.clone()).unwrap();
+        }
+
+        let denom = match test.order_direction {
+            OrderDirection::Bid => QUOTE_DENOM,
+            OrderDirection::Ask => BASE_DENOM,
+        };
+        let res = place_pegged_limit(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(DEFAULT_SENDER, &[coin(quantity.u128(), denom)]),
+            test.order_direction,
+            quantity,
+            test.peg.clone(),
+            None,
+        )
+        .unwrap();
+        let placed: PlaceLimitResponseData = from_json(res.data.unwrap()).unwrap();
+
+        for op in test.operations {
+            op.run(deps.as_mut(), env.clone(), info.clone()).unwrap();
+        }
+        let order = orders()
+            .load(deps.as_ref().storage, &(placed.tick_id, placed.order_id))
+            .unwrap();
+        let liquidity =
+            get_directional_liquidity(deps.as_ref().storage, test.order_direction).unwrap();
+
+        // -- System under test --
+        let res = reprice(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("keeper", &[]),
+            placed.tick_id,
+            placed.order_id,
+        );
+
+        // -- Post Test Assertions --
+        if let Some(expected_error) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+        res.unwrap();
+        verify_all_trees(deps.as_ref().storage);
+
+        // The order is moved to the pegged tick with its remaining quantity
+        let repriced = orders()
+            .load(
+                deps.as_ref().storage,
+                &(test.expected_tick_id, placed.order_id),
+            )
+            .unwrap();
+        assert_eq!(
+            repriced.quantity,
+            order.quantity,
+            "{}",
+            format_test_name(test.name)
+        );
+        assert_eq!(
+            get_directional_liquidity(deps.as_ref().storage, test.order_direction).unwrap(),
+            liquidity,
+            "{}",
+            format_test_name(test.name)
+        );
+        if test.expected_tick_id == placed.tick_id {
+            assert_eq!(repriced, order, "{}", format_test_name(test.name));
+            continue;
+        }
+        // Lookups of the old tick point to where the order moved
+        assert_eq!(
+            load_order(deps.as_ref().storage, placed.tick_id, placed.order_id).unwrap_err(),
+            ContractError::OrderNotFound {
+                tick_id: placed.tick_id,
+                order_id: placed.order_id,
+                reason: Some(OrderNotFoundReason::Moved {
+                    tick_id: test.expected_tick_id,
+                }),
+            },
+            "{}",
+            format_test_name(test.name)
+        );
+
+        // Priority is reset, queueing the order behind all liquidity on the new tick
+        let tick_values = TICK_STATE
+            .load(deps.as_ref().storage, test.expected_tick_id)
+            .unwrap()
+            .get_values(test.order_direction);
+        assert_eq!(
+            repriced.etas,
+            tick_values
+                .cumulative_total_value
+                .checked_sub(Decimal256::from_ratio(repriced.quantity, 1u128))
+                .unwrap(),
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+struct PlaceLimitFundsTestCase {
+    name: &'static str,
+    order_direction: OrderDirection,
+    quantity: Uint128,
+    funds: Vec<Coin>,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_place_limit_funds_denom() {
+    let test_cases = vec![
+        PlaceLimitFundsTestCase {
+            name: "ask funded with base denom",
+            order_direction: OrderDirection::Ask,
+            quantity: Uint128::new(100),
+            funds: vec![coin(100, BASE_DENOM)],
+            expected_error: None,
+        },
+        PlaceLimitFundsTestCase {
+            name: "ask funded with quote denom",
+            order_direction: OrderDirection::Ask,
+            quantity: Uint128::new(100),
+            funds: vec![coin(100, QUOTE_DENOM)],
+            expected_error: Some(ContractError::WrongDenom {
+                expected: BASE_DENOM.to_string(),
+                received: QUOTE_DENOM.to_string(),
+            }),
+        },
+        PlaceLimitFundsTestCase {
+            name: "bid funded with base denom",
+            order_direction: OrderDirection::Bid,
+            quantity: Uint128::new(100),
+            funds: vec![coin(100, BASE_DENOM)],
+            expected_error: Some(ContractError::WrongDenom {
+                expected: QUOTE_DENOM.to_string(),
+                received: BASE_DENOM.to_string(),
+            }),
+        },
+        PlaceLimitFundsTestCase {
+            name: "bid funded with unrelated denom",
+            order_direction: OrderDirection::Bid,
+            quantity: Uint128::new(100),
+            funds: vec![coin(100, "uother")],
+            expected_error: Some(ContractError::WrongDenom {
+                expected: QUOTE_DENOM.to_string(),
+                received: "uother".to_string(),
+            }),
+        },
+        PlaceLimitFundsTestCase {
+            name: "ask underfunded with base denom",
+            order_direction: OrderDirection::Ask,
+            quantity: Uint128::new(100),
+            funds: vec![coin(50, BASE_DENOM)],
+            expected_error: Some(ContractError::InsufficientFunds {
+                sent: Uint128::new(50),
+                required: Uint128::new(100),
+            }),
+        },
+        PlaceLimitFundsTestCase {
+            name: "both denoms sent",
+            order_direction: OrderDirection::Ask,
+            quantity: Uint128::new(100),
+            funds: vec![coin(100, BASE_DENOM), coin(100, QUOTE_DENOM)],
+            expected_error: Some(ContractError::PaymentError(PaymentError::MultipleDenoms {})),
+        },
+        PlaceLimitFundsTestCase {
+            name: "no funds sent",
+            order_direction: OrderDirection::Ask,
+            quantity: Uint128::new(100),
+            funds: vec![],
+            expected_error: Some(ContractError::PaymentError(PaymentError::NoFunds {})),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(DEFAULT_OWNER, &test.funds);
+
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        // -- System under test --
+        let res = place_limit(
+            &mut deps.as_mut(),
+            env,
+            info,
+            1,
+            test.order_direction,
+            test.quantity,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+
+        // -- Post Test Assertions --
+        match test.expected_error {
+            Some(expected_error) => assert_eq!(
+                res.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            ),
+            None => {
+                res.unwrap();
+            }
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use cosmwasm_std::{from_json, to_json_string};
+
+use crate::types::OrderDirection;
+
+use super::test_utils::format_test_name;
+
+struct DeserializeDirectionTestCase {
+    name: &'static str,
+    json: &'static str,
+    expected_direction: Option<OrderDirection>,
+}
+
+#[test]
+fn test_deserialize_order_direction() {
+    let test_cases = vec![
+        DeserializeDirectionTestCase {
+            name: "bid",
+            json: "\"bid\"",
+            expected_direction: Some(OrderDirection::Bid),
+        },
+        DeserializeDirectionTestCase {
+            name: "ask",
+            json: "\"ask\"",
+            expected_direction: Some(OrderDirection::Ask),
+        },
+        DeserializeDirectionTestCase {
+            name: "buy alias",
+            json: "\"buy\"",
+            expected_direction: Some(OrderDirection::Bid),
+        },
+        DeserializeDirectionTestCase {
+            name: "sell alias",
+            json: "\"sell\"",
+            expected_direction: Some(OrderDirection::Ask),
+        },
+        DeserializeDirectionTestCase {
+            name: "unknown direction",
+            json: "\"long\"",
+            expected_direction: None,
+        },
+    ];
+
+    for test in test_cases {
+        let res = from_json::<OrderDirection>(test.json.as_bytes());
+
+        match test.expected_direction {
+            Some(expected_direction) => {
+                assert_eq!(
+                    res.unwrap(),
+                    expected_direction,
+                    "{}",
+                    format_test_name(test.name)
+                );
+            }
+            None => assert!(res.is_err(), "{}", format_test_name(test.name)),
+        }
+    }
+}
+
+#[test]
+fn test_order_direction_canonical_form() {
+    for (direction, expected) in [(OrderDirection::Bid, "bid"), (OrderDirection::Ask, "ask")] {
+        // Attributes and storage keys use the string form
+        assert_eq!(direction.to_string(), expected);
+        // Aliases only apply to deserialization, so serialization is unchanged
+        assert_eq!(
+            to_json_string(&direction).unwrap(),
+            format!("\"{}\"", expected)
+        );
+    }
+}
@@ -1,11 +1,25 @@
+use cosmwasm_std::{
+    coin,
+    testing::{mock_env, mock_info},
+    Addr, Coin, Uint128,
+};
+
 use crate::{
-    constants::{MAX_TICK, MIN_TICK},
+    constants::{EXPECTED_SWAP_FEE, MAX_TICK, MIN_TICK},
+    contract::{execute, query},
+    msg::{ExecuteMsg, QueryMsg, SudoMsg},
     orderbook::*,
-    state::ORDERBOOK,
+    state::{get_next_tick_with_liquidity, ORDERBOOK},
+    sudo::sudo,
     tests::{
         mock_querier::mock_dependencies_custom,
-        test_constants::{BASE_DENOM, QUOTE_DENOM},
+        test_constants::{
+            BASE_DENOM, DEFAULT_SENDER, LARGE_NEGATIVE_TICK, LARGE_POSITIVE_TICK, QUOTE_DENOM,
+        },
+        test_utils::{format_test_name, OrderOperation},
     },
+    tick_math::{tick_to_price, tick_to_price_pair},
+    types::{CachedTickPrice, MarketOrder, OrderDirection, Orderbook},
     ContractError,
 };
 
@@ -13,6 +27,9 @@ struct CreateOrderbookTestCase {
     name: &'static str,
     quote_denom: String,
     base_denom: String,
+    min_tick: Option<i64>,
+    max_tick: Option<i64>,
+    expected_tick_bounds: (i64, i64),
     expected_error: Option<ContractError>,
 }
 
@@ -23,12 +40,78 @@ fn test_create_orderbook() {
             name: "valid_orderbook",
             quote_denom: QUOTE_DENOM.to_string(),
             base_denom: BASE_DENOM.to_string(),
+            min_tick: None,
+            max_tick: None,
+            expected_tick_bounds: (MIN_TICK, MAX_TICK),
+            expected_error: None,
+        },
+        CreateOrderbookTestCase {
+            name: "custom tick bounds",
+            quote_denom: QUOTE_DENOM.to_string(),
+            base_denom: BASE_DENOM.to_string(),
+            min_tick: Some(-100),
+            max_tick: Some(100),
+            expected_tick_bounds: (-100, 100),
+            expected_error: None,
+        },
+        CreateOrderbookTestCase {
+            name: "single tick bounds",
+            quote_denom: QUOTE_DENOM.to_string(),
+            base_denom: BASE_DENOM.to_string(),
+            min_tick: Some(0),
+            max_tick: Some(0),
+            expected_tick_bounds: (0, 0),
+            expected_error: None,
+        },
+        CreateOrderbookTestCase {
+            name: "only min tick override",
+            quote_denom: QUOTE_DENOM.to_string(),
+            base_denom: BASE_DENOM.to_string(),
+            min_tick: Some(-100),
+            max_tick: None,
+            expected_tick_bounds: (-100, MAX_TICK),
+            expected_error: None,
+        },
+        CreateOrderbookTestCase {
+            name: "tick bounds clamped to global limits",
+            quote_denom: QUOTE_DENOM.to_string(),
+            base_denom: BASE_DENOM.to_string(),
+            min_tick: Some(MIN_TICK - 1),
+            max_tick: Some(MAX_TICK + 1),
+            expected_tick_bounds: (MIN_TICK, MAX_TICK),
             expected_error: None,
         },
+        CreateOrderbookTestCase {
+            name: "min tick greater than max tick",
+            quote_denom: QUOTE_DENOM.to_string(),
+            base_denom: BASE_DENOM.to_string(),
+            min_tick: Some(100),
+            max_tick: Some(-100),
+            expected_tick_bounds: (MIN_TICK, MAX_TICK),
+            expected_error: Some(ContractError::InvalidTickBounds {
+                min_tick: 100,
+                max_tick: -100,
+            }),
+        },
+        CreateOrderbookTestCase {
+            name: "min tick above global max tick",
+            quote_denom: QUOTE_DENOM.to_string(),
+            base_denom: BASE_DENOM.to_string(),
+            min_tick: Some(MAX_TICK + 1),
+            max_tick: None,
+            expected_tick_bounds: (MIN_TICK, MAX_TICK),
+            expected_error: Some(ContractError::InvalidTickBounds {
+                min_tick: MAX_TICK + 1,
+                max_tick: MAX_TICK,
+            }),
+        },
         CreateOrderbookTestCase {
             name: "invalid quote denom",
             quote_denom: "notadenom".to_string(),
             base_denom: BASE_DENOM.to_string(),
+            min_tick: None,
+            max_tick: None,
+            expected_tick_bounds: (MIN_TICK, MAX_TICK),
             expected_error: Some(ContractError::InvalidDenom {
                 denom: "notadenom".to_string(),
             }),
@@ -37,6 +120,9 @@ fn test_create_orderbook() {
             name: "invalid base denom",
             quote_denom: QUOTE_DENOM.to_string(),
             base_denom: "notadenom".to_string(),
+            min_tick: None,
+            max_tick: None,
+            expected_tick_bounds: (MIN_TICK, MAX_TICK),
             expected_error: Some(ContractError::InvalidDenom {
                 denom: "notadenom".to_string(),
             }),
@@ -45,6 +131,9 @@ fn test_create_orderbook() {
             name: "empty denom",
             quote_denom: QUOTE_DENOM.to_string(),
             base_denom: "".to_string(),
+            min_tick: None,
+            max_tick: None,
+            expected_tick_bounds: (MIN_TICK, MAX_TICK),
             expected_error: Some(ContractError::InvalidDenom {
                 denom: "".to_string(),
             }),
@@ -53,6 +142,9 @@ fn test_create_orderbook() {
             name: "duplicate denoms",
             quote_denom: QUOTE_DENOM.to_string(),
             base_denom: QUOTE_DENOM.to_string(),
+            min_tick: None,
+            max_tick: None,
+            expected_tick_bounds: (MIN_TICK, MAX_TICK),
             expected_error: Some(ContractError::DuplicateDenoms {}),
         },
     ];
@@ -66,6 +158,8 @@ fn test_create_orderbook() {
             deps.as_mut(),
             test.quote_denom.clone(),
             test.base_denom.clone(),
+            test.min_tick,
+            test.max_tick,
         );
 
         // -- Post Test Assertions --
@@ -86,5 +180,444 @@ fn test_create_orderbook() {
         assert_eq!(orderbook.current_tick, 0);
         assert_eq!(orderbook.next_bid_tick, MIN_TICK);
         assert_eq!(orderbook.next_ask_tick, MAX_TICK);
+        assert_eq!(
+            (orderbook.min_tick, orderbook.max_tick),
+            test.expected_tick_bounds,
+            "{}: tick bounds were not set correctly",
+            test.name
+        );
+    }
+}
+
+#[test]
+fn test_orderbook_denoms() {
+    let orderbook = Orderbook::new(
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+        0,
+        MIN_TICK,
+        MAX_TICK,
+    );
+
+    // Bids are placed with the quote denom and filled into the base denom
+    assert_eq!(orderbook.input_denom(&OrderDirection::Bid), QUOTE_DENOM);
+    assert_eq!(orderbook.output_denom(&OrderDirection::Bid), BASE_DENOM);
+
+    // Asks are placed with the base denom and filled into the quote denom
+    assert_eq!(orderbook.input_denom(&OrderDirection::Ask), BASE_DENOM);
+    assert_eq!(orderbook.output_denom(&OrderDirection::Ask), QUOTE_DENOM);
+
+    // Direction is derived from the same mapping
+    for direction in [OrderDirection::Bid, OrderDirection::Ask] {
+        assert_eq!(
+            orderbook
+                .direction_from_pair(
+                    orderbook.input_denom(&direction),
+                    orderbook.output_denom(&direction)
+                )
+                .unwrap(),
+            direction
+        );
+    }
+    assert_eq!(
+        orderbook
+            .direction_from_pair(QUOTE_DENOM.to_string(), QUOTE_DENOM.to_string())
+            .unwrap_err(),
+        ContractError::InvalidPair {
+            token_in_denom: QUOTE_DENOM.to_string(),
+            token_out_denom: QUOTE_DENOM.to_string(),
+        }
+    );
+}
+
+enum EntryPointMsg {
+    Execute(ExecuteMsg),
+    Sudo(SudoMsg),
+    Query(QueryMsg),
+}
+
+struct OrderbookNotFoundTestCase {
+    name: &'static str,
+    msg: EntryPointMsg,
+    funds: Vec<Coin>,
+}
+
+#[test]
+fn test_orderbook_not_found() {
+    let test_cases = vec![
+        OrderbookNotFoundTestCase {
+            name: "place limit",
+            msg: EntryPointMsg::Execute(ExecuteMsg::PlaceLimit {
+                tick_id: 0,
+                order_direction: OrderDirection::Bid,
+                quantity: Uint128::from(100u128),
+                claim_bounty: None,
+                owner: None,
+                client_order_id: None,
+                memo: None,
+                auto_settle: None,
+            }),
+            funds: vec![coin(100u128, QUOTE_DENOM)],
+        },
+        OrderbookNotFoundTestCase {
+            name: "batch claim",
+            msg: EntryPointMsg::Execute(ExecuteMsg::BatchClaim {
+                orders: vec![(0, 0)],
+                waive_bounty: false,
+            }),
+            funds: vec![],
+        },
+        OrderbookNotFoundTestCase {
+            name: "swap exact amount in",
+            msg: EntryPointMsg::Sudo(SudoMsg::SwapExactAmountIn {
+                sender: DEFAULT_SENDER.to_string(),
+                token_in: coin(100u128, QUOTE_DENOM),
+                token_out_denom: BASE_DENOM.to_string(),
+                token_out_min_amount: Uint128::one(),
+                swap_fee: EXPECTED_SWAP_FEE,
+                recipient: None,
+                max_slippage_ticks: None,
+            }),
+            funds: vec![],
+        },
+        OrderbookNotFoundTestCase {
+            name: "spot price query",
+            msg: EntryPointMsg::Query(QueryMsg::SpotPrice {
+                quote_asset_denom: QUOTE_DENOM.to_string(),
+                base_asset_denom: BASE_DENOM.to_string(),
+            }),
+            funds: vec![],
+        },
+        OrderbookNotFoundTestCase {
+            name: "calc out amount given in query",
+            msg: EntryPointMsg::Query(QueryMsg::CalcOutAmountGivenIn {
+                token_in: coin(100u128, QUOTE_DENOM),
+                token_out_denom: BASE_DENOM.to_string(),
+                swap_fee: EXPECTED_SWAP_FEE,
+            }),
+            funds: vec![],
+        },
+        OrderbookNotFoundTestCase {
+            name: "total pool liquidity query",
+            msg: EntryPointMsg::Query(QueryMsg::GetTotalPoolLiquidity {}),
+            funds: vec![],
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        // No orderbook is created
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(DEFAULT_SENDER, &test.funds);
+
+        // -- System under test --
+        let err = match test.msg {
+            EntryPointMsg::Execute(msg) => execute(deps.as_mut(), env, info, msg).unwrap_err(),
+            EntryPointMsg::Sudo(msg) => sudo(deps.as_mut(), env, msg).unwrap_err(),
+            EntryPointMsg::Query(msg) => query(deps.as_ref(), env, msg).unwrap_err(),
+        };
+
+        // -- Post Test Assertions --
+        assert_eq!(
+            err,
+            ContractError::OrderbookNotFound,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct PriceCacheTestCase {
+    name: &'static str,
+    operations: Vec<OrderOperation>,
+    expected_pointers: (i64, i64),
+}
+
+#[test]
+fn test_price_cache() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let place = |tick_ids: Vec<i64>, direction: OrderDirection| {
+        OrderOperation::PlaceLimitMulti((tick_ids, 1, Uint128::from(10u128), direction))
+    };
+
+    let test_cases = vec![
+        PriceCacheTestCase {
+            name: "new orderbook",
+            operations: vec![],
+            expected_pointers: (MIN_TICK, MAX_TICK),
+        },
+        PriceCacheTestCase {
+            name: "placements move pointers",
+            operations: vec![
+                place(vec![-2000, LARGE_NEGATIVE_TICK], OrderDirection::Bid),
+                place(vec![4010000, LARGE_POSITIVE_TICK], OrderDirection::Ask),
+            ],
+            expected_pointers: (-2000, LARGE_POSITIVE_TICK),
+        },
+        PriceCacheTestCase {
+            name: "market order moves pointer",
+            operations: vec![
+                place(vec![-2000], OrderDirection::Bid),
+                place(vec![100, LARGE_POSITIVE_TICK], OrderDirection::Ask),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(15u128),
+                    OrderDirection::Bid,
+                    sender.clone(),
+                )),
+            ],
+            expected_pointers: (-2000, LARGE_POSITIVE_TICK),
+        },
+        PriceCacheTestCase {
+            name: "cancel moves pointer",
+            operations: vec![
+                place(vec![-2000, LARGE_NEGATIVE_TICK], OrderDirection::Bid),
+                OrderOperation::Cancel((-2000, 0)),
+            ],
+            expected_pointers: (LARGE_NEGATIVE_TICK, MAX_TICK),
+        },
+        PriceCacheTestCase {
+            name: "cancel empties side of the book",
+            operations: vec![
+                place(vec![100], OrderDirection::Ask),
+                place(vec![-100], OrderDirection::Bid),
+                OrderOperation::Cancel((100, 0)),
+            ],
+            expected_pointers: (-100, MAX_TICK),
+        },
+        PriceCacheTestCase {
+            name: "fill, claim and replace",
+            operations: vec![
+                place(vec![LARGE_NEGATIVE_TICK], OrderDirection::Bid),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(5u128),
+                    OrderDirection::Ask,
+                    sender.clone(),
+                )),
+                OrderOperation::Claim((LARGE_NEGATIVE_TICK, 0)),
+                place(vec![-50], OrderDirection::Bid),
+            ],
+            expected_pointers: (-50, MAX_TICK),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        // -- System under test --
+        for op in test.operations {
+            op.run(deps.as_mut(), env.clone(), info.clone()).unwrap();
+        }
+
+        // -- Post Test Assertions --
+        let orderbook = ORDERBOOK.load(deps.as_ref().storage).unwrap();
+        assert_eq!(
+            (orderbook.next_bid_tick, orderbook.next_ask_tick),
+            test.expected_pointers,
+            "{}",
+            format_test_name(test.name)
+        );
+        for (direction, cached) in [
+            (OrderDirection::Bid, orderbook.next_bid_price),
+            (OrderDirection::Ask, orderbook.next_ask_price),
+        ] {
+            let tick_id = orderbook.next_tick(direction);
+            let (price, inverse_price) = tick_to_price_pair(tick_id).unwrap();
+            assert_eq!(
+                cached,
+                Some(CachedTickPrice {
+                    tick_id,
+                    price,
+                    inverse_price,
+                }),
+                "{}: {} price cache is stale",
+                format_test_name(test.name),
+                direction
+            );
+            assert_eq!(
+                orderbook.next_tick_price(direction).unwrap(),
+                tick_to_price(tick_id).unwrap(),
+                "{}",
+                format_test_name(test.name)
+            );
+        }
+    }
+}
+
+#[test]
+fn test_price_cache_ignores_stale_entries() {
+    let mut orderbook = Orderbook::new(
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+        0,
+        MIN_TICK,
+        MAX_TICK,
+    );
+    orderbook.refresh_price_cache().unwrap();
+
+    // Pointers moved without refreshing the cache are priced from the tick
+    orderbook.next_bid_tick = LARGE_NEGATIVE_TICK;
+    orderbook.next_ask_tick = LARGE_POSITIVE_TICK;
+    assert_eq!(
+        orderbook.next_tick_price_pair(OrderDirection::Bid).unwrap(),
+        tick_to_price_pair(LARGE_NEGATIVE_TICK).unwrap()
+    );
+    assert_eq!(
+        orderbook.next_tick_price_pair(OrderDirection::Ask).unwrap(),
+        tick_to_price_pair(LARGE_POSITIVE_TICK).unwrap()
+    );
+
+    // Refreshing only recomputes the entries that moved
+    orderbook.next_ask_tick = MAX_TICK;
+    let bid_entry = orderbook.next_bid_price;
+    orderbook.refresh_price_cache().unwrap();
+    assert_ne!(orderbook.next_bid_price, bid_entry);
+    assert_eq!(orderbook.next_ask_price.unwrap().tick_id, MAX_TICK);
+}
+
+struct FirstPlacementTestCase {
+    name: &'static str,
+    tick_bounds: (Option<i64>, Option<i64>),
+    placements: Vec<(i64, OrderDirection)>,
+    expected_pointers: (i64, i64),
+}
+
+#[test]
+fn test_first_placement_initializes_pointers() {
+    let test_cases = vec![
+        FirstPlacementTestCase {
+            name: "first ask on tick zero",
+            tick_bounds: (None, None),
+            placements: vec![(0, OrderDirection::Ask)],
+            expected_pointers: (MIN_TICK, 0),
+        },
+        FirstPlacementTestCase {
+            name: "first ask on positive tick",
+            tick_bounds: (None, None),
+            placements: vec![(LARGE_POSITIVE_TICK, OrderDirection::Ask)],
+            expected_pointers: (MIN_TICK, LARGE_POSITIVE_TICK),
+        },
+        FirstPlacementTestCase {
+            name: "first ask on negative tick",
+            tick_bounds: (None, None),
+            placements: vec![(LARGE_NEGATIVE_TICK, OrderDirection::Ask)],
+            expected_pointers: (MIN_TICK, LARGE_NEGATIVE_TICK),
+        },
+        FirstPlacementTestCase {
+            name: "first ask on max tick",
+            tick_bounds: (None, None),
+            placements: vec![(MAX_TICK, OrderDirection::Ask)],
+            expected_pointers: (MIN_TICK, MAX_TICK),
+        },
+        FirstPlacementTestCase {
+            name: "first bid on tick zero",
+            tick_bounds: (None, None),
+            placements: vec![(0, OrderDirection::Bid)],
+            expected_pointers: (0, MAX_TICK),
+        },
+        FirstPlacementTestCase {
+            name: "first bid on positive tick",
+            tick_bounds: (None, None),
+            placements: vec![(LARGE_POSITIVE_TICK, OrderDirection::Bid)],
+            expected_pointers: (LARGE_POSITIVE_TICK, MAX_TICK),
+        },
+        FirstPlacementTestCase {
+            name: "first bid on negative tick",
+            tick_bounds: (None, None),
+            placements: vec![(LARGE_NEGATIVE_TICK, OrderDirection::Bid)],
+            expected_pointers: (LARGE_NEGATIVE_TICK, MAX_TICK),
+        },
+        FirstPlacementTestCase {
+            name: "first bid on min tick",
+            tick_bounds: (None, None),
+            placements: vec![(MIN_TICK, OrderDirection::Bid)],
+            expected_pointers: (MIN_TICK, MAX_TICK),
+        },
+        FirstPlacementTestCase {
+            name: "first bid then first ask on non-zero ticks",
+            tick_bounds: (None, None),
+            placements: vec![(-500, OrderDirection::Bid), (500, OrderDirection::Ask)],
+            expected_pointers: (-500, 500),
+        },
+        FirstPlacementTestCase {
+            name: "first ask then first bid on non-zero ticks",
+            tick_bounds: (None, None),
+            placements: vec![(500, OrderDirection::Ask), (-500, OrderDirection::Bid)],
+            expected_pointers: (-500, 500),
+        },
+        FirstPlacementTestCase {
+            name: "first orders on the bounds of a restricted book",
+            tick_bounds: (Some(-1000), Some(1000)),
+            placements: vec![(-1000, OrderDirection::Bid), (1000, OrderDirection::Ask)],
+            expected_pointers: (-1000, 1000),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let sender = Addr::unchecked(DEFAULT_SENDER);
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            test.tick_bounds.0,
+            test.tick_bounds.1,
+        )
+        .unwrap();
+
+        // -- System under test --
+        for (tick_id, direction) in test.placements.iter() {
+            OrderOperation::PlaceLimitMulti((vec![*tick_id], 1, Uint128::from(10u128), *direction))
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        // -- Post Test Assertions --
+        let orderbook = ORDERBOOK.load(deps.as_ref().storage).unwrap();
+        assert_eq!(
+            (orderbook.next_bid_tick, orderbook.next_ask_tick),
+            test.expected_pointers,
+            "{}",
+            format_test_name(test.name)
+        );
+        for direction in [OrderDirection::Bid, OrderDirection::Ask] {
+            assert_eq!(
+                orderbook.next_tick_price(direction).unwrap(),
+                tick_to_price(orderbook.next_tick(direction)).unwrap(),
+                "{}",
+                format_test_name(test.name)
+            );
+        }
+
+        // Liquidity lookups from each pointer, as done by market orders, start on the first placement
+        for (tick_id, direction) in test.placements {
+            assert_eq!(
+                get_next_tick_with_liquidity(
+                    deps.as_ref().storage,
+                    direction,
+                    orderbook.next_tick(direction),
+                    None
+                )
+                .unwrap(),
+                Some(tick_id),
+                "{}",
+                format_test_name(test.name)
+            );
+        }
     }
 }
@@ -1,24 +1,37 @@
 use std::str::FromStr;
 
 use cosmwasm_std::{
-    coin,
+    coin, from_json,
     testing::{mock_env, mock_info},
-    Addr, Coin, Decimal, Decimal256, Uint128,
+    Addr, Coin, Decimal, Decimal256, Uint128, Uint256,
 };
 
 use crate::{
-    constants::{EXPECTED_SWAP_FEE, MAX_TICK, MIN_TICK},
-    orderbook::create_orderbook,
+    constants::{
+        EXPECTED_SWAP_FEE, MAX_FILL_HISTORY, MAX_TICK, MAX_TICK_SCAN_ITERATIONS, MIN_TICK,
+    },
+    contract::{execute, query as contract_query},
+    msg::{
+        ActiveTick, ActiveTicksResponse, BookStateResponse, DenomObligation, ExecuteMsg,
+        PlaceLimitResponseData, QueryMsg, SimulatePlaceLimitResponse, SolvencyResponse, SudoMsg,
+    },
+    order::{claim_limit, run_market_order},
+    orderbook::{create_orderbook, set_placement_fee},
     query,
-    state::IS_ACTIVE,
+    state::{IS_ACTIVE, PRUNE_REWARD_POOL, TICK_STATE},
+    sudo::sudo,
     tests::mock_querier::mock_dependencies_custom,
-    types::{coin_u256, Coin256, LimitOrder, MarketOrder, OrderDirection, TickState, TickValues},
+    types::{
+        coin_u256, Coin256, FillRecord, LimitOrder, MarketOrder, OrderDirection, OrderSort,
+        TickState, TickValues,
+    },
     ContractError,
 };
 
 use super::{
     test_constants::{
-        BASE_DENOM, DEFAULT_SENDER, LARGE_NEGATIVE_TICK, LARGE_POSITIVE_TICK, QUOTE_DENOM,
+        BASE_DENOM, DEFAULT_OWNER, DEFAULT_SENDER, LARGE_NEGATIVE_TICK, LARGE_POSITIVE_TICK,
+        QUOTE_DENOM,
     },
     test_utils::{decimal256_from_u128, format_test_name, generate_tick_ids, OrderOperation},
 };
@@ -392,6 +405,8 @@ fn test_query_spot_price() {
             deps.as_mut(),
             QUOTE_DENOM.to_string(),
             BASE_DENOM.to_string(),
+            None,
+            None,
         )
         .unwrap();
 
@@ -505,7 +520,7 @@ fn test_calc_out_amount_given_in() {
                     None,
                 )),
                 OrderOperation::PlaceLimit(LimitOrder::new(
-                    LARGE_POSITIVE_TICK,
+                    LARGE_NEGATIVE_TICK,
                     0,
                     OrderDirection::Bid,
                     sender.clone(),
@@ -577,7 +592,7 @@ fn test_calc_out_amount_given_in() {
                     None,
                 )),
                 OrderOperation::PlaceLimit(LimitOrder::new(
-                    0,
+                    LARGE_POSITIVE_TICK,
                     0,
                     OrderDirection::Ask,
                     sender.clone(),
@@ -670,6 +685,8 @@ fn test_calc_out_amount_given_in() {
             deps.as_mut(),
             QUOTE_DENOM.to_string(),
             BASE_DENOM.to_string(),
+            None,
+            None,
         )
         .unwrap();
 
@@ -682,6 +699,7 @@ fn test_calc_out_amount_given_in() {
 
         let res = query::calc_out_amount_given_in(
             deps.as_ref(),
+            mock_env(),
             test.token_in.clone(),
             test.token_out_denom.to_string(),
             test.swap_fee,
@@ -787,6 +805,8 @@ fn test_total_pool_liquidity() {
             deps.as_mut(),
             QUOTE_DENOM.to_string(),
             BASE_DENOM.to_string(),
+            None,
+            None,
         )
         .unwrap();
 
@@ -833,6 +853,50 @@ struct AllTicksTestCase {
 fn test_all_ticks() {
     let sender = Addr::unchecked(DEFAULT_SENDER);
 
+    // Asks rest on ticks [0, 99] and bids on ticks [-100, -1] so that the book is not crossed
+    let multi_direction_operations = vec![
+        OrderOperation::PlaceLimitMulti((
+            generate_tick_ids(100),
+            10,
+            Uint128::from(10u128),
+            OrderDirection::Ask,
+        )),
+        OrderOperation::PlaceLimitMulti((
+            generate_tick_ids(100)
+                .iter()
+                .map(|tick_id| tick_id - 100)
+                .collect(),
+            10,
+            Uint128::from(100u128),
+            OrderDirection::Bid,
+        )),
+    ];
+    let multi_direction_tick_state = |tick_id: i64| {
+        if tick_id < 0 {
+            TickState {
+                ask_values: TickValues::default(),
+                bid_values: TickValues {
+                    total_amount_of_liquidity: decimal256_from_u128(1000u128),
+                    cumulative_total_value: decimal256_from_u128(1000u128),
+                    effective_total_amount_swapped: Decimal256::zero(),
+                    cumulative_realized_cancels: Decimal256::zero(),
+                    last_tick_sync_etas: Decimal256::zero(),
+                },
+            }
+        } else {
+            TickState {
+                ask_values: TickValues {
+                    total_amount_of_liquidity: decimal256_from_u128(100u128),
+                    cumulative_total_value: decimal256_from_u128(100u128),
+                    effective_total_amount_swapped: Decimal256::zero(),
+                    cumulative_realized_cancels: Decimal256::zero(),
+                    last_tick_sync_etas: Decimal256::zero(),
+                },
+                bid_values: TickValues::default(),
+            }
+        }
+    };
+
     let test_cases: Vec<AllTicksTestCase> = vec![
         AllTicksTestCase {
             name: "Test all ticks",
@@ -911,202 +975,41 @@ fn test_all_ticks() {
         },
         AllTicksTestCase {
             name: "Multiple directions, many ticks",
-            pre_operations: vec![
-                OrderOperation::PlaceLimitMulti((
-                    generate_tick_ids(100),
-                    10,
-                    Uint128::from(10u128),
-                    OrderDirection::Ask,
-                )),
-                OrderOperation::PlaceLimitMulti((
-                    generate_tick_ids(100),
-                    10,
-                    Uint128::from(100u128),
-                    OrderDirection::Bid,
-                )),
-            ],
-            expected_output: generate_tick_ids(100)
-                .iter()
-                .map(|_| TickState {
-                    ask_values: TickValues {
-                        total_amount_of_liquidity: decimal256_from_u128(100u128),
-                        cumulative_total_value: decimal256_from_u128(100u128),
-                        effective_total_amount_swapped: Decimal256::zero(),
-                        cumulative_realized_cancels: Decimal256::zero(),
-                        last_tick_sync_etas: Decimal256::zero(),
-                    },
-                    bid_values: TickValues {
-                        total_amount_of_liquidity: decimal256_from_u128(1000u128),
-                        cumulative_total_value: decimal256_from_u128(1000u128),
-                        effective_total_amount_swapped: Decimal256::zero(),
-                        cumulative_realized_cancels: Decimal256::zero(),
-                        last_tick_sync_etas: Decimal256::zero(),
-                    },
-                })
-                .collect(),
+            pre_operations: multi_direction_operations.clone(),
+            expected_output: (-100..=99).map(multi_direction_tick_state).collect(),
             start_after: None,
             end_at: None,
             limit: None,
         },
         AllTicksTestCase {
             name: "Multiple directions, many ticks w/ limit",
-            pre_operations: vec![
-                OrderOperation::PlaceLimitMulti((
-                    generate_tick_ids(100),
-                    10,
-                    Uint128::from(10u128),
-                    OrderDirection::Ask,
-                )),
-                OrderOperation::PlaceLimitMulti((
-                    generate_tick_ids(100),
-                    10,
-                    Uint128::from(100u128),
-                    OrderDirection::Bid,
-                )),
-            ],
-            expected_output: generate_tick_ids(50)
-                .iter()
-                .map(|_| TickState {
-                    ask_values: TickValues {
-                        total_amount_of_liquidity: decimal256_from_u128(100u128),
-                        cumulative_total_value: decimal256_from_u128(100u128),
-                        effective_total_amount_swapped: Decimal256::zero(),
-                        cumulative_realized_cancels: Decimal256::zero(),
-                        last_tick_sync_etas: Decimal256::zero(),
-                    },
-                    bid_values: TickValues {
-                        total_amount_of_liquidity: decimal256_from_u128(1000u128),
-                        cumulative_total_value: decimal256_from_u128(1000u128),
-                        effective_total_amount_swapped: Decimal256::zero(),
-                        cumulative_realized_cancels: Decimal256::zero(),
-                        last_tick_sync_etas: Decimal256::zero(),
-                    },
-                })
-                .collect(),
+            pre_operations: multi_direction_operations.clone(),
+            expected_output: (-100..=-51).map(multi_direction_tick_state).collect(),
             start_after: None,
             end_at: None,
             limit: Some(50),
         },
         AllTicksTestCase {
             name: "Multiple directions, many ticks w/ start after",
-            pre_operations: vec![
-                OrderOperation::PlaceLimitMulti((
-                    generate_tick_ids(100),
-                    10,
-                    Uint128::from(10u128),
-                    OrderDirection::Ask,
-                )),
-                OrderOperation::PlaceLimitMulti((
-                    generate_tick_ids(100),
-                    10,
-                    Uint128::from(100u128),
-                    OrderDirection::Bid,
-                )),
-            ],
-            expected_output: generate_tick_ids(100)
-                .iter()
-                .enumerate()
-                .filter(|(id, _)| *id >= 90)
-                .map(|_| TickState {
-                    ask_values: TickValues {
-                        total_amount_of_liquidity: decimal256_from_u128(100u128),
-                        cumulative_total_value: decimal256_from_u128(100u128),
-                        effective_total_amount_swapped: Decimal256::zero(),
-                        cumulative_realized_cancels: Decimal256::zero(),
-                        last_tick_sync_etas: Decimal256::zero(),
-                    },
-                    bid_values: TickValues {
-                        total_amount_of_liquidity: decimal256_from_u128(1000u128),
-                        cumulative_total_value: decimal256_from_u128(1000u128),
-                        effective_total_amount_swapped: Decimal256::zero(),
-                        cumulative_realized_cancels: Decimal256::zero(),
-                        last_tick_sync_etas: Decimal256::zero(),
-                    },
-                })
-                .collect(),
-            start_after: Some(90i64),
+            pre_operations: multi_direction_operations.clone(),
+            expected_output: (-10..=99).map(multi_direction_tick_state).collect(),
+            start_after: Some(-10i64),
             end_at: None,
             limit: None,
         },
         AllTicksTestCase {
             name: "Multiple directions, many ticks w/ end at",
-            pre_operations: vec![
-                OrderOperation::PlaceLimitMulti((
-                    generate_tick_ids(100),
-                    10,
-                    Uint128::from(10u128),
-                    OrderDirection::Ask,
-                )),
-                OrderOperation::PlaceLimitMulti((
-                    generate_tick_ids(100),
-                    10,
-                    Uint128::from(100u128),
-                    OrderDirection::Bid,
-                )),
-            ],
-            expected_output: generate_tick_ids(100)
-                .iter()
-                .enumerate()
-                .filter(|(id, _)| *id <= 44)
-                .map(|_| TickState {
-                    ask_values: TickValues {
-                        total_amount_of_liquidity: decimal256_from_u128(100u128),
-                        cumulative_total_value: decimal256_from_u128(100u128),
-                        effective_total_amount_swapped: Decimal256::zero(),
-                        cumulative_realized_cancels: Decimal256::zero(),
-                        last_tick_sync_etas: Decimal256::zero(),
-                    },
-                    bid_values: TickValues {
-                        total_amount_of_liquidity: decimal256_from_u128(1000u128),
-                        cumulative_total_value: decimal256_from_u128(1000u128),
-                        effective_total_amount_swapped: Decimal256::zero(),
-                        cumulative_realized_cancels: Decimal256::zero(),
-                        last_tick_sync_etas: Decimal256::zero(),
-                    },
-                })
-                .collect(),
+            pre_operations: multi_direction_operations.clone(),
+            expected_output: (-100..=44).map(multi_direction_tick_state).collect(),
             start_after: None,
             end_at: Some(44i64),
             limit: None,
         },
         AllTicksTestCase {
             name: "Multiple directions, many ticks w/ start after & end at",
-            pre_operations: vec![
-                OrderOperation::PlaceLimitMulti((
-                    generate_tick_ids(100),
-                    10,
-                    Uint128::from(10u128),
-                    OrderDirection::Ask,
-                )),
-                OrderOperation::PlaceLimitMulti((
-                    generate_tick_ids(100),
-                    10,
-                    Uint128::from(100u128),
-                    OrderDirection::Bid,
-                )),
-            ],
-            expected_output: generate_tick_ids(100)
-                .iter()
-                .enumerate()
-                .filter(|(id, _)| *id <= 44 && *id >= 21)
-                .map(|_| TickState {
-                    ask_values: TickValues {
-                        total_amount_of_liquidity: decimal256_from_u128(100u128),
-                        cumulative_total_value: decimal256_from_u128(100u128),
-                        effective_total_amount_swapped: Decimal256::zero(),
-                        cumulative_realized_cancels: Decimal256::zero(),
-                        last_tick_sync_etas: Decimal256::zero(),
-                    },
-                    bid_values: TickValues {
-                        total_amount_of_liquidity: decimal256_from_u128(1000u128),
-                        cumulative_total_value: decimal256_from_u128(1000u128),
-                        effective_total_amount_swapped: Decimal256::zero(),
-                        cumulative_realized_cancels: Decimal256::zero(),
-                        last_tick_sync_etas: Decimal256::zero(),
-                    },
-                })
-                .collect(),
-            start_after: Some(21i64),
+            pre_operations: multi_direction_operations.clone(),
+            expected_output: (-21..=44).map(multi_direction_tick_state).collect(),
+            start_after: Some(-21i64),
             end_at: Some(44i64),
             limit: None,
         },
@@ -1169,6 +1072,8 @@ fn test_all_ticks() {
             deps.as_mut(),
             QUOTE_DENOM.to_string(),
             BASE_DENOM.to_string(),
+            None,
+            None,
         )
         .unwrap();
 
@@ -1302,7 +1207,7 @@ fn test_orders_by_owner() {
                 OrderOperation::PlaceLimit(LimitOrder::new(
                     0,
                     0,
-                    OrderDirection::Ask,
+                    OrderDirection::Bid,
                     Addr::unchecked("sender"),
                     Uint128::from(50u128),
                     Decimal256::zero(),
@@ -1311,7 +1216,7 @@ fn test_orders_by_owner() {
                 OrderOperation::PlaceLimit(LimitOrder::new(
                     1,
                     1,
-                    OrderDirection::Bid,
+                    OrderDirection::Ask,
                     Addr::unchecked("sender"),
                     Uint128::from(150u128),
                     Decimal256::zero(),
@@ -1321,7 +1226,7 @@ fn test_orders_by_owner() {
             expected_output: vec![LimitOrder::new(
                 0,
                 0,
-                OrderDirection::Ask,
+                OrderDirection::Bid,
                 Addr::unchecked("sender"),
                 Uint128::from(50u128),
                 Decimal256::zero(),
@@ -1339,7 +1244,7 @@ fn test_orders_by_owner() {
                 OrderOperation::PlaceLimit(LimitOrder::new(
                     0,
                     0,
-                    OrderDirection::Ask,
+                    OrderDirection::Bid,
                     Addr::unchecked("sender"),
                     Uint128::from(50u128),
                     Decimal256::zero(),
@@ -1348,7 +1253,7 @@ fn test_orders_by_owner() {
                 OrderOperation::PlaceLimit(LimitOrder::new(
                     1,
                     1,
-                    OrderDirection::Bid,
+                    OrderDirection::Ask,
                     Addr::unchecked("sender"),
                     Uint128::from(150u128),
                     Decimal256::zero(),
@@ -1358,7 +1263,7 @@ fn test_orders_by_owner() {
             expected_output: vec![LimitOrder::new(
                 1,
                 1,
-                OrderDirection::Bid,
+                OrderDirection::Ask,
                 Addr::unchecked("sender"),
                 Uint128::from(150u128),
                 Decimal256::zero(),
@@ -1376,7 +1281,7 @@ fn test_orders_by_owner() {
                 OrderOperation::PlaceLimit(LimitOrder::new(
                     0,
                     0,
-                    OrderDirection::Ask,
+                    OrderDirection::Bid,
                     Addr::unchecked("sender"),
                     Uint128::from(50u128),
                     Decimal256::zero(),
@@ -1385,7 +1290,7 @@ fn test_orders_by_owner() {
                 OrderOperation::PlaceLimit(LimitOrder::new(
                     1,
                     1,
-                    OrderDirection::Bid,
+                    OrderDirection::Ask,
                     Addr::unchecked("sender"),
                     Uint128::from(150u128),
                     Decimal256::zero(),
@@ -1395,7 +1300,7 @@ fn test_orders_by_owner() {
             expected_output: vec![LimitOrder::new(
                 0,
                 0,
-                OrderDirection::Ask,
+                OrderDirection::Bid,
                 Addr::unchecked("sender"),
                 Uint128::from(50u128),
                 Decimal256::zero(),
@@ -1413,7 +1318,7 @@ fn test_orders_by_owner() {
                 OrderOperation::PlaceLimit(LimitOrder::new(
                     0,
                     0,
-                    OrderDirection::Ask,
+                    OrderDirection::Bid,
                     Addr::unchecked("sender"),
                     Uint128::from(50u128),
                     Decimal256::zero(),
@@ -1422,7 +1327,7 @@ fn test_orders_by_owner() {
                 OrderOperation::PlaceLimit(LimitOrder::new(
                     1,
                     1,
-                    OrderDirection::Bid,
+                    OrderDirection::Ask,
                     Addr::unchecked("sender"),
                     Uint128::from(150u128),
                     Decimal256::zero(),
@@ -1431,7 +1336,7 @@ fn test_orders_by_owner() {
                 OrderOperation::PlaceLimit(LimitOrder::new(
                     1,
                     2,
-                    OrderDirection::Bid,
+                    OrderDirection::Ask,
                     Addr::unchecked("sender"),
                     Uint128::from(150u128),
                     Decimal256::zero(),
@@ -1440,7 +1345,7 @@ fn test_orders_by_owner() {
                 OrderOperation::PlaceLimit(LimitOrder::new(
                     0,
                     3,
-                    OrderDirection::Ask,
+                    OrderDirection::Bid,
                     Addr::unchecked("sender"),
                     Uint128::from(150u128),
                     Decimal256::zero(),
@@ -1452,7 +1357,7 @@ fn test_orders_by_owner() {
                 LimitOrder::new(
                     0,
                     0,
-                    OrderDirection::Ask,
+                    OrderDirection::Bid,
                     Addr::unchecked("sender"),
                     Uint128::from(50u128),
                     Decimal256::zero(),
@@ -1461,7 +1366,7 @@ fn test_orders_by_owner() {
                 LimitOrder::new(
                     0,
                     3,
-                    OrderDirection::Ask,
+                    OrderDirection::Bid,
                     Addr::unchecked("sender"),
                     Uint128::from(150u128),
                     decimal256_from_u128(50u128),
@@ -1470,7 +1375,7 @@ fn test_orders_by_owner() {
                 LimitOrder::new(
                     1,
                     2,
-                    OrderDirection::Bid,
+                    OrderDirection::Ask,
                     Addr::unchecked("sender"),
                     Uint128::from(150u128),
                     decimal256_from_u128(150u128),
@@ -1495,6 +1400,8 @@ fn test_orders_by_owner() {
             deps.as_mut(),
             quote_denom.to_string(),
             base_denom.to_string(),
+            None,
+            None,
         )
         .unwrap();
 
@@ -1511,6 +1418,7 @@ fn test_orders_by_owner() {
             test.start_from,
             test.end_at,
             test.limit,
+            None,
         );
 
         if let Some(err) = test.expected_error {
@@ -1534,7 +1442,7 @@ fn test_orders_by_owner() {
             res,
             test.expected_output
                 .iter()
-                .map(|o| o.clone().with_placed_at(env.block.time))
+                .map(|o| o.clone().with_placed_at(&env.block))
                 .collect::<Vec<LimitOrder>>(),
             "{}: output did not match",
             test.name
@@ -1542,6 +1450,107 @@ fn test_orders_by_owner() {
     }
 }
 
+struct OrdersByOwnerSortTestCase {
+    name: &'static str,
+    sort_by: Option<OrderSort>,
+    expected_keys: Vec<(i64, u64)>,
+}
+
+#[test]
+fn test_orders_by_owner_sort() {
+    let owner = Addr::unchecked(DEFAULT_SENDER);
+    let test_cases = vec![
+        OrdersByOwnerSortTestCase {
+            name: "no sort",
+            sort_by: None,
+            expected_keys: vec![(-5, 2), (0, 0), (10, 1)],
+        },
+        OrdersByOwnerSortTestCase {
+            name: "tick ascending",
+            sort_by: Some(OrderSort::TickAscending),
+            expected_keys: vec![(-5, 2), (0, 0), (10, 1)],
+        },
+        OrdersByOwnerSortTestCase {
+            name: "tick descending",
+            sort_by: Some(OrderSort::TickDescending),
+            expected_keys: vec![(10, 1), (0, 0), (-5, 2)],
+        },
+        OrdersByOwnerSortTestCase {
+            name: "fill percentage",
+            sort_by: Some(OrderSort::FillPercentage),
+            expected_keys: vec![(0, 0), (-5, 2), (10, 1)],
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(DEFAULT_SENDER, &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        // The ask on tick 0 is half filled, the other orders are unfilled
+        let operations = vec![
+            OrderOperation::PlaceLimit(LimitOrder::new(
+                0,
+                0,
+                OrderDirection::Ask,
+                owner.clone(),
+                Uint128::from(100u128),
+                Decimal256::zero(),
+                None,
+            )),
+            OrderOperation::PlaceLimit(LimitOrder::new(
+                10,
+                1,
+                OrderDirection::Ask,
+                owner.clone(),
+                Uint128::from(100u128),
+                Decimal256::zero(),
+                None,
+            )),
+            OrderOperation::PlaceLimit(LimitOrder::new(
+                -5,
+                2,
+                OrderDirection::Bid,
+                owner.clone(),
+                Uint128::from(100u128),
+                Decimal256::zero(),
+                None,
+            )),
+            OrderOperation::RunMarket(MarketOrder::new(
+                Uint128::from(50u128),
+                OrderDirection::Bid,
+                Addr::unchecked("buyer"),
+            )),
+        ];
+        for operation in operations {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        // -- System under test --
+        let res =
+            query::orders_by_owner(deps.as_ref(), owner.clone(), None, None, None, test.sort_by)
+                .unwrap();
+
+        // -- Post Test Assertions --
+        let keys: Vec<(i64, u64)> = res
+            .iter()
+            .map(|order| (order.tick_id, order.order_id))
+            .collect();
+        assert_eq!(keys, test.expected_keys, "{}", format_test_name(test.name));
+    }
+}
+
 struct TestOrdersByTicksCase {
     name: &'static str,
     pre_operations: Vec<OrderOperation>,
@@ -1819,6 +1828,8 @@ fn test_orders_by_ticks() {
             deps.as_mut(),
             QUOTE_DENOM.to_string(),
             BASE_DENOM.to_string(),
+            None,
+            None,
         )
         .unwrap();
 
@@ -1858,7 +1869,7 @@ fn test_orders_by_ticks() {
             res.orders,
             test.expected_output
                 .iter()
-                .map(|o| o.clone().with_placed_at(env.block.time))
+                .map(|o| o.clone().with_placed_at(&env.block))
                 .collect::<Vec<LimitOrder>>(),
             "{}: output did not match",
             test.name
@@ -2057,6 +2068,8 @@ fn test_ticks_by_id() {
             deps.as_mut(),
             QUOTE_DENOM.to_string(),
             BASE_DENOM.to_string(),
+            None,
+            None,
         )
         .unwrap();
 
@@ -2189,6 +2202,8 @@ fn test_tick_unrealized_cancels_by_id() {
             deps.as_mut(),
             QUOTE_DENOM.to_string(),
             BASE_DENOM.to_string(),
+            None,
+            None,
         )
         .unwrap();
 
@@ -2230,3 +2245,2938 @@ fn test_tick_unrealized_cancels_by_id() {
         );
     }
 }
+
+#[test]
+fn test_fill_history() {
+    let maker = Addr::unchecked("maker");
+    let buyer = Addr::unchecked("buyer");
+    let mut deps = mock_dependencies_custom();
+    let mut env = mock_env();
+    let info = mock_info(maker.as_str(), &[]);
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+        None,
+        None,
+    )
+    .unwrap();
+
+    // Place an ask and partially fill it
+    let operations = vec![
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            0,
+            OrderDirection::Ask,
+            maker.clone(),
+            Uint128::from(100u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::RunMarket(MarketOrder::new(
+            Uint128::from(40u128),
+            OrderDirection::Bid,
+            buyer.clone(),
+        )),
+    ];
+    for operation in operations {
+        operation
+            .run(deps.as_mut(), env.clone(), info.clone())
+            .unwrap();
+    }
+    claim_limit(deps.as_mut(), env.clone(), info.clone(), 0, 0, false).unwrap();
+
+    // Fill the remainder of the order and claim in a later block
+    OrderOperation::RunMarket(MarketOrder::new(
+        Uint128::from(60u128),
+        OrderDirection::Bid,
+        buyer.clone(),
+    ))
+    .run(deps.as_mut(), env.clone(), info.clone())
+    .unwrap();
+    env.block.height += 1;
+    claim_limit(deps.as_mut(), env.clone(), info.clone(), 0, 0, false).unwrap();
+
+    // History is kept after the order is fully claimed and removed
+    let res = query::fill_history(deps.as_ref(), maker.clone(), None, None).unwrap();
+    assert_eq!(
+        res.fills,
+        vec![
+            FillRecord {
+                seq: 0,
+                tick_id: 0,
+                order_id: 0,
+                amount: Uint256::from(40u128),
+                price: Decimal256::one(),
+                block_height: env.block.height - 1,
+            },
+            FillRecord {
+                seq: 1,
+                tick_id: 0,
+                order_id: 0,
+                amount: Uint256::from(60u128),
+                price: Decimal256::one(),
+                block_height: env.block.height,
+            },
+        ]
+    );
+
+    // Pagination
+    let res = query::fill_history(deps.as_ref(), maker.clone(), Some(0), None).unwrap();
+    assert_eq!(res.fills.len(), 1);
+    assert_eq!(res.fills[0].seq, 1);
+    let res = query::fill_history(deps.as_ref(), maker.clone(), None, Some(1)).unwrap();
+    assert_eq!(res.fills.len(), 1);
+    assert_eq!(res.fills[0].seq, 0);
+
+    // Other owners have no history
+    let res = query::fill_history(deps.as_ref(), buyer, None, None).unwrap();
+    assert!(res.fills.is_empty());
+}
+
+#[test]
+fn test_fill_history_bounded() {
+    let maker = Addr::unchecked("maker");
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let info = mock_info(maker.as_str(), &[]);
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+        None,
+        None,
+    )
+    .unwrap();
+
+    let num_claims = MAX_FILL_HISTORY + 5;
+    OrderOperation::PlaceLimit(LimitOrder::new(
+        0,
+        0,
+        OrderDirection::Ask,
+        maker.clone(),
+        Uint128::from(num_claims as u128),
+        Decimal256::zero(),
+        None,
+    ))
+    .run(deps.as_mut(), env.clone(), info.clone())
+    .unwrap();
+
+    // Fill and claim the order one unit at a time
+    for _ in 0..num_claims {
+        OrderOperation::RunMarket(MarketOrder::new(
+            Uint128::one(),
+            OrderDirection::Bid,
+            Addr::unchecked("buyer"),
+        ))
+        .run(deps.as_mut(), env.clone(), info.clone())
+        .unwrap();
+        claim_limit(deps.as_mut(), env.clone(), info.clone(), 0, 0, false).unwrap();
+    }
+
+    // Only the most recent records are kept
+    let res = query::fill_history(deps.as_ref(), maker, None, Some(num_claims)).unwrap();
+    assert_eq!(res.fills.len() as u64, MAX_FILL_HISTORY);
+    assert_eq!(
+        res.fills.first().unwrap().seq,
+        num_claims - MAX_FILL_HISTORY
+    );
+    assert_eq!(res.fills.last().unwrap().seq, num_claims - 1);
+}
+
+struct DirectionalLiquidityTestCase {
+    name: &'static str,
+    pre_operations: Vec<OrderOperation>,
+    direction: OrderDirection,
+    expected_output: Decimal256,
+}
+
+#[test]
+fn test_directional_liquidity() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+
+    let test_cases = vec![
+        DirectionalLiquidityTestCase {
+            name: "empty orderbook",
+            pre_operations: vec![],
+            direction: OrderDirection::Bid,
+            expected_output: Decimal256::zero(),
+        },
+        DirectionalLiquidityTestCase {
+            name: "only counts liquidity in the queried direction",
+            pre_operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![-3, -2, -1],
+                    10,
+                    Uint128::from(10u128),
+                    OrderDirection::Bid,
+                )),
+                OrderOperation::PlaceLimitMulti((
+                    vec![1, 2, 3],
+                    10,
+                    Uint128::from(20u128),
+                    OrderDirection::Ask,
+                )),
+            ],
+            direction: OrderDirection::Ask,
+            // 3 ticks with 10 orders of 20 each
+            expected_output: decimal256_from_u128(600u128),
+        },
+        DirectionalLiquidityTestCase {
+            name: "reflects cancellations",
+            pre_operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![-1],
+                    10,
+                    Uint128::from(10u128),
+                    OrderDirection::Bid,
+                )),
+                OrderOperation::Cancel((-1, 0)),
+                OrderOperation::Cancel((-1, 1)),
+            ],
+            direction: OrderDirection::Bid,
+            expected_output: decimal256_from_u128(80u128),
+        },
+        DirectionalLiquidityTestCase {
+            name: "reflects market order fills",
+            pre_operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![0],
+                    10,
+                    Uint128::from(10u128),
+                    OrderDirection::Ask,
+                )),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(25u128),
+                    OrderDirection::Bid,
+                    sender.clone(),
+                )),
+            ],
+            direction: OrderDirection::Ask,
+            // 25 units filled at a price of 1
+            expected_output: decimal256_from_u128(75u128),
+        },
+        DirectionalLiquidityTestCase {
+            name: "claims do not change resting liquidity",
+            pre_operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![0],
+                    10,
+                    Uint128::from(10u128),
+                    OrderDirection::Ask,
+                )),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(25u128),
+                    OrderDirection::Bid,
+                    sender.clone(),
+                )),
+                OrderOperation::Claim((0, 0)),
+                OrderOperation::Claim((0, 2)),
+            ],
+            direction: OrderDirection::Ask,
+            expected_output: decimal256_from_u128(75u128),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        // Perform any setup market operations
+        for op in test.pre_operations {
+            op.run(deps.as_mut(), env.clone(), info.clone()).unwrap();
+        }
+
+        // -- System under test --
+        let res = query::directional_liquidity(deps.as_ref(), test.direction).unwrap();
+
+        assert_eq!(
+            res,
+            test.expected_output,
+            "{}: output did not match",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct BookStateTestCase {
+    name: &'static str,
+    pre_operations: Vec<OrderOperation>,
+    expected_output: BookStateResponse,
+}
+
+#[test]
+fn test_book_state() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+
+    let test_cases = vec![
+        BookStateTestCase {
+            name: "empty orderbook",
+            pre_operations: vec![],
+            expected_output: BookStateResponse {
+                bid_liquidity: Decimal256::zero(),
+                ask_liquidity: Decimal256::zero(),
+                next_bid_tick: MIN_TICK,
+                next_ask_tick: MAX_TICK,
+                spread: None,
+            },
+        },
+        BookStateTestCase {
+            name: "one sided book has no spread",
+            pre_operations: vec![OrderOperation::PlaceLimitMulti((
+                vec![-2000],
+                10,
+                Uint128::from(10u128),
+                OrderDirection::Bid,
+            ))],
+            expected_output: BookStateResponse {
+                bid_liquidity: decimal256_from_u128(100u128),
+                ask_liquidity: Decimal256::zero(),
+                next_bid_tick: -2000,
+                next_ask_tick: MAX_TICK,
+                spread: None,
+            },
+        },
+        BookStateTestCase {
+            name: "two sided book",
+            pre_operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![-2000, -9999900],
+                    10,
+                    Uint128::from(10u128),
+                    OrderDirection::Bid,
+                )),
+                OrderOperation::PlaceLimitMulti((
+                    vec![4010000, 40000000],
+                    5,
+                    Uint128::from(20u128),
+                    OrderDirection::Ask,
+                )),
+            ],
+            expected_output: BookStateResponse {
+                bid_liquidity: decimal256_from_u128(200u128),
+                ask_liquidity: decimal256_from_u128(200u128),
+                next_bid_tick: -2000,
+                next_ask_tick: 4010000,
+                // 5.01 - 0.9998
+                spread: Some(Decimal256::from_str("4.0102").unwrap()),
+            },
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        // Perform any setup market operations
+        for op in test.pre_operations {
+            op.run(deps.as_mut(), env.clone(), info.clone()).unwrap();
+        }
+
+        // -- System under test --
+        let res = query::book_state(deps.as_ref()).unwrap();
+
+        assert_eq!(
+            res,
+            test.expected_output,
+            "{}: output did not match",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct SpreadBpsTestCase {
+    name: &'static str,
+    pre_operations: Vec<OrderOperation>,
+    expected_output: Option<u64>,
+}
+
+#[test]
+fn test_spread_bps() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let place = |tick_id: i64, direction: OrderDirection| {
+        OrderOperation::PlaceLimitMulti((vec![tick_id], 1, Uint128::from(10u128), direction))
+    };
+
+    let test_cases = vec![
+        SpreadBpsTestCase {
+            name: "empty orderbook",
+            pre_operations: vec![],
+            expected_output: None,
+        },
+        SpreadBpsTestCase {
+            name: "bids only",
+            pre_operations: vec![place(-2000, OrderDirection::Bid)],
+            expected_output: None,
+        },
+        SpreadBpsTestCase {
+            name: "asks only",
+            pre_operations: vec![place(100, OrderDirection::Ask)],
+            expected_output: None,
+        },
+        SpreadBpsTestCase {
+            name: "bid and ask on the same tick",
+            pre_operations: vec![place(0, OrderDirection::Bid), place(0, OrderDirection::Ask)],
+            expected_output: Some(0),
+        },
+        SpreadBpsTestCase {
+            name: "tight spread",
+            pre_operations: vec![
+                place(-2000, OrderDirection::Bid),
+                place(100, OrderDirection::Ask),
+            ],
+            // (1.0001 - 0.9998) / 0.99995 * 10000 = 3.0001...
+            expected_output: Some(3),
+        },
+        SpreadBpsTestCase {
+            name: "wide spread",
+            pre_operations: vec![
+                place(LARGE_NEGATIVE_TICK, OrderDirection::Bid),
+                place(LARGE_POSITIVE_TICK, OrderDirection::Ask),
+            ],
+            // (2 - 0.5) / 1.25 * 10000
+            expected_output: Some(12000),
+        },
+        SpreadBpsTestCase {
+            name: "spread rounds down",
+            pre_operations: vec![
+                place(-2000, OrderDirection::Bid),
+                place(4010000, OrderDirection::Ask),
+            ],
+            // (5.01 - 0.9998) / 3.0049 * 10000 = 13345.71...
+            expected_output: Some(13345),
+        },
+        SpreadBpsTestCase {
+            name: "pointers use best ticks",
+            pre_operations: vec![
+                place(-2000, OrderDirection::Bid),
+                place(LARGE_NEGATIVE_TICK, OrderDirection::Bid),
+                place(100, OrderDirection::Ask),
+                place(LARGE_POSITIVE_TICK, OrderDirection::Ask),
+            ],
+            expected_output: Some(3),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        for op in test.pre_operations {
+            op.run(deps.as_mut(), env.clone(), info.clone()).unwrap();
+        }
+
+        // -- System under test --
+        let res = query::spread_bps(deps.as_ref()).unwrap();
+
+        // -- Post Test Assertions --
+        assert_eq!(
+            res,
+            test.expected_output,
+            "{}: output did not match",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct SolvencyTestCase {
+    name: &'static str,
+    pre_operations: Vec<OrderOperation>,
+    // (base owed, quote owed)
+    expected_owed: (u128, u128),
+}
+
+#[test]
+fn test_solvency() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+
+    let test_cases = vec![
+        SolvencyTestCase {
+            name: "empty orderbook",
+            pre_operations: vec![],
+            expected_owed: (0, 0),
+        },
+        SolvencyTestCase {
+            name: "resting orders are owed as refunds",
+            pre_operations: vec![
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    -2000,
+                    0,
+                    OrderDirection::Bid,
+                    sender.clone(),
+                    Uint128::from(100u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    4010000,
+                    1,
+                    OrderDirection::Ask,
+                    sender.clone(),
+                    Uint128::from(50u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+            ],
+            expected_owed: (50, 100),
+        },
+        SolvencyTestCase {
+            name: "filled orders are owed in the opposite denom",
+            pre_operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![LARGE_POSITIVE_TICK],
+                    2,
+                    Uint128::from(10u128),
+                    OrderDirection::Ask,
+                )),
+                // 7 quote in at a price of 2 fills 14 base across both orders
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(7u128),
+                    OrderDirection::Bid,
+                    sender.clone(),
+                )),
+            ],
+            // 6 base unfilled, 14 base filled for 7 quote
+            expected_owed: (6, 7),
+        },
+        SolvencyTestCase {
+            name: "fills account for unrealized cancels",
+            pre_operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![0],
+                    3,
+                    Uint128::from(10u128),
+                    OrderDirection::Ask,
+                )),
+                // Cancel the first order on the tick without syncing it
+                OrderOperation::Cancel((0, 0)),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(15u128),
+                    OrderDirection::Bid,
+                    sender.clone(),
+                )),
+            ],
+            // The cancelled order is skipped, so the second order is fully filled
+            // and the third order is half filled
+            expected_owed: (5, 15),
+        },
+        SolvencyTestCase {
+            name: "claimed amounts are no longer owed",
+            pre_operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![0],
+                    3,
+                    Uint128::from(10u128),
+                    OrderDirection::Ask,
+                )),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(15u128),
+                    OrderDirection::Bid,
+                    sender.clone(),
+                )),
+                OrderOperation::Claim((0, 0)),
+                OrderOperation::Claim((0, 1)),
+            ],
+            // 15 base unfilled, 5 quote left unclaimed
+            expected_owed: (15, 0),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        // Perform any setup market operations
+        for op in test.pre_operations {
+            op.run(deps.as_mut(), env.clone(), info.clone()).unwrap();
+        }
+
+        // -- System under test --
+        let res = query::solvency(deps.as_ref()).unwrap();
+
+        let (base_owed, quote_owed) = test.expected_owed;
+        assert_eq!(
+            res,
+            SolvencyResponse {
+                obligations: vec![
+                    DenomObligation {
+                        denom: BASE_DENOM.to_string(),
+                        direction: OrderDirection::Ask,
+                        owed: Uint256::from(base_owed),
+                    },
+                    DenomObligation {
+                        denom: QUOTE_DENOM.to_string(),
+                        direction: OrderDirection::Bid,
+                        owed: Uint256::from(quote_owed),
+                    },
+                ],
+            },
+            "{}: output did not match",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct ClosestTickWithLiquidityTestCase {
+    name: &'static str,
+    pre_operations: Vec<OrderOperation>,
+    direction: OrderDirection,
+    from_tick: i64,
+    expected_output: Option<i64>,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_closest_tick_with_liquidity() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let place = |tick_id: i64, order_id: u64, direction: OrderDirection| {
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            tick_id,
+            order_id,
+            direction,
+            sender.clone(),
+            Uint128::from(10u128),
+            Decimal256::zero(),
+            None,
+        ))
+    };
+
+    let test_cases = vec![
+        ClosestTickWithLiquidityTestCase {
+            name: "empty orderbook",
+            pre_operations: vec![],
+            direction: OrderDirection::Ask,
+            from_tick: 0,
+            expected_output: None,
+            expected_error: None,
+        },
+        ClosestTickWithLiquidityTestCase {
+            name: "ask scan starts at from tick",
+            pre_operations: vec![
+                place(10, 0, OrderDirection::Ask),
+                place(LARGE_POSITIVE_TICK, 1, OrderDirection::Ask),
+            ],
+            direction: OrderDirection::Ask,
+            from_tick: 10,
+            expected_output: Some(10),
+            expected_error: None,
+        },
+        ClosestTickWithLiquidityTestCase {
+            name: "ask scan moves upwards",
+            pre_operations: vec![
+                place(10, 0, OrderDirection::Ask),
+                place(LARGE_POSITIVE_TICK, 1, OrderDirection::Ask),
+            ],
+            direction: OrderDirection::Ask,
+            from_tick: 11,
+            expected_output: Some(LARGE_POSITIVE_TICK),
+            expected_error: None,
+        },
+        ClosestTickWithLiquidityTestCase {
+            name: "no ask liquidity above from tick",
+            pre_operations: vec![place(10, 0, OrderDirection::Ask)],
+            direction: OrderDirection::Ask,
+            from_tick: 11,
+            expected_output: None,
+            expected_error: None,
+        },
+        ClosestTickWithLiquidityTestCase {
+            name: "bid scan moves downwards",
+            pre_operations: vec![
+                place(-10, 0, OrderDirection::Bid),
+                place(LARGE_NEGATIVE_TICK, 1, OrderDirection::Bid),
+            ],
+            direction: OrderDirection::Bid,
+            from_tick: -11,
+            expected_output: Some(LARGE_NEGATIVE_TICK),
+            expected_error: None,
+        },
+        ClosestTickWithLiquidityTestCase {
+            name: "liquidity in the other direction is ignored",
+            pre_operations: vec![place(-10, 0, OrderDirection::Bid)],
+            direction: OrderDirection::Ask,
+            from_tick: MIN_TICK,
+            expected_output: None,
+            expected_error: None,
+        },
+        ClosestTickWithLiquidityTestCase {
+            name: "cancelled tick is skipped",
+            pre_operations: vec![
+                place(10, 0, OrderDirection::Ask),
+                place(20, 1, OrderDirection::Ask),
+                OrderOperation::Cancel((10, 0)),
+            ],
+            direction: OrderDirection::Ask,
+            from_tick: 0,
+            expected_output: Some(20),
+            expected_error: None,
+        },
+        ClosestTickWithLiquidityTestCase {
+            name: "filled tick is skipped",
+            pre_operations: vec![
+                place(0, 0, OrderDirection::Ask),
+                place(20, 1, OrderDirection::Ask),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(10u128),
+                    OrderDirection::Bid,
+                    sender.clone(),
+                )),
+            ],
+            direction: OrderDirection::Ask,
+            from_tick: 0,
+            expected_output: Some(20),
+            expected_error: None,
+        },
+        ClosestTickWithLiquidityTestCase {
+            name: "from tick out of range",
+            pre_operations: vec![],
+            direction: OrderDirection::Ask,
+            from_tick: MAX_TICK + 1,
+            expected_output: None,
+            expected_error: Some(ContractError::InvalidTickId {
+                tick_id: MAX_TICK + 1,
+            }),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        // Perform any setup market operations
+        for op in test.pre_operations {
+            op.run(deps.as_mut(), env.clone(), info.clone()).unwrap();
+        }
+
+        // -- System under test --
+        let res = query::closest_tick_with_liquidity(deps.as_ref(), test.direction, test.from_tick);
+
+        if let Some(err) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                err,
+                "{}: did not receive expected error",
+                format_test_name(test.name)
+            );
+
+            continue;
+        }
+
+        assert_eq!(
+            res.unwrap(),
+            test.expected_output,
+            "{}: output did not match",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct OrderQueryTestCase {
+    name: &'static str,
+    pre_operations: Vec<OrderOperation>,
+    tick_id: i64,
+    order_id: u64,
+    // (remaining quantity, claimable, fill percentage), or None if the order should not exist
+    expected: Option<(u128, u128, Decimal256)>,
+}
+
+#[test]
+fn test_order_query() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+
+    let test_cases = vec![
+        OrderQueryTestCase {
+            name: "order never placed",
+            pre_operations: vec![],
+            tick_id: 0,
+            order_id: 0,
+            expected: None,
+        },
+        OrderQueryTestCase {
+            name: "unfilled order",
+            pre_operations: vec![OrderOperation::PlaceLimitMulti((
+                vec![0],
+                1,
+                Uint128::from(10u128),
+                OrderDirection::Ask,
+            ))],
+            tick_id: 0,
+            order_id: 0,
+            expected: Some((10, 0, Decimal256::zero())),
+        },
+        OrderQueryTestCase {
+            name: "partially filled order",
+            pre_operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![0],
+                    1,
+                    Uint128::from(10u128),
+                    OrderDirection::Ask,
+                )),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(5u128),
+                    OrderDirection::Bid,
+                    sender.clone(),
+                )),
+            ],
+            tick_id: 0,
+            order_id: 0,
+            expected: Some((10, 5, Decimal256::percent(50))),
+        },
+        OrderQueryTestCase {
+            name: "fully filled order at non-unit price",
+            pre_operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![LARGE_POSITIVE_TICK],
+                    2,
+                    Uint128::from(10u128),
+                    OrderDirection::Ask,
+                )),
+                // 7 quote in at a price of 2 fills 14 base across both orders
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(7u128),
+                    OrderDirection::Bid,
+                    sender.clone(),
+                )),
+            ],
+            tick_id: LARGE_POSITIVE_TICK,
+            order_id: 0,
+            expected: Some((10, 5, Decimal256::percent(100))),
+        },
+        OrderQueryTestCase {
+            name: "partially filled order at non-unit price",
+            pre_operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![LARGE_POSITIVE_TICK],
+                    2,
+                    Uint128::from(10u128),
+                    OrderDirection::Ask,
+                )),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(7u128),
+                    OrderDirection::Bid,
+                    sender.clone(),
+                )),
+            ],
+            tick_id: LARGE_POSITIVE_TICK,
+            order_id: 1,
+            expected: Some((10, 2, Decimal256::percent(40))),
+        },
+        OrderQueryTestCase {
+            name: "fill accounts for unrealized cancels",
+            pre_operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![0],
+                    3,
+                    Uint128::from(10u128),
+                    OrderDirection::Ask,
+                )),
+                // Cancel the first order on the tick without syncing it
+                OrderOperation::Cancel((0, 0)),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(15u128),
+                    OrderDirection::Bid,
+                    sender.clone(),
+                )),
+            ],
+            tick_id: 0,
+            order_id: 2,
+            expected: Some((10, 5, Decimal256::percent(50))),
+        },
+        OrderQueryTestCase {
+            name: "partially claimed order",
+            pre_operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![0],
+                    1,
+                    Uint128::from(10u128),
+                    OrderDirection::Ask,
+                )),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(5u128),
+                    OrderDirection::Bid,
+                    sender.clone(),
+                )),
+                OrderOperation::Claim((0, 0)),
+            ],
+            tick_id: 0,
+            order_id: 0,
+            expected: Some((5, 0, Decimal256::zero())),
+        },
+        OrderQueryTestCase {
+            name: "fully claimed order",
+            pre_operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![0],
+                    1,
+                    Uint128::from(10u128),
+                    OrderDirection::Ask,
+                )),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(10u128),
+                    OrderDirection::Bid,
+                    sender.clone(),
+                )),
+                OrderOperation::Claim((0, 0)),
+            ],
+            tick_id: 0,
+            order_id: 0,
+            expected: None,
+        },
+        OrderQueryTestCase {
+            name: "cancelled order",
+            pre_operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![0],
+                    1,
+                    Uint128::from(10u128),
+                    OrderDirection::Ask,
+                )),
+                OrderOperation::Cancel((0, 0)),
+            ],
+            tick_id: 0,
+            order_id: 0,
+            expected: None,
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        // Perform any setup market operations
+        for op in test.pre_operations {
+            op.run(deps.as_mut(), env.clone(), info.clone()).unwrap();
+        }
+
+        // -- System under test --
+        let res = query::order(deps.as_ref(), test.tick_id, test.order_id).unwrap();
+
+        // -- Post Test Assertions --
+        let Some((quantity, claimable, fill_percentage)) = test.expected else {
+            assert!(res.is_none(), "{}", format_test_name(test.name));
+            continue;
+        };
+        let res = res.unwrap();
+        assert_eq!(res.order.tick_id, test.tick_id);
+        assert_eq!(res.order.order_id, test.order_id);
+        assert_eq!(
+            res.order.quantity,
+            Uint128::from(quantity),
+            "{}",
+            format_test_name(test.name)
+        );
+        assert_eq!(
+            res.claimable,
+            Uint256::from(claimable),
+            "{}",
+            format_test_name(test.name)
+        );
+        assert_eq!(
+            res.fill_percentage,
+            fill_percentage,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct VolumeQueryTestCase {
+    name: &'static str,
+    pre_operations: Vec<OrderOperation>,
+    // Market orders run after the pre operations, each with the tick bound it stops at
+    bounded_market_orders: Vec<(MarketOrder, i64)>,
+    expected_quote_volume: u128,
+    expected_base_volume: u128,
+}
+
+#[test]
+fn test_volume_query() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+
+    let test_cases = vec![
+        VolumeQueryTestCase {
+            name: "no market orders",
+            pre_operations: vec![OrderOperation::PlaceLimitMulti((
+                vec![0],
+                1,
+                Uint128::from(10u128),
+                OrderDirection::Ask,
+            ))],
+            bounded_market_orders: vec![],
+            expected_quote_volume: 0,
+            expected_base_volume: 0,
+        },
+        VolumeQueryTestCase {
+            name: "several market orders",
+            pre_operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![0],
+                    3,
+                    Uint128::from(10u128),
+                    OrderDirection::Ask,
+                )),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(5u128),
+                    OrderDirection::Bid,
+                    sender.clone(),
+                )),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(12u128),
+                    OrderDirection::Bid,
+                    sender.clone(),
+                )),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(8u128),
+                    OrderDirection::Bid,
+                    sender.clone(),
+                )),
+            ],
+            bounded_market_orders: vec![],
+            expected_quote_volume: 0,
+            expected_base_volume: 25,
+        },
+        VolumeQueryTestCase {
+            name: "both directions at non-unit prices",
+            pre_operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![LARGE_POSITIVE_TICK],
+                    2,
+                    Uint128::from(10u128),
+                    OrderDirection::Ask,
+                )),
+                OrderOperation::PlaceLimitMulti((
+                    vec![LARGE_NEGATIVE_TICK],
+                    1,
+                    Uint128::from(10u128),
+                    OrderDirection::Bid,
+                )),
+                // 7 quote in at a price of 2 fills 14 base
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(7u128),
+                    OrderDirection::Bid,
+                    sender.clone(),
+                )),
+                // 4 base in at a price of 0.5 fills 8 quote
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(4u128),
+                    OrderDirection::Ask,
+                    sender.clone(),
+                )),
+            ],
+            bounded_market_orders: vec![],
+            expected_quote_volume: 8,
+            expected_base_volume: 14,
+        },
+        VolumeQueryTestCase {
+            name: "partial fill counts filled output only",
+            pre_operations: vec![OrderOperation::PlaceLimitMulti((
+                vec![0, 100],
+                1,
+                Uint128::from(10u128),
+                OrderDirection::Ask,
+            ))],
+            // Requests 15 but the tick bound stops the fill after the first tick
+            bounded_market_orders: vec![(
+                MarketOrder::new(Uint128::from(15u128), OrderDirection::Bid, sender.clone()),
+                0,
+            )],
+            expected_quote_volume: 0,
+            expected_base_volume: 10,
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        // Perform any setup market operations
+        for op in test.pre_operations {
+            op.run(deps.as_mut(), env.clone(), info.clone()).unwrap();
+        }
+        for (mut order, tick_bound) in test.bounded_market_orders {
+            run_market_order(deps.as_mut().storage, &env, &mut order, tick_bound, None).unwrap();
+        }
+
+        // -- System under test --
+        let res = query::volume(deps.as_ref()).unwrap();
+
+        // -- Post Test Assertions --
+        assert_eq!(
+            res.quote_volume,
+            coin_u256(test.expected_quote_volume, QUOTE_DENOM),
+            "{}",
+            format_test_name(test.name)
+        );
+        assert_eq!(
+            res.base_volume,
+            coin_u256(test.expected_base_volume, BASE_DENOM),
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct EstimateTicksTraversedTestCase {
+    name: &'static str,
+    pre_operations: Vec<OrderOperation>,
+    quantity: Uint128,
+    direction: OrderDirection,
+    tick_bound: Option<i64>,
+    expected_ticks_traversed: u32,
+    expected_orders_consumed: u64,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_estimate_ticks_traversed() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    // Three asks of 10 at a price of 1 and two asks of 10 at a price of 2
+    let place_asks = vec![
+        OrderOperation::PlaceLimitMulti((vec![0], 3, Uint128::from(10u128), OrderDirection::Ask)),
+        OrderOperation::PlaceLimitMulti((
+            vec![LARGE_POSITIVE_TICK],
+            2,
+            Uint128::from(10u128),
+            OrderDirection::Ask,
+        )),
+    ];
+
+    let test_cases = vec![
+        EstimateTicksTraversedTestCase {
+            name: "single tick, partially consumes second order",
+            pre_operations: place_asks.clone(),
+            quantity: Uint128::from(15u128),
+            direction: OrderDirection::Bid,
+            tick_bound: None,
+            expected_ticks_traversed: 1,
+            expected_orders_consumed: 2,
+            expected_error: None,
+        },
+        EstimateTicksTraversedTestCase {
+            name: "multiple ticks",
+            pre_operations: place_asks.clone(),
+            // 30 quote fills tick 0, then 10 quote at a price of 2 fills 20 base
+            quantity: Uint128::from(40u128),
+            direction: OrderDirection::Bid,
+            tick_bound: None,
+            expected_ticks_traversed: 2,
+            expected_orders_consumed: 5,
+            expected_error: None,
+        },
+        EstimateTicksTraversedTestCase {
+            name: "skips orders already filled but not claimed",
+            pre_operations: [
+                place_asks.clone(),
+                vec![OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(15u128),
+                    OrderDirection::Bid,
+                    sender.clone(),
+                ))],
+            ]
+            .concat(),
+            // Only the remainder of the second order is filled
+            quantity: Uint128::from(5u128),
+            direction: OrderDirection::Bid,
+            tick_bound: None,
+            expected_ticks_traversed: 1,
+            expected_orders_consumed: 1,
+            expected_error: None,
+        },
+        EstimateTicksTraversedTestCase {
+            name: "tick bound stops traversal",
+            pre_operations: place_asks.clone(),
+            quantity: Uint128::from(40u128),
+            direction: OrderDirection::Bid,
+            tick_bound: Some(0),
+            expected_ticks_traversed: 1,
+            expected_orders_consumed: 3,
+            expected_error: None,
+        },
+        EstimateTicksTraversedTestCase {
+            name: "ask direction",
+            pre_operations: vec![OrderOperation::PlaceLimitMulti((
+                vec![LARGE_NEGATIVE_TICK, 0],
+                2,
+                Uint128::from(10u128),
+                OrderDirection::Bid,
+            ))],
+            // 20 base fills tick 0, then 5 base at a price of 0.5 fills 10 quote
+            quantity: Uint128::from(25u128),
+            direction: OrderDirection::Ask,
+            tick_bound: None,
+            expected_ticks_traversed: 2,
+            expected_orders_consumed: 3,
+            expected_error: None,
+        },
+        EstimateTicksTraversedTestCase {
+            name: "insufficient liquidity",
+            pre_operations: place_asks,
+            quantity: Uint128::from(1000u128),
+            direction: OrderDirection::Bid,
+            tick_bound: None,
+            expected_ticks_traversed: 0,
+            expected_orders_consumed: 0,
+            expected_error: Some(ContractError::InsufficientLiquidity),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(DEFAULT_SENDER, &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        for op in test.pre_operations {
+            op.run(deps.as_mut(), env.clone(), info.clone()).unwrap();
+        }
+
+        // -- System under test --
+        let res = query::estimate_ticks_traversed(
+            deps.as_ref(),
+            env.clone(),
+            test.quantity,
+            test.direction,
+            test.tick_bound,
+        );
+
+        // -- Post Test Assertions --
+        if let Some(err) = test.expected_error {
+            assert_eq!(res.unwrap_err(), err, "{}", format_test_name(test.name));
+            continue;
+        }
+
+        let res = res.unwrap();
+        assert_eq!(
+            res.ticks_traversed,
+            test.expected_ticks_traversed,
+            "{}",
+            format_test_name(test.name)
+        );
+        assert_eq!(
+            res.orders_consumed,
+            test.expected_orders_consumed,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct MarketFillReportTestCase {
+    name: &'static str,
+    pre_operations: Vec<OrderOperation>,
+    quantity: Uint128,
+    direction: OrderDirection,
+    tick_bound: Option<i64>,
+    // (tick_id, price, input_consumed, output_produced)
+    expected_fills: Vec<(i64, &'static str, u128, u128)>,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_market_fill_report() {
+    // Three asks of 10 at a price of 1 and two asks of 10 at a price of 2
+    let place_asks = vec![
+        OrderOperation::PlaceLimitMulti((vec![0], 3, Uint128::from(10u128), OrderDirection::Ask)),
+        OrderOperation::PlaceLimitMulti((
+            vec![LARGE_POSITIVE_TICK],
+            2,
+            Uint128::from(10u128),
+            OrderDirection::Ask,
+        )),
+    ];
+
+    let test_cases = vec![
+        MarketFillReportTestCase {
+            name: "single tick",
+            pre_operations: place_asks.clone(),
+            quantity: Uint128::from(15u128),
+            direction: OrderDirection::Bid,
+            tick_bound: None,
+            expected_fills: vec![(0, "1", 15, 15)],
+            expected_error: None,
+        },
+        MarketFillReportTestCase {
+            name: "multiple ticks",
+            pre_operations: place_asks.clone(),
+            // 30 quote fills tick 0, then 10 quote at a price of 2 fills 20 base
+            quantity: Uint128::from(40u128),
+            direction: OrderDirection::Bid,
+            tick_bound: None,
+            expected_fills: vec![(0, "1", 30, 30), (LARGE_POSITIVE_TICK, "2", 10, 20)],
+            expected_error: None,
+        },
+        MarketFillReportTestCase {
+            name: "multiple ticks, partially fills last tick",
+            pre_operations: place_asks.clone(),
+            quantity: Uint128::from(35u128),
+            direction: OrderDirection::Bid,
+            tick_bound: None,
+            expected_fills: vec![(0, "1", 30, 30), (LARGE_POSITIVE_TICK, "2", 5, 10)],
+            expected_error: None,
+        },
+        MarketFillReportTestCase {
+            name: "tick bound stops fill",
+            pre_operations: place_asks.clone(),
+            quantity: Uint128::from(40u128),
+            direction: OrderDirection::Bid,
+            tick_bound: Some(0),
+            expected_fills: vec![(0, "1", 30, 30)],
+            expected_error: None,
+        },
+        MarketFillReportTestCase {
+            name: "ask direction",
+            pre_operations: vec![OrderOperation::PlaceLimitMulti((
+                vec![LARGE_NEGATIVE_TICK, 0],
+                2,
+                Uint128::from(10u128),
+                OrderDirection::Bid,
+            ))],
+            // 20 base fills tick 0, then 5 base at a price of 0.5 fills 10 quote
+            quantity: Uint128::from(25u128),
+            direction: OrderDirection::Ask,
+            tick_bound: None,
+            expected_fills: vec![(0, "1", 20, 20), (LARGE_NEGATIVE_TICK, "0.5", 5, 10)],
+            expected_error: None,
+        },
+        MarketFillReportTestCase {
+            name: "insufficient liquidity",
+            pre_operations: place_asks,
+            quantity: Uint128::from(1000u128),
+            direction: OrderDirection::Bid,
+            tick_bound: None,
+            expected_fills: vec![],
+            expected_error: Some(ContractError::InsufficientLiquidity),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(DEFAULT_SENDER, &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        for op in test.pre_operations {
+            op.run(deps.as_mut(), env.clone(), info.clone()).unwrap();
+        }
+
+        // -- System under test --
+        let res = query::market_fill_report(
+            deps.as_ref(),
+            env.clone(),
+            test.quantity,
+            test.direction,
+            test.tick_bound,
+        );
+
+        // -- Post Test Assertions --
+        if let Some(err) = test.expected_error {
+            assert_eq!(res.unwrap_err(), err, "{}", format_test_name(test.name));
+            continue;
+        }
+
+        let res = res.unwrap();
+        let fills: Vec<(i64, Decimal256, Uint256, Uint256)> = res
+            .fills
+            .iter()
+            .map(|fill| {
+                (
+                    fill.tick_id,
+                    fill.price,
+                    fill.input_consumed,
+                    fill.output_produced,
+                )
+            })
+            .collect();
+        let expected_fills: Vec<(i64, Decimal256, Uint256, Uint256)> = test
+            .expected_fills
+            .iter()
+            .map(|(tick_id, price, input, output)| {
+                (
+                    *tick_id,
+                    Decimal256::from_str(price).unwrap(),
+                    Uint256::from(*input),
+                    Uint256::from(*output),
+                )
+            })
+            .collect();
+        assert_eq!(fills, expected_fills, "{}", format_test_name(test.name));
+
+        // The breakdown sums to the reported totals
+        let (input_sum, output_sum) = res.fills.iter().fold(
+            (Uint256::zero(), Uint256::zero()),
+            |(input, output), fill| (input + fill.input_consumed, output + fill.output_produced),
+        );
+        assert_eq!(
+            res.total_input,
+            input_sum,
+            "{}",
+            format_test_name(test.name)
+        );
+        assert_eq!(
+            res.total_output,
+            output_sum,
+            "{}",
+            format_test_name(test.name)
+        );
+
+        // The totals match what the market order actually fills
+        let tick_bound = test.tick_bound.unwrap_or(match test.direction {
+            OrderDirection::Bid => MAX_TICK,
+            OrderDirection::Ask => MIN_TICK,
+        });
+        let mut order = MarketOrder::new(
+            test.quantity,
+            test.direction,
+            Addr::unchecked(DEFAULT_SENDER),
+        );
+        let (output, ..) =
+            run_market_order(deps.as_mut().storage, &env, &mut order, tick_bound, None).unwrap();
+        assert_eq!(output, res.total_output, "{}", format_test_name(test.name));
+    }
+}
+
+struct ActiveTicksTestCase {
+    name: &'static str,
+    pre_operations: Vec<OrderOperation>,
+    direction: OrderDirection,
+    start_after: Option<i64>,
+    limit: Option<u32>,
+    // (tick_id, price, remaining)
+    expected_ticks: Vec<(i64, &'static str, u128)>,
+    expected_next_start_after: Option<i64>,
+}
+
+#[test]
+fn test_active_ticks() {
+    let place_asks_and_bid = vec![
+        OrderOperation::PlaceLimitMulti((
+            vec![-1, 0, 1],
+            1,
+            Uint128::from(10u128),
+            OrderDirection::Ask,
+        )),
+        OrderOperation::PlaceLimitMulti((vec![-5], 1, Uint128::from(10u128), OrderDirection::Bid)),
+    ];
+    let test_cases = vec![
+        ActiveTicksTestCase {
+            name: "empty orderbook",
+            pre_operations: vec![],
+            direction: OrderDirection::Ask,
+            start_after: None,
+            limit: None,
+            expected_ticks: vec![],
+            expected_next_start_after: None,
+        },
+        ActiveTicksTestCase {
+            name: "ask ticks skip bid liquidity",
+            pre_operations: place_asks_and_bid.clone(),
+            direction: OrderDirection::Ask,
+            start_after: None,
+            limit: None,
+            expected_ticks: vec![(-1, "0.9999999", 10), (0, "1", 10), (1, "1.000001", 10)],
+            expected_next_start_after: None,
+        },
+        ActiveTicksTestCase {
+            name: "bid ticks skip ask liquidity",
+            pre_operations: place_asks_and_bid.clone(),
+            direction: OrderDirection::Bid,
+            start_after: None,
+            limit: None,
+            expected_ticks: vec![(-5, "0.9999995", 10)],
+            expected_next_start_after: None,
+        },
+        ActiveTicksTestCase {
+            name: "first page",
+            pre_operations: place_asks_and_bid.clone(),
+            direction: OrderDirection::Ask,
+            start_after: None,
+            limit: Some(2),
+            expected_ticks: vec![(-1, "0.9999999", 10), (0, "1", 10)],
+            expected_next_start_after: Some(0),
+        },
+        ActiveTicksTestCase {
+            name: "last page",
+            pre_operations: place_asks_and_bid,
+            direction: OrderDirection::Ask,
+            start_after: Some(0),
+            limit: Some(2),
+            expected_ticks: vec![(1, "1.000001", 10)],
+            expected_next_start_after: None,
+        },
+        ActiveTicksTestCase {
+            name: "skips fully filled ticks",
+            pre_operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![0, 1],
+                    1,
+                    Uint128::from(10u128),
+                    OrderDirection::Ask,
+                )),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(15u128),
+                    OrderDirection::Bid,
+                    Addr::unchecked(DEFAULT_SENDER),
+                )),
+            ],
+            direction: OrderDirection::Ask,
+            start_after: None,
+            limit: None,
+            // 10 quote fills tick 0, the remaining 5 partially fills tick 1
+            expected_ticks: vec![(1, "1.000001", 5)],
+            expected_next_start_after: None,
+        },
+        ActiveTicksTestCase {
+            name: "skips cancelled ticks",
+            pre_operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![0, 1],
+                    1,
+                    Uint128::from(10u128),
+                    OrderDirection::Ask,
+                )),
+                OrderOperation::Cancel((0, 0)),
+            ],
+            direction: OrderDirection::Ask,
+            start_after: None,
+            limit: None,
+            expected_ticks: vec![(1, "1.000001", 10)],
+            expected_next_start_after: None,
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(DEFAULT_SENDER, &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        for op in test.pre_operations {
+            op.run(deps.as_mut(), env.clone(), info.clone()).unwrap();
+        }
+
+        // -- System under test --
+        let res = query::active_ticks(deps.as_ref(), test.direction, test.start_after, test.limit)
+            .unwrap();
+
+        // -- Post Test Assertions --
+        let expected_ticks: Vec<ActiveTick> = test
+            .expected_ticks
+            .into_iter()
+            .map(|(tick_id, price, remaining)| ActiveTick {
+                tick_id,
+                price: Decimal256::from_str(price).unwrap(),
+                remaining: decimal256_from_u128(remaining),
+            })
+            .collect();
+        assert_eq!(
+            res,
+            ActiveTicksResponse {
+                ticks: expected_ticks,
+                next_start_after: test.expected_next_start_after,
+            },
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+#[test]
+fn test_active_ticks_scan_limit() {
+    // -- Test Setup --
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let info = mock_info(DEFAULT_SENDER, &[]);
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+        None,
+        None,
+    )
+    .unwrap();
+
+    // More ticks without liquidity than a single page inspects, followed by one active tick
+    let scan_limit = i64::from(MAX_TICK_SCAN_ITERATIONS);
+    for tick_id in 0..scan_limit + 5 {
+        TICK_STATE
+            .save(deps.as_mut().storage, tick_id, &TickState::default())
+            .unwrap();
+    }
+    let active_tick_id = scan_limit + 10;
+    OrderOperation::PlaceLimitMulti((
+        vec![active_tick_id],
+        1,
+        Uint128::from(10u128),
+        OrderDirection::Ask,
+    ))
+    .run(deps.as_mut(), env, info)
+    .unwrap();
+
+    // -- System under test --
+    let first_page =
+        query::active_ticks(deps.as_ref(), OrderDirection::Ask, None, Some(10)).unwrap();
+    let second_page = query::active_ticks(
+        deps.as_ref(),
+        OrderDirection::Ask,
+        first_page.next_start_after,
+        Some(10),
+    )
+    .unwrap();
+
+    // -- Post Test Assertions --
+    // The first page stops at the scan limit and resumes from the last tick inspected
+    assert_eq!(
+        first_page,
+        ActiveTicksResponse {
+            ticks: vec![],
+            next_start_after: Some(scan_limit - 1),
+        }
+    );
+    assert_eq!(
+        second_page
+            .ticks
+            .iter()
+            .map(|tick| tick.tick_id)
+            .collect::<Vec<_>>(),
+        vec![active_tick_id]
+    );
+    assert_eq!(second_page.next_start_after, None);
+}
+
+struct ExportBookTestCase {
+    name: &'static str,
+    pre_operations: Vec<OrderOperation>,
+    start_after: Option<(i64, u64)>,
+    limit: u32,
+    expected_orders: Vec<(i64, u64)>,
+    expected_next_start_after: Option<(i64, u64)>,
+}
+
+#[test]
+fn test_export_book() {
+    // Order ids 0-5 are asks on ticks -1, 0 and 1, order id 6 is a bid on tick -5
+    let place_orders = vec![
+        OrderOperation::PlaceLimitMulti((
+            vec![-1, 0, 1],
+            2,
+            Uint128::from(10u128),
+            OrderDirection::Ask,
+        )),
+        OrderOperation::PlaceLimitMulti((vec![-5], 1, Uint128::from(10u128), OrderDirection::Bid)),
+    ];
+    let test_cases = vec![
+        ExportBookTestCase {
+            name: "empty orderbook",
+            pre_operations: vec![],
+            start_after: None,
+            limit: 10,
+            expected_orders: vec![],
+            expected_next_start_after: None,
+        },
+        ExportBookTestCase {
+            name: "full book in one page",
+            pre_operations: place_orders.clone(),
+            start_after: None,
+            limit: 10,
+            expected_orders: vec![(-5, 6), (-1, 0), (-1, 1), (0, 2), (0, 3), (1, 4), (1, 5)],
+            expected_next_start_after: None,
+        },
+        ExportBookTestCase {
+            name: "first page",
+            pre_operations: place_orders.clone(),
+            start_after: None,
+            limit: 4,
+            expected_orders: vec![(-5, 6), (-1, 0), (-1, 1), (0, 2)],
+            expected_next_start_after: Some((0, 2)),
+        },
+        ExportBookTestCase {
+            name: "page starting within a tick",
+            pre_operations: place_orders.clone(),
+            start_after: Some((0, 2)),
+            limit: 2,
+            expected_orders: vec![(0, 3), (1, 4)],
+            expected_next_start_after: Some((1, 4)),
+        },
+        ExportBookTestCase {
+            name: "last page",
+            pre_operations: place_orders.clone(),
+            start_after: Some((0, 2)),
+            limit: 4,
+            expected_orders: vec![(0, 3), (1, 4), (1, 5)],
+            expected_next_start_after: None,
+        },
+        ExportBookTestCase {
+            name: "cursor is stable when orders are removed between pages",
+            pre_operations: [
+                place_orders.clone(),
+                vec![
+                    OrderOperation::Cancel((0, 2)),
+                    OrderOperation::Cancel((0, 3)),
+                ],
+            ]
+            .concat(),
+            start_after: Some((0, 2)),
+            limit: 2,
+            expected_orders: vec![(1, 4), (1, 5)],
+            expected_next_start_after: Some((1, 5)),
+        },
+        ExportBookTestCase {
+            name: "filled orders remain until claimed",
+            pre_operations: [
+                place_orders,
+                vec![
+                    OrderOperation::RunMarket(MarketOrder::new(
+                        Uint128::from(20u128),
+                        OrderDirection::Bid,
+                        Addr::unchecked(DEFAULT_SENDER),
+                    )),
+                    OrderOperation::Claim((-1, 0)),
+                ],
+            ]
+            .concat(),
+            start_after: None,
+            limit: 3,
+            expected_orders: vec![(-5, 6), (-1, 1), (0, 2)],
+            expected_next_start_after: Some((0, 2)),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(DEFAULT_SENDER, &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        for op in test.pre_operations {
+            op.run(deps.as_mut(), env.clone(), info.clone()).unwrap();
+        }
+
+        // -- System under test --
+        let res = query::export_book(deps.as_ref(), test.start_after, test.limit).unwrap();
+
+        // -- Post Test Assertions --
+        let orders: Vec<(i64, u64)> = res
+            .orders
+            .iter()
+            .map(|order| (order.tick_id, order.order_id))
+            .collect();
+        assert_eq!(
+            orders,
+            test.expected_orders,
+            "{}",
+            format_test_name(test.name)
+        );
+        assert_eq!(
+            res.next_start_after,
+            test.expected_next_start_after,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct PrefixSumTestCase {
+    name: &'static str,
+    pre_operations: Vec<OrderOperation>,
+    tick_id: i64,
+    direction: OrderDirection,
+    etas: Decimal256,
+    expected_prefix_sum: Decimal256,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_prefix_sum() {
+    // Asks of 10 on tick 0 at ETAS 0, 10 and 20, with the middle order cancelled
+    let cancel_middle_order = vec![
+        OrderOperation::PlaceLimitMulti((
+            vec![0, 0, 0],
+            1,
+            Uint128::from(10u128),
+            OrderDirection::Ask,
+        )),
+        OrderOperation::Cancel((0, 1)),
+    ];
+    // Asks of 10 on tick 0 at ETAS 0, 10 and 20, with the first and last orders cancelled
+    let cancel_outer_orders = vec![
+        OrderOperation::PlaceLimitMulti((
+            vec![0, 0, 0],
+            1,
+            Uint128::from(10u128),
+            OrderDirection::Ask,
+        )),
+        OrderOperation::Cancel((0, 0)),
+        OrderOperation::Cancel((0, 2)),
+    ];
+    let test_cases = vec![
+        PrefixSumTestCase {
+            name: "cutoff below cancelled order",
+            pre_operations: cancel_middle_order.clone(),
+            tick_id: 0,
+            direction: OrderDirection::Ask,
+            etas: decimal256_from_u128(5u128),
+            expected_prefix_sum: Decimal256::zero(),
+            expected_error: None,
+        },
+        PrefixSumTestCase {
+            name: "cutoff at cancelled order",
+            pre_operations: cancel_middle_order.clone(),
+            tick_id: 0,
+            direction: OrderDirection::Ask,
+            etas: decimal256_from_u128(10u128),
+            expected_prefix_sum: decimal256_from_u128(10u128),
+            expected_error: None,
+        },
+        PrefixSumTestCase {
+            name: "cutoff past all orders",
+            pre_operations: cancel_middle_order.clone(),
+            tick_id: 0,
+            direction: OrderDirection::Ask,
+            etas: decimal256_from_u128(100u128),
+            expected_prefix_sum: decimal256_from_u128(10u128),
+            expected_error: None,
+        },
+        PrefixSumTestCase {
+            name: "cutoff at first of multiple cancels",
+            pre_operations: cancel_outer_orders.clone(),
+            tick_id: 0,
+            direction: OrderDirection::Ask,
+            etas: Decimal256::zero(),
+            expected_prefix_sum: decimal256_from_u128(10u128),
+            expected_error: None,
+        },
+        PrefixSumTestCase {
+            name: "cutoff at last of multiple cancels",
+            pre_operations: cancel_outer_orders,
+            tick_id: 0,
+            direction: OrderDirection::Ask,
+            etas: decimal256_from_u128(20u128),
+            expected_prefix_sum: decimal256_from_u128(20u128),
+            expected_error: None,
+        },
+        PrefixSumTestCase {
+            name: "no cancels in direction",
+            pre_operations: cancel_middle_order.clone(),
+            tick_id: 0,
+            direction: OrderDirection::Bid,
+            etas: decimal256_from_u128(100u128),
+            expected_prefix_sum: Decimal256::zero(),
+            expected_error: None,
+        },
+        PrefixSumTestCase {
+            name: "tick without state",
+            pre_operations: cancel_middle_order.clone(),
+            tick_id: 1,
+            direction: OrderDirection::Ask,
+            etas: Decimal256::zero(),
+            expected_prefix_sum: Decimal256::zero(),
+            expected_error: Some(ContractError::InvalidTickId { tick_id: 1 }),
+        },
+        PrefixSumTestCase {
+            name: "tick outside global range",
+            pre_operations: cancel_middle_order,
+            tick_id: MAX_TICK + 1,
+            direction: OrderDirection::Ask,
+            etas: Decimal256::zero(),
+            expected_prefix_sum: Decimal256::zero(),
+            expected_error: Some(ContractError::InvalidTickId {
+                tick_id: MAX_TICK + 1,
+            }),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(DEFAULT_SENDER, &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        for op in test.pre_operations {
+            op.run(deps.as_mut(), env.clone(), info.clone()).unwrap();
+        }
+
+        // -- System under test --
+        let res = query::prefix_sum(deps.as_ref(), test.tick_id, test.direction, test.etas);
+
+        // -- Post Test Assertions --
+        if let Some(err) = test.expected_error {
+            assert_eq!(res.unwrap_err(), err, "{}", format_test_name(test.name));
+            continue;
+        }
+
+        assert_eq!(
+            res.unwrap(),
+            test.expected_prefix_sum,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+#[test]
+fn test_verify_tree() {
+    // -- Test Setup --
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let info = mock_info(DEFAULT_SENDER, &[]);
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+        None,
+        None,
+    )
+    .unwrap();
+    // Cancelling an order builds the tick's sumtree
+    OrderOperation::PlaceLimitMulti((vec![0, 0, 0], 1, Uint128::from(10u128), OrderDirection::Ask))
+        .run(deps.as_mut(), env.clone(), info.clone())
+        .unwrap();
+    OrderOperation::Cancel((0, 1))
+        .run(deps.as_mut(), env.clone(), info)
+        .unwrap();
+
+    // -- System under test --
+    let res = contract_query(
+        deps.as_ref(),
+        env,
+        QueryMsg::VerifyTree {
+            tick_id: 0,
+            direction: OrderDirection::Ask,
+        },
+    );
+
+    // -- Post Test Assertions --
+    // The query is part of every build, but only debug builds walk the tree
+    if cfg!(debug_assertions) {
+        assert!(from_json::<bool>(res.unwrap()).unwrap());
+    } else {
+        assert_eq!(res.unwrap_err(), ContractError::DebugQueryUnavailable);
+    }
+}
+
+struct ExpectedReservesTestCase {
+    name: &'static str,
+    pre_operations: Vec<OrderOperation>,
+    prune_reward_pool: Vec<Coin>,
+    expected_reserves: Vec<Coin256>,
+}
+
+#[test]
+fn test_expected_reserves() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    // Places 20 base as two asks priced at 2, then fills 14 base of them with 7 quote
+    let place_and_fill = vec![
+        OrderOperation::PlaceLimitMulti((
+            vec![LARGE_POSITIVE_TICK],
+            2,
+            Uint128::from(10u128),
+            OrderDirection::Ask,
+        )),
+        OrderOperation::RunMarket(MarketOrder::new(
+            Uint128::from(7u128),
+            OrderDirection::Bid,
+            sender.clone(),
+        )),
+    ];
+
+    let test_cases = vec![
+        ExpectedReservesTestCase {
+            name: "empty orderbook",
+            pre_operations: vec![],
+            prune_reward_pool: vec![],
+            expected_reserves: vec![coin_u256(0u128, BASE_DENOM), coin_u256(0u128, QUOTE_DENOM)],
+        },
+        ExpectedReservesTestCase {
+            name: "placed and filled orders",
+            pre_operations: place_and_fill.clone(),
+            prune_reward_pool: vec![],
+            // 20 base in - 14 base out, 7 quote in
+            expected_reserves: vec![coin_u256(6u128, BASE_DENOM), coin_u256(7u128, QUOTE_DENOM)],
+        },
+        ExpectedReservesTestCase {
+            name: "claimed orders",
+            pre_operations: [
+                place_and_fill.clone(),
+                vec![OrderOperation::Claim((LARGE_POSITIVE_TICK, 0))],
+            ]
+            .concat(),
+            prune_reward_pool: vec![],
+            // The first order is fully filled, paying out 10 base worth of quote
+            expected_reserves: vec![coin_u256(6u128, BASE_DENOM), coin_u256(2u128, QUOTE_DENOM)],
+        },
+        ExpectedReservesTestCase {
+            name: "cancelled orders",
+            pre_operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![LARGE_POSITIVE_TICK],
+                    2,
+                    Uint128::from(10u128),
+                    OrderDirection::Ask,
+                )),
+                OrderOperation::Cancel((LARGE_POSITIVE_TICK, 1)),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(5u128),
+                    OrderDirection::Bid,
+                    sender.clone(),
+                )),
+            ],
+            prune_reward_pool: vec![],
+            // 20 base in - 10 base refunded - 10 base out, 5 quote in
+            expected_reserves: vec![coin_u256(0u128, BASE_DENOM), coin_u256(5u128, QUOTE_DENOM)],
+        },
+        ExpectedReservesTestCase {
+            name: "prune reward pool",
+            pre_operations: place_and_fill,
+            prune_reward_pool: vec![coin(5u128, QUOTE_DENOM), coin(50u128, "reward")],
+            expected_reserves: vec![
+                coin_u256(6u128, BASE_DENOM),
+                coin_u256(12u128, QUOTE_DENOM),
+                coin_u256(50u128, "reward"),
+            ],
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        for op in test.pre_operations {
+            op.run(deps.as_mut(), env.clone(), info.clone()).unwrap();
+        }
+        for pool in test.prune_reward_pool {
+            PRUNE_REWARD_POOL
+                .save(deps.as_mut().storage, &pool.denom, &pool.amount)
+                .unwrap();
+        }
+
+        // -- System under test --
+        let res = query::expected_reserves(deps.as_ref()).unwrap();
+
+        // -- Post Test Assertions --
+        assert_eq!(
+            res,
+            test.expected_reserves,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct ArithmeticTwapTestCase {
+    name: &'static str,
+    // Offsets in seconds from the time the first orders were placed
+    start_time: u64,
+    end_time: Option<u64>,
+    expected_output: Result<&'static str, ContractError>,
+}
+
+#[test]
+fn test_arithmetic_twap() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let genesis = mock_env().block.time;
+    let at = |seconds: u64| {
+        let mut env = mock_env();
+        env.block.time = genesis.plus_seconds(seconds);
+        env
+    };
+    let place = |tick_id: i64, order_direction: OrderDirection| {
+        let denom = match order_direction {
+            OrderDirection::Bid => QUOTE_DENOM,
+            OrderDirection::Ask => BASE_DENOM,
+        };
+        (
+            mock_info(sender.as_str(), &[coin(10, denom)]),
+            ExecuteMsg::PlaceLimit {
+                tick_id,
+                order_direction,
+                quantity: Uint128::from(10u128),
+                claim_bounty: None,
+                owner: None,
+                client_order_id: None,
+                memo: None,
+                auto_settle: None,
+            },
+        )
+    };
+
+    // -- Test Setup --
+    let mut deps = mock_dependencies_custom();
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+        None,
+        None,
+    )
+    .unwrap();
+
+    let history = vec![
+        // Mid price 1.25 once both sides have liquidity
+        (0, place(LARGE_NEGATIVE_TICK, OrderDirection::Bid)),
+        (0, place(LARGE_POSITIVE_TICK, OrderDirection::Ask)),
+        // Mid price 1.4999, overwritten by the end of the block with 1.5
+        (100, place(-2000, OrderDirection::Bid)),
+        (100, place(0, OrderDirection::Bid)),
+        // Asks are emptied, carrying the mid price of 1.5 forward
+        (
+            200,
+            (
+                mock_info(sender.as_str(), &[]),
+                ExecuteMsg::CancelLimit {
+                    tick_id: LARGE_POSITIVE_TICK,
+                    order_id: 1,
+                },
+            ),
+        ),
+        // Mid price 3.005
+        (300, place(4010000, OrderDirection::Ask)),
+    ];
+    for (seconds, (info, msg)) in history {
+        execute(deps.as_mut(), at(seconds), info, msg).unwrap();
+    }
+
+    let test_cases = vec![
+        ArithmeticTwapTestCase {
+            name: "single price",
+            start_time: 0,
+            end_time: Some(100),
+            expected_output: Ok("1.25"),
+        },
+        ArithmeticTwapTestCase {
+            name: "window between snapshots",
+            start_time: 20,
+            end_time: Some(80),
+            expected_output: Ok("1.25"),
+        },
+        ArithmeticTwapTestCase {
+            name: "closing mid price of a block is used",
+            start_time: 0,
+            end_time: Some(200),
+            // (1.25 * 100 + 1.5 * 100) / 200
+            expected_output: Ok("1.375"),
+        },
+        ArithmeticTwapTestCase {
+            name: "window straddling a snapshot",
+            start_time: 50,
+            end_time: Some(150),
+            // (1.25 * 50 + 1.5 * 50) / 100
+            expected_output: Ok("1.375"),
+        },
+        ArithmeticTwapTestCase {
+            name: "empty side carries mid price forward",
+            start_time: 100,
+            end_time: Some(300),
+            expected_output: Ok("1.5"),
+        },
+        ArithmeticTwapTestCase {
+            name: "blocks without changes are weighted",
+            start_time: 200,
+            end_time: Some(400),
+            // (1.5 * 100 + 3.005 * 100) / 200
+            expected_output: Ok("2.2525"),
+        },
+        ArithmeticTwapTestCase {
+            name: "end time defaults to block time",
+            start_time: 0,
+            end_time: None,
+            // (1.25 * 100 + 1.5 * 200 + 3.005 * 100) / 400
+            expected_output: Ok("1.81375"),
+        },
+        ArithmeticTwapTestCase {
+            name: "empty window",
+            start_time: 100,
+            end_time: Some(100),
+            expected_output: Err(ContractError::InvalidTwapWindow {
+                start_time: genesis.plus_seconds(100),
+                end_time: genesis.plus_seconds(100),
+            }),
+        },
+        ArithmeticTwapTestCase {
+            name: "inverted window",
+            start_time: 200,
+            end_time: Some(100),
+            expected_output: Err(ContractError::InvalidTwapWindow {
+                start_time: genesis.plus_seconds(200),
+                end_time: genesis.plus_seconds(100),
+            }),
+        },
+        ArithmeticTwapTestCase {
+            name: "end time in the future",
+            start_time: 0,
+            end_time: Some(401),
+            expected_output: Err(ContractError::TwapEndTimeInFuture {
+                end_time: genesis.plus_seconds(401),
+                block_time: genesis.plus_seconds(400),
+            }),
+        },
+    ];
+
+    for test in test_cases {
+        // -- System under test --
+        let res = query::arithmetic_twap(
+            deps.as_ref(),
+            at(400),
+            genesis.plus_seconds(test.start_time),
+            test.end_time.map(|end_time| genesis.plus_seconds(end_time)),
+        );
+
+        // -- Post Test Assertions --
+        match test.expected_output {
+            Ok(expected) => assert_eq!(
+                res.unwrap().arithmetic_twap,
+                Decimal256::from_str(expected).unwrap(),
+                "{}",
+                format_test_name(test.name)
+            ),
+            Err(expected) => assert_eq!(
+                res.unwrap_err(),
+                expected,
+                "{}",
+                format_test_name(test.name)
+            ),
+        }
+    }
+
+    // No snapshot exists before both sides of the book had liquidity
+    let mut deps = mock_dependencies_custom();
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+        None,
+        None,
+    )
+    .unwrap();
+    let (info, msg) = place(LARGE_NEGATIVE_TICK, OrderDirection::Bid);
+    execute(deps.as_mut(), at(0), info, msg).unwrap();
+    let err = query::arithmetic_twap(deps.as_ref(), at(100), genesis, None).unwrap_err();
+    assert_eq!(err, ContractError::TwapUnavailable { time: genesis });
+}
+
+#[test]
+fn test_next_order_id() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let next_order_id = |deps: cosmwasm_std::Deps| -> u64 {
+        from_json(contract_query(deps, mock_env(), QueryMsg::NextOrderId {}).unwrap()).unwrap()
+    };
+
+    // -- Test Setup --
+    let mut deps = mock_dependencies_custom();
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+        None,
+        None,
+    )
+    .unwrap();
+    assert_eq!(next_order_id(deps.as_ref()), 0);
+
+    // IDs are shared across ticks and directions, and are not reused once an order is removed
+    let placements = vec![
+        (0, OrderDirection::Ask),
+        (-10, OrderDirection::Bid),
+        (0, OrderDirection::Ask),
+        (LARGE_POSITIVE_TICK, OrderDirection::Ask),
+    ];
+    for (i, (tick_id, order_direction)) in placements.into_iter().enumerate() {
+        let expected_order_id = next_order_id(deps.as_ref());
+        let denom = match order_direction {
+            OrderDirection::Bid => QUOTE_DENOM,
+            OrderDirection::Ask => BASE_DENOM,
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender.as_str(), &[coin(10, denom)]),
+            ExecuteMsg::PlaceLimit {
+                tick_id,
+                order_direction,
+                quantity: Uint128::from(10u128),
+                claim_bounty: None,
+                owner: None,
+                client_order_id: None,
+                memo: None,
+                auto_settle: None,
+            },
+        )
+        .unwrap();
+        let placed: PlaceLimitResponseData = from_json(res.data.unwrap()).unwrap();
+        assert_eq!(placed.order_id, expected_order_id, "placement {i}");
+        assert_eq!(next_order_id(deps.as_ref()), expected_order_id + 1);
+
+        if i == 1 {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(sender.as_str(), &[]),
+                ExecuteMsg::CancelLimit {
+                    tick_id,
+                    order_id: placed.order_id,
+                },
+            )
+            .unwrap();
+            assert_eq!(next_order_id(deps.as_ref()), expected_order_id + 1);
+        }
+    }
+}
+
+struct SimulatePlaceLimitTestCase {
+    name: &'static str,
+    pre_operations: Vec<OrderOperation>,
+    placement_fee: Option<Coin>,
+    tick_id: i64,
+    direction: OrderDirection,
+    quantity: Uint128,
+    expected: Option<SimulatePlaceLimitResponse>,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_simulate_place_limit() {
+    let book = || {
+        vec![
+            OrderOperation::PlaceLimitMulti((
+                vec![-10],
+                1,
+                Uint128::from(10u128),
+                OrderDirection::Bid,
+            )),
+            OrderOperation::PlaceLimitMulti((
+                vec![10],
+                1,
+                Uint128::from(10u128),
+                OrderDirection::Ask,
+            )),
+        ]
+    };
+    let test_cases = vec![
+        SimulatePlaceLimitTestCase {
+            name: "empty book",
+            pre_operations: vec![],
+            placement_fee: None,
+            tick_id: 0,
+            direction: OrderDirection::Bid,
+            quantity: Uint128::from(100u128),
+            expected: Some(SimulatePlaceLimitResponse {
+                required_funds: coin(100, QUOTE_DENOM),
+                placement_fee: None,
+                would_cross: false,
+                assigned_order_id: 0,
+            }),
+            expected_error: None,
+        },
+        SimulatePlaceLimitTestCase {
+            name: "bid below best ask",
+            pre_operations: book(),
+            placement_fee: None,
+            tick_id: 5,
+            direction: OrderDirection::Bid,
+            quantity: Uint128::from(100u128),
+            expected: Some(SimulatePlaceLimitResponse {
+                required_funds: coin(100, QUOTE_DENOM),
+                placement_fee: None,
+                would_cross: false,
+                assigned_order_id: 2,
+            }),
+            expected_error: None,
+        },
+        SimulatePlaceLimitTestCase {
+            name: "bid on best ask tick",
+            pre_operations: book(),
+            placement_fee: None,
+            tick_id: 10,
+            direction: OrderDirection::Bid,
+            quantity: Uint128::from(100u128),
+            expected: Some(SimulatePlaceLimitResponse {
+                required_funds: coin(100, QUOTE_DENOM),
+                placement_fee: None,
+                would_cross: false,
+                assigned_order_id: 2,
+            }),
+            expected_error: None,
+        },
+        SimulatePlaceLimitTestCase {
+            name: "bid above best ask",
+            pre_operations: book(),
+            placement_fee: None,
+            tick_id: 11,
+            direction: OrderDirection::Bid,
+            quantity: Uint128::from(100u128),
+            expected: Some(SimulatePlaceLimitResponse {
+                required_funds: coin(100, QUOTE_DENOM),
+                placement_fee: None,
+                would_cross: true,
+                assigned_order_id: 2,
+            }),
+            expected_error: None,
+        },
+        SimulatePlaceLimitTestCase {
+            name: "ask above best bid",
+            pre_operations: book(),
+            placement_fee: None,
+            tick_id: -5,
+            direction: OrderDirection::Ask,
+            quantity: Uint128::from(100u128),
+            expected: Some(SimulatePlaceLimitResponse {
+                required_funds: coin(100, BASE_DENOM),
+                placement_fee: None,
+                would_cross: false,
+                assigned_order_id: 2,
+            }),
+            expected_error: None,
+        },
+        SimulatePlaceLimitTestCase {
+            name: "ask below best bid",
+            pre_operations: book(),
+            placement_fee: None,
+            tick_id: -11,
+            direction: OrderDirection::Ask,
+            quantity: Uint128::from(100u128),
+            expected: Some(SimulatePlaceLimitResponse {
+                required_funds: coin(100, BASE_DENOM),
+                placement_fee: None,
+                would_cross: true,
+                assigned_order_id: 2,
+            }),
+            expected_error: None,
+        },
+        SimulatePlaceLimitTestCase {
+            name: "placement fee",
+            pre_operations: vec![],
+            placement_fee: Some(coin(5, "ufee")),
+            tick_id: 0,
+            direction: OrderDirection::Ask,
+            quantity: Uint128::from(100u128),
+            expected: Some(SimulatePlaceLimitResponse {
+                required_funds: coin(100, BASE_DENOM),
+                placement_fee: Some(coin(5, "ufee")),
+                would_cross: false,
+                assigned_order_id: 0,
+            }),
+            expected_error: None,
+        },
+        SimulatePlaceLimitTestCase {
+            name: "zero quantity",
+            pre_operations: vec![],
+            placement_fee: None,
+            tick_id: 0,
+            direction: OrderDirection::Bid,
+            quantity: Uint128::zero(),
+            expected: None,
+            expected_error: Some(ContractError::InvalidQuantity {
+                quantity: Uint128::zero(),
+            }),
+        },
+        SimulatePlaceLimitTestCase {
+            name: "tick out of bounds",
+            pre_operations: vec![],
+            placement_fee: None,
+            tick_id: MAX_TICK + 1,
+            direction: OrderDirection::Bid,
+            quantity: Uint128::from(100u128),
+            expected: None,
+            expected_error: Some(ContractError::InvalidTickId {
+                tick_id: MAX_TICK + 1,
+            }),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(DEFAULT_SENDER, &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        for op in test.pre_operations {
+            op.run(deps.as_mut(), env.clone(), info.clone()).unwrap();
+        }
+        set_placement_fee(deps.as_mut().storage, test.placement_fee.clone()).unwrap();
+
+        // -- System under test --
+        let res =
+            query::simulate_place_limit(deps.as_ref(), test.tick_id, test.direction, test.quantity);
+
+        // -- Post Test Assertions --
+        if let Some(expected_error) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+        let simulated = res.unwrap();
+        assert_eq!(
+            Some(simulated.clone()),
+            test.expected,
+            "{}",
+            format_test_name(test.name)
+        );
+
+        // The simulation matches the outcome of the placement it describes
+        let mut funds = vec![simulated.required_funds.clone()];
+        funds.extend(simulated.placement_fee.clone());
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(DEFAULT_SENDER, &funds),
+            ExecuteMsg::PlaceLimit {
+                tick_id: test.tick_id,
+                order_direction: test.direction,
+                quantity: test.quantity,
+                claim_bounty: None,
+                owner: None,
+                client_order_id: None,
+                memo: None,
+                auto_settle: None,
+            },
+        );
+        if simulated.would_cross {
+            assert!(
+                matches!(res, Err(ContractError::CrossingLimitOrder { .. })),
+                "{}",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+        let placed: PlaceLimitResponseData = from_json(res.unwrap().data.unwrap()).unwrap();
+        assert_eq!(
+            placed.order_id,
+            simulated.assigned_order_id,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct RealizedCancelsTestCase {
+    name: &'static str,
+    operations: Vec<OrderOperation>,
+    tick_id: i64,
+    direction: OrderDirection,
+    expected: Decimal256,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_realized_cancels() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let quantity = Uint128::from(10u128);
+    let test_cases = vec![
+        RealizedCancelsTestCase {
+            name: "no cancels",
+            operations: vec![OrderOperation::PlaceLimitMulti((
+                vec![0],
+                2,
+                quantity,
+                OrderDirection::Ask,
+            ))],
+            tick_id: 0,
+            direction: OrderDirection::Ask,
+            expected: Decimal256::zero(),
+            expected_error: None,
+        },
+        RealizedCancelsTestCase {
+            name: "cancel at front of queue is realized immediately",
+            operations: vec![
+                OrderOperation::PlaceLimitMulti((vec![0], 2, quantity, OrderDirection::Ask)),
+                OrderOperation::Cancel((0, 0)),
+            ],
+            tick_id: 0,
+            direction: OrderDirection::Ask,
+            expected: decimal256_from_u128(10u128),
+            expected_error: None,
+        },
+        RealizedCancelsTestCase {
+            name: "cancel behind resting order is not realized",
+            operations: vec![
+                OrderOperation::PlaceLimitMulti((vec![0], 3, quantity, OrderDirection::Ask)),
+                OrderOperation::Cancel((0, 1)),
+            ],
+            tick_id: 0,
+            direction: OrderDirection::Ask,
+            expected: Decimal256::zero(),
+            expected_error: None,
+        },
+        RealizedCancelsTestCase {
+            name: "fill stops before cancelled order",
+            operations: vec![
+                OrderOperation::PlaceLimitMulti((vec![0], 3, quantity, OrderDirection::Ask)),
+                OrderOperation::Cancel((0, 1)),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(5u128),
+                    OrderDirection::Bid,
+                    sender.clone(),
+                )),
+            ],
+            tick_id: 0,
+            direction: OrderDirection::Ask,
+            expected: Decimal256::zero(),
+            expected_error: None,
+        },
+        RealizedCancelsTestCase {
+            name: "fill past cancelled order realizes it",
+            operations: vec![
+                OrderOperation::PlaceLimitMulti((vec![0], 3, quantity, OrderDirection::Ask)),
+                OrderOperation::Cancel((0, 1)),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(15u128),
+                    OrderDirection::Bid,
+                    sender.clone(),
+                )),
+            ],
+            tick_id: 0,
+            direction: OrderDirection::Ask,
+            expected: decimal256_from_u128(10u128),
+            expected_error: None,
+        },
+        RealizedCancelsTestCase {
+            name: "multiple cancels are summed",
+            operations: vec![
+                OrderOperation::PlaceLimitMulti((vec![0], 4, quantity, OrderDirection::Ask)),
+                OrderOperation::Cancel((0, 1)),
+                OrderOperation::Cancel((0, 2)),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(15u128),
+                    OrderDirection::Bid,
+                    sender.clone(),
+                )),
+            ],
+            tick_id: 0,
+            direction: OrderDirection::Ask,
+            expected: decimal256_from_u128(20u128),
+            expected_error: None,
+        },
+        RealizedCancelsTestCase {
+            name: "other direction is unaffected",
+            operations: vec![
+                OrderOperation::PlaceLimitMulti((vec![0], 2, quantity, OrderDirection::Ask)),
+                OrderOperation::Cancel((0, 0)),
+            ],
+            tick_id: 0,
+            direction: OrderDirection::Bid,
+            expected: Decimal256::zero(),
+            expected_error: None,
+        },
+        RealizedCancelsTestCase {
+            name: "tick without state",
+            operations: vec![],
+            tick_id: 0,
+            direction: OrderDirection::Ask,
+            expected: Decimal256::zero(),
+            expected_error: Some(ContractError::InvalidTickId { tick_id: 0 }),
+        },
+        RealizedCancelsTestCase {
+            name: "tick out of range",
+            operations: vec![],
+            tick_id: MAX_TICK + 1,
+            direction: OrderDirection::Ask,
+            expected: Decimal256::zero(),
+            expected_error: Some(ContractError::InvalidTickId {
+                tick_id: MAX_TICK + 1,
+            }),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        for operation in test.operations {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        // -- System under test --
+        let res = contract_query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RealizedCancels {
+                tick_id: test.tick_id,
+                direction: test.direction,
+            },
+        );
+
+        // -- Post test assertions --
+        if let Some(expected_error) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+
+        let realized: Decimal256 = from_json(res.unwrap()).unwrap();
+        assert_eq!(realized, test.expected, "{}", format_test_name(test.name));
+    }
+}
+
+#[test]
+fn test_order_count() {
+    let owner = Addr::unchecked(DEFAULT_OWNER);
+    let recipient = Addr::unchecked("recipient");
+    let order_count = |deps: cosmwasm_std::Deps, owner: &Addr| -> u32 {
+        from_json(
+            contract_query(
+                deps,
+                mock_env(),
+                QueryMsg::OrderCount {
+                    owner: owner.clone(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    let place = |auto_settle: Option<Uint128>| ExecuteMsg::PlaceLimit {
+        tick_id: 0,
+        order_direction: OrderDirection::Ask,
+        quantity: Uint128::from(10u128),
+        claim_bounty: None,
+        owner: None,
+        client_order_id: None,
+        memo: None,
+        auto_settle,
+    };
+    let swap = |quantity: u128| SudoMsg::SwapExactAmountIn {
+        sender: DEFAULT_SENDER.to_string(),
+        token_in: coin(quantity, QUOTE_DENOM),
+        token_out_denom: BASE_DENOM.to_string(),
+        token_out_min_amount: Uint128::zero(),
+        swap_fee: EXPECTED_SWAP_FEE,
+        recipient: None,
+        max_slippage_ticks: None,
+    };
+
+    // -- Test Setup --
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let owner_info = mock_info(owner.as_str(), &[coin(10, BASE_DENOM)]);
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+        None,
+        None,
+    )
+    .unwrap();
+    assert_eq!(order_count(deps.as_ref(), &owner), 0);
+
+    // -- System under test --
+    // Orders 0 to 3 are placed, order 3 opting into auto-settlement
+    for auto_settle in [None, None, None, Some(Uint128::one())] {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            owner_info.clone(),
+            place(auto_settle),
+        )
+        .unwrap();
+    }
+    assert_eq!(order_count(deps.as_ref(), &owner), 4);
+
+    // Cancelling removes the order
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(owner.as_str(), &[]),
+        ExecuteMsg::CancelLimit {
+            tick_id: 0,
+            order_id: 1,
+        },
+    )
+    .unwrap();
+    assert_eq!(order_count(deps.as_ref(), &owner), 3);
+
+    // Transferring moves the order to the new owner
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(owner.as_str(), &[]),
+        ExecuteMsg::TransferOrder {
+            tick_id: 0,
+            order_id: 2,
+            new_owner: recipient.clone(),
+        },
+    )
+    .unwrap();
+    assert_eq!(order_count(deps.as_ref(), &owner), 2);
+    assert_eq!(order_count(deps.as_ref(), &recipient), 1);
+
+    // Filled orders are counted until claimed
+    sudo(deps.as_mut(), env.clone(), swap(20)).unwrap();
+    assert_eq!(order_count(deps.as_ref(), &owner), 2);
+    assert_eq!(order_count(deps.as_ref(), &recipient), 1);
+
+    // Fully claiming removes the order
+    for (claimer, order_id) in [(&owner, 0), (&recipient, 2)] {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(claimer.as_str(), &[]),
+            ExecuteMsg::ClaimLimit {
+                tick_id: 0,
+                order_id,
+                waive_bounty: false,
+            },
+        )
+        .unwrap();
+    }
+    assert_eq!(order_count(deps.as_ref(), &owner), 1);
+    assert_eq!(order_count(deps.as_ref(), &recipient), 0);
+
+    // Fully filling an auto-settled order removes it within the swap
+    sudo(deps.as_mut(), env.clone(), swap(10)).unwrap();
+    assert_eq!(order_count(deps.as_ref(), &owner), 0);
+}
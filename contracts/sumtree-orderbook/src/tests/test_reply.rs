@@ -0,0 +1,134 @@
+use cosmwasm_std::{testing::mock_env, Reply, SubMsgResponse, SubMsgResult};
+
+use super::mock_querier::mock_dependencies_custom;
+use crate::{
+    contract::reply,
+    types::{
+        REPLY_ID_CLAIM, REPLY_ID_CLAIM_BOUNTY, REPLY_ID_MAKER_FEE, REPLY_ID_PRUNE_REWARD,
+        REPLY_ID_REFUND, REPLY_ID_SUDO_SWAP_EXACT_IN,
+    },
+    ContractError,
+};
+
+struct ReplyTestCase {
+    name: &'static str,
+    reply_id: u64,
+    result: SubMsgResult,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_reply() {
+    let failed_send = "0base is smaller than 100base: insufficient funds".to_string();
+    let test_cases = vec![
+        ReplyTestCase {
+            name: "failed claim send",
+            reply_id: REPLY_ID_CLAIM,
+            result: SubMsgResult::Err(failed_send.clone()),
+            expected_error: Some(ContractError::ReplyError {
+                reply_id: REPLY_ID_CLAIM,
+                reason: failed_send.clone(),
+            }),
+        },
+        ReplyTestCase {
+            name: "failed claim bounty send",
+            reply_id: REPLY_ID_CLAIM_BOUNTY,
+            result: SubMsgResult::Err(failed_send.clone()),
+            expected_error: Some(ContractError::ReplyError {
+                reply_id: REPLY_ID_CLAIM_BOUNTY,
+                reason: failed_send.clone(),
+            }),
+        },
+        ReplyTestCase {
+            name: "failed maker fee send",
+            reply_id: REPLY_ID_MAKER_FEE,
+            result: SubMsgResult::Err(failed_send.clone()),
+            expected_error: Some(ContractError::ReplyError {
+                reply_id: REPLY_ID_MAKER_FEE,
+                reason: failed_send.clone(),
+            }),
+        },
+        ReplyTestCase {
+            name: "failed refund send",
+            reply_id: REPLY_ID_REFUND,
+            result: SubMsgResult::Err(failed_send.clone()),
+            expected_error: Some(ContractError::ReplyError {
+                reply_id: REPLY_ID_REFUND,
+                reason: failed_send.clone(),
+            }),
+        },
+        ReplyTestCase {
+            name: "failed swap output send",
+            reply_id: REPLY_ID_SUDO_SWAP_EXACT_IN,
+            result: SubMsgResult::Err(failed_send.clone()),
+            expected_error: Some(ContractError::ReplyError {
+                reply_id: REPLY_ID_SUDO_SWAP_EXACT_IN,
+                reason: failed_send.clone(),
+            }),
+        },
+        ReplyTestCase {
+            name: "failed prune reward send",
+            reply_id: REPLY_ID_PRUNE_REWARD,
+            result: SubMsgResult::Err(failed_send.clone()),
+            expected_error: Some(ContractError::ReplyError {
+                reply_id: REPLY_ID_PRUNE_REWARD,
+                reason: failed_send.clone(),
+            }),
+        },
+        ReplyTestCase {
+            name: "successful send",
+            reply_id: REPLY_ID_CLAIM,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+            }),
+            expected_error: None,
+        },
+        ReplyTestCase {
+            name: "unknown reply ID",
+            reply_id: 100,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+            }),
+            expected_error: Some(ContractError::ReplyError {
+                reply_id: 100,
+                reason: "Unknown reply ID".to_string(),
+            }),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+
+        // -- System under test --
+        let res = reply(
+            deps.as_mut(),
+            env,
+            Reply {
+                id: test.reply_id,
+                result: test.result,
+            },
+        );
+
+        // -- Post Test Assertions --
+        if let Some(err) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                err,
+                "{}: did not receive expected error",
+                test.name
+            );
+            continue;
+        }
+
+        assert!(
+            res.is_ok(),
+            "{}: reply unexpectedly failed; {}",
+            test.name,
+            res.unwrap_err()
+        );
+    }
+}
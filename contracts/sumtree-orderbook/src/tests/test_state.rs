@@ -1,7 +1,12 @@
+use std::str::FromStr;
+
+use crate::constants::TWAP_RETENTION_PERIOD;
 use crate::state::*;
-use crate::types::{FilterOwnerOrders, LimitOrder, OrderDirection, TickState};
+use crate::tests::test_constants::{LARGE_NEGATIVE_TICK, LARGE_POSITIVE_TICK};
+use crate::types::{FilterOwnerOrders, LimitOrder, OrderDirection, Orderbook, TickState};
+use crate::ContractError;
 use cosmwasm_std::testing::MockStorage;
-use cosmwasm_std::{Addr, Decimal256, Order, Uint128};
+use cosmwasm_std::{Addr, Decimal256, Order, Timestamp, Uint128};
 
 #[test]
 fn test_order_id_works() {
@@ -189,6 +194,156 @@ fn test_get_orders_by_owner_by_tick() {
     });
 }
 
+#[test]
+fn test_get_orders_by_owner_by_direction() {
+    let mut storage = MockStorage::new();
+    let order_amount = 100;
+    let owner = "owner1";
+
+    // Create orders alternating direction, with every third order belonging to another owner
+    (0..order_amount).for_each(|i| {
+        let order_id = new_order_id(&mut storage).unwrap();
+        let other_owner = &format!("owner{i}");
+        let current_owner = Addr::unchecked(if i % 3 == 0 { other_owner } else { owner });
+        let direction = if i % 2 == 0 {
+            OrderDirection::Bid
+        } else {
+            OrderDirection::Ask
+        };
+        let order = LimitOrder::new(
+            0,
+            order_id,
+            direction,
+            current_owner,
+            Uint128::new(i as u128),
+            Decimal256::zero(),
+            None,
+        );
+        orders().save(&mut storage, &(0, i as u64), &order).unwrap();
+    });
+
+    [OrderDirection::Bid, OrderDirection::Ask]
+        .iter()
+        .for_each(|&direction| {
+            let owner_orders = get_orders_by_owner(
+                &storage,
+                FilterOwnerOrders::by_direction(Addr::unchecked(owner), direction),
+                None,
+                None,
+                Some(100),
+            )
+            .unwrap();
+            let expected_count = (0..order_amount)
+                .filter(|i| i % 3 != 0 && (i % 2 == 0) == (direction == OrderDirection::Bid))
+                .count();
+            assert_eq!(owner_orders.len(), expected_count);
+            owner_orders.iter().for_each(|order| {
+                assert_eq!(order.owner, Addr::unchecked(owner));
+                assert_eq!(order.order_direction, direction);
+            });
+        });
+}
+
+struct TickRangeTestCase {
+    min_tick: i64,
+    max_tick: i64,
+    start_after: Option<(i64, u64)>,
+    expected_ticks: Vec<i64>,
+}
+
+#[test]
+fn test_get_orders_by_owner_by_tick_range() {
+    let mut storage = MockStorage::new();
+    let ticks = [-200, -100, -1, 0, 1, 100, 200];
+    let owner = "owner1";
+
+    // Create two orders for the owner and one for another owner on each tick
+    ticks.iter().for_each(|&tick| {
+        for current_owner in [owner, owner, "owner2"] {
+            let order_id = new_order_id(&mut storage).unwrap();
+            let order = LimitOrder::new(
+                tick,
+                order_id,
+                OrderDirection::Ask,
+                Addr::unchecked(current_owner),
+                Uint128::new(10),
+                Decimal256::zero(),
+                None,
+            );
+            orders()
+                .save(&mut storage, &(tick, order_id), &order)
+                .unwrap();
+        }
+    });
+
+    let test_cases = vec![
+        TickRangeTestCase {
+            min_tick: -100,
+            max_tick: 100,
+            start_after: None,
+            expected_ticks: vec![-100, -100, -1, -1, 0, 0, 1, 1, 100, 100],
+        },
+        TickRangeTestCase {
+            min_tick: -1,
+            max_tick: 0,
+            start_after: None,
+            expected_ticks: vec![-1, -1, 0, 0],
+        },
+        TickRangeTestCase {
+            min_tick: 1,
+            max_tick: 1,
+            start_after: None,
+            expected_ticks: vec![1, 1],
+        },
+        TickRangeTestCase {
+            min_tick: 50,
+            max_tick: 99,
+            start_after: None,
+            expected_ticks: vec![],
+        },
+        TickRangeTestCase {
+            min_tick: 100,
+            max_tick: -100,
+            start_after: None,
+            expected_ticks: vec![],
+        },
+        // Pagination within the range continues after the given order
+        TickRangeTestCase {
+            min_tick: -100,
+            max_tick: 100,
+            start_after: Some((0, 9)),
+            expected_ticks: vec![0, 1, 1, 100, 100],
+        },
+        // Pagination before the range starts at the start of the range
+        TickRangeTestCase {
+            min_tick: -1,
+            max_tick: 1,
+            start_after: Some((-200, 0)),
+            expected_ticks: vec![-1, -1, 0, 0, 1, 1],
+        },
+    ];
+
+    for test in test_cases {
+        let owner_orders = get_orders_by_owner(
+            &storage,
+            FilterOwnerOrders::by_tick_range(Addr::unchecked(owner), test.min_tick, test.max_tick),
+            test.start_after,
+            None,
+            Some(100),
+        )
+        .unwrap();
+        let actual_ticks: Vec<i64> = owner_orders.iter().map(|order| order.tick_id).collect();
+        assert_eq!(
+            actual_ticks, test.expected_ticks,
+            "range [{}, {}] after {:?}",
+            test.min_tick, test.max_tick, test.start_after
+        );
+        owner_orders.iter().for_each(|order| {
+            assert_eq!(order.owner, Addr::unchecked(owner));
+        });
+    }
+}
+
 #[test]
 fn test_get_orders_by_owner_with_pagination() {
     let mut storage = MockStorage::new();
@@ -253,3 +408,87 @@ fn test_get_orders_by_owner_with_pagination() {
         (order_amount as f64 / page_size as f64).ceil() as u64
     );
 }
+
+#[test]
+fn test_get_next_tick_with_liquidity_scan_limit() {
+    let mut storage = MockStorage::new();
+    let direction = OrderDirection::Ask;
+
+    // Two ticks in the same batching bucket, the first of which has no liquidity
+    TICK_STATE
+        .save(&mut storage, 0, &TickState::default())
+        .unwrap();
+    let mut tick_state = TickState::default();
+    let mut tick_values = tick_state.get_values(direction);
+    tick_values.total_amount_of_liquidity = Decimal256::one();
+    tick_state.set_values(direction, tick_values);
+    TICK_STATE.save(&mut storage, 1, &tick_state).unwrap();
+    add_layer_liquidity(&mut storage, direction, 1, Decimal256::one()).unwrap();
+
+    // Unbounded scans find the tick with liquidity
+    let res = get_next_tick_with_liquidity(&storage, direction, 0, None).unwrap();
+    assert_eq!(res, Some(1));
+
+    // Scans error if they inspect more ticks than allowed
+    let err = get_next_tick_with_liquidity(&storage, direction, 0, Some(1)).unwrap_err();
+    assert_eq!(err, ContractError::TickScanLimitExceeded { limit: 1 });
+}
+
+#[test]
+fn test_twap_accumulator_pruning() {
+    let mut storage = MockStorage::new();
+    let genesis = Timestamp::from_seconds(1_000_000);
+    let mut orderbook = Orderbook::new(
+        "quote".to_string(),
+        "base".to_string(),
+        0,
+        LARGE_NEGATIVE_TICK,
+        LARGE_POSITIVE_TICK,
+    );
+    save_orderbook(&mut storage, &mut orderbook).unwrap();
+    for direction in [OrderDirection::Bid, OrderDirection::Ask] {
+        DIRECTION_TOTAL_LIQUIDITY
+            .save(&mut storage, &direction.to_string(), &Decimal256::one())
+            .unwrap();
+    }
+
+    // Write a snapshot every hour by alternating the bid pointer
+    let snapshot_count = TWAP_RETENTION_PERIOD / 3600 + 5;
+    for i in 0..snapshot_count {
+        orderbook.next_bid_tick = if i % 2 == 0 { LARGE_NEGATIVE_TICK } else { 0 };
+        save_orderbook(&mut storage, &mut orderbook).unwrap();
+        update_twap_accumulator(&mut storage, genesis.plus_seconds(i * 3600)).unwrap();
+    }
+
+    // One expired snapshot is pruned per update, keeping the newest one before the cutoff
+    let now = genesis.plus_seconds((snapshot_count - 1) * 3600);
+    let cutoff = now.minus_seconds(TWAP_RETENTION_PERIOD);
+    let timestamps: Vec<u64> = TWAP_ACCUMULATOR
+        .keys(&storage, None, None, Order::Ascending)
+        .map(|key| key.unwrap())
+        .collect();
+    assert_eq!(timestamps.len() as u64, snapshot_count - 3);
+    assert_eq!(
+        timestamps
+            .iter()
+            .filter(|timestamp| **timestamp < cutoff.nanos())
+            .count(),
+        1
+    );
+
+    // The accumulator is still available at the cutoff
+    // Mid prices alternate between (0.5 + 2) / 2 and (1 + 2) / 2
+    let twap_start = get_twap_accumulator(&storage, cutoff).unwrap();
+    let twap_end = get_twap_accumulator(&storage, now).unwrap();
+    let elapsed = Decimal256::from_ratio(now.nanos() - cutoff.nanos(), 1u128);
+    assert_eq!(
+        (twap_end - twap_start) / elapsed,
+        Decimal256::from_str("1.375").unwrap()
+    );
+
+    // Queries before the retained history error
+    assert_eq!(
+        get_twap_accumulator(&storage, genesis).unwrap_err(),
+        ContractError::TwapUnavailable { time: genesis }
+    );
+}
@@ -1,30 +1,40 @@
 use cosmwasm_std::{
-    coin,
-    testing::{mock_env, mock_info},
-    to_json_binary, Addr, BankMsg, Coin, Decimal, Decimal256, Empty, StdError, SubMsg, Uint128,
+    coin, from_json,
+    testing::{mock_env, mock_info, MockApi},
+    Addr, Api, BankMsg, Coin, CosmosMsg, Decimal, Decimal256, Empty, StdError, SubMsg, Uint128,
     Uint256,
 };
+use osmosis_std::types::cosmos::bank::v1beta1::MsgSend;
 
 use crate::{
     auth::ADMIN,
-    constants::EXPECTED_SWAP_FEE,
+    constants::{DEFAULT_MAKER_FEE_RECIPIENT, EXPECTED_SWAP_FEE},
     contract::execute,
-    msg::{AuthExecuteMsg, ExecuteMsg, SudoMsg, SwapExactAmountInResponseData},
-    orderbook::create_orderbook,
-    state::IS_ACTIVE,
+    msg::{AuthExecuteMsg, ExecuteMsg, SudoMsg, SwapExactAmountInResponseData, SwapResult},
+    order::{claim_limit, place_limit},
+    orderbook::{
+        create_orderbook, set_max_price_impact, set_max_ticks_per_order, set_min_market_order_size,
+    },
+    query,
+    state::{
+        get_paused_ops, orders, ACTIVATION_DELAY, DUST_AUDIT, IS_ACTIVE, PRUNE_REWARD_POOL,
+        TICK_STATE,
+    },
     sudo::{
         dispatch_swap_exact_amount_in, ensure_is_active, set_active, sudo, validate_output_amount,
     },
     tests::{mock_querier::mock_dependencies_custom, test_constants::QUOTE_DENOM},
     types::{
-        coin_u256, Coin256, LimitOrder, MsgSend256, OrderDirection, REPLY_ID_REFUND,
-        REPLY_ID_SUDO_SWAP_EXACT_IN,
+        coin_u256, Coin256, LimitOrder, MarketOrder, MsgSend256, OrderDirection, PausedOps,
+        REPLY_ID_REFUND, REPLY_ID_SUDO_SWAP_EXACT_IN,
     },
     ContractError,
 };
 
 use super::{
-    test_constants::{BASE_DENOM, DEFAULT_SENDER},
+    test_constants::{
+        BASE_DENOM, DEFAULT_OWNER, DEFAULT_SENDER, LARGE_NEGATIVE_TICK, LARGE_POSITIVE_TICK,
+    },
     test_utils::{format_test_name, OrderOperation},
 };
 
@@ -358,6 +368,8 @@ fn test_swap_exact_amount_in() {
             deps.as_mut(),
             QUOTE_DENOM.to_string(),
             BASE_DENOM.to_string(),
+            None,
+            None,
         )
         .unwrap();
 
@@ -376,6 +388,8 @@ fn test_swap_exact_amount_in() {
             test.token_out_min_amount,
             test.swap_fee,
             test.target_tick,
+            None,
+            None,
         );
 
         // -- Post test assertions --
@@ -427,12 +441,518 @@ fn test_swap_exact_amount_in() {
             );
         }
 
-        let expected_data = to_json_binary(&SwapExactAmountInResponseData {
-            token_out_amount: test.expected_output.amount,
-        })
+        let data: SwapExactAmountInResponseData = from_json(response.data.unwrap()).unwrap();
+        assert_eq!(
+            data.token_out_amount,
+            test.expected_output.amount,
+            "{}",
+            format_test_name(test.name)
+        );
+        assert_eq!(
+            data.swap_result.token_out,
+            test.expected_output,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct SwapExactAmountInWithRouteTestCase {
+    name: &'static str,
+    pre_operations: Vec<OrderOperation>,
+    token_in: Coin,
+    token_out_denom: &'static str,
+    token_out_min_amount: Uint128,
+    tranches: Vec<(Decimal256, Uint128)>,
+    recipient: Option<&'static str>,
+    max_slippage_ticks: Option<u32>,
+    expected_output: Option<Coin256>,
+    expected_refund: Option<Coin>,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_swap_exact_amount_in_with_route() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let price_one = Decimal256::one();
+    let price_two = Decimal256::percent(200);
+    let price_half = Decimal256::percent(50);
+    let test_cases = vec![
+        SwapExactAmountInWithRouteTestCase {
+            name: "BID: two tranches at different price limits",
+            pre_operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![0],
+                    1,
+                    Uint128::from(10u128),
+                    OrderDirection::Ask,
+                )),
+                OrderOperation::PlaceLimitMulti((
+                    vec![LARGE_POSITIVE_TICK],
+                    1,
+                    Uint128::from(20u128),
+                    OrderDirection::Ask,
+                )),
+            ],
+            token_in: coin(30u128, QUOTE_DENOM),
+            token_out_denom: BASE_DENOM,
+            token_out_min_amount: Uint128::from(22u128),
+            // 10 quote fills 10 base at a price of 1, then 6 quote fills 12 base at a price of 2
+            tranches: vec![
+                (price_one, Uint128::from(10u128)),
+                (price_two, Uint128::from(6u128)),
+            ],
+            recipient: None,
+            max_slippage_ticks: None,
+            expected_output: Some(coin_u256(22u128, BASE_DENOM)),
+            expected_refund: Some(coin(14u128, QUOTE_DENOM)),
+            expected_error: None,
+        },
+        SwapExactAmountInWithRouteTestCase {
+            name: "BID: tranche below best price is skipped",
+            pre_operations: vec![OrderOperation::PlaceLimitMulti((
+                vec![LARGE_POSITIVE_TICK],
+                1,
+                Uint128::from(20u128),
+                OrderDirection::Ask,
+            ))],
+            token_in: coin(15u128, QUOTE_DENOM),
+            token_out_denom: BASE_DENOM,
+            token_out_min_amount: Uint128::from(10u128),
+            tranches: vec![
+                (price_one, Uint128::from(10u128)),
+                (price_two, Uint128::from(5u128)),
+            ],
+            recipient: None,
+            max_slippage_ticks: None,
+            expected_output: Some(coin_u256(10u128, BASE_DENOM)),
+            expected_refund: Some(coin(10u128, QUOTE_DENOM)),
+            expected_error: None,
+        },
+        SwapExactAmountInWithRouteTestCase {
+            name: "ASK: later tranche fills remaining liquidity within its band",
+            pre_operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![0],
+                    1,
+                    Uint128::from(10u128),
+                    OrderDirection::Bid,
+                )),
+                OrderOperation::PlaceLimitMulti((
+                    vec![LARGE_NEGATIVE_TICK],
+                    1,
+                    Uint128::from(20u128),
+                    OrderDirection::Bid,
+                )),
+            ],
+            token_in: coin(20u128, BASE_DENOM),
+            token_out_denom: QUOTE_DENOM,
+            token_out_min_amount: Uint128::from(10u128),
+            // Both tranches are filled entirely on tick 0, which lies within both bands
+            tranches: vec![
+                (price_one, Uint128::from(5u128)),
+                (price_half, Uint128::from(5u128)),
+            ],
+            recipient: None,
+            max_slippage_ticks: None,
+            expected_output: Some(coin_u256(10u128, QUOTE_DENOM)),
+            expected_refund: Some(coin(10u128, BASE_DENOM)),
+            expected_error: None,
+        },
+        SwapExactAmountInWithRouteTestCase {
+            name: "tranches consume the full input",
+            pre_operations: vec![OrderOperation::PlaceLimitMulti((
+                vec![0],
+                1,
+                Uint128::from(10u128),
+                OrderDirection::Ask,
+            ))],
+            token_in: coin(10u128, QUOTE_DENOM),
+            token_out_denom: BASE_DENOM,
+            token_out_min_amount: Uint128::from(10u128),
+            tranches: vec![
+                (price_one, Uint128::from(4u128)),
+                (price_one, Uint128::from(100u128)),
+            ],
+            recipient: None,
+            max_slippage_ticks: None,
+            expected_output: Some(coin_u256(10u128, BASE_DENOM)),
+            expected_refund: None,
+            expected_error: None,
+        },
+        SwapExactAmountInWithRouteTestCase {
+            name: "no tranche reaches liquidity",
+            pre_operations: vec![OrderOperation::PlaceLimitMulti((
+                vec![LARGE_POSITIVE_TICK],
+                1,
+                Uint128::from(20u128),
+                OrderDirection::Ask,
+            ))],
+            token_in: coin(10u128, QUOTE_DENOM),
+            token_out_denom: BASE_DENOM,
+            token_out_min_amount: Uint128::zero(),
+            tranches: vec![(price_one, Uint128::from(10u128))],
+            recipient: None,
+            max_slippage_ticks: None,
+            expected_output: None,
+            expected_refund: Some(coin(10u128, QUOTE_DENOM)),
+            expected_error: None,
+        },
+        SwapExactAmountInWithRouteTestCase {
+            name: "min amount not met",
+            pre_operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![0],
+                    1,
+                    Uint128::from(10u128),
+                    OrderDirection::Ask,
+                )),
+                OrderOperation::PlaceLimitMulti((
+                    vec![LARGE_POSITIVE_TICK],
+                    1,
+                    Uint128::from(20u128),
+                    OrderDirection::Ask,
+                )),
+            ],
+            token_in: coin(30u128, QUOTE_DENOM),
+            token_out_denom: BASE_DENOM,
+            token_out_min_amount: Uint128::from(23u128),
+            tranches: vec![
+                (price_one, Uint128::from(10u128)),
+                (price_two, Uint128::from(6u128)),
+            ],
+            recipient: None,
+            max_slippage_ticks: None,
+            expected_output: None,
+            expected_refund: None,
+            expected_error: Some(ContractError::InvalidSwap {
+                error: "Did not meet minimum swap amount: expected 23 received 22".to_string(),
+            }),
+        },
+        SwapExactAmountInWithRouteTestCase {
+            name: "BID: output sent to recipient",
+            pre_operations: vec![OrderOperation::PlaceLimitMulti((
+                vec![0],
+                1,
+                Uint128::from(10u128),
+                OrderDirection::Ask,
+            ))],
+            token_in: coin(15u128, QUOTE_DENOM),
+            token_out_denom: BASE_DENOM,
+            token_out_min_amount: Uint128::from(10u128),
+            tranches: vec![(price_one, Uint128::from(15u128))],
+            recipient: Some("recipient"),
+            max_slippage_ticks: None,
+            expected_output: Some(coin_u256(10u128, BASE_DENOM)),
+            expected_refund: Some(coin(5u128, QUOTE_DENOM)),
+            expected_error: None,
+        },
+        SwapExactAmountInWithRouteTestCase {
+            name: "BID: max slippage bounds every tranche",
+            pre_operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![0],
+                    1,
+                    Uint128::from(10u128),
+                    OrderDirection::Ask,
+                )),
+                OrderOperation::PlaceLimitMulti((
+                    vec![LARGE_POSITIVE_TICK],
+                    1,
+                    Uint128::from(20u128),
+                    OrderDirection::Ask,
+                )),
+            ],
+            token_in: coin(30u128, QUOTE_DENOM),
+            token_out_denom: BASE_DENOM,
+            token_out_min_amount: Uint128::from(10u128),
+            // The second tranche's band reaches the ask at a price of 2, but the slippage bound
+            // measured from tick 0 stops it short
+            tranches: vec![
+                (price_one, Uint128::from(10u128)),
+                (price_two, Uint128::from(10u128)),
+            ],
+            recipient: None,
+            max_slippage_ticks: Some(10),
+            expected_output: Some(coin_u256(10u128, BASE_DENOM)),
+            expected_refund: Some(coin(20u128, QUOTE_DENOM)),
+            expected_error: None,
+        },
+        SwapExactAmountInWithRouteTestCase {
+            name: "invalid recipient",
+            pre_operations: vec![],
+            token_in: coin(10u128, QUOTE_DENOM),
+            token_out_denom: BASE_DENOM,
+            token_out_min_amount: Uint128::zero(),
+            tranches: vec![(price_one, Uint128::from(10u128))],
+            recipient: Some("Invalid Recipient"),
+            max_slippage_ticks: None,
+            expected_output: None,
+            expected_refund: None,
+            expected_error: Some(ContractError::Std(
+                MockApi::default()
+                    .addr_validate("Invalid Recipient")
+                    .unwrap_err(),
+            )),
+        },
+        SwapExactAmountInWithRouteTestCase {
+            name: "BID: unbounded tranche thinner than its quantity",
+            pre_operations: vec![OrderOperation::PlaceLimitMulti((
+                vec![0],
+                1,
+                Uint128::from(10u128),
+                OrderDirection::Ask,
+            ))],
+            token_in: coin(30u128, QUOTE_DENOM),
+            token_out_denom: BASE_DENOM,
+            token_out_min_amount: Uint128::from(10u128),
+            // The first tranche is bounded at MAX_TICK and runs out of liquidity after 10 quote,
+            // its leftover carries over to the second tranche and then the refund
+            tranches: vec![
+                (Decimal256::MAX, Uint128::from(25u128)),
+                (price_two, Uint128::from(5u128)),
+            ],
+            recipient: None,
+            max_slippage_ticks: None,
+            expected_output: Some(coin_u256(10u128, BASE_DENOM)),
+            expected_refund: Some(coin(20u128, QUOTE_DENOM)),
+            expected_error: None,
+        },
+        SwapExactAmountInWithRouteTestCase {
+            name: "ASK: unbounded tranche thinner than its quantity",
+            pre_operations: vec![OrderOperation::PlaceLimitMulti((
+                vec![0],
+                1,
+                Uint128::from(10u128),
+                OrderDirection::Bid,
+            ))],
+            token_in: coin(30u128, BASE_DENOM),
+            token_out_denom: QUOTE_DENOM,
+            token_out_min_amount: Uint128::from(10u128),
+            // Bounded at MIN_TICK, the tranche fills the 10 resting quote and refunds the rest
+            tranches: vec![(Decimal256::zero(), Uint128::from(25u128))],
+            recipient: None,
+            max_slippage_ticks: None,
+            expected_output: Some(coin_u256(10u128, QUOTE_DENOM)),
+            expected_refund: Some(coin(20u128, BASE_DENOM)),
+            expected_error: None,
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
         .unwrap();
 
-        assert_eq!(response.data, Some(expected_data))
+        // Run any pre-operations
+        for op in test.pre_operations {
+            op.run(deps.as_mut(), env.clone(), info.clone()).unwrap();
+        }
+
+        // -- System under test --
+        let token_in = test.token_in;
+        let response = sudo(
+            deps.as_mut(),
+            env.clone(),
+            SudoMsg::SwapExactAmountInWithRoute {
+                sender: sender.to_string(),
+                token_in: token_in.clone(),
+                token_out_denom: test.token_out_denom.to_string(),
+                token_out_min_amount: test.token_out_min_amount,
+                swap_fee: EXPECTED_SWAP_FEE,
+                tranches: test.tranches,
+                recipient: test.recipient.map(str::to_string),
+                max_slippage_ticks: test.max_slippage_ticks,
+            },
+        );
+
+        // -- Post test assertions --
+        if let Some(error) = test.expected_error {
+            assert_eq!(
+                error,
+                response.unwrap_err(),
+                "{}: did not receive expected error",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+
+        let response = response.unwrap();
+        let mut expected_msgs: Vec<SubMsg> = vec![];
+        if let Some(output) = test.expected_output.clone() {
+            expected_msgs.push(SubMsg::reply_on_error(
+                MsgSend256 {
+                    from_address: env.contract.address.to_string(),
+                    to_address: test.recipient.unwrap_or(sender.as_str()).to_string(),
+                    amount: vec![output],
+                },
+                REPLY_ID_SUDO_SWAP_EXACT_IN,
+            ));
+        }
+        if let Some(refund) = test.expected_refund.clone() {
+            expected_msgs.push(SubMsg::reply_on_error(
+                BankMsg::Send {
+                    to_address: sender.to_string(),
+                    amount: vec![refund],
+                },
+                REPLY_ID_REFUND,
+            ));
+        }
+        assert_eq!(
+            response.messages,
+            expected_msgs,
+            "{}: did not receive expected messages",
+            format_test_name(test.name)
+        );
+
+        let data: SwapExactAmountInResponseData = from_json(response.data.unwrap()).unwrap();
+        let expected_output = test
+            .expected_output
+            .unwrap_or(coin_u256(0u128, test.token_out_denom));
+        assert_eq!(
+            data.token_out_amount,
+            expected_output.amount,
+            "{}",
+            format_test_name(test.name)
+        );
+        assert_eq!(
+            data.swap_result.token_out,
+            expected_output,
+            "{}",
+            format_test_name(test.name)
+        );
+        let expected_refund = test
+            .expected_refund
+            .map_or(Uint128::zero(), |refund| refund.amount);
+        assert_eq!(
+            data.swap_result.token_in,
+            coin(
+                token_in.amount.checked_sub(expected_refund).unwrap().u128(),
+                &token_in.denom
+            ),
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct SwapWithRouteLimitsTestCase {
+    name: &'static str,
+    max_ticks_per_order: Option<u32>,
+    max_price_impact: Option<Decimal256>,
+    tranches: Vec<(Decimal256, Uint128)>,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_swap_exact_amount_in_with_route_limits() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    // Asks of 10 at prices of 1, 1.04 and 1.08
+    let ask_ticks = vec![0, 40000, 80000];
+    let test_cases = vec![
+        SwapWithRouteLimitsTestCase {
+            name: "route within max ticks per order",
+            max_ticks_per_order: Some(2),
+            max_price_impact: None,
+            tranches: vec![
+                (Decimal256::percent(110), Uint128::from(10u128)),
+                (Decimal256::percent(110), Uint128::from(10u128)),
+            ],
+            expected_error: None,
+        },
+        SwapWithRouteLimitsTestCase {
+            name: "split route cannot exceed max ticks per order",
+            max_ticks_per_order: Some(2),
+            max_price_impact: None,
+            // Each tranche fills a single tick
+            tranches: vec![
+                (Decimal256::percent(110), Uint128::from(10u128)),
+                (Decimal256::percent(110), Uint128::from(10u128)),
+                (Decimal256::percent(110), Uint128::from(10u128)),
+            ],
+            expected_error: Some(ContractError::TooManyTicks { limit: 2 }),
+        },
+        SwapWithRouteLimitsTestCase {
+            name: "route within max price impact",
+            max_ticks_per_order: None,
+            max_price_impact: Some(Decimal256::percent(5)),
+            tranches: vec![
+                (Decimal256::percent(104), Uint128::from(15u128)),
+                (Decimal256::percent(104), Uint128::from(5u128)),
+            ],
+            expected_error: None,
+        },
+        SwapWithRouteLimitsTestCase {
+            name: "split route cannot exceed max price impact",
+            max_ticks_per_order: None,
+            max_price_impact: Some(Decimal256::percent(5)),
+            // Each tranche moves the price by less than 5%, but the route moves it by 8%
+            tranches: vec![
+                (Decimal256::percent(104), Uint128::from(15u128)),
+                (Decimal256::percent(108), Uint128::from(15u128)),
+            ],
+            expected_error: Some(ContractError::PriceImpactTooHigh {
+                impact: Decimal256::percent(8),
+            }),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        if let Some(max_ticks_per_order) = test.max_ticks_per_order {
+            set_max_ticks_per_order(deps.as_mut().storage, max_ticks_per_order).unwrap();
+        }
+        set_max_price_impact(deps.as_mut().storage, test.max_price_impact).unwrap();
+        OrderOperation::PlaceLimitMulti((
+            ask_ticks.clone(),
+            1,
+            Uint128::from(10u128),
+            OrderDirection::Ask,
+        ))
+        .run(deps.as_mut(), env.clone(), info.clone())
+        .unwrap();
+
+        // -- System under test --
+        let res = sudo(
+            deps.as_mut(),
+            env.clone(),
+            SudoMsg::SwapExactAmountInWithRoute {
+                sender: sender.to_string(),
+                token_in: coin(40u128, QUOTE_DENOM),
+                token_out_denom: BASE_DENOM.to_string(),
+                token_out_min_amount: Uint128::zero(),
+                swap_fee: EXPECTED_SWAP_FEE,
+                tranches: test.tranches,
+                recipient: None,
+                max_slippage_ticks: None,
+            },
+        );
+
+        // -- Post test assertions --
+        match test.expected_error {
+            Some(error) => assert_eq!(res.unwrap_err(), error, "{}", format_test_name(test.name)),
+            None => assert!(res.is_ok(), "{}", format_test_name(test.name)),
+        }
     }
 }
 
@@ -568,6 +1088,10 @@ fn test_set_active_execute() {
                 order_direction: OrderDirection::Ask,
                 quantity: Uint128::from(100u128),
                 claim_bounty: None,
+                owner: None,
+                client_order_id: None,
+                memo: None,
+                auto_settle: None,
             },
             active_status: Some(true),
             expected_error: None,
@@ -579,6 +1103,10 @@ fn test_set_active_execute() {
                 order_direction: OrderDirection::Ask,
                 quantity: Uint128::from(100u128),
                 claim_bounty: None,
+                owner: None,
+                client_order_id: None,
+                memo: None,
+                auto_settle: None,
             },
             active_status: None,
             expected_error: None,
@@ -590,6 +1118,10 @@ fn test_set_active_execute() {
                 order_direction: OrderDirection::Ask,
                 quantity: Uint128::from(100u128),
                 claim_bounty: None,
+                owner: None,
+                client_order_id: None,
+                memo: None,
+                auto_settle: None,
             },
             active_status: Some(false),
             expected_error: Some(ContractError::Inactive),
@@ -630,6 +1162,8 @@ fn test_set_active_execute() {
             deps.as_mut(),
             QUOTE_DENOM.to_string(),
             BASE_DENOM.to_string(),
+            None,
+            None,
         )
         .unwrap();
 
@@ -691,6 +1225,8 @@ fn test_set_active_sudo() {
                 token_out_denom: BASE_DENOM.to_string(),
                 token_out_min_amount: Uint128::from(100u128),
                 swap_fee: Decimal::zero(),
+                recipient: None,
+                max_slippage_ticks: None,
             },
             active_status: Some(true),
             expected_error: None,
@@ -712,6 +1248,8 @@ fn test_set_active_sudo() {
                 token_out_denom: BASE_DENOM.to_string(),
                 token_out_min_amount: Uint128::from(100u128),
                 swap_fee: Decimal::zero(),
+                recipient: None,
+                max_slippage_ticks: None,
             },
             active_status: Some(false),
             expected_error: Some(ContractError::Inactive),
@@ -728,6 +1266,8 @@ fn test_set_active_sudo() {
             deps.as_mut(),
             QUOTE_DENOM.to_string(),
             BASE_DENOM.to_string(),
+            None,
+            None,
         )
         .unwrap();
 
@@ -764,3 +1304,1514 @@ fn test_set_active_sudo() {
         );
     }
 }
+
+enum PausedTestMsg {
+    Execute(ExecuteMsg, Vec<Coin>),
+    Sudo(SudoMsg),
+}
+
+struct SetPausedOpsTestCase {
+    name: &'static str,
+    msg: PausedTestMsg,
+    // Returns whether the message is halted by the given paused operations
+    is_paused: fn(&PausedOps) -> bool,
+    paused_error: Option<ContractError>,
+}
+
+#[test]
+fn test_set_paused_ops() {
+    let valid_tick_id = 0;
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let test_cases = vec![
+        SetPausedOpsTestCase {
+            name: "place limit",
+            msg: PausedTestMsg::Execute(
+                ExecuteMsg::PlaceLimit {
+                    tick_id: valid_tick_id,
+                    order_direction: OrderDirection::Ask,
+                    quantity: Uint128::from(100u128),
+                    claim_bounty: None,
+                    owner: None,
+                    client_order_id: None,
+                    memo: None,
+                    auto_settle: None,
+                },
+                vec![coin(100u128, BASE_DENOM)],
+            ),
+            is_paused: |paused_ops| paused_ops.placements,
+            paused_error: Some(ContractError::PlacementsPaused),
+        },
+        SetPausedOpsTestCase {
+            name: "swap exact in",
+            msg: PausedTestMsg::Sudo(SudoMsg::SwapExactAmountIn {
+                sender: sender.to_string(),
+                token_in: coin(10u128, QUOTE_DENOM),
+                token_out_denom: BASE_DENOM.to_string(),
+                token_out_min_amount: Uint128::zero(),
+                swap_fee: Decimal::zero(),
+                recipient: None,
+                max_slippage_ticks: None,
+            }),
+            is_paused: |paused_ops| paused_ops.market_orders,
+            paused_error: Some(ContractError::MarketOrdersPaused),
+        },
+        SetPausedOpsTestCase {
+            name: "swap to tick",
+            msg: PausedTestMsg::Sudo(SudoMsg::SwapToTick {
+                sender: sender.to_string(),
+                token_in: coin(10u128, QUOTE_DENOM),
+                token_out_denom: BASE_DENOM.to_string(),
+                token_out_min_amount: Uint128::zero(),
+                swap_fee: Decimal::zero(),
+                target_tick: valid_tick_id,
+                recipient: None,
+                max_slippage_ticks: None,
+            }),
+            is_paused: |paused_ops| paused_ops.market_orders,
+            paused_error: Some(ContractError::MarketOrdersPaused),
+        },
+        SetPausedOpsTestCase {
+            name: "claim limit",
+            msg: PausedTestMsg::Execute(
+                ExecuteMsg::ClaimLimit {
+                    tick_id: valid_tick_id,
+                    order_id: 0,
+                    waive_bounty: false,
+                },
+                vec![],
+            ),
+            is_paused: |paused_ops| paused_ops.claims,
+            paused_error: Some(ContractError::ClaimsPaused),
+        },
+        SetPausedOpsTestCase {
+            name: "batch claim",
+            msg: PausedTestMsg::Execute(
+                ExecuteMsg::BatchClaim {
+                    orders: vec![(valid_tick_id, 0)],
+                    waive_bounty: false,
+                },
+                vec![],
+            ),
+            is_paused: |paused_ops| paused_ops.claims,
+            paused_error: Some(ContractError::ClaimsPaused),
+        },
+        SetPausedOpsTestCase {
+            name: "cancel limit",
+            msg: PausedTestMsg::Execute(
+                ExecuteMsg::CancelLimit {
+                    tick_id: valid_tick_id,
+                    order_id: 1,
+                },
+                vec![],
+            ),
+            // Cancellations can never be paused
+            is_paused: |_| false,
+            paused_error: None,
+        },
+    ];
+
+    // Every combination of paused operations
+    let all_paused_ops: Vec<PausedOps> = (0..8u8)
+        .map(|flags| PausedOps {
+            placements: flags & 1 != 0,
+            market_orders: flags & 2 != 0,
+            claims: flags & 4 != 0,
+        })
+        .collect();
+
+    for test in test_cases {
+        for paused_ops in all_paused_ops.iter().copied() {
+            let test_name = format!("{}, paused ops: {:?}", test.name, paused_ops);
+
+            // -- Test Setup --
+            let mut deps = mock_dependencies_custom();
+            let env = mock_env();
+            let info = mock_info(DEFAULT_SENDER, &[]);
+
+            create_orderbook(
+                deps.as_mut(),
+                QUOTE_DENOM.to_string(),
+                BASE_DENOM.to_string(),
+                None,
+                None,
+            )
+            .unwrap();
+
+            // Place two asks and partially fill the first so it can be claimed
+            let pre_operations = vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![valid_tick_id],
+                    2,
+                    Uint128::from(100u128),
+                    OrderDirection::Ask,
+                )),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(50u128),
+                    OrderDirection::Bid,
+                    sender.clone(),
+                )),
+            ];
+            for op in pre_operations {
+                op.run(deps.as_mut(), env.clone(), info.clone()).unwrap();
+            }
+
+            sudo(
+                deps.as_mut(),
+                env.clone(),
+                SudoMsg::SetPausedOps { paused_ops },
+            )
+            .unwrap();
+            assert_eq!(
+                get_paused_ops(deps.as_ref().storage).unwrap(),
+                paused_ops,
+                "{}",
+                format_test_name(&test_name)
+            );
+
+            // -- System under test --
+            let resp = match &test.msg {
+                PausedTestMsg::Execute(msg, funds) => execute(
+                    deps.as_mut(),
+                    env,
+                    mock_info(DEFAULT_SENDER, funds),
+                    msg.clone(),
+                ),
+                PausedTestMsg::Sudo(msg) => sudo(deps.as_mut(), env, msg.clone()),
+            };
+
+            if (test.is_paused)(&paused_ops) {
+                assert_eq!(
+                    &resp.unwrap_err(),
+                    test.paused_error.as_ref().unwrap(),
+                    "{}",
+                    format_test_name(&test_name)
+                );
+                continue;
+            }
+
+            assert!(
+                resp.is_ok(),
+                "{}: message unexpectedly failed; {}",
+                format_test_name(&test_name),
+                resp.unwrap_err()
+            );
+        }
+    }
+}
+
+struct SetMaxClaimBountyTestCase {
+    name: &'static str,
+    max_claim_bounty: Option<Decimal256>,
+    claim_bounty: Decimal256,
+    expected_sudo_error: Option<ContractError>,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_set_max_claim_bounty() {
+    let test_cases = vec![
+        SetMaxClaimBountyTestCase {
+            name: "default cap",
+            max_claim_bounty: None,
+            claim_bounty: Decimal256::permille(8),
+            expected_sudo_error: None,
+            expected_error: None,
+        },
+        SetMaxClaimBountyTestCase {
+            name: "lowered cap rejects previously valid bounty",
+            max_claim_bounty: Some(Decimal256::permille(5)),
+            claim_bounty: Decimal256::permille(8),
+            expected_sudo_error: None,
+            expected_error: Some(ContractError::InvalidClaimBounty {
+                claim_bounty: Some(Decimal256::permille(8)),
+            }),
+        },
+        SetMaxClaimBountyTestCase {
+            name: "bounty equal to lowered cap",
+            max_claim_bounty: Some(Decimal256::permille(5)),
+            claim_bounty: Decimal256::permille(5),
+            expected_sudo_error: None,
+            expected_error: None,
+        },
+        SetMaxClaimBountyTestCase {
+            name: "raised cap accepts bounty above default",
+            max_claim_bounty: Some(Decimal256::percent(2)),
+            claim_bounty: Decimal256::percent(2),
+            expected_sudo_error: None,
+            expected_error: None,
+        },
+        SetMaxClaimBountyTestCase {
+            name: "cap above 100%",
+            max_claim_bounty: Some(Decimal256::percent(101)),
+            claim_bounty: Decimal256::permille(8),
+            expected_sudo_error: Some(ContractError::InvalidMaxClaimBounty {
+                max_claim_bounty: Decimal256::percent(101),
+            }),
+            expected_error: None,
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        // -- System under test --
+        if let Some(max_claim_bounty) = test.max_claim_bounty {
+            let res = sudo(
+                deps.as_mut(),
+                env.clone(),
+                SudoMsg::SetMaxClaimBounty { max_claim_bounty },
+            );
+            if let Some(err) = test.expected_sudo_error {
+                assert_eq!(res.unwrap_err(), err, "{}", format_test_name(test.name));
+                continue;
+            }
+            res.unwrap();
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info(DEFAULT_SENDER, &[coin(100u128, QUOTE_DENOM)]),
+            ExecuteMsg::PlaceLimit {
+                tick_id: 0,
+                order_direction: OrderDirection::Bid,
+                quantity: Uint128::from(100u128),
+                claim_bounty: Some(test.claim_bounty),
+                owner: None,
+                client_order_id: None,
+                memo: None,
+                auto_settle: None,
+            },
+        );
+
+        // -- Post Test Assertions --
+        match test.expected_error {
+            Some(err) => assert_eq!(res.unwrap_err(), err, "{}", format_test_name(test.name)),
+            None => {
+                res.unwrap();
+            }
+        }
+    }
+}
+
+#[test]
+fn test_swap_result_data() {
+    // -- Test Setup --
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let owner_info = mock_info(DEFAULT_OWNER, &[]);
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+        None,
+        None,
+    )
+    .unwrap();
+    // 50 units of ask liquidity at a price of 1 and 100 units at a price of 2
+    for (tick_id, order_id, quantity) in [(0, 0, 50u128), (1_000_000, 1, 100u128)] {
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            tick_id,
+            order_id,
+            OrderDirection::Ask,
+            Addr::unchecked(DEFAULT_OWNER),
+            Uint128::from(quantity),
+            Decimal256::zero(),
+            None,
+        ))
+        .run(deps.as_mut(), env.clone(), owner_info.clone())
+        .unwrap();
+    }
+
+    // -- System under test --
+    let response = sudo(
+        deps.as_mut(),
+        env.clone(),
+        SudoMsg::SwapExactAmountIn {
+            sender: DEFAULT_SENDER.to_string(),
+            token_in: coin(100u128, QUOTE_DENOM),
+            token_out_denom: BASE_DENOM.to_string(),
+            token_out_min_amount: Uint128::zero(),
+            swap_fee: EXPECTED_SWAP_FEE,
+            recipient: None,
+            max_slippage_ticks: None,
+        },
+    )
+    .unwrap();
+
+    // -- Post Test Assertions --
+    // Composing contracts decode the fill from the response data
+    let data: SwapExactAmountInResponseData = from_json(response.data.unwrap()).unwrap();
+    assert_eq!(
+        data,
+        SwapExactAmountInResponseData {
+            token_out_amount: Uint256::from(150u128),
+            swap_result: SwapResult {
+                token_in: coin(100u128, QUOTE_DENOM),
+                token_out: coin_u256(150u128, BASE_DENOM),
+                avg_price: Some(Decimal256::percent(150)),
+                ticks_traversed: 2,
+            },
+        }
+    );
+}
+
+struct MinMarketOrderSizeTestCase {
+    name: &'static str,
+    min_market_order_size: Option<Uint128>,
+    quantity: Uint128,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_min_market_order_size() {
+    let test_cases = vec![
+        MinMarketOrderSizeTestCase {
+            name: "no minimum",
+            min_market_order_size: None,
+            quantity: Uint128::one(),
+            expected_error: None,
+        },
+        MinMarketOrderSizeTestCase {
+            name: "input at minimum",
+            min_market_order_size: Some(Uint128::from(10u128)),
+            quantity: Uint128::from(10u128),
+            expected_error: None,
+        },
+        MinMarketOrderSizeTestCase {
+            name: "input below minimum",
+            min_market_order_size: Some(Uint128::from(10u128)),
+            quantity: Uint128::from(9u128),
+            expected_error: Some(ContractError::MarketOrderTooSmall {
+                quantity: Uint128::from(9u128),
+                min_quantity: Uint128::from(10u128),
+            }),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        if let Some(min_market_order_size) = test.min_market_order_size {
+            set_min_market_order_size(deps.as_mut().storage, min_market_order_size).unwrap();
+        }
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            0,
+            OrderDirection::Ask,
+            Addr::unchecked(DEFAULT_OWNER),
+            Uint128::from(100u128),
+            Decimal256::zero(),
+            None,
+        ))
+        .run(deps.as_mut(), env.clone(), mock_info(DEFAULT_OWNER, &[]))
+        .unwrap();
+
+        // -- System under test --
+        let res = sudo(
+            deps.as_mut(),
+            env.clone(),
+            SudoMsg::SwapExactAmountIn {
+                sender: DEFAULT_SENDER.to_string(),
+                token_in: coin(test.quantity.u128(), QUOTE_DENOM),
+                token_out_denom: BASE_DENOM.to_string(),
+                token_out_min_amount: Uint128::zero(),
+                swap_fee: EXPECTED_SWAP_FEE,
+                recipient: None,
+                max_slippage_ticks: None,
+            },
+        );
+
+        // -- Post Test Assertions --
+        if let Some(expected_error) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+
+        let data: SwapExactAmountInResponseData = from_json(res.unwrap().data.unwrap()).unwrap();
+        assert_eq!(
+            data.swap_result.token_in,
+            coin(test.quantity.u128(), QUOTE_DENOM),
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct AutoSettleTestCase {
+    name: &'static str,
+    auto_settle: Option<Uint128>,
+    // Amount paid out to the owner by each swap
+    expected_settlements: Vec<u128>,
+}
+
+#[test]
+fn test_auto_settle() {
+    let swaps = [30u128, 30u128];
+    let total_filled: u128 = swaps.iter().sum();
+    let test_cases = vec![
+        AutoSettleTestCase {
+            name: "fills accrue until claimed",
+            auto_settle: None,
+            expected_settlements: vec![0, 0],
+        },
+        AutoSettleTestCase {
+            name: "each fill is settled",
+            auto_settle: Some(Uint128::one()),
+            expected_settlements: vec![30, 30],
+        },
+        AutoSettleTestCase {
+            name: "fills below the minimum payout accrue",
+            auto_settle: Some(Uint128::from(50u128)),
+            expected_settlements: vec![0, 60],
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        place_limit(
+            &mut deps.as_mut(),
+            env.clone(),
+            mock_info(DEFAULT_OWNER, &[coin(100u128, BASE_DENOM)]),
+            0,
+            OrderDirection::Ask,
+            Uint128::from(100u128),
+            None,
+            None,
+            None,
+            None,
+            test.auto_settle,
+        )
+        .unwrap();
+
+        // -- System under test --
+        let mut settlements = vec![];
+        for quantity in swaps {
+            let res = sudo(
+                deps.as_mut(),
+                env.clone(),
+                SudoMsg::SwapExactAmountIn {
+                    sender: DEFAULT_SENDER.to_string(),
+                    token_in: coin(quantity, QUOTE_DENOM),
+                    token_out_denom: BASE_DENOM.to_string(),
+                    token_out_min_amount: Uint128::zero(),
+                    swap_fee: EXPECTED_SWAP_FEE,
+                    recipient: None,
+                    max_slippage_ticks: None,
+                },
+            )
+            .unwrap();
+            settlements.push(owner_payout(res.messages));
+        }
+
+        // -- Post Test Assertions --
+        assert_eq!(
+            settlements,
+            test.expected_settlements,
+            "{}",
+            format_test_name(test.name)
+        );
+
+        // Whatever was not settled is claimed, so the owner receives the same total either way
+        let settled: u128 = settlements.iter().sum();
+        let claimed = match claim_limit(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(DEFAULT_OWNER, &[]),
+            0,
+            0,
+            false,
+        ) {
+            Ok(res) => owner_payout(res.messages),
+            Err(ContractError::ZeroClaim) => 0,
+            Err(err) => panic!("{}: unexpected error {err}", test.name),
+        };
+        assert_eq!(
+            settled + claimed,
+            total_filled,
+            "{}",
+            format_test_name(test.name)
+        );
+        let order = orders().load(deps.as_ref().storage, &(0, 0)).unwrap();
+        assert_eq!(
+            order.quantity,
+            Uint128::from(100 - total_filled),
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+// Sums the quote denom sent to the default owner by the given messages
+fn owner_payout(messages: Vec<SubMsg>) -> u128 {
+    messages
+        .into_iter()
+        .filter_map(|msg| match msg.msg {
+            CosmosMsg::Stargate { value, .. } => {
+                let send = MsgSend::try_from(value).unwrap();
+                (send.to_address == DEFAULT_OWNER && send.amount[0].denom == QUOTE_DENOM)
+                    .then(|| send.amount[0].amount.parse::<u128>().unwrap())
+            }
+            _ => None,
+        })
+        .sum()
+}
+
+struct SwapExactAmountInRecipientTestCase {
+    name: &'static str,
+    recipient: Option<&'static str>,
+    expected_recipient: &'static str,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_swap_exact_amount_in_recipient() {
+    let sender = DEFAULT_SENDER;
+    let test_cases = vec![
+        SwapExactAmountInRecipientTestCase {
+            name: "no recipient defaults to sender",
+            recipient: None,
+            expected_recipient: sender,
+            expected_error: None,
+        },
+        SwapExactAmountInRecipientTestCase {
+            name: "recipient receives output",
+            recipient: Some("recipient"),
+            expected_recipient: "recipient",
+            expected_error: None,
+        },
+        SwapExactAmountInRecipientTestCase {
+            name: "invalid recipient",
+            recipient: Some("Invalid Recipient"),
+            expected_recipient: "",
+            expected_error: Some(ContractError::Std(
+                MockApi::default()
+                    .addr_validate("Invalid Recipient")
+                    .unwrap_err(),
+            )),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            0,
+            OrderDirection::Ask,
+            Addr::unchecked(DEFAULT_OWNER),
+            Uint128::from(100u128),
+            Decimal256::zero(),
+            None,
+        ))
+        .run(deps.as_mut(), env.clone(), mock_info(DEFAULT_OWNER, &[]))
+        .unwrap();
+
+        // -- System under test --
+        let response = dispatch_swap_exact_amount_in(
+            deps.as_mut(),
+            env.clone(),
+            sender.to_string(),
+            coin(50u128, QUOTE_DENOM),
+            BASE_DENOM.to_string(),
+            Uint128::from(50u128),
+            EXPECTED_SWAP_FEE,
+            None,
+            None,
+            test.recipient.map(str::to_string),
+        );
+
+        // -- Post test assertions --
+        if let Some(expected_error) = test.expected_error {
+            assert_eq!(
+                response.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+
+        let response = response.unwrap();
+        assert_eq!(
+            response.messages,
+            vec![SubMsg::reply_on_error(
+                MsgSend256 {
+                    from_address: env.contract.address.to_string(),
+                    to_address: test.expected_recipient.to_string(),
+                    amount: vec![coin_u256(50u128, BASE_DENOM)],
+                },
+                REPLY_ID_SUDO_SWAP_EXACT_IN,
+            )],
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct SweepDustTestCase {
+    name: &'static str,
+    pre_operations: Vec<OrderOperation>,
+    prune_reward_pool: Vec<Coin>,
+    balance: Vec<Coin>,
+    denom: &'static str,
+    expected_sweep: Option<Coin>,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_sweep_dust() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let recipient = "recipient";
+    // Leaves 6 base and 7 quote owed to the order owners
+    let place_and_fill = vec![
+        OrderOperation::PlaceLimitMulti((
+            vec![LARGE_POSITIVE_TICK],
+            2,
+            Uint128::from(10u128),
+            OrderDirection::Ask,
+        )),
+        OrderOperation::RunMarket(MarketOrder::new(
+            Uint128::from(7u128),
+            OrderDirection::Bid,
+            sender.clone(),
+        )),
+    ];
+
+    let test_cases = vec![
+        SweepDustTestCase {
+            name: "dust with no obligations",
+            pre_operations: vec![],
+            prune_reward_pool: vec![],
+            balance: vec![coin(5, BASE_DENOM)],
+            denom: BASE_DENOM,
+            expected_sweep: Some(coin(5, BASE_DENOM)),
+            expected_error: None,
+        },
+        SweepDustTestCase {
+            name: "only surplus above obligations is swept",
+            pre_operations: place_and_fill.clone(),
+            prune_reward_pool: vec![],
+            balance: vec![coin(6, BASE_DENOM), coin(10, QUOTE_DENOM)],
+            denom: QUOTE_DENOM,
+            expected_sweep: Some(coin(3, QUOTE_DENOM)),
+            expected_error: None,
+        },
+        SweepDustTestCase {
+            name: "prune reward pool is not swept",
+            pre_operations: place_and_fill.clone(),
+            prune_reward_pool: vec![coin(5, QUOTE_DENOM)],
+            balance: vec![coin(6, BASE_DENOM), coin(13, QUOTE_DENOM)],
+            denom: QUOTE_DENOM,
+            expected_sweep: Some(coin(1, QUOTE_DENOM)),
+            expected_error: None,
+        },
+        SweepDustTestCase {
+            name: "denom outside of the orderbook",
+            pre_operations: place_and_fill.clone(),
+            prune_reward_pool: vec![],
+            balance: vec![coin(6, BASE_DENOM), coin(7, QUOTE_DENOM), coin(4, "uosmo")],
+            denom: "uosmo",
+            expected_sweep: Some(coin(4, "uosmo")),
+            expected_error: None,
+        },
+        SweepDustTestCase {
+            name: "balance matches obligations",
+            pre_operations: place_and_fill.clone(),
+            prune_reward_pool: vec![],
+            balance: vec![coin(6, BASE_DENOM), coin(7, QUOTE_DENOM)],
+            denom: BASE_DENOM,
+            expected_sweep: None,
+            expected_error: Some(ContractError::NoSweepableSurplus {
+                denom: BASE_DENOM.to_string(),
+                balance: Uint256::from(6u128),
+                expected: Uint256::from(6u128),
+            }),
+        },
+        SweepDustTestCase {
+            name: "balance below obligations",
+            pre_operations: place_and_fill,
+            prune_reward_pool: vec![],
+            balance: vec![coin(6, BASE_DENOM), coin(5, QUOTE_DENOM)],
+            denom: QUOTE_DENOM,
+            expected_sweep: None,
+            expected_error: Some(ContractError::NoSweepableSurplus {
+                denom: QUOTE_DENOM.to_string(),
+                balance: Uint256::from(5u128),
+                expected: Uint256::from(7u128),
+            }),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        for op in test.pre_operations {
+            op.run(deps.as_mut(), env.clone(), info.clone()).unwrap();
+        }
+        for pool in test.prune_reward_pool {
+            PRUNE_REWARD_POOL
+                .save(deps.as_mut().storage, &pool.denom, &pool.amount)
+                .unwrap();
+        }
+        deps.querier
+            .base
+            .update_balance(env.contract.address.clone(), test.balance);
+
+        // -- System under test --
+        let res = sudo(
+            deps.as_mut(),
+            env,
+            SudoMsg::SweepDust {
+                denom: test.denom.to_string(),
+                recipient: recipient.to_string(),
+                limit: None,
+            },
+        );
+
+        // -- Post Test Assertions --
+        if let Some(expected_error) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+
+        let expected_msgs: Vec<SubMsg> = test
+            .expected_sweep
+            .into_iter()
+            .map(|sweep| {
+                SubMsg::new(BankMsg::Send {
+                    to_address: recipient.to_string(),
+                    amount: vec![sweep],
+                })
+            })
+            .collect();
+        assert_eq!(
+            res.unwrap().messages,
+            expected_msgs,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+#[test]
+fn test_sweep_dust_audit() {
+    // -- Test Setup --
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let info = mock_info(sender.as_str(), &[]);
+    let recipient = "recipient";
+    let sweep = SudoMsg::SweepDust {
+        denom: QUOTE_DENOM.to_string(),
+        recipient: recipient.to_string(),
+        limit: Some(1),
+    };
+
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+        None,
+        None,
+    )
+    .unwrap();
+    // Two asks of 10 on a single tick, leaving 20 base owed to the order owners
+    OrderOperation::PlaceLimitMulti((
+        vec![LARGE_POSITIVE_TICK],
+        2,
+        Uint128::from(10u128),
+        OrderDirection::Ask,
+    ))
+    .run(deps.as_mut(), env.clone(), info.clone())
+    .unwrap();
+    deps.querier.base.update_balance(
+        env.contract.address.clone(),
+        vec![coin(20, BASE_DENOM), coin(3, QUOTE_DENOM)],
+    );
+
+    // -- System under test --
+    // The first order is counted, leaving the audit in progress
+    let res = sudo(deps.as_mut(), env.clone(), sweep.clone()).unwrap();
+    assert!(res.messages.is_empty());
+    assert!(DUST_AUDIT.may_load(&deps.storage).unwrap().is_some());
+
+    // A fill adds quote obligations to the order already counted, restarting the audit
+    OrderOperation::RunMarket(MarketOrder::new(
+        Uint128::from(7u128),
+        OrderDirection::Bid,
+        sender.clone(),
+    ))
+    .run(deps.as_mut(), env.clone(), info)
+    .unwrap();
+    deps.querier.base.update_balance(
+        env.contract.address.clone(),
+        vec![coin(17, BASE_DENOM), coin(10, QUOTE_DENOM)],
+    );
+    assert!(DUST_AUDIT.may_load(&deps.storage).unwrap().is_none());
+
+    // Both orders are counted in full pages before the audit completes
+    for _ in 0..2 {
+        let res = sudo(deps.as_mut(), env.clone(), sweep.clone()).unwrap();
+        assert!(res.messages.is_empty());
+    }
+    let res = sudo(deps.as_mut(), env.clone(), sweep).unwrap();
+
+    // -- Post Test Assertions --
+    // Only the quote held before the fill is swept, the 7 quote filled is owed to the first order
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![coin(3, QUOTE_DENOM)],
+        })]
+    );
+    assert!(DUST_AUDIT.may_load(&deps.storage).unwrap().is_none());
+}
+
+struct DonateDustTestCase {
+    name: &'static str,
+    pre_operations: Vec<OrderOperation>,
+    balance: Vec<Coin>,
+    funds: Vec<Coin>,
+    denom: &'static str,
+    expected_donation: Option<Coin>,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_donate_dust() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    // Leaves 6 base and 7 quote owed to the order owners
+    let place_and_fill = vec![
+        OrderOperation::PlaceLimitMulti((
+            vec![LARGE_POSITIVE_TICK],
+            2,
+            Uint128::from(10u128),
+            OrderDirection::Ask,
+        )),
+        OrderOperation::RunMarket(MarketOrder::new(
+            Uint128::from(7u128),
+            OrderDirection::Bid,
+            sender.clone(),
+        )),
+    ];
+
+    let test_cases = vec![
+        DonateDustTestCase {
+            name: "dust with no obligations",
+            pre_operations: vec![],
+            balance: vec![coin(5, BASE_DENOM)],
+            funds: vec![],
+            denom: BASE_DENOM,
+            expected_donation: Some(coin(5, BASE_DENOM)),
+            expected_error: None,
+        },
+        DonateDustTestCase {
+            name: "only surplus above obligations is donated",
+            pre_operations: place_and_fill.clone(),
+            balance: vec![coin(6, BASE_DENOM), coin(10, QUOTE_DENOM)],
+            funds: vec![],
+            denom: QUOTE_DENOM,
+            expected_donation: Some(coin(3, QUOTE_DENOM)),
+            expected_error: None,
+        },
+        DonateDustTestCase {
+            name: "balance matches obligations",
+            pre_operations: place_and_fill.clone(),
+            balance: vec![coin(6, BASE_DENOM), coin(7, QUOTE_DENOM)],
+            funds: vec![],
+            denom: BASE_DENOM,
+            expected_donation: None,
+            expected_error: Some(ContractError::NoSweepableSurplus {
+                denom: BASE_DENOM.to_string(),
+                balance: Uint256::from(6u128),
+                expected: Uint256::from(6u128),
+            }),
+        },
+        DonateDustTestCase {
+            name: "funds sent",
+            pre_operations: vec![],
+            balance: vec![coin(5, BASE_DENOM)],
+            funds: vec![coin(5, BASE_DENOM)],
+            denom: BASE_DENOM,
+            expected_donation: None,
+            expected_error: Some(ContractError::PaymentError(
+                cw_utils::PaymentError::NonPayable {},
+            )),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        for op in test.pre_operations {
+            op.run(deps.as_mut(), env.clone(), info.clone()).unwrap();
+        }
+        deps.querier
+            .base
+            .update_balance(env.contract.address.clone(), test.balance);
+
+        // -- System under test --
+        // Anyone may donate, so the caller is neither the admin nor an order owner
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info("anyone", &test.funds),
+            ExecuteMsg::DonateDust {
+                denom: test.denom.to_string(),
+                limit: None,
+            },
+        );
+
+        // -- Post Test Assertions --
+        if let Some(expected_error) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+
+        let expected_msgs: Vec<SubMsg> = test
+            .expected_donation
+            .into_iter()
+            .map(|donation| {
+                SubMsg::new(BankMsg::Send {
+                    to_address: DEFAULT_MAKER_FEE_RECIPIENT.to_string(),
+                    amount: vec![donation],
+                })
+            })
+            .collect();
+        assert_eq!(
+            res.unwrap().messages,
+            expected_msgs,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct DonateDustAuditTestCase {
+    name: &'static str,
+    limit: Option<u32>,
+    // Places an ask of 10 on a tick the first call has already counted
+    place_after_first_call: bool,
+    expected_calls: u32,
+}
+
+#[test]
+fn test_donate_dust_audit() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let test_cases = vec![
+        DonateDustAuditTestCase {
+            name: "default limit counts the book in one call",
+            limit: None,
+            place_after_first_call: false,
+            expected_calls: 1,
+        },
+        DonateDustAuditTestCase {
+            name: "audit spans several calls",
+            limit: Some(2),
+            place_after_first_call: false,
+            expected_calls: 2,
+        },
+        DonateDustAuditTestCase {
+            name: "full final page is followed by an empty page",
+            limit: Some(3),
+            place_after_first_call: false,
+            expected_calls: 2,
+        },
+        DonateDustAuditTestCase {
+            name: "placement restarts the audit",
+            limit: Some(2),
+            place_after_first_call: true,
+            // The restarted audit counts the four orders two at a time
+            expected_calls: 4,
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        // Leaves 30 base owed to the order owners, with 5 base of dust
+        OrderOperation::PlaceLimitMulti((
+            vec![0, 1, 2],
+            1,
+            Uint128::from(10u128),
+            OrderDirection::Ask,
+        ))
+        .run(deps.as_mut(), env.clone(), info.clone())
+        .unwrap();
+        deps.querier
+            .base
+            .update_balance(env.contract.address.clone(), vec![coin(35, BASE_DENOM)]);
+
+        // -- System under test --
+        for call in 1..=test.expected_calls {
+            let res = execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("anyone", &[]),
+                ExecuteMsg::DonateDust {
+                    denom: BASE_DENOM.to_string(),
+                    limit: test.limit,
+                },
+            )
+            .unwrap();
+
+            // -- Post Test Assertions --
+            if call < test.expected_calls {
+                assert!(
+                    res.messages.is_empty(),
+                    "{}: donated before the audit completed",
+                    format_test_name(test.name)
+                );
+                assert!(
+                    DUST_AUDIT.may_load(&deps.storage).unwrap().is_some(),
+                    "{}",
+                    format_test_name(test.name)
+                );
+            } else {
+                // The surplus never includes funds of orders placed during the audit
+                assert_eq!(
+                    res.messages,
+                    vec![SubMsg::new(BankMsg::Send {
+                        to_address: DEFAULT_MAKER_FEE_RECIPIENT.to_string(),
+                        amount: vec![coin(5, BASE_DENOM)],
+                    })],
+                    "{}",
+                    format_test_name(test.name)
+                );
+                assert!(
+                    DUST_AUDIT.may_load(&deps.storage).unwrap().is_none(),
+                    "{}",
+                    format_test_name(test.name)
+                );
+            }
+
+            if call == 1 && test.place_after_first_call {
+                OrderOperation::PlaceLimitMulti((
+                    vec![-1],
+                    1,
+                    Uint128::from(10u128),
+                    OrderDirection::Ask,
+                ))
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+                deps.querier
+                    .base
+                    .update_balance(env.contract.address.clone(), vec![coin(45, BASE_DENOM)]);
+                assert!(
+                    DUST_AUDIT.may_load(&deps.storage).unwrap().is_none(),
+                    "{}: placement did not restart the audit",
+                    format_test_name(test.name)
+                );
+            }
+        }
+    }
+}
+
+struct ActivationDelayTestCase {
+    name: &'static str,
+    activation_delay: u64,
+    // (tick_id, blocks after genesis placed at, quantity) of each resting ask
+    orders: Vec<(i64, u64, u128)>,
+    // Blocks after genesis the swap is made at
+    swap_at: u64,
+    swap_quantity: u128,
+    expected_output: u128,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_activation_delay() {
+    let test_cases = vec![
+        ActivationDelayTestCase {
+            name: "no delay fills immediately",
+            activation_delay: 0,
+            orders: vec![(0, 0, 10)],
+            swap_at: 0,
+            swap_quantity: 10,
+            expected_output: 10,
+            expected_error: None,
+        },
+        ActivationDelayTestCase {
+            name: "order within delay is not filled",
+            activation_delay: 5,
+            orders: vec![(0, 0, 10)],
+            swap_at: 4,
+            swap_quantity: 10,
+            expected_output: 0,
+            expected_error: Some(ContractError::InsufficientLiquidity),
+        },
+        ActivationDelayTestCase {
+            name: "order is filled once delay has passed",
+            activation_delay: 5,
+            orders: vec![(0, 0, 10)],
+            swap_at: 5,
+            swap_quantity: 10,
+            expected_output: 10,
+            expected_error: None,
+        },
+        ActivationDelayTestCase {
+            name: "active order ahead of inactive order is filled",
+            activation_delay: 5,
+            orders: vec![(0, 0, 10), (0, 3, 10)],
+            swap_at: 5,
+            swap_quantity: 10,
+            expected_output: 10,
+            expected_error: None,
+        },
+        ActivationDelayTestCase {
+            name: "inactive order behind active order is not filled",
+            activation_delay: 5,
+            orders: vec![(0, 0, 10), (0, 3, 10)],
+            swap_at: 5,
+            swap_quantity: 15,
+            expected_output: 0,
+            expected_error: Some(ContractError::InsufficientLiquidity),
+        },
+        ActivationDelayTestCase {
+            name: "tick with only inactive orders is skipped",
+            activation_delay: 5,
+            orders: vec![(0, 3, 10), (100, 0, 10)],
+            swap_at: 5,
+            swap_quantity: 10,
+            expected_output: 10,
+            expected_error: None,
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let genesis = mock_env();
+        let at = |blocks: u64| {
+            let mut env = genesis.clone();
+            env.block.height += blocks;
+            env
+        };
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        ACTIVATION_DELAY
+            .save(deps.as_mut().storage, &test.activation_delay)
+            .unwrap();
+        for (tick_id, placed_at, quantity) in test.orders {
+            place_limit(
+                &mut deps.as_mut(),
+                at(placed_at),
+                mock_info(DEFAULT_OWNER, &[coin(quantity, BASE_DENOM)]),
+                tick_id,
+                OrderDirection::Ask,
+                Uint128::from(quantity),
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+        }
+        let token_in = coin(test.swap_quantity, QUOTE_DENOM);
+
+        // -- System under test --
+        let simulated = query::calc_out_amount_given_in(
+            deps.as_ref(),
+            at(test.swap_at),
+            token_in.clone(),
+            BASE_DENOM.to_string(),
+            EXPECTED_SWAP_FEE,
+        );
+        let res = sudo(
+            deps.as_mut(),
+            at(test.swap_at),
+            SudoMsg::SwapExactAmountIn {
+                sender: DEFAULT_SENDER.to_string(),
+                token_in,
+                token_out_denom: BASE_DENOM.to_string(),
+                token_out_min_amount: Uint128::zero(),
+                swap_fee: EXPECTED_SWAP_FEE,
+                recipient: None,
+                max_slippage_ticks: None,
+            },
+        );
+
+        // -- Post Test Assertions --
+        // The simulation observes the same activation delay as the swap
+        if let Some(expected_error) = test.expected_error {
+            assert_eq!(
+                simulated.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            );
+            assert_eq!(
+                res.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+
+        assert_eq!(
+            simulated.unwrap().token_out.amount,
+            test.expected_output.to_string(),
+            "{}",
+            format_test_name(test.name)
+        );
+        let data: SwapExactAmountInResponseData = from_json(res.unwrap().data.unwrap()).unwrap();
+        assert_eq!(
+            data.token_out_amount,
+            Uint256::from(test.expected_output),
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct MaxSlippageTicksTestCase {
+    name: &'static str,
+    pre_operations: Vec<OrderOperation>,
+    order_direction: OrderDirection,
+    target_tick: Option<i64>,
+    max_slippage_ticks: u32,
+    // (tick_id, remaining liquidity) on the side filled by the swap
+    expected_liquidity: Vec<(i64, u128)>,
+}
+
+#[test]
+fn test_swap_max_slippage_ticks() {
+    let asks = [0, 10, 20, 30];
+    let bids = [0, -10, -20, -30];
+    let place = |tick_ids: &[i64], direction: OrderDirection| {
+        OrderOperation::PlaceLimitMulti((tick_ids.to_vec(), 1, Uint128::from(10u128), direction))
+    };
+    let test_cases = vec![
+        MaxSlippageTicksTestCase {
+            name: "bid bounded relative to best ask",
+            pre_operations: vec![place(&asks, OrderDirection::Ask)],
+            order_direction: OrderDirection::Bid,
+            target_tick: None,
+            max_slippage_ticks: 10,
+            expected_liquidity: vec![(0, 0), (10, 0), (20, 10), (30, 10)],
+        },
+        MaxSlippageTicksTestCase {
+            name: "best ask moved up between quote and execution",
+            pre_operations: vec![
+                place(&asks, OrderDirection::Ask),
+                // Empties tick 0 after the swap was quoted against it
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(10u128),
+                    OrderDirection::Bid,
+                    Addr::unchecked("frontrunner"),
+                )),
+            ],
+            order_direction: OrderDirection::Bid,
+            target_tick: None,
+            max_slippage_ticks: 10,
+            // The bound follows the best ask to tick 10, still stopping 10 ticks past it
+            expected_liquidity: vec![(0, 0), (10, 0), (20, 0), (30, 10)],
+        },
+        MaxSlippageTicksTestCase {
+            name: "ask bounded relative to best bid",
+            pre_operations: vec![place(&bids, OrderDirection::Bid)],
+            order_direction: OrderDirection::Ask,
+            target_tick: None,
+            max_slippage_ticks: 10,
+            expected_liquidity: vec![(0, 0), (-10, 0), (-20, 10), (-30, 10)],
+        },
+        MaxSlippageTicksTestCase {
+            name: "best bid moved down between quote and execution",
+            pre_operations: vec![
+                place(&bids, OrderDirection::Bid),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(10u128),
+                    OrderDirection::Ask,
+                    Addr::unchecked("frontrunner"),
+                )),
+            ],
+            order_direction: OrderDirection::Ask,
+            target_tick: None,
+            max_slippage_ticks: 10,
+            expected_liquidity: vec![(0, 0), (-10, 0), (-20, 0), (-30, 10)],
+        },
+        MaxSlippageTicksTestCase {
+            name: "zero slippage fills only the best tick",
+            pre_operations: vec![place(&asks, OrderDirection::Ask)],
+            order_direction: OrderDirection::Bid,
+            target_tick: None,
+            max_slippage_ticks: 0,
+            expected_liquidity: vec![(0, 0), (10, 10), (20, 10), (30, 10)],
+        },
+        MaxSlippageTicksTestCase {
+            name: "target tick reached first",
+            pre_operations: vec![place(&asks, OrderDirection::Ask)],
+            order_direction: OrderDirection::Bid,
+            target_tick: Some(5),
+            max_slippage_ticks: 20,
+            expected_liquidity: vec![(0, 0), (10, 10), (20, 10), (30, 10)],
+        },
+        MaxSlippageTicksTestCase {
+            name: "slippage bound reached first",
+            pre_operations: vec![place(&asks, OrderDirection::Ask)],
+            order_direction: OrderDirection::Bid,
+            target_tick: Some(25),
+            max_slippage_ticks: 10,
+            expected_liquidity: vec![(0, 0), (10, 0), (20, 10), (30, 10)],
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        for op in test.pre_operations {
+            op.run(deps.as_mut(), env.clone(), mock_info(DEFAULT_OWNER, &[]))
+                .unwrap();
+        }
+
+        // -- System under test --
+        let (denom_in, denom_out) = match test.order_direction {
+            OrderDirection::Bid => (QUOTE_DENOM, BASE_DENOM),
+            OrderDirection::Ask => (BASE_DENOM, QUOTE_DENOM),
+        };
+        let response = dispatch_swap_exact_amount_in(
+            deps.as_mut(),
+            env.clone(),
+            DEFAULT_SENDER.to_string(),
+            coin(100u128, denom_in),
+            denom_out.to_string(),
+            Uint128::zero(),
+            EXPECTED_SWAP_FEE,
+            test.target_tick,
+            Some(test.max_slippage_ticks),
+            None,
+        )
+        .unwrap();
+
+        // -- Post test assertions --
+        let filled_direction = test.order_direction.opposite();
+        for (tick_id, expected_liquidity) in test.expected_liquidity {
+            let liquidity = TICK_STATE
+                .load(deps.as_ref().storage, tick_id)
+                .unwrap()
+                .get_values(filled_direction)
+                .total_amount_of_liquidity;
+            assert_eq!(
+                liquidity,
+                Decimal256::from_ratio(expected_liquidity, 1u128),
+                "{}: tick {tick_id}",
+                format_test_name(test.name)
+            );
+        }
+
+        // Input beyond the bound is refunded rather than filled
+        assert!(
+            response
+                .messages
+                .iter()
+                .any(|msg| msg.id == REPLY_ID_REFUND),
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
@@ -1,10 +1,20 @@
+use crate::contract::execute;
+use crate::msg::ExecuteMsg;
+use crate::orderbook::{create_orderbook, set_prune_reward};
 use crate::state::TICK_STATE;
-use crate::sumtree::node::NodeType;
+use crate::sumtree::node::{NodeType, NODES};
 use crate::sumtree::test::test_tree::insert_and_refetch;
+use crate::sumtree::tree::TREE;
+use crate::tests::mock_querier::mock_dependencies_custom;
+use crate::tests::test_constants::{BASE_DENOM, DEFAULT_OWNER, DEFAULT_SENDER, QUOTE_DENOM};
+use crate::tests::test_utils::{format_test_name, OrderOperation};
 use crate::tick::sync_tick;
-use crate::types::{OrderDirection, TickState, TickValues};
-use cosmwasm_std::testing::mock_dependencies;
-use cosmwasm_std::{Decimal256, Storage};
+use crate::types::{MarketOrder, OrderDirection, TickState, TickValues, REPLY_ID_PRUNE_REWARD};
+use crate::ContractError;
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cosmwasm_std::{
+    coin, to_json_vec, Addr, BankMsg, Coin, Decimal256, Order, Storage, SubMsg, Uint128,
+};
 
 struct SyncTickTestCase {
     name: &'static str,
@@ -638,3 +648,544 @@ fn increment_tick_etas(
 
     (updated_bid_etas, updated_ask_etas)
 }
+
+struct PruneTickTestCase {
+    name: &'static str,
+    pre_operations: Vec<OrderOperation>,
+    // Directions pruned on the tick before the system under test
+    pre_prunes: Vec<OrderDirection>,
+    tick_id: i64,
+    direction: OrderDirection,
+    reward: Option<Coin>,
+    pool: Vec<Coin>,
+    expected_reward: Option<Coin>,
+    expected_tick_removed: bool,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_prune_tick() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let keeper = "keeper";
+    let reward_denom = "ureward";
+
+    let test_cases = vec![
+        PruneTickTestCase {
+            name: "filled and claimed tick",
+            pre_operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![0],
+                    1,
+                    Uint128::from(10u128),
+                    OrderDirection::Ask,
+                )),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(10u128),
+                    OrderDirection::Bid,
+                    sender.clone(),
+                )),
+                OrderOperation::Claim((0, 0)),
+            ],
+            pre_prunes: vec![],
+            tick_id: 0,
+            direction: OrderDirection::Ask,
+            reward: Some(coin(5, reward_denom)),
+            pool: vec![coin(100, reward_denom)],
+            expected_reward: Some(coin(5, reward_denom)),
+            expected_tick_removed: true,
+            expected_error: None,
+        },
+        PruneTickTestCase {
+            name: "tick with cancelled orders",
+            pre_operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![0],
+                    2,
+                    Uint128::from(10u128),
+                    OrderDirection::Bid,
+                )),
+                OrderOperation::Cancel((0, 0)),
+                OrderOperation::Cancel((0, 1)),
+            ],
+            pre_prunes: vec![],
+            tick_id: 0,
+            direction: OrderDirection::Bid,
+            reward: None,
+            pool: vec![],
+            expected_reward: None,
+            expected_tick_removed: true,
+            expected_error: None,
+        },
+        PruneTickTestCase {
+            name: "cancelled placement is not rewarded",
+            pre_operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![0],
+                    1,
+                    Uint128::from(10u128),
+                    OrderDirection::Ask,
+                )),
+                OrderOperation::Cancel((0, 0)),
+            ],
+            pre_prunes: vec![],
+            tick_id: 0,
+            direction: OrderDirection::Ask,
+            reward: Some(coin(5, reward_denom)),
+            pool: vec![coin(100, reward_denom)],
+            expected_reward: None,
+            expected_tick_removed: true,
+            expected_error: None,
+        },
+        PruneTickTestCase {
+            name: "partially filled, claimed then cancelled",
+            pre_operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![0],
+                    1,
+                    Uint128::from(10u128),
+                    OrderDirection::Ask,
+                )),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(4u128),
+                    OrderDirection::Bid,
+                    sender.clone(),
+                )),
+                OrderOperation::Claim((0, 0)),
+                OrderOperation::Cancel((0, 0)),
+            ],
+            pre_prunes: vec![],
+            tick_id: 0,
+            direction: OrderDirection::Ask,
+            reward: Some(coin(5, reward_denom)),
+            pool: vec![coin(100, reward_denom)],
+            expected_reward: Some(coin(5, reward_denom)),
+            expected_tick_removed: true,
+            expected_error: None,
+        },
+        PruneTickTestCase {
+            name: "pool smaller than reward",
+            pre_operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![0],
+                    1,
+                    Uint128::from(10u128),
+                    OrderDirection::Ask,
+                )),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(10u128),
+                    OrderDirection::Bid,
+                    sender.clone(),
+                )),
+                OrderOperation::Claim((0, 0)),
+            ],
+            pre_prunes: vec![],
+            tick_id: 0,
+            direction: OrderDirection::Ask,
+            reward: Some(coin(5, reward_denom)),
+            pool: vec![coin(3, reward_denom)],
+            expected_reward: Some(coin(3, reward_denom)),
+            expected_tick_removed: true,
+            expected_error: None,
+        },
+        PruneTickTestCase {
+            name: "empty pool",
+            pre_operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![0],
+                    1,
+                    Uint128::from(10u128),
+                    OrderDirection::Ask,
+                )),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(10u128),
+                    OrderDirection::Bid,
+                    sender.clone(),
+                )),
+                OrderOperation::Claim((0, 0)),
+            ],
+            pre_prunes: vec![],
+            tick_id: 0,
+            direction: OrderDirection::Ask,
+            reward: Some(coin(5, reward_denom)),
+            pool: vec![coin(100, "other")],
+            expected_reward: None,
+            expected_tick_removed: true,
+            expected_error: None,
+        },
+        PruneTickTestCase {
+            name: "other side still active",
+            pre_operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![0],
+                    1,
+                    Uint128::from(10u128),
+                    OrderDirection::Ask,
+                )),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(10u128),
+                    OrderDirection::Bid,
+                    sender.clone(),
+                )),
+                OrderOperation::Claim((0, 0)),
+                OrderOperation::PlaceLimitMulti((
+                    vec![0],
+                    1,
+                    Uint128::from(10u128),
+                    OrderDirection::Bid,
+                )),
+            ],
+            pre_prunes: vec![],
+            tick_id: 0,
+            direction: OrderDirection::Ask,
+            reward: Some(coin(5, reward_denom)),
+            pool: vec![coin(100, reward_denom)],
+            expected_reward: Some(coin(5, reward_denom)),
+            expected_tick_removed: false,
+            expected_error: None,
+        },
+        PruneTickTestCase {
+            name: "unclaimed fill",
+            pre_operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![0],
+                    1,
+                    Uint128::from(10u128),
+                    OrderDirection::Ask,
+                )),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(10u128),
+                    OrderDirection::Bid,
+                    sender.clone(),
+                )),
+            ],
+            pre_prunes: vec![],
+            tick_id: 0,
+            direction: OrderDirection::Ask,
+            reward: Some(coin(5, reward_denom)),
+            pool: vec![coin(100, reward_denom)],
+            expected_reward: None,
+            expected_tick_removed: false,
+            expected_error: Some(ContractError::TickNotEmpty { tick_id: 0 }),
+        },
+        PruneTickTestCase {
+            name: "resting liquidity",
+            pre_operations: vec![OrderOperation::PlaceLimitMulti((
+                vec![0],
+                1,
+                Uint128::from(10u128),
+                OrderDirection::Ask,
+            ))],
+            pre_prunes: vec![],
+            tick_id: 0,
+            direction: OrderDirection::Ask,
+            reward: None,
+            pool: vec![],
+            expected_reward: None,
+            expected_tick_removed: false,
+            expected_error: Some(ContractError::TickNotEmpty { tick_id: 0 }),
+        },
+        PruneTickTestCase {
+            name: "side already pruned",
+            pre_operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![0],
+                    1,
+                    Uint128::from(10u128),
+                    OrderDirection::Ask,
+                )),
+                OrderOperation::Cancel((0, 0)),
+                OrderOperation::PlaceLimitMulti((
+                    vec![0],
+                    1,
+                    Uint128::from(10u128),
+                    OrderDirection::Bid,
+                )),
+            ],
+            pre_prunes: vec![OrderDirection::Ask],
+            tick_id: 0,
+            direction: OrderDirection::Ask,
+            reward: Some(coin(5, reward_denom)),
+            pool: vec![coin(100, reward_denom)],
+            expected_reward: None,
+            expected_tick_removed: false,
+            expected_error: Some(ContractError::TickAlreadyPruned { tick_id: 0 }),
+        },
+        PruneTickTestCase {
+            name: "tick without state",
+            pre_operations: vec![],
+            pre_prunes: vec![],
+            tick_id: 0,
+            direction: OrderDirection::Ask,
+            reward: None,
+            pool: vec![],
+            expected_reward: None,
+            expected_tick_removed: true,
+            expected_error: Some(ContractError::InvalidTickId { tick_id: 0 }),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        set_prune_reward(deps.as_mut().storage, test.reward.clone()).unwrap();
+        if !test.pool.is_empty() {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(DEFAULT_OWNER, &test.pool),
+                ExecuteMsg::FundPruneRewards {},
+            )
+            .unwrap();
+        }
+
+        for op in test.pre_operations {
+            op.run(deps.as_mut(), env.clone(), info.clone()).unwrap();
+        }
+        for direction in test.pre_prunes {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(keeper, &[]),
+                ExecuteMsg::PruneTick {
+                    tick_id: test.tick_id,
+                    direction,
+                },
+            )
+            .unwrap();
+        }
+
+        // -- System under test --
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(keeper, &[]),
+            ExecuteMsg::PruneTick {
+                tick_id: test.tick_id,
+                direction: test.direction,
+            },
+        );
+
+        // -- Post Test Assertions --
+        if let Some(expected_error) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+
+        let res = res.unwrap();
+        let expected_messages: Vec<SubMsg> = test
+            .expected_reward
+            .into_iter()
+            .map(|reward| {
+                SubMsg::reply_on_error(
+                    BankMsg::Send {
+                        to_address: keeper.to_string(),
+                        amount: vec![reward],
+                    },
+                    REPLY_ID_PRUNE_REWARD,
+                )
+            })
+            .collect();
+        assert_eq!(
+            res.messages,
+            expected_messages,
+            "{}",
+            format_test_name(test.name)
+        );
+
+        assert!(
+            TREE.may_load(
+                deps.as_ref().storage,
+                &(test.tick_id, &test.direction.to_string())
+            )
+            .unwrap()
+            .is_none(),
+            "{}",
+            format_test_name(test.name)
+        );
+        let tick_state = TICK_STATE
+            .may_load(deps.as_ref().storage, test.tick_id)
+            .unwrap();
+        if test.expected_tick_removed {
+            assert!(tick_state.is_none(), "{}", format_test_name(test.name));
+            assert!(
+                NODES
+                    .prefix(test.tick_id)
+                    .keys(deps.as_ref().storage, None, None, Order::Ascending)
+                    .next()
+                    .is_none(),
+                "{}",
+                format_test_name(test.name)
+            );
+        } else {
+            assert_eq!(
+                tick_state.unwrap().get_values(test.direction),
+                TickValues::default(),
+                "{}",
+                format_test_name(test.name)
+            );
+        }
+
+        // Placing on the pruned side starts from fresh tick values
+        OrderOperation::PlaceLimitMulti((
+            vec![test.tick_id],
+            1,
+            Uint128::from(10u128),
+            test.direction,
+        ))
+        .run(deps.as_mut(), env.clone(), info.clone())
+        .unwrap();
+    }
+}
+
+struct TickValuesDerivedTestCase {
+    name: &'static str,
+    cumulative_total_value: Decimal256,
+    effective_total_amount_swapped: Decimal256,
+    expected_remaining: Decimal256,
+    expected_fill_ratio: Decimal256,
+}
+
+#[test]
+fn test_tick_values_remaining_and_fill_ratio() {
+    let test_cases = vec![
+        TickValuesDerivedTestCase {
+            name: "empty tick",
+            cumulative_total_value: Decimal256::zero(),
+            effective_total_amount_swapped: Decimal256::zero(),
+            expected_remaining: Decimal256::zero(),
+            expected_fill_ratio: Decimal256::zero(),
+        },
+        TickValuesDerivedTestCase {
+            name: "unfilled tick",
+            cumulative_total_value: Decimal256::from_ratio(100u128, 1u128),
+            effective_total_amount_swapped: Decimal256::zero(),
+            expected_remaining: Decimal256::from_ratio(100u128, 1u128),
+            expected_fill_ratio: Decimal256::zero(),
+        },
+        TickValuesDerivedTestCase {
+            name: "partially filled tick",
+            cumulative_total_value: Decimal256::from_ratio(100u128, 1u128),
+            effective_total_amount_swapped: Decimal256::from_ratio(25u128, 1u128),
+            expected_remaining: Decimal256::from_ratio(75u128, 1u128),
+            expected_fill_ratio: Decimal256::percent(25),
+        },
+        TickValuesDerivedTestCase {
+            name: "fully filled tick",
+            cumulative_total_value: Decimal256::from_ratio(100u128, 1u128),
+            effective_total_amount_swapped: Decimal256::from_ratio(100u128, 1u128),
+            expected_remaining: Decimal256::zero(),
+            expected_fill_ratio: Decimal256::one(),
+        },
+        TickValuesDerivedTestCase {
+            name: "overfilled tick clamps",
+            cumulative_total_value: Decimal256::from_ratio(100u128, 1u128),
+            effective_total_amount_swapped: Decimal256::from_ratio(150u128, 1u128),
+            expected_remaining: Decimal256::zero(),
+            expected_fill_ratio: Decimal256::one(),
+        },
+        TickValuesDerivedTestCase {
+            name: "swapped without any placed value",
+            cumulative_total_value: Decimal256::zero(),
+            effective_total_amount_swapped: Decimal256::from_ratio(10u128, 1u128),
+            expected_remaining: Decimal256::zero(),
+            expected_fill_ratio: Decimal256::zero(),
+        },
+        TickValuesDerivedTestCase {
+            name: "ratio too large to represent clamps",
+            cumulative_total_value: Decimal256::from_atomics(1u128, 18).unwrap(),
+            effective_total_amount_swapped: Decimal256::MAX,
+            expected_remaining: Decimal256::zero(),
+            expected_fill_ratio: Decimal256::one(),
+        },
+    ];
+
+    for test in test_cases {
+        let tick_values = TickValues {
+            cumulative_total_value: test.cumulative_total_value,
+            effective_total_amount_swapped: test.effective_total_amount_swapped,
+            ..TickValues::default()
+        };
+
+        assert_eq!(
+            tick_values.remaining(),
+            test.expected_remaining,
+            "{}",
+            format_test_name(test.name)
+        );
+        assert_eq!(
+            tick_values.fill_ratio(),
+            test.expected_fill_ratio,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+#[test]
+fn test_tick_state_defaults() {
+    let mut deps = mock_dependencies();
+    let tick_id = 5;
+    let zeroed = TickValues {
+        total_amount_of_liquidity: Decimal256::zero(),
+        cumulative_total_value: Decimal256::zero(),
+        effective_total_amount_swapped: Decimal256::zero(),
+        cumulative_realized_cancels: Decimal256::zero(),
+        last_tick_sync_etas: Decimal256::zero(),
+    };
+
+    // An unwritten tick loads as the default state with both directions zeroed
+    let tick_state = TICK_STATE
+        .may_load(deps.as_ref().storage, tick_id)
+        .unwrap()
+        .unwrap_or_default();
+    assert_eq!(tick_state, TickState::default());
+    for direction in [OrderDirection::Bid, OrderDirection::Ask] {
+        assert_eq!(tick_state.get_values(direction), zeroed);
+    }
+
+    for direction in [OrderDirection::Bid, OrderDirection::Ask] {
+        let values = TickValues {
+            total_amount_of_liquidity: Decimal256::percent(1000),
+            cumulative_total_value: Decimal256::percent(2000),
+            effective_total_amount_swapped: Decimal256::percent(500),
+            cumulative_realized_cancels: Decimal256::percent(100),
+            last_tick_sync_etas: Decimal256::percent(500),
+        };
+
+        // Setting one direction leaves the other untouched
+        let mut tick_state = TickState::default();
+        tick_state.set_values(direction, values.clone());
+        assert_eq!(tick_state.get_values(direction), values);
+        assert_eq!(tick_state.get_values(direction.opposite()), zeroed);
+
+        // The state survives a round trip through storage
+        TICK_STATE
+            .save(deps.as_mut().storage, tick_id, &tick_state)
+            .unwrap();
+        let loaded = TICK_STATE.load(deps.as_ref().storage, tick_id).unwrap();
+        assert_eq!(loaded, tick_state);
+        assert_eq!(loaded.get_values(direction.opposite()), zeroed);
+
+        // Re-serializing the loaded state yields identical bytes
+        assert_eq!(
+            to_json_vec(&loaded).unwrap(),
+            to_json_vec(&tick_state).unwrap()
+        );
+    }
+}
@@ -1,9 +1,15 @@
 use crate::constants::*;
 use crate::error::ContractError;
+use crate::tests::test_utils::format_test_name;
 use crate::tick_math::{
-    divide_by_price, multiply_by_price, pow_ten, tick_to_price, RoundingDirection,
+    divide_by_price, input_for_output, multiply_by_price, multiply_by_ratio, output_for_input,
+    pow_base, pow_ten, price_limit_to_tick_bound, tick_base, tick_to_price, tick_to_price_pair,
+    RoundingMode,
+};
+use crate::types::OrderDirection;
+use cosmwasm_std::{
+    Decimal256, DivideByZeroError, OverflowError, OverflowOperation, Uint128, Uint256,
 };
-use cosmwasm_std::{Decimal256, OverflowError, OverflowOperation, Uint128, Uint256};
 use std::str::FromStr;
 
 struct TickToPriceTestCase {
@@ -34,6 +40,12 @@ fn test_tick_to_price() {
             expected_price: Decimal256::from_str("0.000000000001").unwrap(),
             expected_error: None,
         },
+        TickToPriceTestCase {
+            // The finest additive increment, 10^-18, is still within Decimal256 precision
+            tick_index: MIN_TICK + 1,
+            expected_price: Decimal256::from_str("0.000000000001000001").unwrap(),
+            expected_error: None,
+        },
         TickToPriceTestCase {
             tick_index: 40000000,
             expected_price: Decimal256::from_str("50000").unwrap(),
@@ -216,7 +228,7 @@ struct OperByPriceTestCase {
     amount: Uint128,
     expected_result: Uint256,
     expected_error: Option<ContractError>,
-    rounding_direction: RoundingDirection,
+    rounding_mode: RoundingMode,
 }
 
 #[test]
@@ -228,7 +240,7 @@ fn test_multiply_by_price() {
             amount: Uint128::from(10u128),
             expected_result: Uint256::from(50u128),
             expected_error: None,
-            rounding_direction: RoundingDirection::Down,
+            rounding_mode: RoundingMode::Down,
         },
         OperByPriceTestCase {
             name: "basic price multiplication w/ rounding (down)",
@@ -238,7 +250,7 @@ fn test_multiply_by_price() {
             // 0.05 * 3 = 0.15, which truncates to 0
             expected_result: Uint256::zero(),
             expected_error: None,
-            rounding_direction: RoundingDirection::Down,
+            rounding_mode: RoundingMode::Down,
         },
         OperByPriceTestCase {
             name: "basic price multiplication w/ rounding (up)",
@@ -248,7 +260,7 @@ fn test_multiply_by_price() {
             // 0.05 * 3 = 0.15, which truncates to 0
             expected_result: Uint256::one(),
             expected_error: None,
-            rounding_direction: RoundingDirection::Up,
+            rounding_mode: RoundingMode::Up,
         },
         OperByPriceTestCase {
             name: "error overflow",
@@ -260,12 +272,12 @@ fn test_multiply_by_price() {
                 operand2: Decimal256::MAX.to_string(),
                 operand1: Uint128::MAX.to_string(),
             })),
-            rounding_direction: RoundingDirection::Down,
+            rounding_mode: RoundingMode::Down,
         },
     ];
 
     for test in test_cases {
-        let result = multiply_by_price(test.amount, test.price, test.rounding_direction);
+        let result = multiply_by_price(test.amount, test.price, test.rounding_mode);
         if let Some(expected_error) = test.expected_error {
             assert_eq!(result.unwrap_err(), expected_error, "{}", test.name);
         } else {
@@ -283,7 +295,7 @@ fn test_divide_by_price() {
             amount: Uint128::from(10u128),
             expected_result: Uint256::from(2u128),
             expected_error: None,
-            rounding_direction: RoundingDirection::Down,
+            rounding_mode: RoundingMode::Down,
         },
         OperByPriceTestCase {
             name: "basic price division w/ rounding (down)",
@@ -291,7 +303,7 @@ fn test_divide_by_price() {
             amount: Uint128::from(1u128),
             expected_result: Uint256::zero(),
             expected_error: None,
-            rounding_direction: RoundingDirection::Down,
+            rounding_mode: RoundingMode::Down,
         },
         OperByPriceTestCase {
             name: "basic price division w/ rounding (up)",
@@ -299,24 +311,23 @@ fn test_divide_by_price() {
             amount: Uint128::from(1u128),
             expected_result: Uint256::one(),
             expected_error: None,
-            rounding_direction: RoundingDirection::Up,
+            rounding_mode: RoundingMode::Up,
         },
         OperByPriceTestCase {
-            name: "error overflow",
+            // The price underflows Decimal256 precision to zero
+            name: "error zero price",
             price: Decimal256::from_ratio(Uint256::one(), Uint256::MAX),
             amount: Uint128::MAX,
             expected_result: Uint256::from(1u128),
-            expected_error: Some(ContractError::Overflow(OverflowError {
-                operation: OverflowOperation::Mul,
-                operand2: Decimal256::from_ratio(Uint256::one(), Uint256::MAX).to_string(),
-                operand1: Uint128::MAX.to_string(),
-            })),
-            rounding_direction: RoundingDirection::Down,
+            expected_error: Some(ContractError::DivideByZero(DivideByZeroError::new(
+                Uint128::MAX,
+            ))),
+            rounding_mode: RoundingMode::Down,
         },
     ];
 
     for test in test_cases {
-        let result = divide_by_price(test.amount, test.price, test.rounding_direction);
+        let result = divide_by_price(test.amount, test.price, test.rounding_mode);
         if let Some(expected_error) = test.expected_error {
             assert_eq!(result.unwrap_err(), expected_error, "{}", test.name);
         } else {
@@ -324,3 +335,470 @@ fn test_divide_by_price() {
         }
     }
 }
+
+struct MultiplyByRatioTestCase {
+    name: &'static str,
+    amount: Uint256,
+    ratio: Decimal256,
+    rounding_mode: RoundingMode,
+    expected_result: Uint256,
+}
+
+#[test]
+fn test_multiply_by_ratio() {
+    let test_cases: Vec<MultiplyByRatioTestCase> = vec![
+        MultiplyByRatioTestCase {
+            name: "exact ratio",
+            amount: Uint256::from(200u128),
+            ratio: Decimal256::percent(1),
+            rounding_mode: RoundingMode::Down,
+            expected_result: Uint256::from(2u128),
+        },
+        MultiplyByRatioTestCase {
+            name: "rounding (down)",
+            amount: Uint256::from(150u128),
+            ratio: Decimal256::percent(1),
+            rounding_mode: RoundingMode::Down,
+            // 150 * 0.01 = 1.5, which truncates to 1
+            expected_result: Uint256::one(),
+        },
+        MultiplyByRatioTestCase {
+            name: "rounding (up)",
+            amount: Uint256::from(150u128),
+            ratio: Decimal256::percent(1),
+            rounding_mode: RoundingMode::Up,
+            expected_result: Uint256::from(2u128),
+        },
+        MultiplyByRatioTestCase {
+            name: "dust amount rounds to zero",
+            amount: Uint256::one(),
+            ratio: Decimal256::percent(1),
+            rounding_mode: RoundingMode::Down,
+            expected_result: Uint256::zero(),
+        },
+    ];
+
+    for test in test_cases {
+        let result = multiply_by_ratio(test.amount, test.ratio, test.rounding_mode).unwrap();
+        assert_eq!(result, test.expected_result, "{}", test.name);
+    }
+}
+
+struct InputOutputTestCase {
+    name: &'static str,
+    tick_id: i64,
+    direction: OrderDirection,
+    input: Uint128,
+    expected_output: Uint256,
+    expected_input_for_output: Uint256,
+}
+
+#[test]
+fn test_input_output_for_tick() {
+    let test_cases: Vec<InputOutputTestCase> = vec![
+        InputOutputTestCase {
+            name: "bid at price one",
+            tick_id: 0,
+            direction: OrderDirection::Bid,
+            input: Uint128::from(100u128),
+            expected_output: Uint256::from(100u128),
+            expected_input_for_output: Uint256::from(100u128),
+        },
+        InputOutputTestCase {
+            name: "bid at fractional price rounds output down",
+            tick_id: 4010000,
+            direction: OrderDirection::Bid,
+            input: Uint128::from(10u128),
+            // 10 * 5.01 = 50.1
+            expected_output: Uint256::from(50u128),
+            // 50 / 5.01 = 9.98
+            expected_input_for_output: Uint256::from(10u128),
+        },
+        InputOutputTestCase {
+            name: "ask at fractional price rounds input up",
+            tick_id: 4010000,
+            direction: OrderDirection::Ask,
+            input: Uint128::from(10u128),
+            // 10 / 5.01 = 1.996
+            expected_output: Uint256::one(),
+            // 1 * 5.01 = 5.01
+            expected_input_for_output: Uint256::from(6u128),
+        },
+        InputOutputTestCase {
+            name: "ask below one unit of output",
+            tick_id: 40000000,
+            direction: OrderDirection::Ask,
+            input: Uint128::from(49999u128),
+            expected_output: Uint256::zero(),
+            expected_input_for_output: Uint256::zero(),
+        },
+        InputOutputTestCase {
+            name: "ask at high price",
+            tick_id: 40000000,
+            direction: OrderDirection::Ask,
+            input: Uint128::from(100000u128),
+            expected_output: Uint256::from(2u128),
+            expected_input_for_output: Uint256::from(100000u128),
+        },
+        InputOutputTestCase {
+            name: "bid below price one",
+            tick_id: -5000000,
+            direction: OrderDirection::Bid,
+            input: Uint128::from(3u128),
+            // 3 * 0.5 = 1.5
+            expected_output: Uint256::one(),
+            expected_input_for_output: Uint256::from(2u128),
+        },
+        InputOutputTestCase {
+            name: "bid at min tick",
+            tick_id: MIN_TICK,
+            direction: OrderDirection::Bid,
+            input: Uint128::from(1_000_000_000_001u128),
+            expected_output: Uint256::one(),
+            expected_input_for_output: Uint256::from(1_000_000_000_000u128),
+        },
+    ];
+
+    for test in test_cases {
+        let price = tick_to_price(test.tick_id).unwrap();
+
+        let output = output_for_input(price, test.input, test.direction).unwrap();
+        assert_eq!(
+            output,
+            test.expected_output,
+            "{}",
+            format_test_name(test.name)
+        );
+
+        let input =
+            input_for_output(price, Uint128::try_from(output).unwrap(), test.direction).unwrap();
+        assert_eq!(
+            input,
+            test.expected_input_for_output,
+            "{}",
+            format_test_name(test.name)
+        );
+
+        // Rounding must never let a round trip extract more than was put in
+        assert!(
+            input <= Uint256::from_uint128(test.input),
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+#[test]
+fn test_input_output_for_tick_rounding_invariant() {
+    let ticks = [MIN_TICK, -5000000, -1, 0, 1, 4010000, 40000001, MAX_TICK];
+    let amounts = [1u128, 7, 999, 1_000_003, 123_456_789_012];
+
+    for tick_id in ticks {
+        let price = tick_to_price(tick_id).unwrap();
+        for direction in [OrderDirection::Bid, OrderDirection::Ask] {
+            for amount in amounts {
+                let input = Uint128::from(amount);
+                let output = output_for_input(price, input, direction).unwrap();
+                let required_input =
+                    input_for_output(price, Uint128::try_from(output).unwrap(), direction).unwrap();
+                assert!(
+                    required_input <= Uint256::from_uint128(input),
+                    "tick {tick_id}, {direction:?}, amount {amount}"
+                );
+            }
+        }
+    }
+}
+
+struct TickToPricePairTestCase {
+    name: &'static str,
+    tick_index: i64,
+    expected_price: Decimal256,
+    expected_inverse_price: Decimal256,
+}
+
+#[test]
+fn test_tick_to_price_pair() {
+    let test_cases = vec![
+        TickToPricePairTestCase {
+            name: "price one",
+            tick_index: 0,
+            expected_price: Decimal256::one(),
+            expected_inverse_price: Decimal256::one(),
+        },
+        TickToPricePairTestCase {
+            name: "exact reciprocal",
+            tick_index: 1000000,
+            expected_price: Decimal256::from_str("2").unwrap(),
+            expected_inverse_price: Decimal256::from_str("0.5").unwrap(),
+        },
+        TickToPricePairTestCase {
+            name: "repeating reciprocal",
+            tick_index: 4010000,
+            expected_price: Decimal256::from_str("5.01").unwrap(),
+            expected_inverse_price: Decimal256::from_str("0.199600798403193612").unwrap(),
+        },
+        TickToPricePairTestCase {
+            name: "price below one",
+            tick_index: -2000,
+            expected_price: Decimal256::from_str("0.9998").unwrap(),
+            expected_inverse_price: Decimal256::from_str("1.0002000400080016").unwrap(),
+        },
+        TickToPricePairTestCase {
+            name: "negative additive ticks",
+            tick_index: -9999900,
+            expected_price: Decimal256::from_str("0.090001").unwrap(),
+            expected_inverse_price: Decimal256::from_str("11.110987655692714525").unwrap(),
+        },
+        TickToPricePairTestCase {
+            name: "large price",
+            tick_index: 40303000,
+            expected_price: Decimal256::from_str("53030").unwrap(),
+            expected_inverse_price: Decimal256::from_str("0.00001885725061286").unwrap(),
+        },
+        TickToPricePairTestCase {
+            name: "min tick",
+            tick_index: MIN_TICK,
+            expected_price: min_spot_price(),
+            expected_inverse_price: Decimal256::from_str("1000000000000").unwrap(),
+        },
+        TickToPricePairTestCase {
+            name: "near min tick",
+            tick_index: -107765433,
+            expected_price: Decimal256::from_str("0.000000000001234567").unwrap(),
+            expected_inverse_price: Decimal256::from_str("810000591300.431649315104000025")
+                .unwrap(),
+        },
+        TickToPricePairTestCase {
+            name: "max tick (reciprocal below precision)",
+            tick_index: MAX_TICK,
+            expected_price: max_spot_price(),
+            expected_inverse_price: Decimal256::zero(),
+        },
+    ];
+
+    for test in test_cases {
+        let (price, inverse_price) = tick_to_price_pair(test.tick_index).unwrap();
+        assert_eq!(price, test.expected_price, "{}: price", test.name);
+        assert_eq!(
+            inverse_price, test.expected_inverse_price,
+            "{}: inverse price",
+            test.name
+        );
+
+        // The price half of the pair always matches `tick_to_price`
+        assert_eq!(
+            price,
+            tick_to_price(test.tick_index).unwrap(),
+            "{}",
+            test.name
+        );
+    }
+}
+
+#[test]
+fn test_tick_to_price_pair_precision() {
+    // The reciprocal is rounded down once to 18 decimal places, so in atomic units
+    // `price * inverse_price` must lie strictly within `price` below 10^36.
+    let one = Uint256::from(10u8).checked_pow(36).unwrap();
+    let tick_step = (MAX_TICK - MIN_TICK) / 997;
+    for tick_index in (MIN_TICK..=MAX_TICK).step_by(tick_step as usize) {
+        let (price, inverse_price) = tick_to_price_pair(tick_index).unwrap();
+        let product = price
+            .atomics()
+            .checked_mul(inverse_price.atomics())
+            .unwrap();
+
+        assert!(product <= one, "tick {tick_index}: product exceeds one");
+        assert!(
+            one - product < price.atomics(),
+            "tick {tick_index}: reciprocal {inverse_price} of {price} exceeds rounding error"
+        );
+    }
+}
+
+struct PriceLimitToTickBoundTestCase {
+    name: &'static str,
+    price_limit: Decimal256,
+    order_direction: OrderDirection,
+    expected_tick_bound: Option<i64>,
+}
+
+#[test]
+fn test_price_limit_to_tick_bound() {
+    let test_cases = vec![
+        PriceLimitToTickBoundTestCase {
+            name: "bid at price one",
+            price_limit: Decimal256::one(),
+            order_direction: OrderDirection::Bid,
+            expected_tick_bound: Some(0),
+        },
+        PriceLimitToTickBoundTestCase {
+            name: "ask at price one",
+            price_limit: Decimal256::one(),
+            order_direction: OrderDirection::Ask,
+            expected_tick_bound: Some(0),
+        },
+        PriceLimitToTickBoundTestCase {
+            name: "bid between ticks rounds down",
+            price_limit: Decimal256::from_str("1.0000005").unwrap(),
+            order_direction: OrderDirection::Bid,
+            expected_tick_bound: Some(0),
+        },
+        PriceLimitToTickBoundTestCase {
+            name: "ask between ticks rounds up",
+            price_limit: Decimal256::from_str("1.0000005").unwrap(),
+            order_direction: OrderDirection::Ask,
+            expected_tick_bound: Some(1),
+        },
+        PriceLimitToTickBoundTestCase {
+            name: "bid at large positive tick price",
+            price_limit: Decimal256::from_str("2").unwrap(),
+            order_direction: OrderDirection::Bid,
+            expected_tick_bound: Some(1000000),
+        },
+        PriceLimitToTickBoundTestCase {
+            name: "ask at large negative tick price",
+            price_limit: Decimal256::from_str("0.5").unwrap(),
+            order_direction: OrderDirection::Ask,
+            expected_tick_bound: Some(-5000000),
+        },
+        PriceLimitToTickBoundTestCase {
+            name: "bid above max price",
+            price_limit: tick_to_price(MAX_TICK).unwrap() + Decimal256::one(),
+            order_direction: OrderDirection::Bid,
+            expected_tick_bound: Some(MAX_TICK),
+        },
+        PriceLimitToTickBoundTestCase {
+            name: "bid below min price",
+            price_limit: Decimal256::zero(),
+            order_direction: OrderDirection::Bid,
+            expected_tick_bound: None,
+        },
+        PriceLimitToTickBoundTestCase {
+            name: "ask below min price",
+            price_limit: Decimal256::zero(),
+            order_direction: OrderDirection::Ask,
+            expected_tick_bound: Some(MIN_TICK),
+        },
+        PriceLimitToTickBoundTestCase {
+            name: "ask above max price",
+            price_limit: tick_to_price(MAX_TICK).unwrap() + Decimal256::one(),
+            order_direction: OrderDirection::Ask,
+            expected_tick_bound: None,
+        },
+    ];
+
+    for test in test_cases {
+        let tick_bound = price_limit_to_tick_bound(test.price_limit, test.order_direction).unwrap();
+        assert_eq!(
+            tick_bound,
+            test.expected_tick_bound,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+#[test]
+fn test_tick_to_price_boundaries() {
+    // Every tier of the additive tick spacing scheme starts at a multiple of the increment distance
+    let min_tier = MIN_TICK / GEOMETRIC_EXPONENT_INCREMENT_DISTANCE_IN_TICKS - 1;
+    let max_tier = MAX_TICK / GEOMETRIC_EXPONENT_INCREMENT_DISTANCE_IN_TICKS + 1;
+    let mut boundaries = vec![MIN_TICK, MAX_TICK];
+    boundaries.extend(
+        (min_tier..=max_tier).map(|tier| tier * GEOMETRIC_EXPONENT_INCREMENT_DISTANCE_IN_TICKS),
+    );
+
+    for boundary in boundaries {
+        let ticks = [boundary - 1, boundary, boundary + 1];
+        let mut prices = vec![];
+        for tick_index in ticks {
+            let price = tick_to_price(tick_index);
+            let price_pair = tick_to_price_pair(tick_index);
+            if !(MIN_TICK..=MAX_TICK).contains(&tick_index) {
+                let expected_error = ContractError::TickOutOfBounds {
+                    tick_id: tick_index,
+                };
+                assert_eq!(price.unwrap_err(), expected_error, "tick {tick_index}");
+                assert_eq!(price_pair.unwrap_err(), expected_error, "tick {tick_index}");
+                continue;
+            }
+
+            let price = price.unwrap();
+            assert_eq!(price_pair.unwrap().0, price, "tick {tick_index}");
+            prices.push(price);
+        }
+
+        // Prices strictly increase across each boundary
+        assert!(
+            prices.windows(2).all(|pair| pair[0] < pair[1]),
+            "boundary {boundary}: prices {prices:?} are not increasing"
+        );
+
+        // Each tier starts at a power of the tick base
+        if boundary % GEOMETRIC_EXPONENT_INCREMENT_DISTANCE_IN_TICKS == 0
+            && (MIN_TICK..=MAX_TICK).contains(&boundary)
+        {
+            let tier = boundary / GEOMETRIC_EXPONENT_INCREMENT_DISTANCE_IN_TICKS;
+            assert_eq!(
+                tick_to_price(boundary).unwrap(),
+                pow_base(tier as i32).unwrap(),
+                "boundary {boundary}"
+            );
+        }
+    }
+
+    // Extreme inputs error rather than panic
+    for tick_index in [i64::MIN, i64::MIN + 1, i64::MAX - 1, i64::MAX] {
+        assert_eq!(
+            tick_to_price(tick_index).unwrap_err(),
+            ContractError::TickOutOfBounds {
+                tick_id: tick_index
+            }
+        );
+    }
+}
+
+#[test]
+#[cfg(not(feature = "tick-base-100"))]
+fn test_default_tick_base() {
+    assert_eq!(tick_base(), Uint256::from(10u8));
+    assert_eq!(pow_base(-2).unwrap(), Decimal256::percent(1));
+
+    // Known tick/price points of the default base
+    for (tick_index, expected_price) in [
+        (1, "1.000001"),
+        (1_000_000, "2"),
+        (9_000_000, "10"),
+        (9_000_001, "10.00001"),
+        (18_000_000, "100"),
+        (-1, "0.9999999"),
+        (-9_000_000, "0.1"),
+        (-18_000_000, "0.01"),
+    ] {
+        assert_eq!(
+            tick_to_price(tick_index).unwrap(),
+            Decimal256::from_str(expected_price).unwrap(),
+            "tick {tick_index}"
+        );
+    }
+}
+
+#[test]
+fn test_pow_ten_out_of_range() {
+    // Decimal256 can represent up to ~1.15 * 10^59
+    assert_eq!(
+        pow_ten(59).unwrap(),
+        Decimal256::from_str(&format!("1{}", "0".repeat(59))).unwrap()
+    );
+    for exponent in [60, 77, 78, i32::MAX, i32::MIN] {
+        assert!(pow_ten(exponent).is_err(), "exponent {exponent}");
+    }
+}
+
+#[test]
+fn test_multiply_by_ratio_overflow() {
+    let result = multiply_by_ratio(Uint256::MAX, Decimal256::percent(1), RoundingMode::Down);
+    assert!(result.is_err());
+}
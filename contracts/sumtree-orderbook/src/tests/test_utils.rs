@@ -1,16 +1,17 @@
 use cosmwasm_std::{
-    coin, testing::mock_info, Addr, Decimal256, DepsMut, Env, MessageInfo, Uint128,
+    coin, testing::mock_info, Addr, Decimal256, DepsMut, Env, MessageInfo, Order, Storage, Uint128,
 };
 
 use crate::{
     constants::{MAX_TICK, MIN_TICK},
     error::ContractResult,
     order::{cancel_limit, claim_order, place_limit, run_market_order},
-    state::orders,
+    state::{orders, ORDERBOOK, TICK_STATE},
+    sumtree::tree::verify_tree,
     types::{LimitOrder, MarketOrder, OrderDirection},
 };
 
-use super::test_constants::{BASE_DENOM, DEFAULT_OWNER, QUOTE_DENOM};
+use super::test_constants::DEFAULT_OWNER;
 
 pub(crate) fn decimal256_from_u128(input: impl Into<u128>) -> Decimal256 {
     Decimal256::from_ratio(input.into(), 1u128)
@@ -26,15 +27,21 @@ pub(crate) enum OrderOperation {
 }
 
 impl OrderOperation {
+    /// Runs the operation, then verifies that every sumtree in the orderbook is still consistent.
     pub(crate) fn run(&self, mut deps: DepsMut, env: Env, info: MessageInfo) -> ContractResult<()> {
+        self.run_unverified(deps.branch(), env, info)?;
+        verify_all_trees(deps.storage);
+        Ok(())
+    }
+
+    fn run_unverified(&self, mut deps: DepsMut, env: Env, info: MessageInfo) -> ContractResult<()> {
         match self.clone() {
             OrderOperation::RunMarket(mut order) => {
                 let tick_bound = match order.order_direction {
                     OrderDirection::Bid => MAX_TICK,
                     OrderDirection::Ask => MIN_TICK,
                 };
-                run_market_order(deps.storage, env.contract.address, &mut order, tick_bound)
-                    .unwrap();
+                run_market_order(deps.storage, &env, &mut order, tick_bound, None).unwrap();
                 Ok(())
             }
             OrderOperation::PlaceLimitMulti((
@@ -53,12 +60,10 @@ impl OrderOperation {
                 Ok(())
             }
             OrderOperation::PlaceLimit(limit_order) => {
+                let orderbook = ORDERBOOK.load(deps.storage)?;
                 let coin_vec = vec![coin(
                     limit_order.quantity.u128(),
-                    match limit_order.order_direction {
-                        OrderDirection::Ask => BASE_DENOM,
-                        OrderDirection::Bid => QUOTE_DENOM,
-                    },
+                    orderbook.input_denom(&limit_order.order_direction),
                 )];
                 let info = mock_info(limit_order.owner.as_str(), &coin_vec);
                 place_limit(
@@ -69,16 +74,22 @@ impl OrderOperation {
                     limit_order.order_direction,
                     limit_order.quantity,
                     limit_order.claim_bounty,
+                    None,
+                    None,
+                    None,
+                    None,
                 )?;
                 Ok(())
             }
             OrderOperation::Claim((tick_id, order_id)) => {
                 claim_order(
                     deps.storage,
+                    deps.api,
+                    &env,
                     info.sender.clone(),
-                    env.contract.address,
                     tick_id,
                     order_id,
+                    false,
                 )?;
                 Ok(())
             }
@@ -94,6 +105,19 @@ impl OrderOperation {
     }
 }
 
+/// Panics if the sumtree for either direction on any tick is corrupted.
+pub(crate) fn verify_all_trees(storage: &dyn Storage) {
+    let tick_ids: Vec<i64> = TICK_STATE
+        .keys(storage, None, None, Order::Ascending)
+        .map(|tick_id| tick_id.unwrap())
+        .collect();
+    for tick_id in tick_ids {
+        for direction in [OrderDirection::Bid, OrderDirection::Ask] {
+            verify_tree(storage, tick_id, direction).unwrap();
+        }
+    }
+}
+
 /// Generates a set of `LimitOrder` objects for testing purposes.
 /// `orders_per_tick` orders are generated for each tick in `tick_ids`,
 /// with order direction being determined such that they are all placed
@@ -129,13 +153,11 @@ pub(crate) fn place_multiple_limit_orders(
     owner: &str,
     orders: Vec<LimitOrder>,
 ) -> ContractResult<()> {
+    let orderbook = ORDERBOOK.load(deps.storage)?;
     for order in orders {
         let coin_vec = vec![coin(
             order.quantity.u128(),
-            match order.order_direction {
-                OrderDirection::Ask => BASE_DENOM,
-                OrderDirection::Bid => QUOTE_DENOM,
-            },
+            orderbook.input_denom(&order.order_direction),
         )];
         let info = mock_info(owner, &coin_vec);
 
@@ -148,6 +170,10 @@ pub(crate) fn place_multiple_limit_orders(
             order.order_direction,
             order.quantity,
             order.claim_bounty,
+            None,
+            None,
+            None,
+            None,
         )?;
     }
     Ok(())
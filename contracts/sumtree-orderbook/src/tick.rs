@@ -1,10 +1,16 @@
 use crate::{
-    error::ContractError,
-    state::TICK_STATE,
-    sumtree::tree::{get_or_init_root_node, get_prefix_sum},
-    types::OrderDirection,
+    error::{ContractError, ContractResult},
+    state::{get_tick_order_count, PRUNE_REWARD, PRUNE_REWARD_POOL, TICK_STATE},
+    sumtree::{
+        node::NODE_ID_COUNTER,
+        tree::{get_or_init_root_node, get_prefix_sum, remove_tree},
+    },
+    types::{OrderDirection, TickState, TickValues, REPLY_ID_PRUNE_REWARD},
 };
-use cosmwasm_std::{ensure, Decimal256, Storage};
+use cosmwasm_std::{
+    coin, ensure, Addr, BankMsg, Coins, Decimal256, DepsMut, MessageInfo, Response, Storage, SubMsg,
+};
+use cw_utils::{nonpayable, PaymentError};
 
 /// Syncs the tick state, ensuring that its ETAS reflects cancellations that have occurred
 /// up until the `current_tick_etas`
@@ -97,3 +103,118 @@ pub fn sync_tick(
 
     Ok(())
 }
+
+/// Removes the state held for one side of a tick once every order on that side has been
+/// claimed or cancelled, paying the caller the configured prune reward if the pool can cover it.
+///
+/// The reward is only paid for sides that were at least partly filled. A side whose orders were
+/// all cancelled can be pruned but pays nothing, as otherwise an order placed, cancelled for a full
+/// refund and pruned by the same account would drain the pool.
+///
+/// The side's tick values are reset and its sumtree is deleted. If the other side of the tick
+/// is also empty, the tick's state entry is removed entirely.
+///
+/// Errors if:
+/// 1. Funds are sent with the message
+/// 2. The tick has no state
+/// 3. The side still holds liquidity or orders with unclaimed fills
+/// 4. The side has already been pruned
+pub(crate) fn prune_tick(
+    deps: DepsMut,
+    info: MessageInfo,
+    tick_id: i64,
+    direction: OrderDirection,
+) -> ContractResult<Response> {
+    nonpayable(&info)?;
+
+    let mut tick_state = TICK_STATE
+        .may_load(deps.storage, tick_id)?
+        .ok_or(ContractError::InvalidTickId { tick_id })?;
+    let tick_values = tick_state.get_values(direction);
+
+    // Orders are counted until claimed, so a zero count also rules out unclaimed fills
+    ensure!(
+        tick_values.total_amount_of_liquidity.is_zero()
+            && get_tick_order_count(deps.storage, tick_id, direction)? == 0,
+        ContractError::TickNotEmpty { tick_id }
+    );
+
+    // No remaining order references the side's ETAS or cancellations, so both can be reset
+    let tree_removed = remove_tree(deps.storage, tick_id, direction)?;
+    ensure!(
+        tree_removed || tick_values != TickValues::default(),
+        ContractError::TickAlreadyPruned { tick_id }
+    );
+    tick_state.set_values(direction, TickValues::default());
+
+    if tick_state == TickState::default() {
+        // Any placement on the other side would have left a non-zero CTT, so it has no sumtree
+        TICK_STATE.remove(deps.storage, tick_id);
+        NODE_ID_COUNTER.remove(deps.storage, &tick_id);
+    } else {
+        TICK_STATE.save(deps.storage, tick_id, &tick_state)?;
+    }
+
+    let mut response = Response::default().add_attributes(vec![
+        ("method", "pruneTick"),
+        ("sender", info.sender.as_str()),
+        ("tick_id", &tick_id.to_string()),
+        ("direction", &direction.to_string()),
+    ]);
+    if !tick_values.swapped().is_zero() {
+        if let Some(reward_msg) = prune_reward_msg(deps.storage, &info.sender)? {
+            response = response.add_submessage(reward_msg);
+        }
+    }
+
+    Ok(response)
+}
+
+/// Adds the funds sent with the message to the prune reward pool.
+///
+/// Errors if no funds are sent.
+pub(crate) fn fund_prune_rewards(deps: DepsMut, info: MessageInfo) -> ContractResult<Response> {
+    ensure!(
+        !info.funds.is_empty(),
+        ContractError::PaymentError(PaymentError::NoFunds {})
+    );
+
+    for fund in &info.funds {
+        let pool = PRUNE_REWARD_POOL
+            .may_load(deps.storage, &fund.denom)?
+            .unwrap_or_default();
+        PRUNE_REWARD_POOL.save(deps.storage, &fund.denom, &pool.checked_add(fund.amount)?)?;
+    }
+
+    Ok(Response::default().add_attributes(vec![
+        ("method", "fundPruneRewards"),
+        ("sender", info.sender.as_str()),
+        ("funds", &Coins::try_from(info.funds)?.to_string()),
+    ]))
+}
+
+/// Deducts the prune reward from the pool, returning the message paying it to `recipient`.
+///
+/// If the pool cannot cover the full reward its remaining balance is paid instead.
+/// Returns `None` if no reward is configured or the pool is empty.
+fn prune_reward_msg(storage: &mut dyn Storage, recipient: &Addr) -> ContractResult<Option<SubMsg>> {
+    let Some(reward) = PRUNE_REWARD.may_load(storage)? else {
+        return Ok(None);
+    };
+    let pool = PRUNE_REWARD_POOL
+        .may_load(storage, &reward.denom)?
+        .unwrap_or_default();
+    let amount = reward.amount.min(pool);
+    if amount.is_zero() {
+        return Ok(None);
+    }
+    PRUNE_REWARD_POOL.save(storage, &reward.denom, &pool.checked_sub(amount)?)?;
+
+    Ok(Some(SubMsg::reply_on_error(
+        BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![coin(amount.u128(), reward.denom)],
+        },
+        REPLY_ID_PRUNE_REWARD,
+    )))
+}
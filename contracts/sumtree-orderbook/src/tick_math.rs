@@ -1,13 +1,18 @@
 use crate::constants::{
     EXPONENT_AT_PRICE_ONE, GEOMETRIC_EXPONENT_INCREMENT_DISTANCE_IN_TICKS, MAX_TICK, MIN_TICK,
+    TICK_BASE, TICK_BASE_DIGITS,
 };
 use crate::error::*;
 use crate::types::OrderDirection;
-use cosmwasm_std::{ensure, Decimal256, OverflowError, OverflowOperation, Uint128, Uint256};
+use cosmwasm_std::{
+    ensure, Decimal256, DivideByZeroError, OverflowError, OverflowOperation, Uint128, Uint256,
+};
 
 // tick_to_price converts a tick index to a price.
 // If tick_index is zero, the function returns Decimal256::one().
-// Errors if the given tick is outside of the bounds allowed by MIN_TICK and MAX_TICK.
+// Errors if the given tick is outside of the bounds allowed by MIN_TICK and MAX_TICK,
+// or if its price cannot be represented within Decimal256's 18 decimal places or range.
+// Never panics for any i64 input.
 #[allow(clippy::manual_range_contains)]
 pub fn tick_to_price(tick_index: i64) -> ContractResult<Decimal256> {
     if tick_index == 0 {
@@ -34,57 +39,190 @@ pub fn tick_to_price(tick_index: i64) -> ContractResult<Decimal256> {
         exponent_at_current_tick -= 1;
     }
 
-    // We can derive the contribution of each additive tick with 10^(exponent_at_current_tick))
-    let current_additive_increment_in_ticks = pow_ten(exponent_at_current_tick as i32)?;
+    // We can derive the contribution of each additive tick with TICK_BASE^(exponent_at_current_tick))
+    let current_additive_increment_in_ticks = pow_base(exponent_at_current_tick as i32)?;
 
     // The current number of additive ticks are equivalent to the portion of the tick index that is not covered by the geometric component.
     let num_additive_ticks =
         tick_index - (geometric_exponent_delta * GEOMETRIC_EXPONENT_INCREMENT_DISTANCE_IN_TICKS);
 
+    // Decimal256 only represents 18 decimal places, so any finer additive increment would be
+    // truncated to zero and silently collapse distinct ticks onto the same price.
+    ensure!(
+        num_additive_ticks == 0
+            || exponent_at_current_tick * TICK_BASE_DIGITS as i64
+                >= -(Decimal256::DECIMAL_PLACES as i64),
+        ContractError::PriceUnderflow {
+            tick_id: tick_index
+        }
+    );
+
     // Price is equal to the sum of the geometric and additive components.
     // Since we derive `geometric_exponent_delta` by division with truncation, we can get the geometric component
-    // by simply taking 10^(geometric_exponent_delta).
+    // by simply taking TICK_BASE^(geometric_exponent_delta).
     //
     // The additive component is simply the number of additive ticks by the current additive increment per tick.
-    let geometric_component = pow_ten(geometric_exponent_delta as i32)?;
+    let price_overflow = || ContractError::PriceOverflow {
+        tick_id: tick_index,
+    };
+    let geometric_component =
+        pow_base(geometric_exponent_delta as i32).map_err(|_| price_overflow())?;
 
     let additive_component = Decimal256::from_ratio(
         Uint256::from(num_additive_ticks.unsigned_abs()),
         Uint256::one(),
     )
-    .checked_mul(current_additive_increment_in_ticks)?;
+    .checked_mul(current_additive_increment_in_ticks)
+    .map_err(|_| price_overflow())?;
 
     // We manually handle sign here to avoid expensive conversions between Decimal256 and SignedDecimal256.
     let price = if num_additive_ticks < 0 {
+        geometric_component.checked_sub(additive_component)?
+    } else {
+        geometric_component
+            .checked_add(additive_component)
+            .map_err(|_| price_overflow())?
+    };
+    ensure!(
+        !price.is_zero(),
+        ContractError::PriceUnderflow {
+            tick_id: tick_index
+        }
+    );
+
+    Ok(price)
+}
+
+// tick_to_price_pair converts a tick index to both its price and the reciprocal of that price (1/price).
+// The reciprocal is derived from the exact rational form of the tick's price rather than by
+// inverting the (potentially truncated) decimal price, so it only incurs a single rounding.
+// Errors if the given tick is outside of the bounds allowed by MIN_TICK and MAX_TICK.
+pub fn tick_to_price_pair(tick_index: i64) -> ContractResult<(Decimal256, Decimal256)> {
+    let price = tick_to_price(tick_index)?;
+    let (numerator, denominator) = tick_to_price_ratio(tick_index)?;
+    let inverse_price = Decimal256::checked_from_ratio(denominator, numerator)?;
+
+    Ok((price, inverse_price))
+}
+
+// tick_to_price_ratio returns the exact price of a tick as a (numerator, denominator) pair of integers.
+// Follows the same geometric and additive decomposition as `tick_to_price`, scaling both components
+// by a common power of ten so that neither is truncated.
+fn tick_to_price_ratio(tick_index: i64) -> ContractResult<(Uint256, Uint256)> {
+    let geometric_exponent_delta: i64 = tick_index / GEOMETRIC_EXPONENT_INCREMENT_DISTANCE_IN_TICKS;
+    let mut exponent_at_current_tick = (EXPONENT_AT_PRICE_ONE as i64) + geometric_exponent_delta;
+    if tick_index < 0 {
+        exponent_at_current_tick -= 1;
+    }
+    let num_additive_ticks =
+        tick_index - (geometric_exponent_delta * GEOMETRIC_EXPONENT_INCREMENT_DISTANCE_IN_TICKS);
+
+    // Scale both components by TICK_BASE^scale so that each is an integer
+    let scale = 0.max(-geometric_exponent_delta.min(exponent_at_current_tick));
+    let base = tick_base();
+    let denominator = base.checked_pow(scale as u32)?;
+    let geometric_component = base.checked_pow((geometric_exponent_delta + scale) as u32)?;
+    let additive_component = Uint256::from(num_additive_ticks.unsigned_abs())
+        .checked_mul(base.checked_pow((exponent_at_current_tick + scale) as u32)?)?;
+
+    let numerator = if num_additive_ticks < 0 {
         geometric_component.checked_sub(additive_component)
     } else {
         geometric_component.checked_add(additive_component)
     }?;
 
-    Ok(price)
+    Ok((numerator, denominator))
+}
+
+// price_limit_to_tick_bound finds the furthest tick a market order in the given direction can fill
+// without crossing `price_limit`, which shares the orientation of tick prices.
+// Bids fill ticks in ascending order, so their bound is the highest tick priced at or below the limit.
+// Asks fill ticks in descending order, so their bound is the lowest tick priced at or above the limit.
+// Returns `None` if every tick lies beyond the limit.
+pub fn price_limit_to_tick_bound(
+    price_limit: Decimal256,
+    order_direction: OrderDirection,
+) -> ContractResult<Option<i64>> {
+    let (mut low, mut high) = (MIN_TICK, MAX_TICK);
+    match order_direction {
+        OrderDirection::Bid => {
+            if tick_to_price(MIN_TICK)? > price_limit {
+                return Ok(None);
+            }
+            // Tick prices are non-decreasing, so binary search for the last tick within the limit
+            while low < high {
+                let mid = low + (high - low + 1) / 2;
+                if tick_to_price(mid)? <= price_limit {
+                    low = mid;
+                } else {
+                    high = mid - 1;
+                }
+            }
+        }
+        OrderDirection::Ask => {
+            if tick_to_price(MAX_TICK)? < price_limit {
+                return Ok(None);
+            }
+            // Tick prices are non-decreasing, so binary search for the first tick within the limit
+            while low < high {
+                let mid = low + (high - low) / 2;
+                if tick_to_price(mid)? >= price_limit {
+                    high = mid;
+                } else {
+                    low = mid + 1;
+                }
+            }
+        }
+    }
+    Ok(Some(low))
+}
+
+// tick_base returns the factor tick prices grow by from one geometric tier to the next.
+// Defaults to 10, see `TICK_BASE` for the alternative bases.
+pub fn tick_base() -> Uint256 {
+    Uint256::from(TICK_BASE as u64)
+}
+
+// Takes an exponent and returns TICK_BASE^exponent. Supports negative exponents.
+// Errors if TICK_BASE^exponent exceeds the range of Decimal256.
+pub fn pow_base(expo: i32) -> ContractResult<Decimal256> {
+    pow_ten(
+        expo.checked_mul(TICK_BASE_DIGITS as i32)
+            .ok_or(ContractError::Overflow(OverflowError {
+                operation: OverflowOperation::Mul,
+                operand1: expo.to_string(),
+                operand2: TICK_BASE_DIGITS.to_string(),
+            }))?,
+    )
 }
 
 // Takes an exponent and returns 10^exponent. Supports negative exponents.
+// Errors if 10^exponent exceeds the range of Decimal256.
 pub fn pow_ten(expo: i32) -> ContractResult<Decimal256> {
     let target_expo = Uint256::from(10u8).checked_pow(expo.unsigned_abs())?;
     if expo < 0 {
         Ok(Decimal256::checked_from_ratio(Uint256::one(), target_expo)?)
     } else {
-        let res = Uint256::one().checked_mul(target_expo)?;
-        Ok(Decimal256::from_ratio(res, Uint256::one()))
+        Ok(Decimal256::checked_from_ratio(target_expo, Uint256::one())?)
     }
 }
 
-// Determines which direction to round a decimal number to an integer
-pub enum RoundingDirection {
+// Determines which direction to round a decimal number to an integer.
+//
+// All conversions from decimal to integer amounts in the fill and claim paths go through
+// `RoundingMode::round` so that each call site states explicitly which way it rounds.
+// Amounts leaving the orderbook are rounded down and amounts owed to it are rounded up,
+// ensuring the orderbook never loses value to rounding.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RoundingMode {
     Down = 0,
     Up = 1,
 }
 
-impl RoundingDirection {
+impl RoundingMode {
     #[inline]
     pub fn round(self, input: Decimal256) -> Uint256 {
-        if matches!(self, RoundingDirection::Down) {
+        if matches!(self, RoundingMode::Down) {
             input.to_uint_floor()
         } else {
             input.to_uint_ceil()
@@ -96,7 +234,7 @@ impl RoundingDirection {
 pub fn multiply_by_price(
     amount: Uint128,
     price: Decimal256,
-    rounding_direction: RoundingDirection,
+    rounding_mode: RoundingMode,
 ) -> ContractResult<Uint256> {
     let amount_to_send_dec256 = price.checked_mul(Decimal256::from_ratio(
         Uint256::from_uint128(amount),
@@ -110,17 +248,34 @@ pub fn multiply_by_price(
             operand2: price.to_string(),
         })
     );
-    let amount_to_send = rounding_direction.round(amount_to_send_dec256?);
+    let amount_to_send = rounding_mode.round(amount_to_send_dec256?);
 
     Ok(amount_to_send)
 }
 
-// Divides a given tick amount by the price for that tick
+// Multiplies an amount by a ratio (e.g. a claim bounty or maker fee)
+pub fn multiply_by_ratio(
+    amount: Uint256,
+    ratio: Decimal256,
+    rounding_mode: RoundingMode,
+) -> ContractResult<Uint256> {
+    let amount_dec256 =
+        Decimal256::checked_from_ratio(amount, Uint256::one())?.checked_mul(ratio)?;
+
+    Ok(rounding_mode.round(amount_dec256))
+}
+
+// Divides a given tick amount by the price for that tick.
+// Errors with `DivideByZero` rather than panicking if the price is zero.
 pub fn divide_by_price(
     amount: Uint128,
     price: Decimal256,
-    rounding_direction: RoundingDirection,
+    rounding_mode: RoundingMode,
 ) -> ContractResult<Uint256> {
+    ensure!(
+        !price.is_zero(),
+        ContractError::DivideByZero(DivideByZeroError::new(amount))
+    );
     let amount_to_send_dec256 = Decimal256::from_ratio(amount, Uint256::one()).checked_div(price);
     ensure!(
         amount_to_send_dec256.is_ok(),
@@ -130,7 +285,7 @@ pub fn divide_by_price(
             operand2: price.to_string(),
         })
     );
-    let amount_to_send = rounding_direction.round(amount_to_send_dec256?);
+    let amount_to_send = rounding_mode.round(amount_to_send_dec256?);
 
     Ok(amount_to_send)
 }
@@ -140,13 +295,37 @@ pub fn amount_to_value(
     order: OrderDirection,
     amount: Uint128,
     price: Decimal256,
-    rounding_direction: RoundingDirection,
+    rounding_mode: RoundingMode,
 ) -> ContractResult<Uint256> {
     if amount.is_zero() {
         return Ok(Uint256::zero());
     }
     match order {
-        OrderDirection::Bid => multiply_by_price(amount, price, rounding_direction),
-        OrderDirection::Ask => divide_by_price(amount, price, rounding_direction),
+        OrderDirection::Bid => multiply_by_price(amount, price, rounding_mode),
+        OrderDirection::Ask => divide_by_price(amount, price, rounding_mode),
     }
 }
+
+/// Returns the output a market order in `direction` receives for `input` at the given tick price.
+///
+/// The output leaves the orderbook, so it is rounded down.
+pub fn output_for_input(
+    tick_price: Decimal256,
+    input: Uint128,
+    direction: OrderDirection,
+) -> ContractResult<Uint256> {
+    amount_to_value(direction, input, tick_price, RoundingMode::Down)
+}
+
+/// Returns the input a market order in `direction` must pay to receive `output` at the given
+/// tick price.
+///
+/// The input is owed to the orderbook, so it is rounded up. Together with `output_for_input`
+/// this guarantees `input_for_output(p, output_for_input(p, x, d), d) <= x`.
+pub fn input_for_output(
+    tick_price: Decimal256,
+    output: Uint128,
+    direction: OrderDirection,
+) -> ContractResult<Uint256> {
+    amount_to_value(direction.opposite(), output, tick_price, RoundingMode::Up)
+}
@@ -0,0 +1,35 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Uint128;
+
+/// The kind of asset backing a denom in the orderbook.
+///
+/// For `Cw20` assets the denom is the address of the token contract.
+#[cw_serde]
+#[derive(Copy, Default)]
+pub enum AssetType {
+    #[default]
+    Native,
+    Cw20,
+}
+
+/// The subset of the CW20 execute interface used by the orderbook to pay out tokens.
+///
+/// Serializes identically to `cw20::Cw20ExecuteMsg::Transfer`.
+#[cw_serde]
+pub enum Cw20ExecuteMsg {
+    Transfer { recipient: String, amount: Uint128 },
+}
+
+/// The subset of the CW20 query interface used by the orderbook to check its token balances.
+///
+/// Serializes identically to `cw20::Cw20QueryMsg::Balance`.
+#[cw_serde]
+pub enum Cw20QueryMsg {
+    Balance { address: String },
+}
+
+/// Wire-compatible replication of `cw20::BalanceResponse`
+#[cw_serde]
+pub struct Cw20BalanceResponse {
+    pub balance: Uint128,
+}
@@ -2,6 +2,8 @@ use std::str::FromStr;
 
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Coin, CosmosMsg, Uint128, Uint256};
+
+use crate::error::ContractResult;
 use osmosis_std::types::cosmos::bank::v1beta1::MsgSend;
 use osmosis_std::types::cosmos::base::v1beta1::Coin as ProtoCoin;
 
@@ -50,6 +52,25 @@ pub struct MsgSend256 {
     pub from_address: String,
 }
 
+impl MsgSend256 {
+    /// Merges `other` into `self` if both are single coin transfers with the same sender, recipient and denom.
+    ///
+    /// Returns whether the merge took place. Errors if the combined amount overflows.
+    pub fn try_merge(&mut self, other: &MsgSend256) -> ContractResult<bool> {
+        if self.from_address != other.from_address || self.to_address != other.to_address {
+            return Ok(false);
+        }
+
+        match (self.amount.as_mut_slice(), other.amount.as_slice()) {
+            ([coin], [other_coin]) if coin.denom == other_coin.denom => {
+                coin.amount = coin.amount.checked_add(other_coin.amount)?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
 impl From<MsgSend256> for MsgSend {
     fn from(msg: MsgSend256) -> Self {
         MsgSend {
@@ -1,9 +1,11 @@
+mod asset;
 mod coin;
 mod order;
 mod orderbook;
 mod reply_id;
 mod tick;
 
+pub use self::asset::*;
 pub use self::coin::{coin_u256, Coin256, MsgSend256};
 pub use self::order::*;
 pub use self::orderbook::*;
@@ -2,12 +2,18 @@ use core::fmt;
 use std::fmt::Display;
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Decimal256, Timestamp, Uint128};
+use cosmwasm_std::{ensure, Addr, BlockInfo, Decimal256, Timestamp, Uint128, Uint256};
 
+use crate::{error::ContractResult, ContractError};
+
+/// Serializes as `"bid"`/`"ask"`, while the common synonyms `"buy"`/`"sell"` are also
+/// accepted when deserializing.
 #[cw_serde]
 #[derive(Copy)]
 pub enum OrderDirection {
+    #[serde(alias = "buy")]
     Bid,
+    #[serde(alias = "sell")]
     Ask,
 }
 
@@ -49,6 +55,19 @@ pub struct LimitOrder {
     pub placed_quantity: Uint128,
     #[serde(default)]
     pub placed_at: Timestamp,
+    // Block height the order was placed at, used for the maker fee discount
+    #[serde(default)]
+    pub placed_at_height: u64,
+    // Keeps the order at an offset from the best bid/ask, see `order::reprice`
+    #[serde(default)]
+    pub peg: Option<PegConfig>,
+    // Free-form note set by the placer, not used for matching
+    #[serde(default)]
+    pub memo: Option<String>,
+    // Minimum payout at which fills are sent to the owner as they happen, see
+    // `order::settle_auto_orders`. `None` if fills accrue until claimed
+    #[serde(default)]
+    pub auto_settle: Option<Uint128>,
 }
 
 impl LimitOrder {
@@ -72,6 +91,10 @@ impl LimitOrder {
             claim_bounty,
             placed_quantity: quantity,
             placed_at: Timestamp::default(),
+            placed_at_height: 0,
+            peg: None,
+            memo: None,
+            auto_settle: None,
         }
     }
 
@@ -81,17 +104,76 @@ impl LimitOrder {
         self
     }
 
-    pub(crate) fn with_placed_at(mut self, placed_at: Timestamp) -> Self {
-        self.placed_at = placed_at;
+    pub(crate) fn with_placed_at(mut self, block: &BlockInfo) -> Self {
+        self.placed_at = block.time;
+        self.placed_at_height = block.height;
+        self
+    }
+
+    pub(crate) fn with_peg(mut self, peg: Option<PegConfig>) -> Self {
+        self.peg = peg;
+        self
+    }
+
+    pub(crate) fn with_memo(mut self, memo: Option<String>) -> Self {
+        self.memo = memo;
+        self
+    }
+
+    pub(crate) fn with_auto_settle(mut self, auto_settle: Option<Uint128>) -> Self {
+        self.auto_settle = auto_settle;
         self
     }
 }
 
+/// The side of the book a pegged order tracks.
+#[cw_serde]
+#[derive(Copy)]
+pub enum PegReference {
+    BestBid,
+    BestAsk,
+}
+
+impl PegReference {
+    /// Returns the direction of the liquidity the reference price is taken from.
+    pub fn direction(&self) -> OrderDirection {
+        match self {
+            PegReference::BestBid => OrderDirection::Bid,
+            PegReference::BestAsk => OrderDirection::Ask,
+        }
+    }
+}
+
+impl Display for PegReference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PegReference::BestBid => write!(f, "best_bid"),
+            PegReference::BestAsk => write!(f, "best_ask"),
+        }
+    }
+}
+
+/// Places an order `offset_ticks` away from the best tick of the `reference` side of the book.
+#[cw_serde]
+pub struct PegConfig {
+    pub reference: PegReference,
+    pub offset_ticks: i64,
+}
+
 #[cw_serde]
 pub struct MarketOrder {
     pub quantity: Uint128,
     pub order_direction: OrderDirection,
     pub owner: Addr,
+    // Allows the order to consume input without producing any output
+    #[serde(default)]
+    pub allow_zero_output: bool,
+    // Allows the order to be left partly unfilled when it is bounded at MIN_TICK or MAX_TICK
+    #[serde(default)]
+    pub allow_partial_fill: bool,
+    // Receives the order's output, defaults to the owner
+    #[serde(default)]
+    pub recipient: Option<Addr>,
 }
 
 impl MarketOrder {
@@ -100,25 +182,107 @@ impl MarketOrder {
             quantity,
             order_direction,
             owner,
+            allow_zero_output: false,
+            allow_partial_fill: false,
+            recipient: None,
         }
     }
+
+    /// Creates a market order, rejecting a zero quantity up front rather than when the order is run.
+    pub fn try_new(
+        quantity: Uint128,
+        order_direction: OrderDirection,
+        owner: Addr,
+    ) -> ContractResult<Self> {
+        ensure!(
+            !quantity.is_zero(),
+            ContractError::InvalidQuantity { quantity }
+        );
+
+        Ok(MarketOrder::new(quantity, order_direction, owner))
+    }
+
+    /// Opts the order into lossy fills, where input may be consumed for zero output.
+    pub fn with_allow_zero_output(mut self, allow_zero_output: bool) -> Self {
+        self.allow_zero_output = allow_zero_output;
+        self
+    }
+
+    /// Allows the order to stop once the book runs out of liquidity, leaving the rest of its
+    /// quantity unfilled, even if it is not bounded by a tick.
+    pub fn with_allow_partial_fill(mut self, allow_partial_fill: bool) -> Self {
+        self.allow_partial_fill = allow_partial_fill;
+        self
+    }
+
+    /// Directs the order's output to the given address instead of the owner.
+    pub fn with_recipient(mut self, recipient: Option<Addr>) -> Self {
+        self.recipient = recipient;
+        self
+    }
+
+    /// Returns the address receiving the order's output.
+    pub fn recipient(&self) -> &Addr {
+        self.recipient.as_ref().unwrap_or(&self.owner)
+    }
 }
 
 impl From<LimitOrder> for MarketOrder {
     fn from(limit_order: LimitOrder) -> Self {
-        MarketOrder {
-            quantity: limit_order.quantity,
-            order_direction: limit_order.order_direction,
-            owner: limit_order.owner,
-        }
+        MarketOrder::new(
+            limit_order.quantity,
+            limit_order.order_direction,
+            limit_order.owner,
+        )
     }
 }
 
+/// A record of a claim against a limit order, kept so that fills remain queryable
+/// after the order has been removed from state.
+#[cw_serde]
+pub struct FillRecord {
+    pub seq: u64,
+    pub tick_id: i64,
+    pub order_id: u64,
+    // Amount of the output denom sent to the order owner
+    pub amount: Uint256,
+    pub price: Decimal256,
+    pub block_height: u64,
+}
+
+/// Orders in which an owner's orders can be returned, applied within each page of results
+#[cw_serde]
+#[derive(Copy)]
+pub enum OrderSort {
+    TickAscending,
+    TickDescending,
+    // Most filled orders first
+    FillPercentage,
+}
+
+/// Why an order could not be found at the requested tick, as far as the contract can tell
+#[cw_serde]
+#[derive(Copy)]
+pub enum OrderNotFoundReason {
+    // No order has been placed with the ID yet
+    NeverPlaced,
+    Cancelled,
+    // Fully filled and claimed
+    Claimed,
+    // Repriced onto another tick, where it can still be found under the same ID
+    Moved { tick_id: i64 },
+    // Cancelled by `ReplaceOrder`, which placed the replacement under a new ID
+    Replaced { tick_id: i64, order_id: u64 },
+}
+
 /// Defines the different way an owners orders can be filtered, all enums filter by owner with each getting more finite
 #[derive(Clone)]
 pub enum FilterOwnerOrders {
     All(Addr),
     ByTick(i64, Addr),
+    ByDirection(Addr, OrderDirection),
+    // Inclusive range of tick IDs
+    ByTickRange(Addr, i64, i64),
 }
 
 impl FilterOwnerOrders {
@@ -129,4 +293,12 @@ impl FilterOwnerOrders {
     pub fn by_tick(tick_id: i64, owner: Addr) -> Self {
         FilterOwnerOrders::ByTick(tick_id, owner)
     }
+
+    pub fn by_direction(owner: Addr, direction: OrderDirection) -> Self {
+        FilterOwnerOrders::ByDirection(owner, direction)
+    }
+
+    pub fn by_tick_range(owner: Addr, min_tick: i64, max_tick: i64) -> Self {
+        FilterOwnerOrders::ByTickRange(owner, min_tick, max_tick)
+    }
 }
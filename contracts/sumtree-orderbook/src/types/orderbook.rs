@@ -1,9 +1,23 @@
 use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Decimal256, Timestamp, Uint256};
 
-use crate::{error::ContractResult, ContractError};
+use crate::{
+    constants::{MAX_TICK, MIN_TICK},
+    error::ContractResult,
+    tick_math::tick_to_price_pair,
+    ContractError,
+};
 
 use super::OrderDirection;
 
+/// Maker fee discount for orders that rest on the book for at least `min_blocks`
+#[cw_serde]
+pub struct MakerFeeDiscount {
+    pub min_blocks: u64,
+    // Fraction of the maker fee waived, one waiving it entirely
+    pub discount: Decimal256,
+}
+
 #[cw_serde]
 pub struct Orderbook {
     pub quote_denom: String,
@@ -13,6 +27,37 @@ pub struct Orderbook {
     pub current_tick: i64,
     pub next_bid_tick: i64,
     pub next_ask_tick: i64,
+
+    // Range of ticks limit orders may be placed on, always within [MIN_TICK, MAX_TICK]
+    // Books stored before the range was introduced span every tick
+    #[serde(default = "default_min_tick")]
+    pub min_tick: i64,
+    #[serde(default = "default_max_tick")]
+    pub max_tick: i64,
+
+    // Prices of the tick pointers, refreshed by `refresh_price_cache` whenever the orderbook is saved
+    #[serde(default)]
+    pub next_bid_price: Option<CachedTickPrice>,
+    #[serde(default)]
+    pub next_ask_price: Option<CachedTickPrice>,
+}
+
+fn default_min_tick() -> i64 {
+    MIN_TICK
+}
+
+fn default_max_tick() -> i64 {
+    MAX_TICK
+}
+
+/// The price of a tick pointer, cached alongside the tick it was computed for so that a stale
+/// entry can always be detected.
+#[cw_serde]
+#[derive(Copy)]
+pub struct CachedTickPrice {
+    pub tick_id: i64,
+    pub price: Decimal256,
+    pub inverse_price: Decimal256,
 }
 
 impl Orderbook {
@@ -29,21 +74,94 @@ impl Orderbook {
             current_tick,
             next_bid_tick,
             next_ask_tick,
+            min_tick: MIN_TICK,
+            max_tick: MAX_TICK,
+            next_bid_price: None,
+            next_ask_price: None,
+        }
+    }
+
+    /// Restricts the range of ticks limit orders may be placed on.
+    pub fn with_tick_bounds(mut self, min_tick: i64, max_tick: i64) -> Self {
+        self.min_tick = min_tick;
+        self.max_tick = max_tick;
+        self
+    }
+
+    /// Returns the tick pointer for the given direction.
+    #[inline]
+    pub fn next_tick(&self, direction: OrderDirection) -> i64 {
+        match direction {
+            OrderDirection::Bid => self.next_bid_tick,
+            OrderDirection::Ask => self.next_ask_tick,
+        }
+    }
+
+    /// Returns the price of the tick pointer for the given direction along with its reciprocal.
+    ///
+    /// The cached price is used if it was computed for the current pointer, otherwise the price
+    /// is recomputed, so a pointer moved since the last refresh never reads a stale price.
+    pub fn next_tick_price_pair(
+        &self,
+        direction: OrderDirection,
+    ) -> ContractResult<(Decimal256, Decimal256)> {
+        let (tick_id, cached) = match direction {
+            OrderDirection::Bid => (self.next_bid_tick, self.next_bid_price),
+            OrderDirection::Ask => (self.next_ask_tick, self.next_ask_price),
+        };
+        match cached {
+            Some(cached) if cached.tick_id == tick_id => Ok((cached.price, cached.inverse_price)),
+            _ => tick_to_price_pair(tick_id),
+        }
+    }
+
+    /// Returns the price of the tick pointer for the given direction.
+    pub fn next_tick_price(&self, direction: OrderDirection) -> ContractResult<Decimal256> {
+        Ok(self.next_tick_price_pair(direction)?.0)
+    }
+
+    /// Recomputes the cached price of each tick pointer that has moved since it was last cached.
+    pub fn refresh_price_cache(&mut self) -> ContractResult<()> {
+        for direction in [OrderDirection::Bid, OrderDirection::Ask] {
+            let tick_id = self.next_tick(direction);
+            let cached = match direction {
+                OrderDirection::Bid => &mut self.next_bid_price,
+                OrderDirection::Ask => &mut self.next_ask_price,
+            };
+            if cached.is_none_or(|cached| cached.tick_id != tick_id) {
+                let (price, inverse_price) = tick_to_price_pair(tick_id)?;
+                *cached = Some(CachedTickPrice {
+                    tick_id,
+                    price,
+                    inverse_price,
+                });
+            }
         }
+        Ok(())
     }
 
-    /// Get the expected denomination for a given order direction.
+    /// Returns true if limit orders may be placed on the given tick.
     #[inline]
-    pub fn get_expected_denom(&self, order_direction: &OrderDirection) -> String {
+    pub fn is_tick_in_bounds(&self, tick_id: i64) -> bool {
+        (self.min_tick..=self.max_tick).contains(&tick_id)
+    }
+
+    /// Get the denomination an order in the given direction is placed with.
+    ///
+    /// This is the quote denom for bids and the base denom for asks.
+    #[inline]
+    pub fn input_denom(&self, order_direction: &OrderDirection) -> String {
         match order_direction {
             OrderDirection::Bid => self.quote_denom.clone(),
             OrderDirection::Ask => self.base_denom.clone(),
         }
     }
 
-    /// Get the opposite denomination for a given order direction.
+    /// Get the denomination an order in the given direction is filled into.
+    ///
+    /// This is the base denom for bids and the quote denom for asks.
     #[inline]
-    pub fn get_opposite_denom(&self, order_direction: &OrderDirection) -> String {
+    pub fn output_denom(&self, order_direction: &OrderDirection) -> String {
         match order_direction {
             OrderDirection::Bid => self.base_denom.clone(),
             OrderDirection::Ask => self.quote_denom.clone(),
@@ -59,21 +177,100 @@ impl Orderbook {
         token_in_denom: String,
         token_out_denom: String,
     ) -> ContractResult<OrderDirection> {
-        let in_out_tuple: (String, String) = (token_in_denom.clone(), token_out_denom.clone());
-
         // Determine order direction based on token in/out denoms
-        let order_direction = if (self.base_denom.clone(), self.quote_denom.clone()) == in_out_tuple
-        {
-            OrderDirection::Ask
-        } else if (self.quote_denom.clone(), self.base_denom.clone()) == in_out_tuple {
-            OrderDirection::Bid
-        } else {
-            return Err(ContractError::InvalidPair {
+        let order_direction = [OrderDirection::Ask, OrderDirection::Bid]
+            .into_iter()
+            .find(|direction| {
+                self.input_denom(direction) == token_in_denom
+                    && self.output_denom(direction) == token_out_denom
+            })
+            .ok_or(ContractError::InvalidPair {
                 token_in_denom,
                 token_out_denom,
-            });
-        };
+            })?;
 
         Ok(order_direction)
     }
 }
+
+/// Set of operations that are currently paused on the orderbook.
+///
+/// Each flag halts a single class of operation so that, for example, new placements
+/// can be disabled during a wind-down while market orders remain live.
+/// Cancellations can never be paused so that users can always recover their funds.
+#[cw_serde]
+#[derive(Copy, Default)]
+pub struct PausedOps {
+    /// Halts placement of new limit orders
+    pub placements: bool,
+    /// Halts market orders, including swaps routed through the pool interface
+    pub market_orders: bool,
+    /// Halts claims of filled limit orders
+    pub claims: bool,
+}
+
+/// How a limit order placed exactly on the best tick of the opposite side is handled, e.g. a bid
+/// placed on the best ask tick.
+///
+/// Orders beyond the best opposite tick always cross the book and are rejected. An order on the
+/// tick itself does not cross, as market orders only ever fill the side of a tick opposite them,
+/// so by default it rests alongside the opposite liquidity.
+#[cw_serde]
+#[derive(Copy, Default)]
+pub enum CrossingTickPolicy {
+    /// The order rests on the tick, leaving both tick pointers on it
+    #[default]
+    Rest,
+    /// The order is rejected as crossing, keeping a spread of at least one tick
+    Reject,
+}
+
+/// Progress of the rebuild of the state derived from the ticks and orders, which `migrate` starts
+/// and `RebuildDerivedState` advances a page at a time so that no single message walks the whole book.
+#[cw_serde]
+pub enum DerivedStateRebuild {
+    /// Removing the derived state stored before the migration
+    Clearing,
+    /// Adding the liquidity of each tick after `start_after` to the batching layers
+    Ticks { start_after: Option<i64> },
+    /// Counting, queueing and indexing each order after `start_after`, in `(tick_id, order_id)` order
+    Orders { start_after: Option<(i64, u64)> },
+}
+
+/// Progress of a dust audit, which counts the obligations of the orderbook a page of orders at a
+/// time so that sweeping dust never requires scanning the whole book in a single message.
+#[cw_serde]
+pub struct DustAudit {
+    /// Key of the last order counted, orders are counted in `(tick_id, order_id)` order
+    pub last_counted: (i64, u64),
+    /// Obligations counted so far in the ask denom
+    pub ask_denom_owed: Uint256,
+    /// Obligations counted so far in the bid denom
+    pub bid_denom_owed: Uint256,
+}
+
+/// A snapshot of the time-weighted price accumulator, taken at the end of a block that changed
+/// the mid price of the orderbook.
+///
+/// `price_cumulative` is the sum of the mid price multiplied by the nanoseconds it was held for,
+/// up to `timestamp`. The orderbook is assumed to stay at `mid_price` until the next snapshot.
+#[cw_serde]
+pub struct TwapRecord {
+    pub timestamp: Timestamp,
+    pub price_cumulative: Decimal256,
+    pub mid_price: Decimal256,
+}
+
+impl TwapRecord {
+    /// Extrapolates the accumulator from this snapshot to the given time.
+    pub fn cumulative_at(&self, time: Timestamp) -> ContractResult<Decimal256> {
+        let elapsed_nanos = time
+            .nanos()
+            .checked_sub(self.timestamp.nanos())
+            .ok_or(ContractError::TwapUnavailable { time })?;
+        let elapsed = Decimal256::from_ratio(elapsed_nanos, 1u128);
+        Ok(self
+            .price_cumulative
+            .checked_add(self.mid_price.checked_mul(elapsed)?)?)
+    }
+}
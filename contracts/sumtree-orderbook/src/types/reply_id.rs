@@ -3,3 +3,4 @@ pub const REPLY_ID_CLAIM: u64 = 2;
 pub const REPLY_ID_CLAIM_BOUNTY: u64 = 3;
 pub const REPLY_ID_MAKER_FEE: u64 = 4;
 pub const REPLY_ID_SUDO_SWAP_EXACT_IN: u64 = 5;
+pub const REPLY_ID_PRUNE_REWARD: u64 = 6;
@@ -44,9 +44,43 @@ impl Default for TickValues {
     }
 }
 
+impl TickValues {
+    /// Returns the value placed on the tick that has not yet been swapped, including cancellations
+    /// that have not been realized by a tick sync.
+    ///
+    /// Clamps to zero if ETAS has been pushed past CTT.
+    pub fn remaining(&self) -> Decimal256 {
+        self.cumulative_total_value
+            .saturating_sub(self.effective_total_amount_swapped)
+    }
+
+    /// Returns the value swapped on the tick, excluding the cancellations realized into its ETAS.
+    ///
+    /// Clamps to zero if the realized cancellations exceed ETAS.
+    pub fn swapped(&self) -> Decimal256 {
+        self.effective_total_amount_swapped
+            .saturating_sub(self.cumulative_realized_cancels)
+    }
+
+    /// Returns the fraction of the value placed on the tick that has been swapped, between zero and one.
+    ///
+    /// Returns zero for a tick that has never had value placed on it.
+    pub fn fill_ratio(&self) -> Decimal256 {
+        if self.cumulative_total_value.is_zero() {
+            return Decimal256::zero();
+        }
+
+        self.effective_total_amount_swapped
+            .checked_div(self.cumulative_total_value)
+            .unwrap_or(Decimal256::one())
+            .min(Decimal256::one())
+    }
+}
+
 /// Represents the state of a specific price tick in a liquidity pool.
 ///
-/// The state is split into two parts for the ask and bid directions.
+/// The state is split into two parts for the ask and bid directions. A tick that has
+/// never been written is treated as the default state, with both directions zeroed.
 #[cw_serde]
 #[derive(Default)]
 pub struct TickState {